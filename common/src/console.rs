@@ -0,0 +1,27 @@
+//! Output of the command line modes of the windows subsystem exes, which
+//! start without a console.
+
+/// Attaches to the console of the terminal the exe was started from, so the
+/// output of command line modes shows up there. Does nothing when started
+/// from explorer or with redirected output.
+pub fn attach_parent() {
+    #[cfg(windows)]
+    {
+        use std::ffi::c_void;
+
+        #[link(name = "kernel32")]
+        unsafe extern "system" {
+            fn AttachConsole(process_id: u32) -> i32;
+            fn GetStdHandle(std_handle: u32) -> *mut c_void;
+        }
+        const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+        const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+        // SAFETY: neither takes pointers, failing leaves the exe without a
+        // console.
+        unsafe {
+            if GetStdHandle(STD_OUTPUT_HANDLE).is_null() {
+                AttachConsole(ATTACH_PARENT_PROCESS);
+            }
+        }
+    }
+}
@@ -1 +1,61 @@
-pub const KEY: &[u8] = b"I just want to run AoE2 without Windows defender shafting me.";
+pub mod console;
+pub mod profile;
+pub mod runtimes;
+pub mod shortcuts;
+pub mod steam_shortcuts;
+#[cfg(feature = "ui")]
+pub mod ui;
+pub mod update;
+
+/// Folder in goldberg's saves holding the game profile copied from the
+/// machine the archive was created on.
+pub const GAME_PROFILE_DIR: &str = "aoe2_profile";
+/// The game keeps settings, hotkeys and single player saves per steam id in
+/// this folder of the user's home.
+pub const GAME_PROFILES_PATH: &str = "Games/Age of Empires 2 DE";
+
+/// Makes launcher.exe check the archive without starting the game.
+pub const DRY_RUN_ARG: &str = "--dry-run";
+
+/// Holds the per-archive key followed by the nonce the loader was encrypted with.
+pub const KEY_FILE: &str = "steamclient_loader_x64.key";
+pub const KEY_LEN: usize = 32;
+pub const NONCE_LEN: usize = 12;
+/// Archives created before each got its own key have no [`KEY_FILE`], their
+/// loader is encrypted with the first [`KEY_LEN`] bytes of this and a zero
+/// nonce.
+pub const LEGACY_KEY: &[u8] = b"I just want to run AoE2 without Windows defender shafting me.";
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// `s` as a single quoted powershell string, which only escapes quotes, by
+/// doubling them.
+pub fn powershell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// [`sha256_hex`] of a file, read a chunk at a time rather than whole.
+pub fn sha256_file(path: &std::path::Path) -> std::io::Result<String> {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+/// Splits the contents of a [`KEY_FILE`] into key and nonce.
+pub fn split_key_file(contents: &[u8]) -> Option<(&[u8], &[u8])> {
+    if contents.len() != KEY_LEN + NONCE_LEN {
+        return None;
+    }
+    Some(contents.split_at(KEY_LEN))
+}
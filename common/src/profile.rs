@@ -0,0 +1,90 @@
+//! The game's profiles, one folder per steam id in [`crate::GAME_PROFILES_PATH`].
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The folder of a profile with its saves and recorded games, the only part
+/// carried between profiles. Settings, hotkeys and mods stay with each.
+pub const SAVES_DIR: &str = "savegame";
+
+/// The game names profile folders after the player's 17 digit steam id.
+pub fn is_steam_id(name: &str) -> bool {
+    name.len() == 17 && name.chars().all(|c| c.is_ascii_digit())
+}
+
+/// The most recently played profile in `profiles`, other than the one of
+/// the steam id `except`.
+pub fn newest_profile(profiles: &Path, except: Option<&str>) -> Option<PathBuf> {
+    fs::read_dir(profiles)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            is_steam_id(&name) && except != Some(name.as_str())
+        })
+        .max_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok())
+        .map(|entry| entry.path())
+}
+
+/// Copies the saves of the profile `from` that are missing in the profile
+/// `to` or older there, so progress made in either place survives. Returns
+/// how many were copied.
+pub fn sync_saves(from: &Path, to: &Path) -> io::Result<usize> {
+    let saves = from.join(SAVES_DIR);
+    if !saves.is_dir() {
+        return Ok(0);
+    }
+    sync_newer(&saves, &to.join(SAVES_DIR))
+}
+
+fn sync_newer(from: &Path, to: &Path) -> io::Result<usize> {
+    fs::create_dir_all(to)?;
+    let mut copied = 0;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copied += sync_newer(&entry.path(), &dest)?;
+            continue;
+        }
+        let modified = entry.metadata()?.modified()?;
+        let dest_modified = fs::metadata(&dest).and_then(|m| m.modified()).ok();
+        if dest_modified.is_none_or(|dest_modified| dest_modified < modified) {
+            fs::copy(entry.path(), &dest)?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_only_newer_files() {
+        let root = std::env::temp_dir().join(format!("aoe2-profile-{}", std::process::id()));
+        let (from, to) = (root.join("from"), root.join("to"));
+        fs::create_dir_all(from.join("savegame")).unwrap();
+        fs::write(from.join("savegame/a.aoe2spgame"), "archive").unwrap();
+        fs::write(from.join("settings.ini"), "archive").unwrap();
+
+        assert_eq!(sync_newer(&from, &to).unwrap(), 2);
+        assert_eq!(
+            fs::read_to_string(to.join("savegame/a.aoe2spgame")).unwrap(),
+            "archive"
+        );
+        // Nothing changed since.
+        assert_eq!(sync_newer(&from, &to).unwrap(), 0);
+
+        // Only the saves of a profile are carried over.
+        let other = root.join("other");
+        assert_eq!(sync_saves(&from, &other).unwrap(), 1);
+        assert!(other.join("savegame/a.aoe2spgame").exists());
+        assert!(!other.join("settings.ini").exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
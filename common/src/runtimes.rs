@@ -0,0 +1,33 @@
+//! Runtimes the game needs besides what Windows ships. The archiver can
+//! bundle their installers, which the archive's launcher offers to run on
+//! machines lacking them.
+
+pub struct Runtime {
+    pub name: &'static str,
+    /// Installed into System32 by the runtime.
+    pub dlls: &'static [&'static str],
+    /// Where the archive keeps the installer.
+    pub installer: &'static str,
+    /// The setup the installer only extracts, for self-extracting redists.
+    pub setup: Option<&'static str>,
+    /// Arguments installing without questions.
+    pub args: &'static [&'static str],
+}
+
+pub const VC_REDIST: Runtime = Runtime {
+    name: "Visual C++ Redistributable",
+    dlls: &["vcruntime140.dll", "vcruntime140_1.dll", "msvcp140.dll"],
+    installer: "redist/vc_redist.x64.exe",
+    setup: None,
+    args: &["/install", "/passive", "/norestart"],
+};
+
+pub const DIRECTX: Runtime = Runtime {
+    name: "DirectX End-User Runtime",
+    dlls: &["d3dx11_43.dll", "xinput1_3.dll"],
+    installer: "redist/directx_redist.exe",
+    setup: Some("DXSETUP.exe"),
+    args: &["/silent"],
+};
+
+pub const RUNTIMES: &[Runtime] = &[VC_REDIST, DIRECTX];
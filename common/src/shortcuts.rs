@@ -0,0 +1,63 @@
+use std::{io, path::Path, process::Command};
+
+pub const SHORTCUT_NAME: &str = "Age of Empires II DE";
+/// A copy of launcher.exe that creates the shortcuts instead of starting the game.
+pub const SHORTCUTS_EXE: &str = "create_shortcuts.exe";
+/// Makes launcher.exe remove the archive from steam's non-Steam games, for
+/// the uninstaller.
+pub const REMOVE_SHORTCUTS_ARG: &str = "--remove-shortcuts";
+const LAUNCHER_EXE: &str = "launcher.exe";
+
+/// Creates shortcuts to the archive's launcher.exe on the desktop and in the
+/// Start Menu of the current user.
+pub fn create_shortcuts(archive: &Path, desktop: bool, start_menu: bool) -> io::Result<()> {
+    let mut folders = vec![];
+    if desktop {
+        folders.push("Desktop");
+    }
+    if start_menu {
+        folders.push("Programs");
+    }
+    if folders.is_empty() {
+        return Ok(());
+    }
+
+    let status = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(shortcut_script(archive, &folders))
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "Creating the shortcuts failed ({status})"
+        )));
+    }
+
+    Ok(())
+}
+
+/// The launcher resolves its files relative to the working directory, and
+/// carries the game's icon.
+fn shortcut_script(archive: &Path, folders: &[&str]) -> String {
+    let launcher = quote(&archive.join(LAUNCHER_EXE).display().to_string());
+    let archive = quote(&archive.display().to_string());
+    let folders = folders
+        .iter()
+        .map(|f| format!("'{f}'"))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!(
+        "$shell = New-Object -ComObject WScript.Shell; \
+         foreach ($folder in {folders}) {{ \
+         $link = $shell.CreateShortcut((Join-Path ([Environment]::GetFolderPath($folder)) '{SHORTCUT_NAME}.lnk')); \
+         $link.TargetPath = '{launcher}'; \
+         $link.WorkingDirectory = '{archive}'; \
+         $link.IconLocation = '{launcher},0'; \
+         $link.Save() }}"
+    )
+}
+
+// Single quoted powershell strings only escape quotes, by doubling them.
+fn quote(s: &str) -> String {
+    s.replace('\'', "''")
+}
@@ -0,0 +1,356 @@
+//! Non-Steam game entries in Steam's binary `shortcuts.vdf`.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::shortcuts::SHORTCUT_NAME;
+
+const LAUNCHER_EXE: &str = "launcher.exe";
+
+const TYPE_MAP: u8 = 0x00;
+const TYPE_STRING: u8 = 0x01;
+const TYPE_INT: u8 = 0x02;
+const TYPE_FLOAT: u8 = 0x03;
+const TYPE_UINT64: u8 = 0x07;
+const TYPE_END: u8 = 0x08;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Map(Vec<(String, Value)>),
+    String(String),
+    Int(u32),
+    Float(f32),
+    UInt64(u64),
+}
+
+/// Adds the archive's launcher.exe as a non-Steam game to every Steam user
+/// that logged in on this machine. Returns how many users got a new entry.
+/// Steam overwrites the file on exit, so it should be closed first. The
+/// previous file is kept as `shortcuts.vdf.bak`.
+pub fn add_to_steam(steam_dir: &Path, archive: &Path) -> io::Result<usize> {
+    let mut added = 0;
+    for shortcuts_vdf in user_shortcut_files(steam_dir)? {
+        let existing = match fs::read(&shortcuts_vdf) {
+            Ok(data) => Some(data),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err),
+        };
+        if let Some(data) = add_entry(existing.as_deref(), archive)? {
+            if let Some(parent) = shortcuts_vdf.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            replace_file(&shortcuts_vdf, &data)?;
+            added += 1;
+        }
+    }
+    Ok(added)
+}
+
+/// Removes the archive's launcher.exe from the non-Steam games of every Steam
+/// user on this machine. Returns how many users had it. Steam has to be
+/// closed, like for [`add_to_steam`].
+pub fn remove_from_steam(steam_dir: &Path, archive: &Path) -> io::Result<usize> {
+    let mut removed = 0;
+    for shortcuts_vdf in user_shortcut_files(steam_dir)? {
+        let existing = match fs::read(&shortcuts_vdf) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        if let Some(data) = remove_entry(&existing, archive)? {
+            replace_file(&shortcuts_vdf, &data)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Backs `path` up next to it, then replaces it with `data` through a
+/// rename, so steam never reads a half written file.
+fn replace_file(path: &Path, data: &[u8]) -> io::Result<()> {
+    let with_suffix = |suffix: &str| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(suffix);
+        PathBuf::from(name)
+    };
+    if path.exists() {
+        fs::copy(path, with_suffix(".bak"))?;
+    }
+    let tmp = with_suffix(".tmp");
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+fn user_shortcut_files(steam_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let userdata = steam_dir.join("userdata");
+    if !userdata.exists() {
+        return Err(io::Error::other(format!(
+            "No steam users found in {}",
+            userdata.display()
+        )));
+    }
+
+    let mut files = vec![];
+    for entry in fs::read_dir(userdata)? {
+        let entry = entry?;
+        // Account folders are named by the numeric account id, "0" is not a user.
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type()?.is_dir() && name != "0" && name.chars().all(|c| c.is_ascii_digit()) {
+            files.push(entry.path().join("config").join("shortcuts.vdf"));
+        }
+    }
+    Ok(files)
+}
+
+/// Returns the new contents of a shortcuts.vdf, or None if the launcher is
+/// already in it.
+fn add_entry(existing: Option<&[u8]>, archive: &Path) -> io::Result<Option<Vec<u8>>> {
+    let mut root = match existing {
+        Some(data) if !data.is_empty() => parse(data)?,
+        _ => vec![("shortcuts".to_string(), Value::Map(vec![]))],
+    };
+    let Some((_, Value::Map(shortcuts))) = root.iter_mut().find(|(k, _)| k == "shortcuts") else {
+        return Err(invalid("missing shortcuts"));
+    };
+
+    let launcher = archive.join(LAUNCHER_EXE).display().to_string();
+    let exe = format!("\"{launcher}\"");
+    if shortcuts.iter().any(|(_, shortcut)| starts(shortcut, &exe)) {
+        return Ok(None);
+    }
+
+    let string = |k: &str, v: &str| (k.to_string(), Value::String(v.to_string()));
+    let int = |k: &str, v: u32| (k.to_string(), Value::Int(v));
+    let entry = Value::Map(vec![
+        int("appid", shortcut_app_id(&exe, SHORTCUT_NAME)),
+        string("AppName", SHORTCUT_NAME),
+        string("Exe", &exe),
+        string("StartDir", &format!("\"{}\"", archive.display())),
+        string("icon", &launcher),
+        string("ShortcutPath", ""),
+        string("LaunchOptions", ""),
+        int("IsHidden", 0),
+        int("AllowDesktopConfig", 1),
+        int("AllowOverlay", 1),
+        int("OpenVR", 0),
+        int("Devkit", 0),
+        string("DevkitGameID", ""),
+        int("DevkitOverrideAppID", 0),
+        int("LastPlayTime", 0),
+        string("FlatpakAppID", ""),
+        ("tags".to_string(), Value::Map(vec![])),
+    ]);
+    shortcuts.push((shortcuts.len().to_string(), entry));
+
+    Ok(Some(serialize(&root)))
+}
+
+/// Returns the new contents of a shortcuts.vdf, or None if the launcher
+/// isn't in it.
+fn remove_entry(existing: &[u8], archive: &Path) -> io::Result<Option<Vec<u8>>> {
+    if existing.is_empty() {
+        return Ok(None);
+    }
+    let mut root = parse(existing)?;
+    let Some((_, Value::Map(shortcuts))) = root.iter_mut().find(|(k, _)| k == "shortcuts") else {
+        return Err(invalid("missing shortcuts"));
+    };
+
+    let exe = format!("\"{}\"", archive.join(LAUNCHER_EXE).display());
+    let count = shortcuts.len();
+    shortcuts.retain(|(_, shortcut)| !starts(shortcut, &exe));
+    if shortcuts.len() == count {
+        return Ok(None);
+    }
+    // Steam expects the entries numbered from 0 without gaps.
+    for (i, (key, _)) in shortcuts.iter_mut().enumerate() {
+        *key = i.to_string();
+    }
+
+    Ok(Some(serialize(&root)))
+}
+
+/// Whether the shortcut starts `exe`, quoted like steam stores it.
+fn starts(shortcut: &Value, exe: &str) -> bool {
+    let Value::Map(fields) = shortcut else {
+        return false;
+    };
+    fields.iter().any(|(k, v)| {
+        k.eq_ignore_ascii_case("exe")
+            && matches!(v, Value::String(s) if s.eq_ignore_ascii_case(exe))
+    })
+}
+
+fn serialize(root: &[(String, Value)]) -> Vec<u8> {
+    let mut out = vec![];
+    write_map(&mut out, root);
+    out.push(TYPE_END);
+    out
+}
+
+/// The id Steam derives for non-Steam games, which artwork and controller
+/// configs are stored by.
+fn shortcut_app_id(exe: &str, name: &str) -> u32 {
+    crc32(format!("{exe}{name}").as_bytes()) | 0x8000_0000
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("shortcuts.vdf: {msg}"))
+}
+
+fn parse(data: &[u8]) -> io::Result<Vec<(String, Value)>> {
+    let mut pos = 0;
+    parse_map(data, &mut pos)
+}
+
+fn parse_map(data: &[u8], pos: &mut usize) -> io::Result<Vec<(String, Value)>> {
+    let mut map = vec![];
+    loop {
+        let kind = *data.get(*pos).ok_or_else(|| invalid("unexpected end"))?;
+        *pos += 1;
+        if kind == TYPE_END {
+            return Ok(map);
+        }
+        let key = read_string(data, pos)?;
+        let value = match kind {
+            TYPE_MAP => Value::Map(parse_map(data, pos)?),
+            TYPE_STRING => Value::String(read_string(data, pos)?),
+            TYPE_INT => Value::Int(u32::from_le_bytes(read_bytes(data, pos)?)),
+            TYPE_FLOAT => Value::Float(f32::from_le_bytes(read_bytes(data, pos)?)),
+            TYPE_UINT64 => Value::UInt64(u64::from_le_bytes(read_bytes(data, pos)?)),
+            _ => return Err(invalid(&format!("unknown type {kind:#x}"))),
+        };
+        map.push((key, value));
+    }
+}
+
+fn read_string(data: &[u8], pos: &mut usize) -> io::Result<String> {
+    let rest = data.get(*pos..).unwrap_or_default();
+    let len = rest
+        .iter()
+        .position(|b| *b == 0)
+        .ok_or_else(|| invalid("unterminated string"))?;
+    *pos += len + 1;
+    Ok(String::from_utf8_lossy(&rest[..len]).into_owned())
+}
+
+fn read_bytes<const N: usize>(data: &[u8], pos: &mut usize) -> io::Result<[u8; N]> {
+    let bytes = data
+        .get(*pos..*pos + N)
+        .ok_or_else(|| invalid("unexpected end"))?;
+    *pos += N;
+    Ok(bytes.try_into().expect("slice of N bytes"))
+}
+
+fn write_map(out: &mut Vec<u8>, map: &[(String, Value)]) {
+    for (key, value) in map {
+        let kind = match value {
+            Value::Map(_) => TYPE_MAP,
+            Value::String(_) => TYPE_STRING,
+            Value::Int(_) => TYPE_INT,
+            Value::Float(_) => TYPE_FLOAT,
+            Value::UInt64(_) => TYPE_UINT64,
+        };
+        out.push(kind);
+        out.extend_from_slice(key.as_bytes());
+        out.push(0);
+        match value {
+            Value::Map(map) => {
+                write_map(out, map);
+                out.push(TYPE_END);
+            }
+            Value::String(s) => {
+                out.extend_from_slice(s.as_bytes());
+                out.push(0);
+            }
+            Value::Int(v) => out.extend_from_slice(&v.to_le_bytes()),
+            Value::Float(v) => out.extend_from_slice(&v.to_le_bytes()),
+            Value::UInt64(v) => out.extend_from_slice(&v.to_le_bytes()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_once_and_keep_other_shortcuts() {
+        let archive = Path::new("D:\\AoE2");
+        let first = add_entry(None, archive).unwrap().unwrap();
+        assert!(add_entry(Some(&first), archive).unwrap().is_none());
+
+        let second = add_entry(Some(&first), Path::new("E:\\AoE2"))
+            .unwrap()
+            .unwrap();
+        let root = parse(&second).unwrap();
+        let Value::Map(shortcuts) = &root[0].1 else {
+            panic!("shortcuts is a map");
+        };
+        assert_eq!(shortcuts.len(), 2);
+        assert_eq!(shortcuts[1].0, "1");
+        assert!(second.ends_with(&[TYPE_END, TYPE_END]));
+    }
+
+    #[test]
+    fn remove_only_the_archive() {
+        let first = add_entry(None, Path::new("D:\\AoE2")).unwrap().unwrap();
+        let both = add_entry(Some(&first), Path::new("E:\\AoE2"))
+            .unwrap()
+            .unwrap();
+
+        let removed = remove_entry(&both, Path::new("D:\\AoE2")).unwrap().unwrap();
+        assert!(
+            remove_entry(&removed, Path::new("D:\\AoE2"))
+                .unwrap()
+                .is_none()
+        );
+        let root = parse(&removed).unwrap();
+        let Value::Map(shortcuts) = &root[0].1 else {
+            panic!("shortcuts is a map");
+        };
+        assert_eq!(shortcuts.len(), 1);
+        assert_eq!(shortcuts[0].0, "0");
+        let kept = Path::new("E:\\AoE2").join(LAUNCHER_EXE);
+        assert!(starts(&shortcuts[0].1, &format!("\"{}\"", kept.display())));
+    }
+
+    #[test]
+    fn replace_keeps_a_backup() {
+        let dir = std::env::temp_dir().join(format!("aoe2-shortcuts-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let vdf = dir.join("shortcuts.vdf");
+        fs::write(&vdf, b"old").unwrap();
+
+        replace_file(&vdf, b"new").unwrap();
+        assert_eq!(fs::read(&vdf).unwrap(), b"new");
+        assert_eq!(fs::read(dir.join("shortcuts.vdf.bak")).unwrap(), b"old");
+        assert!(!dir.join("shortcuts.vdf.tmp").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn crc32_matches_reference() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+}
@@ -0,0 +1,12 @@
+use eframe::egui;
+
+/// Decodes one of the icons in assets for a window.
+pub fn icon_data(data: &[u8]) -> image::ImageResult<egui::IconData> {
+    let rgba = image::load_from_memory(data)?.to_rgba8();
+    let (width, height) = rgba.dimensions();
+    Ok(egui::IconData {
+        rgba: rgba.into_raw(),
+        width,
+        height,
+    })
+}
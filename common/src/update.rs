@@ -0,0 +1,189 @@
+//! Archives update themselves from a copy the creator maintains, listed in a
+//! manifest of file hashes.
+
+use crate::sha256_hex;
+use std::{fs, io, path::Path};
+
+pub const UPDATE_MANIFEST: &str = "update_manifest.txt";
+/// The sizes of the game files, which updates leave alone, for checking the
+/// archive.
+pub const GAME_MANIFEST: &str = "game_manifest.txt";
+/// What went into the archive, written by the archiver.
+pub const METADATA_FILE: &str = "archive.json";
+/// Settings of the archive's launcher, next to launcher.exe.
+pub const LAUNCH_CONFIG: &str = "launch.ini";
+/// How far the archiver got creating the archive, gone once it finished.
+pub const RUN_STATE: &str = "archiver_state.json";
+/// The warnings and errors of the runs that created the archive.
+pub const RUN_LOG: &str = "archiver_run.log";
+/// The summary of the run that created the archive, saved after the
+/// manifests were written.
+pub const RUN_REPORT: &str = "archive-report.txt";
+/// The parts of the archive updates can replace, besides the files in its
+/// root. The game files are too big to hash on every start.
+const UPDATE_DIRS: &[&str] = &["goldberg", "launcher", "server", "companion"];
+/// Files each machine changes for itself.
+const LOCAL_FILES: &[&str] = &[
+    LAUNCH_CONFIG,
+    RUN_STATE,
+    RUN_LOG,
+    RUN_REPORT,
+    "launch.lock",
+    "goldberg/ColdClientLoader.ini",
+    "goldberg/steam_settings/account_avatar.png",
+    "goldberg/steam_settings/configs.user.ini",
+    "goldberg/steamclient_loader_x64.exe",
+    "launcher/resources/config.age2.toml",
+];
+const LOCAL_DIRS: &[&str] = &["goldberg/saves"];
+
+/// Lists the updatable files of an archive with their hashes, one
+/// `<sha256> <path>` per line like sha256sum.
+pub fn manifest(archive: &Path) -> io::Result<String> {
+    let mut files = vec![];
+    for entry in fs::read_dir(archive)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type()?.is_file() && name != UPDATE_MANIFEST && !is_log(&name) {
+            files.push(name);
+        }
+    }
+    for dir in UPDATE_DIRS {
+        list_files(archive, dir, &mut files)?;
+    }
+    files.retain(|f| !LOCAL_FILES.contains(&f.as_str()));
+    files.sort();
+
+    let mut manifest = String::new();
+    for file in files {
+        let hash = sha256_hex(&fs::read(archive.join(&file))?);
+        manifest.push_str(&format!("{hash} {file}\n"));
+    }
+    Ok(manifest)
+}
+
+fn list_files(archive: &Path, dir: &str, files: &mut Vec<String>) -> io::Result<()> {
+    let path = archive.join(dir);
+    if !path.is_dir() || LOCAL_DIRS.contains(&dir) {
+        return Ok(());
+    }
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let name = format!("{dir}/{}", entry.file_name().to_string_lossy());
+        if entry.file_type()?.is_dir() {
+            if !name.ends_with("/logs") {
+                list_files(archive, &name, files)?;
+            }
+        } else if !is_log(&name) {
+            files.push(name);
+        }
+    }
+    Ok(())
+}
+
+/// Logs of the launcher, the lan server and the game starts differ on every
+/// machine.
+fn is_log(name: &str) -> bool {
+    name.ends_with(".log")
+}
+
+/// The `(sha256, path)` entries of a manifest, skipping paths that would
+/// leave the archive.
+pub fn parse_manifest(manifest: &str) -> Vec<(&str, &str)> {
+    manifest
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(hash, path)| (hash.trim(), path.trim()))
+        .filter(|(hash, path)| hash.len() == 64 && is_enclosed(path))
+        .collect()
+}
+
+/// Lists the files of the archive's game folder with their sizes, one
+/// `<size> <path>` per line. Hashing them would take minutes.
+pub fn game_manifest(archive: &Path, game_folder: &str) -> io::Result<String> {
+    let mut files = vec![];
+    list_files(archive, game_folder, &mut files)?;
+    // Goldberg's settings in steam_api archives change on every machine.
+    let settings = format!("{game_folder}/steam_settings/");
+    files.retain(|f| !f.starts_with(&settings));
+    files.sort();
+
+    let mut manifest = String::new();
+    for file in files {
+        let size = fs::metadata(archive.join(&file))?.len();
+        manifest.push_str(&format!("{size} {file}\n"));
+    }
+    Ok(manifest)
+}
+
+/// The `(size, path)` entries of a [`GAME_MANIFEST`].
+pub fn parse_game_manifest(manifest: &str) -> Vec<(u64, &str)> {
+    manifest
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .filter_map(|(size, path)| Some((size.trim().parse().ok()?, path.trim())))
+        .filter(|(_, path)| is_enclosed(path))
+        .collect()
+}
+
+fn is_enclosed(path: &str) -> bool {
+    !path.is_empty()
+        && !path.starts_with(['/', '\\'])
+        && !path.contains(':')
+        && !path.split(['/', '\\']).any(|c| c == "..")
+}
+
+/// The numbers of a version like `v1.2.3`, compared piece by piece.
+pub fn parse_version(version: &str) -> Vec<u64> {
+    version
+        .trim()
+        .trim_start_matches(['v', 'V'])
+        .split('.')
+        .map_while(|piece| {
+            let digits: String = piece.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_versions() {
+        assert!(parse_version("v1.10.0") > parse_version("1.9.3"));
+        assert!(parse_version("v0.2.0-beta") > parse_version("0.1.0"));
+        assert_eq!(parse_version("V1.2"), [1, 2]);
+        assert!(parse_version("") < parse_version("0.0.1"));
+    }
+
+    #[test]
+    fn skip_paths_outside_the_archive() {
+        let hash = "0".repeat(64);
+        let manifest = format!(
+            "{hash} goldberg/dlls/a.dll\n{hash} ../evil.exe\n{hash} C:/Windows/x.dll\n{hash} /etc/x\nbad line\n"
+        );
+        assert_eq!(
+            parse_manifest(&manifest),
+            [(hash.as_str(), "goldberg/dlls/a.dll")]
+        );
+    }
+
+    #[test]
+    fn list_game_file_sizes() {
+        let archive = std::env::temp_dir().join(format!("aoe2-game-{}", std::process::id()));
+        fs::create_dir_all(archive.join("AoE2DE/steam_settings")).unwrap();
+        fs::write(archive.join("AoE2DE/AoE2DE_s.exe"), b"game").unwrap();
+        fs::write(archive.join("AoE2DE/steam_settings/configs.user.ini"), b"").unwrap();
+
+        let manifest = game_manifest(&archive, "AoE2DE").unwrap();
+        assert_eq!(manifest, "4 AoE2DE/AoE2DE_s.exe\n");
+        assert_eq!(
+            parse_game_manifest(&format!("{manifest}x ../y\n1 ../evil.exe\n")),
+            [(4, "AoE2DE/AoE2DE_s.exe")]
+        );
+
+        fs::remove_dir_all(&archive).unwrap();
+    }
+}
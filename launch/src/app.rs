@@ -0,0 +1,915 @@
+use crate::{
+    archive::{
+        SavesExport, ServerOverride, UserSettings, decline_certificate, export_saves, extra_args,
+        game_version, has_archived_profile, has_server, override_server, remove_decrypted_loader,
+        restore_profile, save_extra_args, save_user_settings, save_verify_before_play, server_host,
+        start_game, start_server, steam_dir, supported_languages, trust_certificate,
+        untrusted_certificate, update_manifest_sha256, update_source, user_settings,
+        verify_before_play,
+    },
+    crash::{Crash, ERROR_LOG},
+    hotkeys::{export_hotkeys, import_hotkeys},
+    instance::{self, InstanceLock},
+    mods::Mods,
+    releases::{Release, check_enabled, install_patch, newer_release, save_check_enabled},
+    runtimes::{install, missing_runtimes},
+    update::{update_from, verify, write_manifest},
+    wizard::{Wizard, WizardResult, language_combo},
+};
+use anyhow::Result;
+use common::{
+    runtimes::Runtime, shortcuts::create_shortcuts, steam_shortcuts::add_to_steam, ui::icon_data,
+};
+use eframe::egui::{self, Button, Color32, RichText, Ui};
+use std::{
+    io::Read,
+    net::{IpAddr, TcpStream, ToSocketAddrs, UdpSocket},
+    path::PathBuf,
+    process::Child,
+    sync::{
+        Arc, Mutex,
+        mpsc::{Receiver, channel},
+    },
+    time::{Duration, SystemTime},
+};
+use tracing::{error, info, warn};
+
+// The lan server's https port.
+const SERVER_PORT: u16 = 443;
+// Seconds a hosted lan server gets to start answering.
+const SERVER_START_TIMEOUT: u32 = 30;
+const LOCAL_HOST: &str = "127.0.0.1";
+// Damaged archives tend to be missing whole folders.
+const MAX_SHOWN_PROBLEMS: usize = 8;
+
+pub fn run(show_tools: bool) -> Result<()> {
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size([380.0, 300.0])
+        .with_resizable(false);
+    if let Ok(icon) = icon_data(include_bytes!("../../assets/aoe.ico")) {
+        viewport = viewport.with_icon(icon);
+    }
+
+    let options = eframe::NativeOptions {
+        viewport,
+        ..Default::default()
+    };
+    let app = LauncherApp::new(show_tools);
+
+    eframe::run_native(
+        "Age of Empires II DE",
+        options,
+        Box::new(|_cc| Ok(Box::new(app))),
+    )
+    .map_err(|e| anyhow::anyhow!("{e}"))
+}
+
+struct LauncherApp {
+    settings: UserSettings,
+    languages: Vec<String>,
+    /// The release of the game in the archive.
+    game_version: Option<String>,
+    /// Command line arguments passed to the game.
+    extra_args: String,
+    /// Check the archive's files before starting the game.
+    verify: bool,
+    /// Set while checking the files after pressing Play.
+    verify_rx: Option<Receiver<Vec<String>>>,
+    /// Missing or damaged files found when pressing Play.
+    damaged_files: Option<Vec<String>>,
+    /// Shown instead of the launcher until the player picked a name.
+    wizard: Option<Wizard>,
+    /// The process id of another launcher running from this archive.
+    other_instance: Option<u32>,
+    _lock: Option<InstanceLock>,
+    /// Game processes this launcher didn't start, e.g. left over from a
+    /// crash.
+    game_processes: Vec<&'static str>,
+    server_status: String,
+    server_rx: Receiver<String>,
+    /// The archive includes the lan server.
+    can_host: bool,
+    /// Set while the hosted lan server starts, receiving whether it answers.
+    host_rx: Option<Receiver<bool>>,
+    /// Where other players find the lan server hosted here.
+    host_address: Option<String>,
+    /// Set while pulling changes from the archive's update source.
+    update_rx: Option<Receiver<Result<usize>>>,
+    /// Look for newer releases of the archiver.
+    check_releases: bool,
+    release_rx: Option<Receiver<Result<Option<Release>>>>,
+    /// A newer archiver release than the one that created the archive.
+    release: Option<Release>,
+    /// Set while installing the release's patch bundle.
+    patch_rx: Option<Receiver<Result<usize>>>,
+    untrusted_certificate: Option<PathBuf>,
+    /// Set once the certificate prompt's decline button got the focus.
+    focused_decline: bool,
+    /// Runtimes the game needs that this machine lacks.
+    missing_runtimes: Vec<&'static Runtime>,
+    /// Set while a runtime installer runs.
+    install_rx: Option<Receiver<Result<()>>>,
+    steam_dir: Option<PathBuf>,
+    has_archived_profile: bool,
+    /// Shown in the mods section when the profile has any.
+    mods: Option<Mods>,
+    show_tools: bool,
+    running: Option<RunningGame>,
+    /// Why the game didn't start, shown until dismissed.
+    crash: Option<Crash>,
+    message: Option<String>,
+    error: Option<String>,
+}
+
+struct RunningGame {
+    child: Child,
+    stderr: Arc<Mutex<String>>,
+    started: SystemTime,
+    /// Restores the player's lan server settings once the game's launcher
+    /// exited.
+    _server: Option<ServerOverride>,
+}
+
+impl LauncherApp {
+    fn new(show_tools: bool) -> Self {
+        let mut error = None;
+        let settings = user_settings().unwrap_or_else(|err| {
+            show_error(&mut error, format!("Unable to read the user config: {err}"));
+            UserSettings::default()
+        });
+        let wizard = (error.is_none() && settings.account_name.is_empty())
+            .then(|| Wizard::new(settings.clone()));
+        let lock = instance::lock().ok();
+        let other_instance = lock.is_none().then(instance::other_instance).flatten();
+        let game_processes = instance::running_game_processes();
+        // Left behind when the launcher was closed while playing.
+        if other_instance.is_none() && game_processes.is_empty() {
+            let _ = remove_decrypted_loader();
+        }
+
+        let mut app = Self {
+            settings,
+            languages: supported_languages(),
+            game_version: game_version(),
+            extra_args: extra_args(),
+            verify: verify_before_play(),
+            verify_rx: None,
+            damaged_files: None,
+            wizard,
+            other_instance,
+            _lock: lock,
+            game_processes,
+            server_status: String::new(),
+            server_rx: channel().1,
+            can_host: has_server(),
+            host_rx: None,
+            host_address: None,
+            update_rx: None,
+            check_releases: check_enabled(),
+            release_rx: None,
+            release: None,
+            patch_rx: None,
+            untrusted_certificate: untrusted_certificate(),
+            focused_decline: false,
+            missing_runtimes: missing_runtimes(),
+            install_rx: None,
+            steam_dir: steam_dir(),
+            has_archived_profile: has_archived_profile(),
+            mods: Mods::load(),
+            show_tools,
+            running: None,
+            crash: None,
+            message: None,
+            error,
+        };
+        app.refresh_server_status();
+        // Files of a running game can't be replaced.
+        if app.other_instance.is_none()
+            && app.game_processes.is_empty()
+            && let Some(source) = update_source()
+        {
+            let (tx, update_rx) = channel();
+            std::thread::spawn(move || {
+                let pinned = update_manifest_sha256();
+                let _ = tx.send(update_from(&source, pinned.as_deref()));
+            });
+            app.update_rx = Some(update_rx);
+        }
+        if app.check_releases {
+            app.check_for_release();
+        }
+        app
+    }
+
+    fn check_for_release(&mut self) {
+        let (tx, release_rx) = channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(newer_release());
+        });
+        self.release_rx = Some(release_rx);
+    }
+
+    fn refresh_server_status(&mut self) {
+        self.server_status = "Checking the lan server...".to_string();
+        let (tx, server_rx) = channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(check_server());
+        });
+        self.server_rx = server_rx;
+    }
+
+    /// Starts the archive's lan server here and points the launcher at it
+    /// once it answers.
+    fn host_server(&mut self) {
+        if let Err(err) = start_server() {
+            show_error(&mut self.error, format!("{err:#}"));
+            return;
+        }
+        let (tx, host_rx) = channel();
+        std::thread::spawn(move || {
+            let up = (0..SERVER_START_TIMEOUT).any(|_| {
+                let up = is_server_up(LOCAL_HOST);
+                if !up {
+                    std::thread::sleep(Duration::from_secs(1));
+                }
+                up
+            });
+            let _ = tx.send(up);
+        });
+        self.host_rx = Some(host_rx);
+    }
+
+    fn play(&mut self, ctx: &egui::Context) -> Result<()> {
+        if self.verify {
+            // Hashing the archive takes a while on slow drives.
+            let (tx, verify_rx) = channel();
+            std::thread::spawn(move || {
+                let problems = verify()
+                    .unwrap_or_else(|err| vec![format!("Unable to check the files: {err:#}")]);
+                let _ = tx.send(problems);
+            });
+            self.verify_rx = Some(verify_rx);
+            return Ok(());
+        }
+        self.start(ctx)
+    }
+
+    fn start(&mut self, ctx: &egui::Context) -> Result<()> {
+        self.damaged_files = None;
+        save_user_settings(&self.settings)?;
+        save_extra_args(&self.extra_args)?;
+        let server = match self.host_address {
+            Some(_) => Some(override_server(LOCAL_HOST)?),
+            None => None,
+        };
+        let started = SystemTime::now();
+        let mut child = start_game()?;
+
+        let stderr = Arc::new(Mutex::new(String::new()));
+        if let Some(mut pipe) = child.stderr.take() {
+            let stderr = stderr.clone();
+            std::thread::spawn(move || {
+                let mut buf = [0; 4096];
+                while let Ok(read) = pipe.read(&mut buf)
+                    && read > 0
+                {
+                    stderr
+                        .lock()
+                        .unwrap()
+                        .push_str(&String::from_utf8_lossy(&buf[..read]));
+                }
+            });
+        }
+
+        self.running = Some(RunningGame {
+            child,
+            stderr,
+            started,
+            _server: server,
+        });
+        self.crash = None;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+        Ok(())
+    }
+
+    /// Closes the launcher once the game exited normally, and explains what
+    /// happened otherwise.
+    fn watch_game(&mut self, ctx: &egui::Context) {
+        let Some(running) = &mut self.running else {
+            return;
+        };
+        let status = match running.child.try_wait() {
+            Ok(Some(status)) => status,
+            Ok(None) => return,
+            Err(err) => {
+                show_error(&mut self.error, format!("Lost track of the game: {err}"));
+                self.running = None;
+                return;
+            }
+        };
+        info!("The lan server launcher exited with {status}");
+        // Fails while the loader still runs, the next start tries again.
+        let _ = remove_decrypted_loader();
+        // The game adds the mods subscribed to while playing.
+        self.mods = Mods::load();
+
+        if status.success() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        } else {
+            let stderr = running.stderr.lock().unwrap().clone();
+            let crash = Crash::collect(status, &stderr, running.started);
+            error!("The game didn't start: {}", crash.summary);
+            if let Err(err) = crash.write_log() {
+                show_error(
+                    &mut self.error,
+                    format!("Unable to write {ERROR_LOG}: {err}"),
+                );
+            }
+            self.crash = Some(crash);
+            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        }
+        self.running = None;
+    }
+}
+
+/// Whether the configured lan server answers, or how the launcher finds one.
+fn check_server() -> String {
+    let Some(host) = server_host() else {
+        return "The launcher looks for a server on the lan, or hosts one".to_string();
+    };
+    info!("Checking the lan server at {host}:{SERVER_PORT}");
+    let reachable = is_server_up(&host);
+    info!(
+        "The lan server at {host} is {}",
+        if reachable { "up" } else { "unreachable" }
+    );
+    if reachable {
+        format!("The lan server at {host} is up")
+    } else {
+        format!("The lan server at {host} isn't reachable yet")
+    }
+}
+
+fn is_server_up(host: &str) -> bool {
+    (host, SERVER_PORT)
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .is_some_and(|addr| TcpStream::connect_timeout(&addr, Duration::from_secs(2)).is_ok())
+}
+
+/// This machine's address on the network, from the route to a public or
+/// private address. Nothing is sent.
+fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    ["8.8.8.8:80", "192.168.0.1:80", "10.0.0.1:80"]
+        .iter()
+        .filter(|target| socket.connect(target).is_ok())
+        .find_map(|_| {
+            let ip = socket.local_addr().ok()?.ip();
+            (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+        })
+}
+
+impl eframe::App for LauncherApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if let Ok(status) = self.server_rx.try_recv() {
+            self.server_status = status;
+        }
+        if let Some(update_rx) = &self.update_rx
+            && let Ok(result) = update_rx.try_recv()
+        {
+            match result {
+                Ok(0) => {}
+                Ok(updated) => self.message = Some(format!("Updated {updated} files")),
+                Err(err) => show_error(
+                    &mut self.error,
+                    format!("Unable to update the archive: {err:#}"),
+                ),
+            }
+            self.update_rx = None;
+        }
+        if let Some(verify_rx) = &self.verify_rx
+            && let Ok(problems) = verify_rx.try_recv()
+        {
+            self.verify_rx = None;
+            if problems.is_empty() {
+                if let Err(err) = self.start(ctx) {
+                    show_error(&mut self.error, format!("{err:#}"));
+                }
+            } else {
+                self.damaged_files = Some(problems);
+            }
+        }
+        if let Some(host_rx) = &self.host_rx
+            && let Ok(up) = host_rx.try_recv()
+        {
+            self.host_rx = None;
+            if up {
+                // The game joins it on this launch only, see start.
+                self.host_address = Some(lan_address().map_or_else(
+                    || "this computer's address, see ipconfig".to_string(),
+                    |ip| ip.to_string(),
+                ));
+                self.server_status = "The lan server on this machine is up".to_string();
+            } else {
+                show_error(
+                    &mut self.error,
+                    format!(
+                        "The lan server didn't answer within {SERVER_START_TIMEOUT} seconds, see its window"
+                    ),
+                );
+            }
+        }
+        if let Some(release_rx) = &self.release_rx
+            && let Ok(result) = release_rx.try_recv()
+        {
+            match result {
+                Ok(release) => self.release = release,
+                // Offline parties are the norm, don't bother the players.
+                Err(err) => tracing::warn!("Unable to check for archiver releases: {err:#}"),
+            }
+            self.release_rx = None;
+        }
+        if let Some(patch_rx) = &self.patch_rx
+            && let Ok(result) = patch_rx.try_recv()
+        {
+            match result {
+                Ok(installed) => {
+                    self.message = Some(format!(
+                        "Installed {installed} patched files, restart the launcher to use them"
+                    ));
+                    self.release = None;
+                }
+                Err(err) => show_error(
+                    &mut self.error,
+                    format!("Unable to install the patch: {err:#}"),
+                ),
+            }
+            self.patch_rx = None;
+        }
+        if let Some(install_rx) = &self.install_rx
+            && let Ok(result) = install_rx.try_recv()
+        {
+            if let Err(err) = result {
+                show_error(&mut self.error, format!("{err:#}"));
+            }
+            self.missing_runtimes = missing_runtimes();
+            self.install_rx = None;
+        }
+        self.watch_game(ctx);
+        if let Some(crash) = &self.crash {
+            let mut open = true;
+            egui::Window::new("The game didn't start")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(RichText::new(&crash.summary).color(Color32::RED));
+                    ui.label(format!("The details were saved to {ERROR_LOG}."));
+                    ui.horizontal(|ui| {
+                        if ui.button("Copy details").clicked() {
+                            ctx.copy_text(crash.details.clone());
+                        }
+                        if ui.button("Close").clicked() {
+                            open = false;
+                        }
+                    });
+                });
+            if !open {
+                self.crash = None;
+            }
+        }
+        if let Some(problems) = &self.damaged_files {
+            let mut play_anyway = false;
+            let mut open = true;
+            egui::Window::new("The archive is damaged")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label("Copy the archive again, the game may crash with these files:");
+                    for problem in problems.iter().take(MAX_SHOWN_PROBLEMS) {
+                        ui.label(RichText::new(problem).color(Color32::RED));
+                    }
+                    if problems.len() > MAX_SHOWN_PROBLEMS {
+                        ui.label(format!("and {} more", problems.len() - MAX_SHOWN_PROBLEMS));
+                    }
+                    ui.horizontal(|ui| {
+                        play_anyway = ui.button("Play anyway").clicked();
+                        open = !ui.button("Cancel").clicked();
+                    });
+                });
+            if play_anyway {
+                if let Err(err) = self.start(ctx) {
+                    show_error(&mut self.error, format!("{err:#}"));
+                }
+            } else if !open {
+                self.damaged_files = None;
+            }
+        }
+        // Background checks only report back on the next frame.
+        ctx.request_repaint_after(Duration::from_millis(250));
+
+        if let Some(pid) = self.other_instance {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.heading("The launcher is already running");
+                ui.add_space(8.0);
+                ui.label("Starting the game twice corrupts its saves.");
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Switch to it").clicked() {
+                        instance::focus(pid);
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                    if ui
+                        .button("Close it")
+                        .on_hover_text("Closes the other launcher, e.g. when it stopped responding")
+                        .clicked()
+                    {
+                        instance::kill(pid);
+                        self.other_instance = None;
+                        self._lock = instance::lock().ok();
+                        self.game_processes = instance::running_game_processes();
+                    }
+                    if ui.button("Quit").clicked() {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                });
+            });
+            return;
+        }
+
+        if let Some(wizard) = &mut self.wizard {
+            let result = egui::CentralPanel::default()
+                .show(ctx, |ui| wizard.show(ui))
+                .inner;
+            if let WizardResult::Finished(settings) = result {
+                self.settings = settings;
+                self.wizard = None;
+                self.refresh_server_status();
+            }
+            return;
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Age of Empires II DE");
+            if let Some(version) = &self.game_version {
+                ui.label(format!("Game version {version}"))
+                    .on_hover_text("Players need the same version to play together");
+            }
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.label("Your name:");
+                ui.text_edit_singleline(&mut self.settings.account_name);
+            });
+            if self.languages.len() > 1 {
+                ui.horizontal(|ui| {
+                    ui.label("Language:");
+                    if language_combo(ui, &mut self.settings.language, &self.languages).changed()
+                        && let Err(err) = save_user_settings(&self.settings)
+                    {
+                        show_error(
+                            &mut self.error,
+                            format!("Unable to save the language: {err:#}"),
+                        );
+                    }
+                });
+            }
+            ui.label(RichText::new(&self.server_status).italics());
+            if let Some(address) = &self.host_address {
+                ui.label(format!(
+                    "The other players join the lan server at {address}"
+                ));
+            } else if self.can_host {
+                let starting = self.host_rx.is_some();
+                if ui
+                    .add_enabled(!starting, Button::new("Host server on this machine"))
+                    .on_hover_text("Start the lan server here, for the other players to join")
+                    .clicked()
+                {
+                    self.host_server();
+                }
+                if starting {
+                    ui.label("Starting the lan server...");
+                }
+            }
+            ui.add_space(8.0);
+
+            if let Some(cert) = self.untrusted_certificate.clone() {
+                ui.label("The lan server's certificate isn't trusted on this computer yet. Only trust it if you know who made this archive.");
+                ui.horizontal(|ui| {
+                    // Focused, so enter declines.
+                    let decline = ui.button("Don't trust");
+                    if !self.focused_decline {
+                        decline.request_focus();
+                        self.focused_decline = true;
+                    }
+                    if decline.clicked() {
+                        self.untrusted_certificate = None;
+                        if let Err(err) = decline_certificate(&cert) {
+                            warn!("Unable to remember the declined certificate: {err}");
+                        }
+                    }
+                    if ui
+                        .button("Trust certificate")
+                        .on_hover_text("Windows asks for administrator rights")
+                        .clicked()
+                    {
+                        match trust_certificate(&cert) {
+                            Ok(()) => {
+                                self.untrusted_certificate = None;
+                                self.message = Some("Certificate trusted".to_string());
+                            }
+                            Err(err) => show_error(
+                                &mut self.error,
+                                format!("Unable to trust the certificate: {err}"),
+                            ),
+                        }
+                    }
+                });
+                ui.add_space(8.0);
+            }
+
+            if let Some(runtime) = self.missing_runtimes.first().copied() {
+                ui.label(format!(
+                    "The {} is missing, the game may not start.",
+                    runtime.name
+                ));
+                let installing = self.install_rx.is_some();
+                if ui
+                    .add_enabled(
+                        !installing,
+                        Button::new(format!("Install {}", runtime.name)),
+                    )
+                    .on_hover_text("Windows asks for administrator rights")
+                    .clicked()
+                {
+                    let (tx, install_rx) = channel();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(install(runtime));
+                    });
+                    self.install_rx = Some(install_rx);
+                }
+                if installing {
+                    ui.label("Installing...");
+                }
+                ui.add_space(8.0);
+            }
+
+            if self.running.is_none() && !self.game_processes.is_empty() {
+                ui.label(format!(
+                    "{} is still running, close it before playing.",
+                    self.game_processes.join(" and ")
+                ));
+                if ui.button("Close the game").clicked() {
+                    instance::kill_game();
+                    self.game_processes = instance::running_game_processes();
+                    if self.game_processes.is_empty() {
+                        let _ = remove_decrypted_loader();
+                    }
+                }
+                ui.add_space(8.0);
+            }
+
+            let updating = self.update_rx.is_some();
+            let verifying = self.verify_rx.is_some();
+            let can_play = !updating
+                && !verifying
+                && self.running.is_none()
+                && self.game_processes.is_empty()
+                && !self.settings.account_name.trim().is_empty();
+            if ui
+                .add_enabled(
+                    can_play,
+                    Button::new(RichText::new("▶ Play").size(18.0)).min_size([120.0, 32.0].into()),
+                )
+                .clicked()
+                && let Err(err) = self.play(ctx)
+            {
+                show_error(&mut self.error, format!("{err:#}"));
+            }
+            if self.running.is_some() {
+                ui.label("The game is running");
+            } else if updating {
+                ui.label("Updating the archive...");
+            } else if verifying {
+                ui.label("Checking the files...");
+            } else if !can_play && self.game_processes.is_empty() {
+                ui.label("Enter a name to play");
+            }
+
+            if let Some(release) = self.release.clone() {
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label(format!("Archiver {} is available", release.tag));
+                    if !release.url.is_empty() {
+                        ui.hyperlink_to("Release notes", &release.url);
+                    }
+                });
+                if release.patch_url.is_some() {
+                    let installing = self.patch_rx.is_some();
+                    if ui
+                        .add_enabled(
+                            !installing && self.running.is_none(),
+                            Button::new("Install patch"),
+                        )
+                        .clicked()
+                    {
+                        let (tx, patch_rx) = channel();
+                        std::thread::spawn(move || {
+                            let _ = tx.send(install_patch(&release));
+                        });
+                        self.patch_rx = Some(patch_rx);
+                    }
+                    if installing {
+                        ui.label("Installing the patch...");
+                    }
+                }
+            }
+
+            ui.add_space(8.0);
+            draw_mods(ui, self);
+            draw_tools(ui, self);
+
+            if let Some(message) = &self.message {
+                ui.label(message);
+            }
+            if let Some(error) = &self.error {
+                ui.label(RichText::new(error).color(Color32::RED));
+            }
+        });
+    }
+}
+
+/// Shows an error below the launcher and logs it.
+fn show_error(error: &mut Option<String>, message: String) {
+    error!("{message}");
+    *error = Some(message);
+}
+
+fn draw_mods(ui: &mut Ui, app: &mut LauncherApp) {
+    let Some(mods) = &mut app.mods else {
+        return;
+    };
+    egui::CollapsingHeader::new("Mods").show(ui, |ui| {
+        egui::ScrollArea::vertical()
+            .max_height(120.0)
+            .show(ui, |ui| {
+                for (index, (title, mut enabled)) in mods.list().into_iter().enumerate() {
+                    if ui.checkbox(&mut enabled, title).changed()
+                        && let Err(err) = mods.set_enabled(index, enabled)
+                    {
+                        show_error(&mut app.error, format!("{err:#}"));
+                    }
+                }
+            });
+    });
+}
+
+fn draw_tools(ui: &mut Ui, app: &mut LauncherApp) {
+    egui::CollapsingHeader::new("Tools")
+        .default_open(app.show_tools)
+        .show(ui, |ui| {
+            if ui
+                .button("Setup")
+                .on_hover_text("Change your name, language and lan server")
+                .clicked()
+            {
+                app.wizard = Some(Wizard::new(app.settings.clone()));
+            }
+
+            if ui
+                .checkbox(&mut app.check_releases, "Check for archiver updates")
+                .on_hover_text("Look for newer releases of the archiver this archive was created with on github")
+                .changed()
+            {
+                if let Err(err) = save_check_enabled(app.check_releases) {
+                    show_error(&mut app.error, format!("Unable to save the setting: {err:#}"));
+                }
+                if app.check_releases {
+                    app.check_for_release();
+                } else {
+                    app.release = None;
+                }
+            }
+
+            if ui
+                .checkbox(&mut app.verify, "Check the files before playing")
+                .on_hover_text("Catches archives damaged while copying them, but takes a moment")
+                .changed()
+                && let Err(err) = save_verify_before_play(app.verify)
+            {
+                show_error(&mut app.error, format!("Unable to save the setting: {err:#}"));
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Game arguments:");
+                ui.text_edit_singleline(&mut app.extra_args)
+                    .on_hover_text("Passed to the game when pressing Play, like -windowed");
+            });
+
+            if app.has_archived_profile
+                && ui
+                    .button("Import the archived game profile")
+                    .on_hover_text("Replace your game settings, hotkeys and single player saves with the ones in the archive")
+                    .clicked()
+            {
+                match restore_profile() {
+                    Ok(()) => {
+                        app.message = Some("Game profile imported".to_string());
+                        app.mods = Mods::load();
+                    }
+                    Err(err) => show_error(&mut app.error, format!("Unable to import the game profile: {err:#}")),
+                }
+            }
+
+            if ui
+                .button("Copy saves to Steam")
+                .on_hover_text("Copy your saves and recorded games into the archive and into the steam profile of this computer")
+                .clicked()
+            {
+                match export_saves() {
+                    Ok(SavesExport { to_archive, to_steam: Some(to_steam) }) => {
+                        app.message = Some(format!("Copied {to_archive} files into the archive and {to_steam} into your steam profile"));
+                    }
+                    Ok(SavesExport { to_archive, to_steam: None }) => {
+                        app.message = Some(format!("Copied {to_archive} files into the archive. Copy them into your steam profile with the archiver at home."));
+                    }
+                    Err(err) => show_error(&mut app.error, format!("Unable to copy the saves: {err:#}")),
+                }
+                app.has_archived_profile = has_archived_profile();
+            }
+
+            ui.horizontal(|ui| {
+                if ui
+                    .button("Import hotkeys")
+                    .on_hover_text("Use your .hkp hotkey profiles from another machine")
+                    .clicked()
+                    && let Some(files) = rfd::FileDialog::new()
+                        .add_filter("Hotkey profiles", &["hkp"])
+                        .pick_files()
+                {
+                    match import_hotkeys(&files) {
+                        Ok(count) => app.message = Some(format!("Imported {count} hotkey profiles")),
+                        Err(err) => show_error(&mut app.error, format!("{err:#}")),
+                    }
+                }
+                if ui
+                    .button("Export hotkeys")
+                    .on_hover_text("Copy your .hkp hotkey profiles to a folder, e.g. a usb stick")
+                    .clicked()
+                    && let Some(dest) = rfd::FileDialog::new().pick_folder()
+                {
+                    match export_hotkeys(&dest) {
+                        Ok(0) => app.message = Some("There are no hotkey profiles yet".to_string()),
+                        Ok(count) => app.message = Some(format!("Exported {count} hotkey profiles")),
+                        Err(err) => show_error(&mut app.error, format!("{err:#}")),
+                    }
+                }
+            });
+
+            if ui
+                .button("Create desktop and Start Menu shortcuts")
+                .clicked()
+            {
+                let result =
+                    std::env::current_dir().and_then(|dir| create_shortcuts(&dir, true, true));
+                match result {
+                    Ok(()) => app.message = Some("Shortcuts created".to_string()),
+                    Err(err) => show_error(&mut app.error, format!("Unable to create shortcuts: {err}")),
+                }
+            }
+
+            if let Some(steam_dir) = app.steam_dir.clone()
+                && ui
+                    .button("Add to Steam")
+                    .on_hover_text("Adds the game as a non-steam game. Close steam first.")
+                    .clicked()
+            {
+                let result = std::env::current_dir().and_then(|dir| add_to_steam(&steam_dir, &dir));
+                match result {
+                    Ok(0) => app.message = Some("The game is already in steam".to_string()),
+                    Ok(_) => {
+                        app.message =
+                            Some("Added to steam, it shows up after steam starts".to_string())
+                    }
+                    Err(err) => show_error(&mut app.error, format!("Unable to add the game to steam: {err}")),
+                }
+            }
+
+            if ui
+                .button("Write update manifest")
+                .on_hover_text("After changing this archive, lets the archives using it as their update source pull the changes")
+                .clicked()
+            {
+                match write_manifest() {
+                    Ok(sha256) => {
+                        info!("Wrote the update manifest, its SHA-256 is {sha256}");
+                        app.message = Some(format!("Update manifest written, pin it with manifest_sha256 = \"{sha256}\""));
+                    }
+                    Err(err) => show_error(&mut app.error, format!("Unable to write the update manifest: {err:#}")),
+                }
+            }
+        });
+}
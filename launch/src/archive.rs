@@ -0,0 +1,771 @@
+//! The files of the archive the launcher reads and writes. Paths are relative
+//! to the archive, which is the working directory.
+
+use anyhow::{Result, anyhow, bail};
+use std::{
+    fs::{read, read_to_string, remove_file, write},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+};
+
+use aes_gcm::{Aes256Gcm, KeyInit, aead::Aead, aes::cipher::Array};
+use common::{
+    GAME_PROFILE_DIR, GAME_PROFILES_PATH, KEY_FILE, KEY_LEN, LEGACY_KEY, NONCE_LEN,
+    profile::{newest_profile, sync_saves},
+    split_key_file,
+    update::{LAUNCH_CONFIG, METADATA_FILE},
+};
+use ini::Ini;
+use toml_edit::{DocumentMut, value};
+use tracing::{info, warn};
+
+pub const START_SCRIPT: &str = "launcher/start_age2.bat";
+const ENC_PATH: &str = "goldberg/steamclient_loader_x64.encrypted";
+const LOADER_PATH: &str = "goldberg/steamclient_loader_x64.exe";
+const COLD_CLIENT_CONFIG: &str = "goldberg/ColdClientLoader.ini";
+const USER_CONFIGS: &str = "goldberg/steam_settings/configs.user.ini";
+// Archives that replace steam_api64.dll keep goldberg's settings in the game folder.
+const STEAM_API_USER_CONFIGS: &str = "steam_settings/configs.user.ini";
+// The game folder of archives written before launch.ini named it.
+const DEFAULT_GAME_FOLDER: &str = "AoE2DE";
+const LAUNCHER_CONFIG: &str = "launcher/resources/config.age2.toml";
+// Steam's language names and the game's folders of text and voices for them.
+const LANGUAGE_RESOURCES: &[(&str, &str)] = &[
+    ("brazilian", "br"),
+    ("english", "en"),
+    ("french", "fr"),
+    ("german", "de"),
+    ("hindi", "hi"),
+    ("italian", "it"),
+    ("japanese", "jp"),
+    ("koreana", "ko"),
+    ("latam", "mx"),
+    ("malay", "ms"),
+    ("polish", "pl"),
+    ("russian", "ru"),
+    ("schinese", "zh"),
+    ("spanish", "es"),
+    ("tchinese", "tw"),
+    ("turkish", "tr"),
+    ("vietnamese", "vi"),
+];
+const SERVER_EXE: &str = "server/server.exe";
+// Written by genCert.exe when the archive was created, newest layout first.
+const CERTIFICATES: &[&str] = &[
+    "server/resources/certificates/cacert.pem",
+    "server/resources/certificates/cert.pem",
+];
+
+pub fn decrypt_launcher() -> Result<()> {
+    // Archives created without encryption ship the plain loader, and those
+    // replacing steam_api64.dll have no loader at all.
+    if Path::new(LOADER_PATH).exists() || !Path::new(ENC_PATH).exists() {
+        return Ok(());
+    }
+
+    info!("Decrypting {ENC_PATH}");
+    let key_file = match read(Path::new("goldberg").join(KEY_FILE)) {
+        Ok(key_file) => key_file,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            info!("No {KEY_FILE}, using the key of older archives");
+            [&LEGACY_KEY[..KEY_LEN], &[0; NONCE_LEN]].concat()
+        }
+        Err(err) => bail!("Unable to read {KEY_FILE}: {err}"),
+    };
+    let (key, nonce) = split_key_file(&key_file).ok_or_else(|| anyhow!("Malformed key file"))?;
+    let key = Array::try_from(key).map_err(|_| anyhow!("Malformed key file"))?;
+    let cipher = Aes256Gcm::new(&key);
+    let nonce = Array::try_from(nonce).map_err(|_| anyhow!("Malformed key file"))?;
+
+    let ciphertext = read(ENC_PATH).map_err(|e| anyhow!("Missing file {ENC_PATH}: {e}"))?;
+    let file = cipher
+        .decrypt(&nonce, &*ciphertext)
+        .map_err(|_| anyhow!("Unable to decrypt the loader, the archive is damaged"))?;
+    write(LOADER_PATH, file).map_err(|e| anyhow!("Unable to write {LOADER_PATH}: {e}"))?;
+    info!("Decrypted the loader to {LOADER_PATH}");
+    Ok(())
+}
+
+/// Removes the loader [`decrypt_launcher`] wrote once the game no longer
+/// needs it, so it's only on disk while playing. Archives shipping the plain
+/// loader keep theirs.
+pub fn remove_decrypted_loader() -> Result<()> {
+    if !Path::new(ENC_PATH).exists() {
+        return Ok(());
+    }
+    match remove_file(LOADER_PATH) {
+        Ok(()) => {
+            info!("Removed the decrypted {LOADER_PATH}");
+            Ok(())
+        }
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => {
+            warn!("Unable to remove the decrypted {LOADER_PATH}: {err}");
+            bail!("Unable to remove the decrypted {LOADER_PATH}: {err}")
+        }
+    }
+}
+
+/// Checks that the archive is ready to start without prompting or starting
+/// the game, for the archiver's test launch.
+pub fn dry_run() -> Result<()> {
+    let cold_client = Path::new(ENC_PATH).exists() || Path::new(LOADER_PATH).exists();
+    if cold_client {
+        decrypt_launcher()?;
+        if !Path::new(LOADER_PATH).exists() {
+            bail!("The loader was not decrypted to {LOADER_PATH}");
+        }
+        println!("Loader ready");
+    }
+
+    if !user_configs().exists() {
+        bail!("Missing goldberg's user config");
+    }
+    if !Path::new(START_SCRIPT).exists() {
+        bail!("Missing {START_SCRIPT}");
+    }
+
+    Ok(())
+}
+
+/// Decrypts the loader and starts the lan server launcher, capturing its
+/// error output.
+pub fn start_game() -> Result<Child> {
+    decrypt_launcher()?;
+    apply_extra_args()?;
+    if archived_profile().exists() && local_profile().is_some_and(|p| !p.exists()) {
+        info!("Restoring the archived game profile");
+        restore_profile()?;
+    }
+
+    let launcher_dir = Path::new(START_SCRIPT).parent().unwrap_or(Path::new("."));
+    let mut command = match launcher_command() {
+        Some(args) => {
+            let mut command = Command::new(launcher_dir.join(&args[0]));
+            command.args(&args[1..]);
+            command
+        }
+        None => {
+            let mut command = Command::new("cmd");
+            command
+                .arg("/C")
+                .arg(Path::new(START_SCRIPT).file_name().unwrap_or_default());
+            command
+        }
+    };
+    info!("Starting {command:?} in {}", launcher_dir.display());
+    command
+        .current_dir(launcher_dir)
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("Unable to start the lan server launcher: {e}"))
+}
+
+/// The command [`START_SCRIPT`] runs, to start the launcher without a batch
+/// file in between. `None` when the script does more than that.
+fn launcher_command() -> Option<Vec<String>> {
+    let script = read_to_string(START_SCRIPT).ok()?;
+    let commands: Vec<&str> = script
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            !line.is_empty()
+                && !line.starts_with(['@', ':'])
+                && !["rem", "cd ", "pause", "exit"]
+                    .iter()
+                    .any(|skip| lower.starts_with(skip))
+        })
+        .collect();
+    let [command] = commands[..] else {
+        return None;
+    };
+
+    let args: Vec<String> = split_command_line(command)
+        .into_iter()
+        .filter(|arg| arg != "%*")
+        // The script's folder is the working directory.
+        .map(|arg| arg.replace("%~dp0\\", "").replace("%~dp0", ""))
+        .collect();
+    args.first()
+        .is_some_and(|exe| exe.to_lowercase().ends_with(".exe"))
+        .then_some(args)
+}
+
+/// Splits at spaces outside of double quotes, dropping the quotes.
+fn split_command_line(line: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut arg = String::new();
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ' ' | '\t' if !quoted => {
+                if !arg.is_empty() {
+                    args.push(std::mem::take(&mut arg));
+                }
+            }
+            c => arg.push(c),
+        }
+    }
+    if !arg.is_empty() {
+        args.push(arg);
+    }
+    args
+}
+
+fn user_configs() -> PathBuf {
+    if Path::new(USER_CONFIGS).exists() {
+        PathBuf::from(USER_CONFIGS)
+    } else {
+        PathBuf::from(game_folder()).join(STEAM_API_USER_CONFIGS)
+    }
+}
+
+/// The player's settings in goldberg's user config. Empty values are left
+/// to goldberg's defaults.
+#[derive(Clone, Default)]
+pub struct UserSettings {
+    /// The name shown to other players, empty until the player picked one.
+    pub account_name: String,
+    pub language: String,
+    /// Two letter country code shown next to the name in lobbies.
+    pub country: String,
+}
+
+pub fn user_settings() -> Result<UserSettings> {
+    let conf = Ini::load_from_file(user_configs())?;
+    let get = |key| {
+        conf.get_from(Some("user::general"), key)
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+    Ok(UserSettings {
+        account_name: get("account_name"),
+        language: get("language"),
+        country: get("ip_country"),
+    })
+}
+
+pub fn save_user_settings(settings: &UserSettings) -> Result<()> {
+    let user_configs = user_configs();
+    let mut conf = Ini::load_from_file(&user_configs)?;
+    conf.with_section(Some("user::general"))
+        .set("account_name", settings.account_name.trim())
+        .set("language", settings.language.trim())
+        .set("ip_country", settings.country.trim().to_uppercase());
+    conf.write_to_file(&user_configs)?;
+    info!(
+        "Saved the name {:?}, language {:?} and country {:?} to {}",
+        settings.account_name.trim(),
+        settings.language.trim(),
+        settings.country.trim(),
+        user_configs.display()
+    );
+
+    let language = settings.language.trim();
+    if !language.is_empty() {
+        for profile in [local_profile(), Some(archived_profile())]
+            .into_iter()
+            .flatten()
+        {
+            set_profile_language(&profile, language)?;
+        }
+    }
+    Ok(())
+}
+
+/// Sets the language in the game's own settings of a profile, which would
+/// otherwise keep the one the profile was last played in. Only settings
+/// that already name a language are changed.
+fn set_profile_language(profile: &Path, language: &str) -> Result<()> {
+    let Ok(entries) = std::fs::read_dir(profile) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path
+            .extension()
+            .is_none_or(|ext| !ext.eq_ignore_ascii_case("ini"))
+        {
+            continue;
+        }
+        let Ok(text) = read_to_string(&path) else {
+            continue;
+        };
+        let mut changed = false;
+        let lines: Vec<String> = text
+            .lines()
+            .map(|line| match line.split_once('=') {
+                Some((key, old)) if key.trim().eq_ignore_ascii_case("language") => {
+                    changed |= old.trim() != language;
+                    format!("{}={language}", key.trim_end())
+                }
+                _ => line.to_string(),
+            })
+            .collect();
+        if changed {
+            write(&path, lines.join("\r\n") + "\r\n")
+                .map_err(|e| anyhow!("Unable to write {}: {e}", path.display()))?;
+            info!("Set the language {language:?} in {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// The picture goldberg shows for the player in lobbies.
+pub fn avatar_path() -> PathBuf {
+    user_configs().with_file_name("account_avatar.png")
+}
+
+/// Converts a picture to goldberg's avatar, cropped to a square of the size
+/// steam uses.
+pub fn save_avatar(picture: &Path) -> Result<()> {
+    const AVATAR_SIZE: u32 = 184;
+    let image =
+        image::open(picture).map_err(|e| anyhow!("Unable to open {}: {e}", picture.display()))?;
+    image
+        .resize_to_fill(
+            AVATAR_SIZE,
+            AVATAR_SIZE,
+            image::imageops::FilterType::Lanczos3,
+        )
+        .save(avatar_path())
+        .map_err(|e| anyhow!("Unable to save the picture: {e}"))?;
+    Ok(())
+}
+
+/// The game profile the archiver copied into goldberg's saves.
+fn archived_profile() -> PathBuf {
+    // steam_settings and saves share a folder.
+    let user_configs = user_configs();
+    let settings_root = user_configs
+        .parent()
+        .and_then(Path::parent)
+        .unwrap_or(Path::new("."));
+    settings_root.join("saves").join(GAME_PROFILE_DIR)
+}
+
+pub fn has_archived_profile() -> bool {
+    archived_profile().exists()
+}
+
+/// Where the game keeps the profile of the steam id goldberg plays as.
+fn local_profile() -> Option<PathBuf> {
+    let conf = Ini::load_from_file(user_configs()).ok()?;
+    let steam_id = conf
+        .get_from(Some("user::general"), "account_steamid")?
+        .trim();
+    let home = std::env::var_os("USERPROFILE")?;
+    (!steam_id.is_empty()).then(|| PathBuf::from(home).join(GAME_PROFILES_PATH).join(steam_id))
+}
+
+/// The profile the game plays with, or the archived one it starts from on
+/// machines that didn't play yet.
+pub fn active_profile() -> PathBuf {
+    local_profile()
+        .filter(|profile| profile.is_dir())
+        .unwrap_or_else(archived_profile)
+}
+
+/// Copies the archived game profile over the local one, so the archived game
+/// starts with the settings, hotkeys and saves of the archive's creator.
+pub fn restore_profile() -> Result<()> {
+    let Some(local) = local_profile() else {
+        bail!("The archive has no steam id to restore the profile for");
+    };
+    copy_dir(&archived_profile(), &local)
+}
+
+/// How many files [`export_saves`] copied where.
+pub struct SavesExport {
+    pub to_archive: usize,
+    /// None without a steam profile on this machine.
+    pub to_steam: Option<usize>,
+}
+
+/// Carries the progress made with the archive on this machine into the
+/// archive, from where the archiver copies it into the steam install at
+/// home, and into this machine's steam profile if it has one.
+pub fn export_saves() -> Result<SavesExport> {
+    let Some(local) = local_profile().filter(|p| p.is_dir()) else {
+        bail!("There are no saves yet, play the game first");
+    };
+    let to_archive = sync_saves(&local, &archived_profile())
+        .map_err(|e| anyhow!("Unable to copy the saves into the archive: {e}"))?;
+
+    let steam_id = local.file_name().and_then(|id| id.to_str());
+    let to_steam = match local
+        .parent()
+        .and_then(|profiles| newest_profile(profiles, steam_id))
+    {
+        Some(steam) => Some(
+            sync_saves(&local, &steam)
+                .map_err(|e| anyhow!("Unable to copy the saves to {}: {e}", steam.display()))?,
+        ),
+        None => None,
+    };
+
+    Ok(SavesExport {
+        to_archive,
+        to_steam,
+    })
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &dest)?;
+        } else {
+            std::fs::copy(entry.path(), &dest)
+                .map_err(|e| anyhow!("Failed to copy {}: {e}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// The release of the game in the archive, recorded by the archiver.
+pub fn game_version() -> Option<String> {
+    let metadata: serde_json::Value = serde_json::from_slice(&read(METADATA_FILE).ok()?).ok()?;
+    let version = metadata.get("game_version")?.as_str()?.trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+/// Where the archive pulls updates from, set by its creator.
+pub fn update_source() -> Option<String> {
+    let conf = Ini::load_from_file(LAUNCH_CONFIG).ok()?;
+    let source = conf.get_from(Some("update"), "source")?.trim();
+    (!source.is_empty()).then(|| source.to_string())
+}
+
+/// The SHA-256 of the update source's manifest, pinned by the archive's
+/// creator so executables are only taken from the copy they published.
+pub fn update_manifest_sha256() -> Option<String> {
+    let conf = Ini::load_from_file(LAUNCH_CONFIG).ok()?;
+    let hash = conf.get_from(Some("update"), "manifest_sha256")?.trim();
+    (!hash.is_empty()).then(|| hash.to_string())
+}
+
+/// Command line arguments the game is started with, like `-windowed`.
+pub fn extra_args() -> String {
+    Ini::load_from_file(LAUNCH_CONFIG)
+        .ok()
+        .and_then(|conf| {
+            conf.get_from(Some("game"), "extra_args")
+                .map(str::to_string)
+        })
+        .unwrap_or_default()
+}
+
+pub fn save_extra_args(args: &str) -> Result<()> {
+    let mut conf = Ini::load_from_file(LAUNCH_CONFIG).unwrap_or_default();
+    conf.with_section(Some("game"))
+        .set("extra_args", args.trim());
+    conf.write_to_file(LAUNCH_CONFIG)?;
+    Ok(())
+}
+
+/// Whether to check the archive's files before starting the game.
+pub fn verify_before_play() -> bool {
+    Ini::load_from_file(LAUNCH_CONFIG)
+        .ok()
+        .and_then(|conf| conf.get_from(Some("game"), "verify").map(|v| v == "true"))
+        .unwrap_or(false)
+}
+
+pub fn save_verify_before_play(verify: bool) -> Result<()> {
+    let mut conf = Ini::load_from_file(LAUNCH_CONFIG).unwrap_or_default();
+    conf.with_section(Some("game"))
+        .set("verify", verify.to_string());
+    conf.write_to_file(LAUNCH_CONFIG)?;
+    Ok(())
+}
+
+/// Hands the extra arguments to whatever starts the game: the cold client
+/// loader when there is one, the lan server launcher otherwise.
+fn apply_extra_args() -> Result<()> {
+    let args = extra_args();
+    if Path::new(COLD_CLIENT_CONFIG).exists() {
+        let mut conf = Ini::load_from_file(COLD_CLIENT_CONFIG)?;
+        conf.with_section(Some("SteamClient"))
+            .set("ExeCommandLine", args.trim());
+        conf.write_to_file(COLD_CLIENT_CONFIG)?;
+        info!("Set the game arguments {args:?} in {COLD_CLIENT_CONFIG}");
+        return Ok(());
+    }
+
+    let mut doc: DocumentMut = read_to_string(LAUNCHER_CONFIG)?
+        .parse()
+        .map_err(|e| anyhow!("Failed to parse {LAUNCHER_CONFIG}: {e}"))?;
+    let client = doc["Client"]
+        .as_table_like_mut()
+        .ok_or_else(|| anyhow!("Client in {LAUNCHER_CONFIG} is not a table"))?;
+    client.insert(
+        "ExecutableArgs",
+        value(toml_edit::Array::from_iter(args.split_whitespace())),
+    );
+    write(LAUNCHER_CONFIG, doc.to_string())?;
+    info!("Set the game arguments {args:?} in {LAUNCHER_CONFIG}");
+    Ok(())
+}
+
+/// The languages goldberg can report to the game, leaving out those the
+/// archived game has no text for.
+pub fn supported_languages() -> Vec<String> {
+    let path = user_configs().with_file_name("supported_languages.txt");
+    let resources = game_dir().join("resources");
+    read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .filter(|language| {
+            // Archives of unknown layouts keep every language.
+            !resources.is_dir()
+                || LANGUAGE_RESOURCES
+                    .iter()
+                    .find(|(name, _)| name == language)
+                    .is_none_or(|(_, dir)| resources.join(dir).is_dir())
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// The archived game's folder, which the lan server launcher starts it from.
+fn game_dir() -> PathBuf {
+    let path = read_to_string(LAUNCHER_CONFIG)
+        .ok()
+        .and_then(|config| config.parse::<DocumentMut>().ok())
+        .and_then(|doc| doc["Client"]["Path"].as_str().map(str::to_string))
+        .filter(|path| path != "auto");
+    match path {
+        Some(path) => Path::new("launcher").join(path),
+        None => PathBuf::from(game_folder()),
+    }
+}
+
+/// The archive's folder of the game, as named by the archiver.
+fn game_folder() -> String {
+    Ini::load_from_file(LAUNCH_CONFIG)
+        .ok()
+        .and_then(|conf| conf.get_from(Some("game"), "folder").map(str::to_string))
+        .map(|folder| folder.trim().to_string())
+        .filter(|folder| !folder.is_empty())
+        .unwrap_or_else(|| DEFAULT_GAME_FOLDER.to_string())
+}
+
+/// Whether the archive includes the lan server, for hosting it.
+pub fn has_server() -> bool {
+    Path::new(SERVER_EXE).exists()
+}
+
+/// Starts the archive's lan server in a console window of its own, which
+/// keeps it running after the launcher closes.
+pub fn start_server() -> Result<()> {
+    let server = Path::new(SERVER_EXE)
+        .canonicalize()
+        .map_err(|e| anyhow!("Missing {SERVER_EXE}: {e}"))?;
+    info!("Starting {SERVER_EXE}");
+    Command::new(&server)
+        .current_dir(server.parent().unwrap_or(Path::new(".")))
+        .spawn()
+        .map_err(|e| anyhow!("Unable to start {SERVER_EXE}: {e}"))?;
+    Ok(())
+}
+
+/// How the lan server launcher finds the lan server.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ServerMode {
+    /// Look for one on the lan and host one if there is none.
+    Auto,
+    /// Always host the server on this machine.
+    Host,
+    /// Connect to the server at a given address.
+    Join,
+}
+
+fn is_unset_host(host: &str) -> bool {
+    host.is_empty() || host == "0.0.0.0" || host == "auto"
+}
+
+/// The lan server the launcher connects to, None when it looks for one.
+pub fn server_host() -> Option<String> {
+    let doc: DocumentMut = read_to_string(LAUNCHER_CONFIG).ok()?.parse().ok()?;
+    let host = doc.get("Server")?.get("Host")?.as_str()?.trim();
+    (!is_unset_host(host)).then(|| host.to_string())
+}
+
+pub fn server_mode() -> ServerMode {
+    let start = read_to_string(LAUNCHER_CONFIG)
+        .ok()
+        .and_then(|config| config.parse::<DocumentMut>().ok())
+        .and_then(|doc| Some(doc.get("Server")?.get("Start")?.as_str()?.to_string()));
+    match start.as_deref() {
+        Some("true") => ServerMode::Host,
+        Some("false") if server_host().is_some() => ServerMode::Join,
+        _ => ServerMode::Auto,
+    }
+}
+
+/// Writes the server settings to the launcher's config, keeping its comments.
+pub fn save_server_settings(mode: ServerMode, host: &str) -> Result<()> {
+    let mut doc: DocumentMut = read_to_string(LAUNCHER_CONFIG)?
+        .parse()
+        .map_err(|e| anyhow!("Failed to parse {LAUNCHER_CONFIG}: {e}"))?;
+    let server = doc["Server"]
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .ok_or_else(|| anyhow!("Server in {LAUNCHER_CONFIG} is not a table"))?;
+
+    let (start, host) = match mode {
+        ServerMode::Auto => ("auto", "0.0.0.0"),
+        ServerMode::Host => ("true", "0.0.0.0"),
+        ServerMode::Join if is_unset_host(host.trim()) => {
+            bail!("Enter the address of the lan server")
+        }
+        ServerMode::Join => ("false", host.trim()),
+    };
+    server.insert("Start", value(start));
+    server.insert("Host", value(host));
+
+    write(LAUNCHER_CONFIG, doc.to_string())?;
+    info!("Set the lan server to Start = {start}, Host = {host} in {LAUNCHER_CONFIG}");
+    Ok(())
+}
+
+/// Restores the launcher's config when dropped, undoing a server set for
+/// a single launch.
+pub struct ServerOverride {
+    original: String,
+}
+
+impl Drop for ServerOverride {
+    fn drop(&mut self) {
+        match write(LAUNCHER_CONFIG, &self.original) {
+            Ok(()) => info!("Restored the lan server settings in {LAUNCHER_CONFIG}"),
+            Err(err) => warn!("Unable to restore {LAUNCHER_CONFIG}: {err}"),
+        }
+    }
+}
+
+/// Points the launcher at a lan server until the returned guard is dropped,
+/// leaving the player's own choice in the config afterwards.
+pub fn override_server(host: &str) -> Result<ServerOverride> {
+    let original = read_to_string(LAUNCHER_CONFIG)?;
+    save_server_settings(ServerMode::Join, host)?;
+    Ok(ServerOverride { original })
+}
+
+/// Whether the player trusted or declined an archive's certificate.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Trust {
+    Trusted,
+    Declined,
+}
+
+/// The lan server's certificate when the player wasn't asked about it for
+/// this archive yet. Remembered per machine, as archives are copied between
+/// them, and per archive, as each one brings its own certificate.
+pub fn untrusted_certificate() -> Option<PathBuf> {
+    let cert_path = CERTIFICATES.iter().map(Path::new).find(|p| p.exists())?;
+    let cert = read_to_string(cert_path).ok()?;
+    let decisions = read_to_string(trust_decisions_path()).unwrap_or_default();
+    let source = archive_source().ok()?;
+    trust_decision(&decisions, &source, &cert)
+        .is_none()
+        .then(|| cert_path.to_path_buf())
+}
+
+/// Remembers the player declined the certificate of this archive, so they
+/// aren't asked again until it changes.
+pub fn decline_certificate(cert_path: &Path) -> Result<()> {
+    let cert = read_to_string(cert_path)?;
+    record_trust(&cert, Trust::Declined)
+}
+
+/// Adds the lan server's certificate to the machine's trusted roots, so the
+/// game accepts the server without manual setup.
+pub fn trust_certificate(cert_path: &Path) -> Result<()> {
+    let cert = read_to_string(cert_path)?;
+    let cert_path = cert_path.canonicalize()?;
+    let status = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(format!(
+            "$p = Start-Process -FilePath certutil -ArgumentList '-addstore','Root',{} -Verb RunAs -Wait -PassThru -WindowStyle Hidden; exit $p.ExitCode",
+            common::powershell_quote(&format!("\"{}\"", cert_path.display()))
+        ))
+        .status()?;
+    if !status.success() {
+        bail!("certutil failed or was declined ({status})");
+    }
+
+    record_trust(&cert, Trust::Trusted)
+}
+
+/// The folder of the archive the certificate came with.
+fn archive_source() -> Result<String> {
+    Ok(std::env::current_dir()?
+        .canonicalize()?
+        .display()
+        .to_string())
+}
+
+/// Lines of `<trusted|declined> <certificate sha256> <archive folder>`, the
+/// latest decision about an archive's certificate last.
+fn trust_decision(decisions: &str, source: &str, cert: &str) -> Option<Trust> {
+    let fingerprint = common::sha256_hex(cert.trim().as_bytes());
+    decisions.lines().rev().find_map(|line| {
+        let mut fields = line.splitn(3, ' ');
+        let trust = match fields.next()? {
+            "trusted" => Trust::Trusted,
+            "declined" => Trust::Declined,
+            _ => return None,
+        };
+        (fields.next()? == fingerprint && fields.next()? == source).then_some(trust)
+    })
+}
+
+fn record_trust(cert: &str, trust: Trust) -> Result<()> {
+    let path = trust_decisions_path();
+    let decisions = read_to_string(&path).unwrap_or_default();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let trust = match trust {
+        Trust::Trusted => "trusted",
+        Trust::Declined => "declined",
+    };
+    let fingerprint = common::sha256_hex(cert.trim().as_bytes());
+    write(
+        &path,
+        format!("{decisions}{trust} {fingerprint} {}\n", archive_source()?),
+    )?;
+    Ok(())
+}
+
+fn trust_decisions_path() -> PathBuf {
+    std::env::var_os("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("aoe2-archiver")
+        .join("trusted_certificates.txt")
+}
+
+/// Where the steam client is installed, read with reg.exe as this launcher
+/// has no registry bindings.
+pub fn steam_dir() -> Option<PathBuf> {
+    let output = Command::new("reg")
+        .args(["query", "HKCU\\Software\\Valve\\Steam", "/v", "SteamPath"])
+        .output()
+        .ok()?;
+    // "    SteamPath    REG_SZ    c:/program files (x86)/steam"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.lines().find(|l| l.contains("SteamPath"))?;
+    let (_, path) = line.split_once("REG_SZ")?;
+    Some(PathBuf::from(path.trim())).filter(|p| p.exists())
+}
@@ -0,0 +1,110 @@
+//! Explains why the game didn't start, instead of a console flashing by.
+
+use std::{
+    fs,
+    io::Write,
+    path::Path,
+    process::ExitStatus,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub const ERROR_LOG: &str = "launch_errors.log";
+// Where the lan server launcher and server write their logs.
+const LOG_DIRS: &[&str] = &["launcher", "server"];
+const ERROR_WORDS: &[&str] = &["error", "fail", "panic", "fatal", "denied", "not found"];
+const MAX_LOG_LINES: usize = 50;
+
+pub struct Crash {
+    /// The line most likely explaining the failure.
+    pub summary: String,
+    pub details: String,
+}
+
+impl Crash {
+    /// Gathers the exit code, the launcher's error output and the logs written
+    /// since the game was started.
+    pub fn collect(status: ExitStatus, stderr: &str, started: SystemTime) -> Self {
+        let mut details = format!("The lan server launcher exited with {status}\n");
+        let mut error_lines: Vec<String> = vec![];
+
+        if !stderr.trim().is_empty() {
+            details.push_str("\n== error output\n");
+            details.push_str(stderr.trim_end());
+            details.push('\n');
+            error_lines.extend(
+                stderr
+                    .lines()
+                    .filter(|l| !l.trim().is_empty())
+                    .map(str::to_string),
+            );
+        }
+
+        let mut logs = vec![];
+        for dir in LOG_DIRS {
+            find_logs(Path::new(dir), started, &mut logs);
+        }
+        for log in logs {
+            let Ok(contents) = fs::read_to_string(&log) else {
+                continue;
+            };
+            let lines: Vec<&str> = contents.lines().collect();
+            let tail = &lines[lines.len().saturating_sub(MAX_LOG_LINES)..];
+            details.push_str(&format!("\n== {}\n{}\n", log.display(), tail.join("\n")));
+            error_lines.extend(tail.iter().filter(|l| is_error(l)).map(|l| l.to_string()));
+        }
+
+        let summary = error_lines
+            .iter()
+            .rev()
+            .find(|l| is_error(l))
+            .or(error_lines.last())
+            .map(|l| l.trim().to_string())
+            .unwrap_or_else(|| format!("The lan server launcher exited with {status}"));
+
+        Self { summary, details }
+    }
+
+    /// Appends the details to [`ERROR_LOG`] in the archive.
+    pub fn write_log(&self) -> std::io::Result<()> {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut log = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(ERROR_LOG)?;
+        writeln!(log, "==== {seconds} (unix time)\n{}", self.details)
+    }
+}
+
+fn is_error(line: &str) -> bool {
+    let line = line.to_lowercase();
+    ERROR_WORDS.iter().any(|w| line.contains(w))
+}
+
+fn find_logs(dir: &Path, since: SystemTime, logs: &mut Vec<std::path::PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_logs(&path, since, logs);
+            continue;
+        }
+        let is_log = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("log") || e.eq_ignore_ascii_case("txt"))
+            && path
+                .components()
+                .any(|c| c.as_os_str().eq_ignore_ascii_case("logs"))
+            || path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("log"));
+        let modified = entry.metadata().and_then(|m| m.modified());
+        if is_log && modified.is_ok_and(|m| m >= since) {
+            logs.push(path);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! The game's hotkey profiles, `.hkp` files in the game profile, carried
+//! between machines as plain files.
+
+use crate::archive::active_profile;
+use anyhow::{Result, anyhow};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+const HOTKEY_EXTENSION: &str = "hkp";
+
+/// Copies the hotkey profiles into `dest`. Returns how many were copied.
+pub fn export_hotkeys(dest: &Path) -> Result<usize> {
+    let files = hotkey_files(&active_profile());
+    for file in &files {
+        let name = file.file_name().unwrap_or_default();
+        fs::copy(file, dest.join(name))
+            .map_err(|e| anyhow!("Unable to export {}: {e}", file.display()))?;
+    }
+    info!(
+        "Exported {} hotkey profiles to {}",
+        files.len(),
+        dest.display()
+    );
+    Ok(files.len())
+}
+
+/// Copies hotkey profiles into the game profile, replacing those of the
+/// same name wherever the game keeps them.
+pub fn import_hotkeys(files: &[PathBuf]) -> Result<usize> {
+    let profile = active_profile();
+    let existing = hotkey_files(&profile);
+    for file in files {
+        let name = file.file_name().unwrap_or_default();
+        let dest = existing
+            .iter()
+            .find(|existing| existing.file_name() == Some(name))
+            .cloned()
+            .unwrap_or_else(|| profile.join("profile").join(name));
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(file, &dest).map_err(|e| anyhow!("Unable to import {}: {e}", file.display()))?;
+        info!("Imported {} to {}", file.display(), dest.display());
+    }
+    Ok(files.len())
+}
+
+fn hotkey_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut files = vec![];
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(hotkey_files(&path));
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(HOTKEY_EXTENSION))
+        {
+            files.push(path);
+        }
+    }
+    files
+}
@@ -0,0 +1,149 @@
+//! Keeps a second launcher from starting the game over a running one, which
+//! corrupts goldberg's saves.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    process::Command,
+};
+use tracing::info;
+
+/// Holds the process id of the running launcher.
+const LOCK_FILE: &str = "launch.lock";
+const GAME_PROCESSES: &[&str] = &["AoE2DE_s.exe", "steamclient_loader_x64.exe"];
+
+/// Removes the lock file when the launcher exits.
+pub struct InstanceLock;
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(LOCK_FILE);
+    }
+}
+
+/// Takes the lock, failing while another launcher holds it. Creating the
+/// file fails if it exists, so of two launchers started at once only one
+/// gets it.
+pub fn lock() -> std::io::Result<InstanceLock> {
+    let mut file = match OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(LOCK_FILE)
+    {
+        // A crashed launcher's lock is removed by other_instance.
+        Err(err) if err.kind() == ErrorKind::AlreadyExists && other_instance().is_none() => {
+            OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(LOCK_FILE)?
+        }
+        result => result?,
+    };
+    let lock = InstanceLock;
+    file.write_all(std::process::id().to_string().as_bytes())?;
+    Ok(lock)
+}
+
+/// The process id of another launcher running from this archive. Removes
+/// the lock file of one that crashed.
+pub fn other_instance() -> Option<u32> {
+    let pid: u32 = fs::read_to_string(LOCK_FILE).ok()?.trim().parse().ok()?;
+    if pid != std::process::id() && is_launcher(pid) {
+        return Some(pid);
+    }
+    info!("Removing the stale lock of launcher process {pid}");
+    let _ = fs::remove_file(LOCK_FILE);
+    None
+}
+
+/// Whether `pid` is still a launcher, not a process that reused its id.
+fn is_launcher(pid: u32) -> bool {
+    let Some(exe) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.file_name()?.to_string_lossy().to_lowercase()))
+    else {
+        return false;
+    };
+    tasklist(&format!("PID eq {pid}"))
+        .to_lowercase()
+        .contains(&format!("\"{exe}\""))
+}
+
+/// The game's processes started from this archive that are running, e.g.
+/// left over from a crash.
+pub fn running_game_processes() -> Vec<&'static str> {
+    let running = archive_game_processes();
+    GAME_PROCESSES
+        .iter()
+        .copied()
+        .filter(|process| running.iter().any(|(_, name)| name == process))
+        .collect()
+}
+
+/// The process ids and executables of the game's processes started from
+/// this archive. Copies of the game elsewhere on the machine are left out.
+fn archive_game_processes() -> Vec<(u32, &'static str)> {
+    let Ok(archive) = std::env::current_dir() else {
+        return vec![];
+    };
+    let names = GAME_PROCESSES
+        .iter()
+        .map(|process| process.trim_end_matches(".exe"))
+        .collect::<Vec<_>>()
+        .join(",");
+    let archive = format!("{}\\", archive.display()).replace('\'', "''");
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(format!(
+            "Get-Process -Name {names} -ErrorAction SilentlyContinue | \
+             Where-Object {{ $_.Path -and $_.Path.StartsWith('{archive}', 'OrdinalIgnoreCase') }} | \
+             ForEach-Object {{ \"$($_.Id) $($_.Name).exe\" }}"
+        ))
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .unwrap_or_default();
+    output
+        .lines()
+        .filter_map(|line| {
+            let (pid, name) = line.trim().split_once(' ')?;
+            let process = GAME_PROCESSES
+                .iter()
+                .find(|process| process.eq_ignore_ascii_case(name))?;
+            Some((pid.parse().ok()?, *process))
+        })
+        .collect()
+}
+
+fn tasklist(filter: &str) -> String {
+    Command::new("tasklist")
+        .args(["/FI", filter, "/NH", "/FO", "CSV"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .unwrap_or_default()
+}
+
+/// Brings the other launcher's window to the front.
+pub fn focus(pid: u32) {
+    let _ = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(format!(
+            "(New-Object -ComObject WScript.Shell).AppActivate({pid})"
+        ))
+        .status();
+}
+
+pub fn kill(pid: u32) {
+    info!("Closing launcher process {pid}");
+    let _ = Command::new("taskkill")
+        .args(["/F", "/PID", &pid.to_string()])
+        .status();
+}
+
+pub fn kill_game() {
+    for (pid, process) in archive_game_processes() {
+        info!("Closing {process} ({pid})");
+        let _ = Command::new("taskkill")
+            .args(["/F", "/PID", &pid.to_string()])
+            .status();
+    }
+}
@@ -0,0 +1,36 @@
+//! Logs what the launcher did, for debugging archives that don't start on
+//! someone else's machine.
+
+use std::{
+    fs::{self, OpenOptions},
+    path::Path,
+    sync::Mutex,
+};
+
+const LOG_FILE: &str = "goldberg/launch.log";
+// The previous log, replaced each time the log outgrows MAX_LOG_SIZE.
+const OLD_LOG_FILE: &str = "goldberg/launch.1.log";
+const MAX_LOG_SIZE: u64 = 1024 * 1024;
+
+/// Appends to [`LOG_FILE`], or to a log next to the launcher in archives
+/// without a goldberg folder.
+pub fn init() {
+    let (path, old_path) = if Path::new("goldberg").is_dir() {
+        (LOG_FILE, OLD_LOG_FILE)
+    } else {
+        ("launch.log", "launch.1.log")
+    };
+    if fs::metadata(path).is_ok_and(|m| m.len() > MAX_LOG_SIZE) {
+        let _ = fs::rename(path, old_path);
+    }
+    let Ok(file) = OpenOptions::new().create(true).append(true).open(path) else {
+        return;
+    };
+
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_target(false)
+        .with_ansi(false)
+        .with_writer(Mutex::new(file))
+        .try_init();
+}
@@ -1,59 +1,99 @@
-use anyhow::Result;
-use std::{
-    fs::{read, write},
-    path::Path,
-    process::Command,
-};
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+
+mod app;
+mod archive;
+mod crash;
+mod hotkeys;
+mod instance;
+mod log;
+mod mods;
+mod releases;
+mod runtimes;
+mod update;
+mod wizard;
 
-use aes_gcm::{Aes256Gcm, KeyInit, aead::Aead, aes::cipher::Array};
-use common::KEY;
+use common::{
+    DRY_RUN_ARG,
+    shortcuts::{REMOVE_SHORTCUTS_ARG, SHORTCUTS_EXE},
+    steam_shortcuts::remove_from_steam,
+};
+use std::path::Path;
 
-const ENC_PATH: &str = "goldberg/steamclient_loader_x64.encrypted";
-const LOADER_PATH: &str = "goldberg/steamclient_loader_x64.exe";
-const USER_CONFIGS: &str = "goldberg/steam_settings/configs.user.ini";
+/// Checks the archive's files against its manifest without starting the game.
+const VERIFY_ARG: &str = "--verify";
 
 fn main() {
-    let _ = ensure_name();
-    let _ = decrypt_launcher();
+    // The archive's paths are relative to it, wherever the launcher is
+    // started from.
+    if let Some(archive) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        let _ = std::env::set_current_dir(archive);
+    }
+    log::init();
+    tracing::info!("Starting launcher.exe {}", env!("CARGO_PKG_VERSION"));
 
-    Command::new("launcher/start_age2.bat").status().unwrap();
-}
+    if std::env::args().any(|arg| arg == VERIFY_ARG) {
+        common::console::attach_parent();
+        match update::verify() {
+            Ok(problems) if problems.is_empty() => println!("All files are intact."),
+            Ok(problems) => {
+                for problem in problems {
+                    eprintln!("{problem}");
+                }
+                std::process::exit(1);
+            }
+            Err(err) => {
+                eprintln!("{err:#}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
 
-fn decrypt_launcher() -> Result<()> {
-    if Path::new(LOADER_PATH).exists() {
-        return Ok(());
+    if std::env::args().any(|arg| arg == DRY_RUN_ARG) {
+        common::console::attach_parent();
+        match archive::dry_run() {
+            Ok(()) => println!("Dry run passed."),
+            Err(err) => {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
+        }
+        return;
     }
 
-    let key = Array::try_from(&KEY[..32]).expect("Key is 32 bytes");
-    let cipher = Aes256Gcm::new(&key);
-    let nonce = Array::try_from([0; 12]).expect("Nonce is 12 bytes");
+    if std::env::args().any(|arg| arg == REMOVE_SHORTCUTS_ARG) {
+        let Some(steam_dir) = archive::steam_dir() else {
+            return;
+        };
+        match std::env::current_dir().and_then(|dir| remove_from_steam(&steam_dir, &dir)) {
+            Ok(removed) => tracing::info!("Removed the archive from {removed} steam users"),
+            Err(err) => {
+                tracing::error!("Unable to remove the archive from steam: {err}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
 
-    let ciphertext = read(ENC_PATH).expect("Missing file: {LOADER_PATH}");
-    let file = cipher
-        .decrypt(&nonce, &*ciphertext)
-        .expect("Decryption failure");
-    write(LOADER_PATH, file).expect("Unable to write file: {LOADER_PATH}");
-    Ok(())
+    // Without a console, a window failing to open would go unnoticed.
+    if let Err(err) = app::run(is_shortcuts_helper()) {
+        tracing::error!("{err}");
+        rfd::MessageDialog::new()
+            .set_level(rfd::MessageLevel::Error)
+            .set_title("Age of Empires II DE")
+            .set_description(format!("Unable to open the launcher: {err}"))
+            .show();
+    }
 }
 
-fn ensure_name() -> Result<()> {
-    use ini::Ini;
-    let mut conf = Ini::load_from_file(USER_CONFIGS)?;
-
-    let user_settings = conf.with_section(Some("user::general"));
-    let username = user_settings.get("account_name");
-    if username.is_some_and(|u| !u.trim().is_empty()) {
-        return Ok(());
-    };
-
-    println!("Enter your desired username:");
-    let mut username = String::new();
-    std::io::stdin().read_line(&mut username)?;
-
-    conf.with_section(Some("user::general"))
-        .set("account_name", username.trim());
-
-    conf.write_to_file(USER_CONFIGS)?;
-
-    Ok(())
+/// The archive ships a copy of this exe named [`SHORTCUTS_EXE`] that opens
+/// with the shortcut tools.
+fn is_shortcuts_helper() -> bool {
+    std::env::current_exe().is_ok_and(|exe| {
+        exe.file_name()
+            .is_some_and(|name| name.eq_ignore_ascii_case(SHORTCUTS_EXE))
+    })
 }
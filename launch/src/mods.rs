@@ -0,0 +1,50 @@
+//! The game's local and workshop mods, which it lists with their state in
+//! `mods/mod-status.json` of the profile.
+
+use crate::archive::active_profile;
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+use std::{fs, path::PathBuf};
+
+const MOD_STATUS: &str = "mods/mod-status.json";
+
+pub struct Mods {
+    path: PathBuf,
+    status: Value,
+}
+
+impl Mods {
+    pub fn load() -> Option<Self> {
+        let path = active_profile().join(MOD_STATUS);
+        let status = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+        let mods = Self { path, status };
+        (!mods.list().is_empty()).then_some(mods)
+    }
+
+    /// The title and state of each mod, in the game's order.
+    pub fn list(&self) -> Vec<(String, bool)> {
+        let Some(mods) = self.status["Mods"].as_array() else {
+            return vec![];
+        };
+        mods.iter()
+            .map(|m| {
+                let title = m["Title"]
+                    .as_str()
+                    .filter(|t| !t.is_empty())
+                    .or(m["Path"].as_str())
+                    .unwrap_or("Unnamed mod");
+                (title.to_string(), m["Enabled"].as_bool().unwrap_or(false))
+            })
+            .collect()
+    }
+
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) -> Result<()> {
+        let entry = self.status["Mods"]
+            .get_mut(index)
+            .ok_or_else(|| anyhow!("No mod {index} in {}", self.path.display()))?;
+        entry["Enabled"] = Value::Bool(enabled);
+        fs::write(&self.path, serde_json::to_string_pretty(&self.status)?)
+            .map_err(|e| anyhow!("Unable to write {}: {e}", self.path.display()))?;
+        Ok(())
+    }
+}
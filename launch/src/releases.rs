@@ -0,0 +1,125 @@
+//! Newer releases of the archiver the archive was created with, and the
+//! small patch bundles published with them.
+
+use anyhow::{Result, anyhow, bail};
+use common::update::{LAUNCH_CONFIG, parse_version};
+use ini::Ini;
+use serde_json::Value;
+use std::{fs, io::Cursor, time::Duration};
+use tracing::info;
+
+// Bigger bundles are left to the release page.
+const MAX_PATCH_SIZE: u64 = 20 * 1024 * 1024;
+
+#[derive(Clone)]
+pub struct Release {
+    pub tag: String,
+    pub url: String,
+    /// Download url of a patch bundle small enough to install from here.
+    pub patch_url: Option<String>,
+}
+
+/// Whether the player wants to hear about archiver releases.
+pub fn check_enabled() -> bool {
+    release_setting("check_releases").is_some_and(|v| v == "true")
+}
+
+pub fn save_check_enabled(enabled: bool) -> Result<()> {
+    let mut conf = Ini::load_from_file(LAUNCH_CONFIG).unwrap_or_default();
+    conf.with_section(Some("update"))
+        .set("check_releases", enabled.to_string());
+    conf.write_to_file(LAUNCH_CONFIG)?;
+    Ok(())
+}
+
+fn release_setting(key: &str) -> Option<String> {
+    let conf = Ini::load_from_file(LAUNCH_CONFIG).ok()?;
+    let value = conf.get_from(Some("update"), key)?.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn client() -> Result<reqwest::blocking::Client> {
+    Ok(reqwest::blocking::Client::builder()
+        .user_agent("aoe2-archive-launcher")
+        .timeout(Duration::from_secs(30))
+        .build()?)
+}
+
+/// The latest release of the archiver when it is newer than the one that
+/// created this archive.
+pub fn newer_release() -> Result<Option<Release>> {
+    let Some(repo) = release_setting("archiver_repo") else {
+        return Ok(None);
+    };
+    let current = release_setting("archiver_version").unwrap_or_default();
+
+    let url = format!("https://api.github.com/repos/{repo}/releases/latest");
+    let release: Value = client()?.get(&url).send()?.error_for_status()?.json()?;
+    let tag = release["tag_name"]
+        .as_str()
+        .ok_or_else(|| anyhow!("The latest release of {repo} has no tag"))?;
+    info!("The latest archiver release is {tag}, this archive was created with {current}");
+    if parse_version(tag) <= parse_version(&current) {
+        return Ok(None);
+    }
+
+    let patch_url = release["assets"].as_array().and_then(|assets| {
+        assets
+            .iter()
+            .filter(|asset| {
+                asset["name"].as_str().is_some_and(|name| {
+                    let name = name.to_lowercase();
+                    name.contains("patch") && name.ends_with(".zip")
+                })
+            })
+            .find(|asset| {
+                asset["size"]
+                    .as_u64()
+                    .is_some_and(|size| size <= MAX_PATCH_SIZE)
+            })
+            .and_then(|asset| asset["browser_download_url"].as_str())
+            .map(str::to_string)
+    });
+
+    Ok(Some(Release {
+        tag: tag.to_string(),
+        url: release["html_url"].as_str().unwrap_or_default().to_string(),
+        patch_url,
+    }))
+}
+
+/// Extracts a patch bundle over the archive. Returns how many files changed.
+pub fn install_patch(release: &Release) -> Result<usize> {
+    let Some(url) = &release.patch_url else {
+        bail!("{} has no patch bundle", release.tag);
+    };
+    info!("Installing the patch bundle {url}");
+    let data = client()?.get(url).send()?.error_for_status()?.bytes()?;
+    let mut zip = zip::ZipArchive::new(Cursor::new(data))?;
+
+    let current_exe = std::env::current_exe()?;
+    let mut installed = 0;
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i)?;
+        // Skips paths leaving the archive.
+        let Some(path) = file.enclosed_name() else {
+            continue;
+        };
+        if file.is_dir() {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // A running exe can't be replaced, but it can be moved out of the way.
+        if current_exe.file_name() == path.file_name() {
+            let _ = fs::rename(&path, path.with_extension("old"));
+        }
+        let mut out = fs::File::create(&path)
+            .map_err(|e| anyhow!("Unable to write {}: {e}", path.display()))?;
+        std::io::copy(&mut file, &mut out)?;
+        installed += 1;
+    }
+    info!("Installed {installed} files of {}", release.tag);
+    Ok(installed)
+}
@@ -0,0 +1,70 @@
+use anyhow::{Result, anyhow, bail};
+use common::runtimes::{RUNTIMES, Runtime};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+// The vc redist's "a newer version is installed" and "restart required".
+const INSTALLED_EXIT_CODES: &[i32] = &[0, 1638, 3010];
+
+/// The runtimes the archive bundles an installer for that this machine lacks.
+pub fn missing_runtimes() -> Vec<&'static Runtime> {
+    let Some(system32) =
+        std::env::var_os("SystemRoot").map(|root| PathBuf::from(root).join("System32"))
+    else {
+        return vec![];
+    };
+    RUNTIMES
+        .iter()
+        .filter(|runtime| Path::new(runtime.installer).exists())
+        .filter(|runtime| runtime.dlls.iter().any(|dll| !system32.join(dll).exists()))
+        .collect()
+}
+
+/// Runs the bundled installer of a runtime, which asks for administrator
+/// rights.
+pub fn install(runtime: &Runtime) -> Result<()> {
+    let installer = Path::new(runtime.installer)
+        .canonicalize()
+        .map_err(|e| anyhow!("Missing {}: {e}", runtime.installer))?;
+    let setup = match runtime.setup {
+        Some(setup) => {
+            let dir = std::env::temp_dir().join("aoe2-archiver-redist");
+            let status = Command::new(&installer)
+                .arg("/Q")
+                .arg(format!("/T:{}", dir.display()))
+                .status()
+                .map_err(|e| anyhow!("Unable to start {}: {e}", runtime.installer))?;
+            if !status.success() {
+                bail!("Unable to extract {} ({status})", runtime.installer);
+            }
+            dir.join(setup)
+        }
+        None => installer,
+    };
+
+    let args = runtime
+        .args
+        .iter()
+        .map(|arg| common::powershell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(",");
+    let status = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(format!(
+            "$p = Start-Process -FilePath {} -ArgumentList {args} -Verb RunAs -Wait -PassThru; exit $p.ExitCode",
+            common::powershell_quote(&setup.display().to_string())
+        ))
+        .status()?;
+    if !status
+        .code()
+        .is_some_and(|code| INSTALLED_EXIT_CODES.contains(&code))
+    {
+        bail!(
+            "The {} installer failed or was declined ({status})",
+            runtime.name
+        );
+    }
+    Ok(())
+}
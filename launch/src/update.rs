@@ -0,0 +1,137 @@
+use anyhow::{Result, anyhow, bail};
+use common::{
+    sha256_hex,
+    update::{GAME_MANIFEST, UPDATE_MANIFEST, manifest, parse_game_manifest, parse_manifest},
+};
+use std::{collections::BTreeMap, fs, path::Path, time::Duration};
+use tracing::{info, warn};
+
+// Files that can't run code, taken from any source. Everything else, like
+// goldberg's encrypted loader and its key, is only taken from a source whose
+// manifest is pinned.
+const DATA_EXTENSIONS: &[&str] = &["txt", "md", "json", "ini", "toml", "png", "jpg", "ico"];
+
+/// Pulls the files that changed in the archive's update source. Returns how
+/// many were replaced. Anything but data files is only replaced when the
+/// source's manifest matches `pinned_sha256`, the hash the archive's creator
+/// set.
+pub fn update_from(source: &str, pinned_sha256: Option<&str>) -> Result<usize> {
+    if source.starts_with("http://") {
+        bail!("Refusing to update from {source}, update sources on the web have to use https");
+    }
+    let client = reqwest::blocking::Client::builder()
+        .https_only(true)
+        .timeout(Duration::from_secs(30))
+        .build()?;
+    let fetch = |path: &str| fetch(&client, source, path);
+
+    let remote = String::from_utf8(fetch(UPDATE_MANIFEST)?)
+        .map_err(|_| anyhow!("{UPDATE_MANIFEST} of the update source is not text"))?;
+    let pinned = match pinned_sha256.map(|pin| pin.trim().to_lowercase()) {
+        Some(pin) if sha256_hex(remote.as_bytes()) != pin => bail!(
+            "{UPDATE_MANIFEST} of {source} doesn't match the pinned hash, not updating from it"
+        ),
+        Some(_) => true,
+        None => false,
+    };
+    let current_exe = std::env::current_exe()?;
+
+    // What the archive is at after the update, for later checks.
+    let mut updated_manifest: BTreeMap<String, String> = fs::read_to_string(UPDATE_MANIFEST)
+        .map(|local| {
+            parse_manifest(&local)
+                .into_iter()
+                .map(|(hash, path)| (path.to_string(), hash.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut updated = 0;
+    for (hash, path) in parse_manifest(&remote) {
+        let local = Path::new(path);
+        if fs::read(local).is_ok_and(|data| sha256_hex(&data) == hash) {
+            updated_manifest.insert(path.to_string(), hash.to_string());
+            continue;
+        }
+        if !pinned && is_executable(local) {
+            warn!("Not updating {path} from {source}, the update source has no pinned hash");
+            continue;
+        }
+
+        let data = fetch(path)?;
+        if sha256_hex(&data) != hash {
+            bail!("{path} in the update source doesn't match its manifest");
+        }
+        if let Some(parent) = local.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // A running exe can't be replaced, but it can be moved out of the way.
+        if current_exe.file_name() == local.file_name() {
+            let _ = fs::rename(local, local.with_extension("old"));
+        }
+        fs::write(local, data).map_err(|e| anyhow!("Unable to update {path}: {e}"))?;
+        info!("Updated {path} from {source}");
+        updated_manifest.insert(path.to_string(), hash.to_string());
+        updated += 1;
+    }
+    // Later checks compare the archive against what it was updated to.
+    let updated_manifest: String = updated_manifest
+        .iter()
+        .map(|(path, hash)| format!("{hash} {path}\n"))
+        .collect();
+    fs::write(UPDATE_MANIFEST, updated_manifest)?;
+
+    Ok(updated)
+}
+
+fn is_executable(path: &Path) -> bool {
+    !path.extension().is_some_and(|ext| {
+        DATA_EXTENSIONS
+            .iter()
+            .any(|data| ext.eq_ignore_ascii_case(data))
+    })
+}
+
+fn fetch(client: &reqwest::blocking::Client, source: &str, path: &str) -> Result<Vec<u8>> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let url = format!("{}/{path}", source.trim_end_matches('/'));
+        let response = client.get(&url).send()?.error_for_status()?;
+        Ok(response.bytes()?.to_vec())
+    } else {
+        let file = Path::new(source).join(path);
+        fs::read(&file).map_err(|e| anyhow!("Unable to read {}: {e}", file.display()))
+    }
+}
+
+/// Checks the archive's files against its manifest, and the game files
+/// against their sizes, describing each missing or damaged one.
+pub fn verify() -> Result<Vec<String>> {
+    let manifest = fs::read_to_string(UPDATE_MANIFEST)
+        .map_err(|e| anyhow!("Unable to read {UPDATE_MANIFEST}: {e}"))?;
+    let mut problems = vec![];
+    for (hash, path) in parse_manifest(&manifest) {
+        match fs::read(path) {
+            Ok(data) if sha256_hex(&data) == hash => {}
+            Ok(_) => problems.push(format!("{path} is damaged")),
+            Err(_) => problems.push(format!("{path} is missing")),
+        }
+    }
+
+    // Archives created before the game files were listed only have the above.
+    let game_manifest = fs::read_to_string(GAME_MANIFEST).unwrap_or_default();
+    for (size, path) in parse_game_manifest(&game_manifest) {
+        match fs::metadata(path) {
+            Ok(metadata) if metadata.len() == size => {}
+            Ok(_) => problems.push(format!("{path} is damaged")),
+            Err(_) => problems.push(format!("{path} is missing")),
+        }
+    }
+    Ok(problems)
+}
+
+/// Lists this archive's files, for archives using it as their update source.
+/// Returns the manifest's SHA-256, for them to pin.
+pub fn write_manifest() -> Result<String> {
+    let manifest = manifest(Path::new("."))?;
+    fs::write(UPDATE_MANIFEST, &manifest)?;
+    Ok(sha256_hex(manifest.as_bytes()))
+}
@@ -0,0 +1,179 @@
+use crate::archive::{
+    ServerMode, UserSettings, avatar_path, save_avatar, save_server_settings, save_user_settings,
+    server_host, server_mode, supported_languages,
+};
+use anyhow::{Result, bail};
+use eframe::egui::{self, Color32, RichText, Ui};
+use std::path::PathBuf;
+
+const PAGES: usize = 3;
+
+/// Walks the player through the settings the archive needs on first start,
+/// saving them all once they are done.
+pub struct Wizard {
+    page: usize,
+    settings: UserSettings,
+    /// A picture the player picked to replace their avatar.
+    avatar: Option<PathBuf>,
+    languages: Vec<String>,
+    server_mode: ServerMode,
+    host: String,
+    error: Option<String>,
+}
+
+pub enum WizardResult {
+    Open,
+    Finished(UserSettings),
+}
+
+impl Wizard {
+    pub fn new(settings: UserSettings) -> Self {
+        Self {
+            page: 0,
+            settings,
+            avatar: None,
+            languages: supported_languages(),
+            server_mode: server_mode(),
+            host: server_host().unwrap_or_default(),
+            error: None,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) -> WizardResult {
+        ui.heading("Welcome");
+        ui.label(format!("Step {} of {PAGES}", self.page + 1));
+        ui.add_space(8.0);
+
+        match self.page {
+            0 => self.draw_profile(ui),
+            1 => self.draw_language(ui),
+            _ => self.draw_server(ui),
+        }
+
+        ui.add_space(8.0);
+        let mut result = WizardResult::Open;
+        ui.horizontal(|ui| {
+            if self.page > 0 && ui.button("Back").clicked() {
+                self.page -= 1;
+            }
+            let last = self.page + 1 == PAGES;
+            if ui.button(if last { "Finish" } else { "Next" }).clicked() {
+                match self.check_page() {
+                    Ok(()) if last => match self.save() {
+                        Ok(()) => result = WizardResult::Finished(self.settings.clone()),
+                        Err(err) => self.error = Some(format!("{err:#}")),
+                    },
+                    Ok(()) => {
+                        self.error = None;
+                        self.page += 1;
+                    }
+                    Err(err) => self.error = Some(format!("{err:#}")),
+                }
+            }
+        });
+
+        if let Some(error) = &self.error {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+        result
+    }
+
+    fn draw_profile(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Your name:");
+            ui.text_edit_singleline(&mut self.settings.account_name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Country:");
+            ui.add(egui::TextEdit::singleline(&mut self.settings.country).desired_width(40.0))
+                .on_hover_text("Two letter country code shown in lobbies, e.g. DE. Empty for US.");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Picture:");
+            let current = match &self.avatar {
+                Some(picture) => picture
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                None if avatar_path().exists() => "Your current picture".to_string(),
+                None => "None".to_string(),
+            };
+            ui.label(RichText::new(current).italics());
+            if ui
+                .button("Choose...")
+                .on_hover_text("Shown to the other players in lobbies")
+                .clicked()
+                && let Some(picture) = rfd::FileDialog::new()
+                    .add_filter("Pictures", &["png", "jpg", "jpeg", "bmp", "gif", "webp"])
+                    .pick_file()
+            {
+                self.avatar = Some(picture);
+            }
+        });
+    }
+
+    fn draw_language(&mut self, ui: &mut Ui) {
+        ui.label("The language the game runs in:");
+        language_combo(ui, &mut self.settings.language, &self.languages);
+    }
+
+    fn draw_server(&mut self, ui: &mut Ui) {
+        ui.label("Which computer runs the lan server?");
+        ui.radio_value(
+            &mut self.server_mode,
+            ServerMode::Auto,
+            "Find it automatically, or host one if there is none",
+        );
+        ui.radio_value(&mut self.server_mode, ServerMode::Host, "This computer");
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.server_mode, ServerMode::Join, "Another computer:");
+            ui.add_enabled(
+                self.server_mode == ServerMode::Join,
+                egui::TextEdit::singleline(&mut self.host).hint_text("192.168.1.10"),
+            );
+        });
+    }
+
+    fn check_page(&self) -> Result<()> {
+        match self.page {
+            0 if self.settings.account_name.trim().is_empty() => bail!("Enter a name"),
+            0 if !matches!(self.settings.country.trim().len(), 0 | 2) => {
+                bail!("The country is a two letter code")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        save_user_settings(&self.settings)?;
+        if let Some(picture) = &self.avatar {
+            save_avatar(picture)?;
+        }
+        save_server_settings(self.server_mode, &self.host)
+    }
+}
+
+/// Picks one of `languages`, goldberg's default english when empty.
+pub fn language_combo(ui: &mut Ui, language: &mut String, languages: &[String]) -> egui::Response {
+    let selected = if language.is_empty() {
+        "english".to_string()
+    } else {
+        language.clone()
+    };
+    let mut changed = false;
+    let mut response = egui::ComboBox::from_id_salt("language")
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            for option in languages {
+                changed |= ui
+                    .selectable_value(language, option.clone(), option)
+                    .changed();
+            }
+        })
+        .response;
+    if changed {
+        response.mark_changed();
+    }
+    response
+}
@@ -1,77 +1,136 @@
 use crate::{
     Context,
-    ctx::{StepStatus, Task},
+    config::{CompanionComponent, pinned_version},
+    ctx::{CancellationToken, InstallStep, Prerequisite, Progress, StepId},
+    download::{ReleaseAsset, ReleaseSource},
+    errors::{ErrorKind, Tag},
     goldberg::GOLDBERG_SUBDIR,
-    utils::{extract_zip, gh_latest_release_dl_url},
+    metadata::record_component,
+    rollback::{self, Journal},
+    utils::{extract_archive, gh_latest_release_dl_url},
 };
-use anyhow::{Result, bail};
-use std::{
-    fs,
-    sync::{
-        Arc,
-        mpsc::{self, Receiver},
-    },
-};
-use tracing::{error, info};
+use anyhow::{Result, anyhow};
+use std::{fs, sync::Arc};
+use tracing::info;
 
-pub fn spawn_install_launcher_companion(ctx: Arc<Context>) -> Result<Receiver<()>> {
-    let guard = ctx.set_task(Task::Companion)?;
+pub struct CompanionStep;
 
-    let (tx, rx) = mpsc::sync_channel(0);
-    std::thread::spawn(move || {
-        let _guard = guard;
-        ctx.set_step_status(2, StepStatus::InProgress);
-        match install_launcher_companion(ctx.clone()) {
-            Ok(_) => {
-                ctx.set_step_status(2, StepStatus::Completed);
-                info!("Companion installed successfully");
-                let _ = tx.send(());
-            }
-            Err(err) => {
-                let err_msg = format!("{:#}", err);
-                ctx.set_step_status(2, StepStatus::Failed(err_msg.clone()));
-                error!("Companion installation failed: {err_msg}");
-            }
-        }
-    });
+impl InstallStep for CompanionStep {
+    fn id(&self) -> StepId {
+        StepId::Companion
+    }
 
-    Ok(rx)
-}
+    fn name(&self) -> &'static str {
+        STEP
+    }
 
-pub fn install_launcher_companion(ctx: Arc<Context>) -> Result<()> {
-    let Some(companion_full_url) = launcher_companion_full_url(&ctx)? else {
-        bail!("Unable to find latest companion release");
-    };
+    fn prerequisites(&self) -> Prerequisite {
+        Prerequisite::Archive
+    }
 
-    info!("Downloading launcher companion.");
+    fn run(&self, ctx: &Arc<Context>, progress: &Progress, _: &CancellationToken) -> Result<()> {
+        rollback::transaction(ctx, STEP, |journal| {
+            install_launcher_companion(ctx, progress, journal)
+        })
+    }
+}
 
-    let companion = reqwest::blocking::get(companion_full_url)?
-        .bytes()?
-        .to_vec();
+const STEP: &str = "Companion";
 
-    let goldberg_dir = ctx.outdir().join(GOLDBERG_SUBDIR);
-    info!("Extracting launcher companion dlls.");
-    for (name, file) in extract_zip(&companion)? {
-        let lc_name = name.to_lowercase();
-        if !lc_name.contains("age2") && !lc_name.contains("fakehost") {
+/// Installs the companion, tracking what it writes in `journal`.
+pub fn install_launcher_companion(
+    ctx: &Context,
+    progress: &Progress,
+    journal: &mut Journal,
+) -> Result<()> {
+    let config = ctx.config();
+    let source = ReleaseSource {
+        name: "launcher companion",
+        local: config.aoe2.companion_zip.as_deref(),
+        verify: true,
+        sha256: config.aoe2.companion_sha256.as_deref(),
+    };
+    let (release, companion) = source.fetch(ctx, progress, 0.8, || {
+        launcher_companion_release(ctx)?
+            .ok_or_else(|| anyhow!("Unable to find the companion release"))
+            .kind(ErrorKind::UpstreamLayoutChanged)
+    })?;
+
+    let outdir = ctx.outdir();
+    let dlls_dir = outdir.join(GOLDBERG_SUBDIR).join("dlls");
+    info!("Extracting launcher companion.");
+    progress.phase(0.8, 1.0, "Extracting");
+    let mut installed = vec![];
+    for (name, file) in extract_archive(ctx, &release.name, &companion)? {
+        let Some(file_name) = name.rsplit('/').next().filter(|n| !n.is_empty()) else {
+            continue;
+        };
+        let Some(component) = companion_component(&config.aoe2.companion_components, file_name)
+        else {
+            continue;
+        };
+        if !component.enabled {
             continue;
         }
 
-        let outpath = goldberg_dir.join("dlls").join(name);
-        fs::write(outpath, file)?;
+        let mut outpath = outdir.join(&component.dest);
+        if component.flatten {
+            outpath = outpath.join(file_name);
+        } else {
+            name.split('/').for_each(|c| outpath = outpath.join(c));
+        }
+        if let Some(parent) = outpath.parent() {
+            journal.create_dir_all(parent)?;
+        }
+        journal.track(&outpath)?;
+        fs::write(&outpath, file)?;
+        info!("Installed {name} ({})", component.name);
+        if outpath.parent() == Some(dlls_dir.as_path()) {
+            installed.push(file_name.to_string());
+        }
     }
+    *ctx.companion_dlls.lock().unwrap() = installed;
+
+    record_component(ctx, "companion", &release.tag, &release.url)?;
 
     info!("Done installing companion.");
 
     Ok(())
 }
 
-fn launcher_companion_full_url(ctx: &Context) -> Result<Option<String>> {
-    info!("Getting latest launcher companion release url.");
+/// The first component claiming a file of the companion zip.
+fn companion_component<'a>(
+    components: &'a [CompanionComponent],
+    file_name: &str,
+) -> Option<&'a CompanionComponent> {
+    let file_name = file_name.to_lowercase();
+    components.iter().find(|c| {
+        c.patterns.is_empty()
+            || c.patterns
+                .iter()
+                .any(|p| file_name.contains(&p.to_lowercase()))
+    })
+}
+
+fn launcher_companion_release(ctx: &Context) -> Result<Option<ReleaseAsset>> {
+    let config = ctx.config();
+    let version = pinned_version(&config.aoe2.companion_version);
+    info!(
+        "Getting launcher companion release url ({}).",
+        version.unwrap_or("latest")
+    );
+    let search: Vec<&str> = config
+        .aoe2
+        .companion_asset_search
+        .iter()
+        .map(String::as_str)
+        .collect();
     gh_latest_release_dl_url(
-        &ctx.config.aoe2.gh_companion_user,
-        &ctx.config.aoe2.gh_companion_repo,
-        None,
-        &["_full_"],
+        ctx,
+        &config.aoe2.gh_companion_user,
+        &config.aoe2.gh_companion_repo,
+        version,
+        config.aoe2.companion_prerelease,
+        &search,
     )
 }
@@ -0,0 +1,112 @@
+use crate::{Context, aoe::aoe2::app_manifest};
+use anyhow::{Result, anyhow};
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Reads the dlc app ids steam has installed depots for from the
+/// `appmanifest` next to the `common` folder the source lives in.
+pub fn installed_dlcs(source: &Path) -> Option<Vec<u32>> {
+    Some(parse_dlc_app_ids(&app_manifest(source)?))
+}
+
+fn parse_dlc_app_ids(manifest: &str) -> Vec<u32> {
+    let mut ids: Vec<u32> = manifest
+        .lines()
+        .filter_map(|line| {
+            let mut tokens = line.split('"').skip(1).step_by(2);
+            if tokens.next()? != "dlcappid" {
+                return None;
+            }
+            tokens.next()?.parse().ok()
+        })
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+/// Warns about owned expansions whose content is missing from the source,
+/// and about expansion content that didn't make it into the copy.
+pub fn verify_dlc_content(ctx: &Context, source: &Path, copy: &Path) {
+    let installed = installed_dlcs(source);
+    if installed.is_none() {
+        warn!("No steam app manifest found, unable to tell which expansions are owned");
+    }
+
+    for dlc in &ctx.config().aoe2.dlcs {
+        let Some(content_dir) = &dlc.content_dir else {
+            continue;
+        };
+        let owned = installed
+            .as_ref()
+            .is_some_and(|ids| ids.contains(&dlc.app_id));
+        let in_source = source.join(content_dir).exists();
+
+        if owned && !in_source {
+            warn!(
+                "{} ({}) is owned, but its content is missing from the source. Try verifying the game files in steam.",
+                dlc.name, dlc.app_id
+            );
+        } else if in_source && !copy.join(content_dir).exists() {
+            warn!(
+                "{} ({}) content is missing from the copy",
+                dlc.name, dlc.app_id
+            );
+        } else if in_source {
+            info!("Found content for {} ({})", dlc.name, dlc.app_id);
+        }
+    }
+}
+
+/// Lists every configured expansion in goldberg's `configs.app.ini`.
+pub fn write_dlc_ids(ctx: &Context, app_ini_path: &Path) -> Result<()> {
+    use ini::Ini;
+
+    let config = ctx.config();
+    if config.aoe2.dlcs.is_empty() {
+        return Ok(());
+    }
+
+    let mut conf = Ini::load_from_file(app_ini_path)
+        .map_err(|e| anyhow!("Failed to load {}: {}", app_ini_path.display(), e))?;
+
+    let mut section = conf.with_section(Some("app::dlcs"));
+    for dlc in &config.aoe2.dlcs {
+        section.set(dlc.app_id.to_string(), &dlc.name);
+    }
+
+    conf.write_to_file(app_ini_path)
+        .map_err(|e| anyhow!("Failed to write {}: {}", app_ini_path.display(), e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_dlc_app_ids;
+
+    #[test]
+    fn parse_manifest_dlcs() {
+        let manifest = r#"
+"AppState"
+{
+	"appid"		"813780"
+	"InstalledDepots"
+	{
+		"813781"
+		{
+			"manifest"		"123"
+			"size"		"456"
+		}
+		"2141581"
+		{
+			"manifest"		"789"
+			"size"		"10"
+			"dlcappid"		"2141580"
+		}
+	}
+}"#;
+
+        assert_eq!(parse_dlc_app_ids(manifest), [2141580]);
+    }
+}
@@ -1,80 +1,155 @@
 use crate::{
-    ctx::{StepStatus, Task},
-    utils::{extract_zip, gh_latest_release_dl_url},
     Context,
+    config::{Config, Injection, pinned_version},
+    ctx::{CancellationToken, InstallStep, Prerequisite, Progress, StepId},
+    download::{ReleaseAsset, ReleaseSource},
+    errors::{ErrorKind, Tag},
+    goldberg::GOLDBERG_SUBDIR,
+    metadata::record_component,
+    rollback::{self, Journal},
+    utils::{extract_archive, gh_latest_release_dl_url},
 };
-use anyhow::{bail, Result};
+use anyhow::{Result, anyhow, bail};
 use std::{
     fs::{self, read_to_string},
+    path::{Path, PathBuf},
     process::Command,
-    sync::{
-        mpsc::{self, Receiver},
-        Arc,
-    },
+    sync::Arc,
 };
-use tracing::{error, info};
-
-pub fn spawn_install_launcher(ctx: Arc<Context>) -> Result<Receiver<()>> {
-    let guard = ctx.set_task(Task::Launcher)?;
-
-    let (tx, rx) = mpsc::sync_channel(0);
-    std::thread::spawn(move || {
-        let _guard = guard;
-        ctx.set_step_status(3, StepStatus::InProgress);
-        match install_launcher(ctx.clone()) {
-            Ok(_) => {
-                ctx.set_step_status(3, StepStatus::Completed);
-                info!("Launcher installed successfully");
-                let _ = tx.send(());
-            }
-            Err(err) => {
-                let err_msg = format!("{:#}", err);
-                ctx.set_step_status(3, StepStatus::Failed(err_msg.clone()));
-                error!("Launcher installation failed: {err_msg}");
-            }
-        }
-    });
+use toml_edit::{Array, DocumentMut, TableLike, Value, table, value};
+use tracing::{info, warn};
+
+pub struct LauncherStep;
+
+impl InstallStep for LauncherStep {
+    fn id(&self) -> StepId {
+        StepId::Launcher
+    }
+
+    fn name(&self) -> &'static str {
+        STEP
+    }
+
+    fn prerequisites(&self) -> Prerequisite {
+        Prerequisite::Archive
+    }
+
+    fn prefetch(&self, ctx: &Arc<Context>) {
+        ctx.prefetch(STEP, fetch_launcher);
+    }
 
-    Ok(rx)
+    fn run(&self, ctx: &Arc<Context>, progress: &Progress, _: &CancellationToken) -> Result<()> {
+        rollback::transaction(ctx, STEP, |journal| {
+            install_launcher(ctx, progress, journal)
+        })
+    }
 }
 
-pub fn install_launcher(ctx: Arc<Context>) -> Result<()> {
-    let Some(launcher_url) = launcher_full_url(&ctx)? else {
-        bail!("Unable to find latest launcher release.");
-    };
-    info!("Downloading launcher.");
+pub const STEP: &str = "Launcher";
 
-    let launcher_zip = reqwest::blocking::get(launcher_url)?.bytes()?.to_vec();
+/// Installs the launcher, tracking what it writes in `journal`.
+pub fn install_launcher(ctx: &Context, progress: &Progress, journal: &mut Journal) -> Result<()> {
+    let (release, launcher_zip) = match ctx.take_prefetched(STEP) {
+        Some(prefetched) => {
+            progress.phase(0.0, 0.6, "Waiting for the download");
+            prefetched?
+        }
+        None => fetch_launcher(ctx)?,
+    };
     let outdir = ctx.outdir();
 
     info!("Extracting launcher.");
 
-    for (name, file) in extract_zip(&launcher_zip)? {
+    progress.phase(0.6, 0.75, "Extracting");
+    let files = extract_archive(ctx, &release.name, &launcher_zip)?;
+
+    progress.phase(0.75, 0.9, "Writing files");
+    let total = files.len();
+    for (i, (name, file)) in files.into_iter().enumerate() {
+        progress.report(
+            &format!("Writing files... {} of {total}", i + 1),
+            (i + 1) as f32 / total as f32,
+        );
         let mut outpath = outdir.to_path_buf();
         name.split("/").for_each(|c| outpath = outpath.join(c));
 
-        if let Some(parent) = outpath.parent() {
-            if !parent.exists() {
-                fs::create_dir_all(parent)?;
-            }
+        if let Some(parent) = outpath.parent()
+            && !parent.exists()
+        {
+            journal.create_dir_all(parent)?;
         }
+        journal.track(&outpath)?;
         fs::write(outpath, file)?;
     }
 
-    patch_launcher_config(&ctx)?;
+    progress.phase(0.9, 0.95, "Patching config");
+    patch_launcher_config(ctx, journal)?;
 
     info!("Generating certs.");
+    progress.phase(0.95, 1.0, "Generating certs");
 
-    let gen_certs_exe = outdir.join("server").join("bin").join("genCert.exe");
+    let certs = outdir.join("server").join("resources").join("certificates");
+    journal.track(&certs)?;
+    generate_certs(&outdir.join("server"))?;
 
-    let _ = Command::new(gen_certs_exe).status();
+    record_component(ctx, "launcher", &release.tag, &release.url)?;
 
     info!("Done installing launcher.");
 
     Ok(())
 }
 
-fn patch_launcher_config(ctx: &Context) -> Result<()> {
+/// Finds, downloads and verifies the launcher, or reads the local zip.
+pub fn fetch_launcher(ctx: &Context) -> Result<(ReleaseAsset, PathBuf)> {
+    let config = ctx.config();
+    let progress = Progress::new(ctx, StepId::Launcher, STEP);
+    let source = ReleaseSource {
+        name: "launcher",
+        local: config.aoe2.launcher_zip.as_deref(),
+        verify: true,
+        sha256: config.aoe2.launcher_sha256.as_deref(),
+    };
+    source.fetch(ctx, &progress, 0.6, || {
+        launcher_release(ctx)?
+            .ok_or_else(|| anyhow!("Unable to find the launcher release."))
+            .kind(ErrorKind::UpstreamLayoutChanged)
+    })
+}
+
+/// Runs the server's genCert.exe, logging its output. A failure here would
+/// otherwise only show up as the game being unable to connect. Servers that
+/// don't ship genCert.exe are only warned about.
+pub fn generate_certs(server_dir: &Path) -> Result<()> {
+    let gen_certs_exe = server_dir.join("bin").join("genCert.exe");
+    if !gen_certs_exe.exists() {
+        warn!(
+            "{} not found, skipping cert generation",
+            gen_certs_exe.display()
+        );
+        return Ok(());
+    }
+    let output = Command::new(&gen_certs_exe)
+        .current_dir(server_dir)
+        .output()
+        .map_err(|e| anyhow!("Failed to run {}: {}", gen_certs_exe.display(), e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for line in stdout.lines().filter(|l| !l.trim().is_empty()) {
+        info!("genCert: {line}");
+    }
+    for line in stderr.lines().filter(|l| !l.trim().is_empty()) {
+        warn!("genCert: {line}");
+    }
+
+    if !output.status.success() {
+        bail!("genCert.exe failed with {}", output.status);
+    }
+
+    Ok(())
+}
+
+fn patch_launcher_config(ctx: &Context, journal: &mut Journal) -> Result<()> {
     // Set the executable directory.
     let outdir = ctx.outdir();
     info!("Patching launcher config.");
@@ -82,28 +157,217 @@ fn patch_launcher_config(ctx: &Context) -> Result<()> {
         .join("launcher")
         .join("resources")
         .join("config.age2.toml");
+    journal.track(&aoe2_config_path)?;
     let aoe2_config = read_to_string(&aoe2_config_path)?;
-    let aoe2_config = aoe2_config.replace(
-        "Executable = 'auto'",
-        r#"Executable = "../goldberg/steamclient_loader_x64.exe""#,
-    );
-    let aoe2_config = aoe2_config.replace("Path = 'auto'", r#"Path = "../AoE2DE""#);
-    let aoe2_config = aoe2_config.replace(
-        "ExecutableArgs = []",
-        "ExecutableArgs = []",
-        // r#"ExecutableArgs = ['--overrideHosts="{HostFilePath}"']"#,
-    );
+    let fake_host_dll = fake_host_dll(ctx);
+    let aoe2_config = patch_age2_config(&aoe2_config, &ctx.config(), fake_host_dll.as_deref())?;
     fs::write(aoe2_config_path, aoe2_config.as_bytes())?;
 
     Ok(())
 }
 
-fn launcher_full_url(ctx: &Context) -> Result<Option<String>> {
-    info!("Getting latest launcher release url.");
+/// The companion's FakeHost dll, whose file name changes with every companion
+/// release. Falls back to the dlls folder when the companion step didn't run
+/// in this session.
+fn fake_host_dll(ctx: &Context) -> Option<String> {
+    let is_fake_host = |name: &str| {
+        let name = name.to_lowercase();
+        name.contains("fakehost") && name.ends_with(".dll")
+    };
+
+    let recorded = ctx.companion_dlls.lock().unwrap().clone();
+    if let Some(dll) = recorded.into_iter().find(|n| is_fake_host(n)) {
+        return Some(dll);
+    }
+
+    let dlls_dir = ctx.outdir().join(GOLDBERG_SUBDIR).join("dlls");
+    fs::read_dir(dlls_dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .find(|n| is_fake_host(n))
+}
+
+/// Points the launcher at the archived game and the configured lan server.
+/// Fails when upstream renamed the settings this relies on, instead of
+/// leaving the launcher to find the steam install.
+fn patch_age2_config(
+    document: &str,
+    config: &Config,
+    fake_host_dll: Option<&str>,
+) -> Result<String> {
+    let mut doc: DocumentMut = document
+        .parse()
+        .map_err(|e| anyhow!("Failed to parse config.age2.toml: {e}"))?;
+
+    let steam_folder = &config.aoe2.steam_folder;
+    let executable = match config.goldberg.injection {
+        Injection::ColdClientLoader => "../goldberg/steamclient_loader_x64.exe".to_string(),
+        Injection::SteamApi => format!("../{steam_folder}/AoE2DE_s.exe"),
+    };
+
+    let client = game_table(&mut doc)?;
+    set_existing(client, "Executable", executable)?;
+    set_existing(client, "Path", format!("../{steam_folder}"))?;
+
+    // The FakeHost dll redirects the game's server hosts to the lan server
+    // using the hosts file the launcher writes. It is only injected by the
+    // cold client loader.
+    match fake_host_dll {
+        Some(dll) if config.goldberg.injection == Injection::ColdClientLoader => {
+            info!("Redirecting hosts through {dll}");
+            set_existing(
+                client,
+                "ExecutableArgs",
+                Array::from_iter([r#"--overrideHosts="{HostFilePath}""#]),
+            )?;
+        }
+        Some(_) => info!("The companion dlls aren't injected when replacing steam_api64.dll"),
+        None => {
+            warn!("No companion FakeHost dll found, the game will use the official servers' hosts")
+        }
+    }
+
+    let Some(server) = doc
+        .entry("Server")
+        .or_insert_with(table)
+        .as_table_like_mut()
+    else {
+        bail!("Server in config.age2.toml is not a table");
+    };
+    server.insert("Start", value(config.server.start.launcher_value()));
+    let host = config.server.host.trim();
+    if !host.is_empty() {
+        info!("Using the lan server at {host}");
+        server.insert("Host", value(host));
+    }
+
+    Ok(doc.to_string())
+}
+
+/// The table holding the game executable settings.
+fn game_table(doc: &mut DocumentMut) -> Result<&mut dyn TableLike> {
+    let Some(name) = doc
+        .iter()
+        .find(|(_, item)| {
+            item.as_table_like()
+                .is_some_and(|t| t.contains_key("Executable"))
+        })
+        .map(|(name, _)| name.to_string())
+    else {
+        return Err(anyhow!(
+            "config.age2.toml has no table with an Executable setting, the launcher's config \
+             format changed"
+        ))
+        .kind(ErrorKind::UpstreamLayoutChanged);
+    };
+
+    Ok(doc[&name].as_table_like_mut().unwrap())
+}
+
+/// Replaces the value of a setting upstream ships, keeping its comments.
+fn set_existing(table: &mut dyn TableLike, key: &str, new_value: impl Into<Value>) -> Result<()> {
+    let Some(existing) = table.get_mut(key).and_then(|item| item.as_value_mut()) else {
+        return Err(anyhow!(
+            "config.age2.toml has no {key} setting, the launcher's config format changed"
+        ))
+        .kind(ErrorKind::UpstreamLayoutChanged);
+    };
+
+    let decor = existing.decor().clone();
+    *existing = new_value.into();
+    *existing.decor_mut() = decor;
+
+    Ok(())
+}
+
+fn launcher_release(ctx: &Context) -> Result<Option<ReleaseAsset>> {
+    let config = ctx.config();
+    let version = pinned_version(&config.aoe2.launcher_version);
+    info!(
+        "Getting launcher release url ({}).",
+        version.unwrap_or("latest")
+    );
+    let search: Vec<&str> = config
+        .aoe2
+        .launcher_asset_search
+        .iter()
+        .map(String::as_str)
+        .collect();
     gh_latest_release_dl_url(
-        &ctx.config.aoe2.gh_launcher_user,
-        &ctx.config.aoe2.gh_launcher_repo,
-        Some("v1.11.2"),
-        &["_full_", "win_x86-64"],
+        ctx,
+        &config.aoe2.gh_launcher_user,
+        &config.aoe2.gh_launcher_repo,
+        version,
+        config.aoe2.launcher_prerelease,
+        &search,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::patch_age2_config;
+    use crate::config::{Config, Injection, ServerStart};
+
+    const UPSTREAM_CONFIG: &str = include_str!("testdata/config.age2.toml");
+
+    fn config() -> Config {
+        toml::from_str(include_str!("../../../config.toml")).unwrap()
+    }
+
+    #[test]
+    fn patch_upstream_config() {
+        let mut config = config();
+        config.server.host = "192.168.1.10".to_string();
+        config.server.start = ServerStart::Never;
+
+        let patched = patch_age2_config(UPSTREAM_CONFIG, &config, None).unwrap();
+        let doc: toml::Table = toml::from_str(&patched).unwrap();
+
+        assert_eq!(
+            doc["Client"]["Executable"].as_str(),
+            Some("../goldberg/steamclient_loader_x64.exe")
+        );
+        assert_eq!(doc["Client"]["Path"].as_str(), Some("../AoE2DE"));
+        assert_eq!(doc["Server"]["Start"].as_str(), Some("false"));
+        assert_eq!(doc["Server"]["Host"].as_str(), Some("192.168.1.10"));
+        // Upstream's comments are kept.
+        assert!(patched.contains("# The game installation folder."));
+    }
+
+    #[test]
+    fn patch_steam_api_executable() {
+        let mut config = config();
+        config.goldberg.injection = Injection::SteamApi;
+
+        let patched = patch_age2_config(UPSTREAM_CONFIG, &config, None).unwrap();
+        let doc: toml::Table = toml::from_str(&patched).unwrap();
+
+        assert_eq!(
+            doc["Client"]["Executable"].as_str(),
+            Some("../AoE2DE/AoE2DE_s.exe")
+        );
+    }
+
+    #[test]
+    fn redirect_hosts_with_fake_host() {
+        let dll = "ageLANServerLauncherCompanion_AgeFakeHost_1.1.0.0.dll";
+        let patched = patch_age2_config(UPSTREAM_CONFIG, &config(), Some(dll)).unwrap();
+        let doc: toml::Table = toml::from_str(&patched).unwrap();
+
+        let args = doc["Client"]["ExecutableArgs"].as_array().unwrap();
+        assert_eq!(
+            args[0].as_str(),
+            Some(r#"--overrideHosts="{HostFilePath}""#)
+        );
+    }
+
+    #[test]
+    fn fail_on_changed_format() {
+        let changed = UPSTREAM_CONFIG.replace("Path = 'auto'", "GamePath = 'auto'");
+        assert!(patch_age2_config(&changed, &config(), None).is_err());
+
+        let changed = UPSTREAM_CONFIG.replace("Executable = 'auto'", "Exe = 'auto'");
+        assert!(patch_age2_config(&changed, &config(), None).is_err());
+    }
+}
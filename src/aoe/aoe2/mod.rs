@@ -1,2 +1,60 @@
 pub mod companion;
+pub mod dlc;
 pub mod launcher;
+pub mod server;
+
+use crate::source_checks::exe_version;
+use std::path::Path;
+
+pub const APP_ID: u32 = 813780;
+
+/// Steam's `appmanifest` of the game, next to the `common` folder `source`
+/// lives in.
+pub fn app_manifest(source: &Path) -> Option<String> {
+    let manifest_path = source
+        .parent()?
+        .parent()?
+        .join(format!("appmanifest_{APP_ID}.acf"));
+    std::fs::read_to_string(manifest_path).ok()
+}
+
+/// The release of the game in `source` for telling archives apart, e.g.
+/// `AoE2 DE build 141935`. Copies without a Steam manifest fall back to the
+/// executable's version.
+pub fn game_version(source: &Path) -> Option<String> {
+    if let Some(build) = app_manifest(source).and_then(|manifest| parse_build_id(&manifest)) {
+        return Some(format!("AoE2 DE build {build}"));
+    }
+    exe_version(source)
+        .ok()
+        .map(|version| format!("AoE2 DE {version}"))
+}
+
+fn parse_build_id(manifest: &str) -> Option<u64> {
+    manifest.lines().find_map(|line| {
+        let mut tokens = line.split('"').skip(1).step_by(2);
+        if tokens.next()? != "buildid" {
+            return None;
+        }
+        tokens.next()?.parse().ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_build_id;
+
+    #[test]
+    fn parse_manifest_build_id() {
+        let manifest = r#"
+"AppState"
+{
+	"appid"		"813780"
+	"buildid"		"141935"
+	"LastOwner"		"76561198000000000"
+}"#;
+
+        assert_eq!(parse_build_id(manifest), Some(141935));
+        assert_eq!(parse_build_id(r#""AppState" {}"#), None);
+    }
+}
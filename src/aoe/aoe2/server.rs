@@ -0,0 +1,139 @@
+use crate::{
+    Context,
+    aoe::aoe2::launcher::generate_certs,
+    config::pinned_version,
+    ctx::{CancellationToken, InstallStep, Prerequisite, Progress, StepId},
+    download::{ReleaseAsset, ReleaseSource},
+    errors::{ErrorKind, Tag},
+    metadata::record_component,
+    rollback::{self, Journal},
+    utils::{extract_archive, gh_latest_release_dl_url},
+};
+use anyhow::{Result, anyhow};
+use std::{fs, sync::Arc};
+use tracing::info;
+
+const STEP: &str = "Server";
+pub const SERVER_DIR: &str = "server";
+const HOST_SCRIPT: &str = "host_server.bat";
+
+pub struct ServerStep;
+
+impl InstallStep for ServerStep {
+    fn id(&self) -> StepId {
+        StepId::Server
+    }
+
+    fn name(&self) -> &'static str {
+        STEP
+    }
+
+    fn prerequisites(&self) -> Prerequisite {
+        Prerequisite::Archive
+    }
+
+    fn enabled(&self, ctx: &Context) -> bool {
+        ctx.config().server.install
+    }
+
+    fn run(&self, ctx: &Arc<Context>, progress: &Progress, _: &CancellationToken) -> Result<()> {
+        rollback::transaction(ctx, STEP, |journal| install_server(ctx, progress, journal))
+    }
+}
+
+/// Installs the ageLANServer into the archive with a script to host it, so
+/// one machine at the party can host without any setup. What it writes is
+/// tracked in `journal`.
+pub fn install_server(ctx: &Context, progress: &Progress, journal: &mut Journal) -> Result<()> {
+    let config = ctx.config();
+    let source = ReleaseSource {
+        name: "server",
+        local: config.aoe2.server_zip.as_deref(),
+        verify: true,
+        sha256: None,
+    };
+    let (release, server_zip) = source.fetch(ctx, progress, 0.7, || {
+        server_release(ctx)?
+            .ok_or_else(|| anyhow!("Unable to find the server release."))
+            .kind(ErrorKind::UpstreamLayoutChanged)
+    })?;
+
+    progress.phase(0.7, 0.8, "Extracting");
+    let files = extract_archive(ctx, &release.name, &server_zip)?;
+
+    // The server-only zip has no server folder of its own.
+    let outdir = ctx.outdir();
+    let nested = files
+        .keys()
+        .all(|name| name.starts_with(&format!("{SERVER_DIR}/")));
+    let server_root = if nested {
+        outdir.clone()
+    } else {
+        outdir.join(SERVER_DIR)
+    };
+
+    progress.phase(0.8, 0.95, "Writing files");
+    let total = files.len();
+    for (i, (name, file)) in files.into_iter().enumerate() {
+        progress.report(
+            &format!("Writing files... {} of {total}", i + 1),
+            (i + 1) as f32 / total as f32,
+        );
+        if name.ends_with('/') {
+            continue;
+        }
+
+        let mut outpath = server_root.clone();
+        name.split('/').for_each(|c| outpath = outpath.join(c));
+        if let Some(parent) = outpath.parent()
+            && !parent.exists()
+        {
+            journal.create_dir_all(parent)?;
+        }
+        journal.track(&outpath)?;
+        fs::write(outpath, file)?;
+    }
+
+    progress.phase(0.95, 1.0, "Generating certs");
+    let server_dir = outdir.join(SERVER_DIR);
+    journal.track(&server_dir.join("resources").join("certificates"))?;
+    generate_certs(&server_dir)?;
+
+    let script = format!(
+        "@echo off\r\ncd /d \"%~dp0{SERVER_DIR}\"\r\nstart \"ageLANServer\" server.exe\r\n"
+    );
+    let script_path = outdir.join(HOST_SCRIPT);
+    journal.track(&script_path)?;
+    fs::write(script_path, script)?;
+    info!("Run {HOST_SCRIPT} in the archive to host the lan server");
+
+    record_component(ctx, "server", &release.tag, &release.url)?;
+
+    info!("Done installing server.");
+
+    Ok(())
+}
+
+fn server_release(ctx: &Context) -> Result<Option<ReleaseAsset>> {
+    let config = ctx.config();
+    // Released together with the launcher.
+    let version = pinned_version(&config.aoe2.launcher_version);
+    info!(
+        "Getting server release url ({}).",
+        version.unwrap_or("latest")
+    );
+    let search: Vec<&str> = config
+        .aoe2
+        .server_asset_search
+        .iter()
+        .map(String::as_str)
+        .collect();
+    gh_latest_release_dl_url(
+        ctx,
+        &config.aoe2.gh_launcher_user,
+        &config.aoe2.gh_launcher_repo,
+        version,
+        config.aoe2.server_prerelease,
+        &search,
+    )
+}
@@ -0,0 +1,117 @@
+use crate::paths;
+use anyhow::{Context as AnyhowContext, Result};
+use fs_extra::dir::get_size;
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Downloads kept across runs, keyed by url and revalidated by etag.
+pub struct DownloadCache {
+    dir: PathBuf,
+    /// The size last measured, until the cache changes. The window shows it
+    /// every frame.
+    size: Mutex<Option<u64>>,
+}
+
+pub struct CacheEntry {
+    pub data_path: PathBuf,
+    pub etag: String,
+}
+
+/// A download in progress, kept in the cache for a later run to resume it
+/// after a crash.
+pub struct Partial {
+    pub path: PathBuf,
+    /// The etag of what the file holds the start of, without one it can't
+    /// be resumed.
+    pub etag: Option<String>,
+}
+
+impl DownloadCache {
+    pub fn new() -> Result<Self> {
+        let dir = paths::cache_dir()?;
+        fs::create_dir_all(&dir).context("Failed to create download cache directory")?;
+        Ok(Self {
+            dir,
+            size: Mutex::default(),
+        })
+    }
+
+    pub fn get(&self, url: &str) -> Option<CacheEntry> {
+        let data_path = self.path(url, "bin");
+        let etag = fs::read_to_string(self.path(url, "etag")).ok()?;
+        data_path.exists().then_some(CacheEntry { data_path, etag })
+    }
+
+    /// Moves a finished download into the cache, returning where it is now.
+    pub fn insert(&self, url: &str, etag: &str, download: &Path) -> Result<PathBuf> {
+        let data_path = self.path(url, "bin");
+        if fs::rename(download, &data_path).is_err() {
+            // The temp dir may live on another drive.
+            fs::copy(download, &data_path)?;
+            fs::remove_file(download)?;
+        }
+        fs::write(self.path(url, "etag"), etag)?;
+        self.changed();
+        Ok(data_path)
+    }
+
+    pub fn insert_data(&self, url: &str, etag: &str, data: &[u8]) -> Result<()> {
+        let (data_path, etag_path) = (self.path(url, "bin"), self.path(url, "etag"));
+        fs::write(data_path, data)?;
+        fs::write(etag_path, etag)?;
+        self.changed();
+        Ok(())
+    }
+
+    pub fn partial(&self, url: &str) -> Partial {
+        Partial {
+            path: self.path(url, "part"),
+            etag: fs::read_to_string(self.path(url, "part.etag")).ok(),
+        }
+    }
+
+    /// Records the etag of the partial download of `url`, or that it has
+    /// none to resume.
+    pub fn set_partial_etag(&self, url: &str, etag: Option<&str>) -> Result<()> {
+        let path = self.path(url, "part.etag");
+        match etag {
+            Some(etag) => fs::write(path, etag)?,
+            None if path.exists() => fs::remove_file(path)?,
+            None => {}
+        }
+        Ok(())
+    }
+
+    pub fn size(&self) -> u64 {
+        *self
+            .size
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| get_size(&self.dir).unwrap_or_default())
+    }
+
+    pub fn clear(&self) -> Result<u64> {
+        let size = self.size();
+        self.changed();
+        for entry in fs::read_dir(&self.dir)? {
+            fs::remove_file(entry?.path())?;
+        }
+        Ok(size)
+    }
+
+    fn changed(&self) {
+        *self.size.lock().unwrap() = None;
+    }
+
+    fn path(&self, url: &str, extension: &str) -> PathBuf {
+        let key: String = Sha256::digest(url.as_bytes())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        self.dir.join(format!("{key}.{extension}"))
+    }
+}
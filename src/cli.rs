@@ -0,0 +1,455 @@
+//! Running the steps without the window, e.g. to refresh an archive from a
+//! script after every game patch. Output shows when redirected, the exe has
+//! no console of its own.
+//!
+//! With `--json` every line of output is a JSON object with an `event` field:
+//! `step_started`, `step_finished`, `progress`, `file`, `log`, `finished` or
+//! `failed`. Failures come with `advice` on what to do next when the
+//! archiver knows. The exit code tells failures apart:
+//!
+//! - 1: something unexpected, e.g. config.toml failed to load
+//! - 2: invalid arguments
+//! - 3: the source isn't an AoE2 DE installation
+//! - 4: a step can't run yet, e.g. Goldberg before the game was copied
+//! - 10: a step failed for another reason than the ones below
+//! - 11: the destination drive is full
+//! - 12: a download couldn't connect, e.g. blocked by a firewall
+//! - 13: github's rate limit was reached
+//! - 14: a file couldn't be written, e.g. in use by the game
+//! - 15: a release no longer has the files where the archiver expects them
+//! - 16: a download doesn't match its checksum
+//! - 17: the run was cancelled
+//!
+//! Which step failed is in the output, `step_finished`'s `step` with
+//! `--json`.
+
+use crate::{
+    AppUpdate,
+    ctx::{Context, OverwritePolicy, Step, StepId, StepStatus},
+    errors::ErrorKind,
+    events::EventBus,
+    log::{self, RunLog},
+    paths, spawn_step,
+    ui::UiLayer,
+    utils::validate_aoe2_source,
+};
+use anyhow::Result;
+use clap::{CommandFactory, Parser};
+use fs_extra::dir::get_size;
+use serde_json::{Value, json};
+use std::{
+    path::PathBuf,
+    process::ExitCode,
+    sync::{
+        Arc,
+        mpsc::{Receiver, RecvTimeoutError, TryRecvError},
+    },
+    time::{Duration, Instant},
+};
+use tracing::{error, info, warn};
+use tracing_subscriber::{filter::LevelFilter, layer::SubscriberExt};
+
+/// Creates a portable copy of Age of Empires II: Definitive Edition. Uses
+/// config.toml and the options saved in the window.
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// The game installation to copy. Looked up in Steam by default.
+    #[arg(long)]
+    source: Option<PathBuf>,
+    /// Where the archive is created. The last one used in the window by
+    /// default.
+    #[arg(long)]
+    dest: Option<PathBuf>,
+    /// The steps to run in order, e.g. copy,goldberg,companion,launcher.
+    /// All of them by default.
+    #[arg(long, value_delimiter = ',')]
+    steps: Vec<String>,
+    /// Delete the game files an earlier run left in the destination before
+    /// copying, instead of copying over them.
+    #[arg(long)]
+    replace: bool,
+    /// Print JSON lines instead of text, for scripts to follow the run.
+    #[arg(long)]
+    json: bool,
+    /// Keep config.toml, the settings, the download cache and the logs next
+    /// to the archiver instead of in the app data, like a portable.txt next
+    /// to it does.
+    #[arg(long)]
+    portable: bool,
+}
+
+/// Why a run failed, each kind with its own exit code.
+enum Failure {
+    Usage(anyhow::Error),
+    Source(anyhow::Error),
+    Prerequisite(String),
+    Step {
+        name: &'static str,
+        error: String,
+        cause: Option<ErrorKind>,
+    },
+    Other(anyhow::Error),
+}
+
+impl Failure {
+    fn code(&self) -> u8 {
+        match self {
+            Failure::Other(_) => 1,
+            Failure::Usage(_) => 2,
+            Failure::Source(_) => 3,
+            Failure::Prerequisite(_) => 4,
+            Failure::Step { cause, .. } => cause.unwrap_or(ErrorKind::Other).exit_code(),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Failure::Other(_) => "other",
+            Failure::Usage(_) => "usage",
+            Failure::Source(_) => "source",
+            Failure::Prerequisite(_) => "prerequisite",
+            Failure::Step { .. } => "step",
+        }
+    }
+
+    /// What to do about it, see [`ErrorKind::advice`].
+    fn advice(&self) -> Option<&'static str> {
+        match self {
+            Failure::Source(_) => ErrorKind::SourceInvalid.advice(),
+            Failure::Step { cause, .. } => cause.and_then(ErrorKind::advice),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failure::Usage(err) | Failure::Source(err) | Failure::Other(err) => {
+                write!(f, "{err:#}")
+            }
+            Failure::Prerequisite(unmet) => f.write_str(unmet),
+            Failure::Step { name, error, .. } => write!(f, "The {name} step failed: {error}"),
+        }
+    }
+}
+
+impl From<anyhow::Error> for Failure {
+    fn from(err: anyhow::Error) -> Self {
+        Failure::Other(err)
+    }
+}
+
+/// Whether the archiver was started with arguments, to run without the
+/// window. `--portable` alone opens the window, as do arguments that aren't
+/// its options, like a folder dropped onto the exe.
+pub fn requested() -> bool {
+    is_requested(
+        std::env::args_os()
+            .skip(1)
+            .filter_map(|arg| arg.into_string().ok()),
+    )
+}
+
+fn is_requested(args: impl IntoIterator<Item = String>) -> bool {
+    let command = Args::command();
+    args.into_iter().any(|arg| {
+        let Some(name) = arg.strip_prefix("--") else {
+            return arg == "-h" || arg == "-V";
+        };
+        let name = name.split_once('=').map_or(name, |(name, _)| name);
+        arg != paths::PORTABLE_FLAG
+            && (name == "help"
+                || name == "version"
+                || command.get_arguments().any(|a| a.get_long() == Some(name)))
+    })
+}
+
+pub fn run() -> ExitCode {
+    // Started from a terminal, the output goes to it.
+    common::console::attach_parent();
+    let args = Args::parse();
+    let events = EventBus::default();
+    let rx = events.subscribe();
+    // The logs arrive with the other updates too, printed as events with
+    // `--json` and saved into the archive.
+    let text = (!args.json).then(|| tracing_subscriber::fmt::layer().with_target(false));
+    let subscriber = tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(UiLayer {
+            events: events.clone(),
+            start: Instant::now(),
+        })
+        .with(text)
+        .with(log::file_layer());
+    tracing::subscriber::set_global_default(subscriber).expect("Failed to set tracing subscriber");
+
+    let mut output = Output {
+        json: args.json,
+        last_percent: None,
+    };
+    let result = run_steps(args, events, &rx, &mut output);
+    output.drain(&rx);
+    match result {
+        Ok(()) => {
+            output.event(json!({ "event": "finished" }));
+            ExitCode::SUCCESS
+        }
+        Err(failure) => {
+            if output.json {
+                output.event(json!({
+                    "event": "failed",
+                    "kind": failure.kind(),
+                    "code": failure.code(),
+                    "message": failure.to_string(),
+                    "advice": failure.advice(),
+                }));
+            } else {
+                error!("{failure}");
+                if let Some(advice) = failure.advice() {
+                    info!("{advice}");
+                }
+            }
+            ExitCode::from(failure.code())
+        }
+    }
+}
+
+fn run_steps(
+    args: Args,
+    events: EventBus,
+    rx: &Receiver<AppUpdate>,
+    output: &mut Output,
+) -> Result<(), Failure> {
+    let ctx = Arc::new(Context::new(events)?);
+    let registered = ctx.steps();
+    let steps = parse_steps(&args.steps, &registered).map_err(Failure::Usage)?;
+    if let Some(source) = args.source {
+        validate_aoe2_source(&source).map_err(Failure::Source)?;
+        ctx.set_sourcedir(source);
+    }
+    if let Some(dest) = args.dest {
+        ctx.set_outdir(dest);
+    }
+    if args.replace {
+        ctx.set_overwrite_policy(OverwritePolicy::Replace);
+    }
+
+    let run_log = RunLog::start(&ctx.events);
+    let result = run_each_step(&ctx, &registered, steps, rx, output);
+    if let Err(err) = run_log.save(&ctx.outdir()) {
+        warn!("Failed to save the run's logs in the archive: {err:#}");
+    }
+    result
+}
+
+fn run_each_step(
+    ctx: &Arc<Context>,
+    registered: &[Step],
+    steps: Vec<usize>,
+    rx: &Receiver<AppUpdate>,
+    output: &mut Output,
+) -> Result<(), Failure> {
+    info!(
+        "Archiving {} to {}",
+        ctx.sourcedir().unwrap_or_default().display(),
+        ctx.outdir().display()
+    );
+    for step in steps {
+        let Step {
+            id,
+            name,
+            prerequisite,
+            ref install,
+            ..
+        } = registered[step];
+        if let Some(unmet) = ctx.unmet(prerequisite) {
+            return Err(Failure::Prerequisite(format!(
+                "Unable to run the {name} step: {unmet}"
+            )));
+        }
+        info!("Running the {name} step");
+        output.event(json!({ "event": "step_started", "step": name.to_lowercase() }));
+        let done = spawn_step(ctx, install.clone())?;
+        // Printing the updates as they come, the step drops its sender
+        // without sending when it fails.
+        while let Err(TryRecvError::Empty) = done.try_recv() {
+            match rx.recv_timeout(Duration::from_millis(100)) {
+                Ok(update) => output.update(update),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        output.drain(rx);
+
+        let status = ctx.step_status(id);
+        let mut event = json!({
+            "event": "step_finished",
+            "step": name.to_lowercase(),
+            "status": match &status {
+                StepStatus::Completed => "completed",
+                StepStatus::Skipped => "skipped",
+                StepStatus::Failed(_) => "failed",
+                StepStatus::NotStarted | StepStatus::InProgress => "unknown",
+            },
+        });
+        let cause = ctx.step_error(id);
+        if let StepStatus::Failed(error) = &status {
+            event["error"] = error.as_str().into();
+            event["advice"] = cause.and_then(ErrorKind::advice).into();
+        }
+        if id == StepId::Copy && output.json {
+            let copied = ctx
+                .existing_game_folder()
+                .and_then(|dir| get_size(dir).ok());
+            event["bytes_copied"] = copied.into();
+        }
+        output.event(event);
+        if let StepStatus::Failed(error) = status {
+            return Err(Failure::Step { name, error, cause });
+        }
+    }
+    info!("Done");
+    Ok(())
+}
+
+/// Prints the updates the steps send, text or JSON lines.
+struct Output {
+    json: bool,
+    /// Progress is printed when the percentage changes.
+    last_percent: Option<u32>,
+}
+
+impl Output {
+    /// Prints `event` when printing JSON lines.
+    fn event(&self, event: Value) {
+        if self.json {
+            println!("{event}");
+        }
+    }
+
+    fn update(&mut self, update: AppUpdate) {
+        match update {
+            AppUpdate::Progress(Some(progress)) => {
+                let percent = (progress.fraction * 100.0) as u32;
+                if self.last_percent == Some(percent) {
+                    return;
+                }
+                self.last_percent = Some(percent);
+                if self.json {
+                    self.event(json!({
+                        "event": "progress",
+                        "step": progress.step.map(|(_, name)| name.to_lowercase()),
+                        "phase": progress.phase,
+                        "fraction": progress.fraction,
+                        "bytes_done": progress.bytes.map(|(done, _)| done),
+                        "bytes_total": progress.bytes.and_then(|(_, total)| total),
+                        "items_done": progress.items.map(|(done, _)| done),
+                        "items_total": progress.items.map(|(_, total)| total),
+                        "bytes_per_second": progress.rate,
+                        "eta_seconds": progress.eta.map(|eta| eta.as_secs()),
+                    }));
+                } else {
+                    println!("[{percent:>3}%] {progress}");
+                }
+            }
+            AppUpdate::File(file) => self.event(json!({
+                "event": "file",
+                "name": file.name,
+                "done": file.done,
+                "total": file.total,
+            })),
+            AppUpdate::Log(entry) => self.event(json!({
+                "event": "log",
+                "level": entry.level.as_str(),
+                "elapsed_ms": entry.elapsed.as_millis() as u64,
+                "message": entry.message,
+            })),
+            _ => {}
+        }
+    }
+
+    /// Prints what arrived so far, e.g. the logs of a step that finished.
+    fn drain(&mut self, rx: &Receiver<AppUpdate>) {
+        while let Ok(update) = rx.try_recv() {
+            self.update(update);
+        }
+    }
+}
+
+/// The positions in `steps` of the named steps, all of them when none are.
+fn parse_steps(names: &[String], steps: &[Step]) -> Result<Vec<usize>> {
+    if names.is_empty() {
+        return Ok((0..steps.len()).collect());
+    }
+    names
+        .iter()
+        .map(|name| {
+            steps
+                .iter()
+                .position(|step| step.name.eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| {
+                    let known: Vec<_> = steps.iter().map(|step| step.name.to_lowercase()).collect();
+                    anyhow::anyhow!("Unknown step {name}, pick from {}", known.join(","))
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Failure, is_requested, parse_steps};
+    use crate::default_steps;
+    use crate::errors::ErrorKind;
+
+    #[test]
+    fn parses_step_names() {
+        let steps = default_steps();
+        assert_eq!(
+            parse_steps(&["copy".into(), "Goldberg".into()], &steps).unwrap(),
+            [0, 1]
+        );
+        assert_eq!(parse_steps(&[], &steps).unwrap().len(), steps.len());
+        assert!(parse_steps(&["unzip".into()], &steps).is_err());
+    }
+
+    #[test]
+    fn only_options_run_without_the_window() {
+        let requested = |args: &[&str]| is_requested(args.iter().map(|arg| arg.to_string()));
+        assert!(requested(&["--dest", "D:/AoE2"]));
+        assert!(requested(&["--steps=copy"]));
+        assert!(requested(&["--help"]));
+        assert!(!requested(&[]));
+        assert!(!requested(&["--portable"]));
+        assert!(!requested(&["C:/Games/AoE2DE"]));
+        assert!(!requested(&["--unknown"]));
+    }
+
+    #[test]
+    fn failures_have_distinct_codes() {
+        let failures = [
+            Failure::Other(anyhow::anyhow!("config")),
+            Failure::Usage(anyhow::anyhow!("steps")),
+            Failure::Source(anyhow::anyhow!("source")),
+            Failure::Prerequisite("unmet".into()),
+            Failure::Step {
+                name: "Copy",
+                error: "copy".into(),
+                cause: None,
+            },
+            Failure::Step {
+                name: "Test",
+                error: "test".into(),
+                cause: Some(ErrorKind::DiskFull),
+            },
+            Failure::Step {
+                name: "Goldberg",
+                error: "goldberg".into(),
+                cause: Some(ErrorKind::ChecksumMismatch),
+            },
+        ];
+        let codes: Vec<_> = failures.iter().map(Failure::code).collect();
+        assert_eq!(codes, [1, 2, 3, 4, 10, 11, 16]);
+    }
+}
@@ -1,37 +1,1027 @@
-use anyhow::Result;
-use serde::Deserialize;
-use std::fs::read_to_string;
+use crate::{
+    paths::{self, CONFIG_FILE},
+    settings::merge,
+};
+use anyhow::{Context as _, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf, sync::Once, time::Duration};
+use toml::Table;
+use toml_edit::DocumentMut;
+use tracing::{info, warn};
 
 const DEFAULT_CONFIG: &str = include_str!("../config.toml");
 
-#[derive(Deserialize)]
+/// The layout of config.toml, raised with every change older files need
+/// migrating for.
+const CONFIG_VERSION: i64 = 2;
+
+/// Bring config.toml from each version to the next, the first one from
+/// files older than the `version` key.
+const MIGRATIONS: [fn(&mut DocumentMut); CONFIG_VERSION as usize] =
+    [drop_default_goldberg_url, move_github_retries];
+
+/// Unknown keys are warned about once, not on every reload.
+static UNKNOWN_KEYS_WARNING: Once = Once::new();
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Config {
+    /// See [`CONFIG_VERSION`].
+    #[serde(default)]
+    pub version: i64,
     pub goldberg: Goldberg,
     pub aoe2: AoE2,
+    #[serde(default)]
+    pub cold_client_loader: ColdClientLoader,
+    #[serde(default)]
+    pub privacy: Privacy,
+    #[serde(default)]
+    pub lan: Lan,
+    #[serde(default)]
+    pub proxy: Proxy,
+    #[serde(default)]
+    pub github: GitHub,
+    #[serde(default)]
+    pub server: Server,
+    #[serde(default)]
+    pub firewall: Firewall,
+    #[serde(default)]
+    pub shortcuts: Shortcuts,
+    #[serde(default)]
+    pub smoke_test: SmokeTest,
+    #[serde(default)]
+    pub update: Update,
+    #[serde(default)]
+    pub redist: Redist,
+    #[serde(default)]
+    pub download: Download,
+    #[serde(default)]
+    pub retries: Retries,
 }
 
 impl Config {
     pub fn load() -> Result<Self> {
-        let config_str = if std::fs::exists("config.toml")? {
-            read_to_string("config.toml")?
-        } else {
-            DEFAULT_CONFIG.to_string()
+        Self::from_table(Self::table()?)
+    }
+
+    /// The config with the options saved from the UI applied over it.
+    pub fn load_with(options: &Table) -> Result<Self> {
+        let mut table = Self::table()?;
+        merge(&mut table, options);
+        match table.clone().try_into() {
+            Ok(config) => {
+                warn_unknown_keys(&table, &config)?;
+                Ok(config)
+            }
+            Err(err) => {
+                warn!("Ignoring the saved options: {err}");
+                Self::load()
+            }
+        }
+    }
+
+    /// The options of the config.toml the archiver ships with.
+    pub fn defaults() -> Result<Self> {
+        Self::from_table(toml::from_str(DEFAULT_CONFIG)?)
+    }
+
+    /// The options as a table, for comparing against config.toml.
+    pub fn to_table(&self) -> Result<Table> {
+        Ok(Table::try_from(self)?)
+    }
+
+    /// Writes the `keys` of `[section] key` into the user's config.toml,
+    /// keeping its comments and other options. Returns where it was written.
+    pub fn save_keys(&self, keys: &[(&str, &str)]) -> Result<PathBuf> {
+        let path = paths::user_config_file()?;
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => DEFAULT_CONFIG.to_string(),
+            Err(err) => {
+                return Err(err).with_context(|| format!("Unable to read {}", path.display()));
+            }
         };
-        Ok(toml::from_str(&config_str)?)
+        let mut doc: DocumentMut = text
+            .parse()
+            .with_context(|| format!("Invalid {CONFIG_FILE}"))?;
+        write_keys(&mut doc, &self.to_table()?, keys)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, doc.to_string())
+            .with_context(|| format!("Unable to write {}", path.display()))?;
+        Ok(path)
     }
+
+    fn table() -> Result<Table> {
+        toml::from_str(&Self::source()?).with_context(|| format!("Invalid {CONFIG_FILE}"))
+    }
+
+    /// Reads config.toml, migrated to [`CONFIG_VERSION`] when it's older.
+    /// The migrated file replaces it, the old one is kept next to it.
+    fn source() -> Result<String> {
+        let path = paths::config_file();
+        if !fs::exists(&path)? {
+            return Ok(DEFAULT_CONFIG.to_string());
+        }
+        let text = fs::read_to_string(&path)?;
+        let mut doc: DocumentMut = text
+            .parse()
+            .with_context(|| format!("Invalid {CONFIG_FILE}"))?;
+        let Some(from) = migrate(&mut doc)? else {
+            return Ok(text);
+        };
+        let backup = path.with_file_name(format!("config.v{from}.toml"));
+        match fs::write(&backup, &text).and_then(|()| fs::write(&path, doc.to_string())) {
+            Ok(()) => info!(
+                "Updated {CONFIG_FILE} from version {from} to {CONFIG_VERSION}, the old one is \
+                 kept as {}",
+                backup.display()
+            ),
+            Err(err) => warn!("Unable to save the updated {CONFIG_FILE}: {err}"),
+        }
+        Ok(doc.to_string())
+    }
+
+    /// Reads `table`, failing with the keys it lacks rather than only the
+    /// first one, and warning about the ones no option reads.
+    fn from_table(table: Table) -> Result<Self> {
+        let config: Self = match table.clone().try_into() {
+            Ok(config) => config,
+            Err(err) => {
+                let missing = missing_keys(&table);
+                if missing.is_empty() {
+                    return Err(anyhow!(err).context(format!("Invalid {CONFIG_FILE}")));
+                }
+                bail!("{CONFIG_FILE} lacks {}", missing.join(", "));
+            }
+        };
+        warn_unknown_keys(&table, &config)?;
+        Ok(config)
+    }
+}
+
+fn warn_unknown_keys(table: &Table, config: &Config) -> Result<()> {
+    let unknown = unknown_keys(table, &config.to_table()?, "");
+    if !unknown.is_empty() {
+        UNKNOWN_KEYS_WARNING.call_once(|| {
+            warn!(
+                "Ignoring unknown keys in {CONFIG_FILE}, misspelled or from another version: {}",
+                unknown.join(", ")
+            );
+        });
+    }
+    Ok(())
+}
+
+/// Migrates `doc` to [`CONFIG_VERSION`], returning the version it had. Files
+/// of newer archivers are left alone, the options they added are ignored.
+fn migrate(doc: &mut DocumentMut) -> Result<Option<i64>> {
+    let version = match doc.get("version") {
+        Some(version) => version
+            .as_integer()
+            .with_context(|| format!("The version in {CONFIG_FILE} isn't a number"))?,
+        None => 0,
+    };
+    if version > CONFIG_VERSION {
+        warn!(
+            "{CONFIG_FILE} is from a newer archiver (version {version}, this one reads up to \
+             {CONFIG_VERSION}), update the archiver for every option to apply"
+        );
+    }
+    if !(0..CONFIG_VERSION).contains(&version) {
+        return Ok(None);
+    }
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(doc);
+    }
+    doc["version"] = toml_edit::value(CONFIG_VERSION);
+    Ok(Some(version))
+}
+
+/// Earlier config.toml files set goldberg's download url to its latest
+/// release, which skips the checksum of the release lookup. The release
+/// lookup they then fall back to gets its repo and asset written out, as
+/// those files had none.
+fn drop_default_goldberg_url(doc: &mut DocumentMut) {
+    const OLD_DEFAULT: &str =
+        "https://github.com/Detanup01/gbe_fork/releases/latest/download/emu-win-release.7z";
+    let Some(goldberg) = doc.get_mut("goldberg").and_then(|g| g.as_table_like_mut()) else {
+        return;
+    };
+    if goldberg.get("download_url").and_then(|url| url.as_str()) != Some(OLD_DEFAULT) {
+        return;
+    }
+    goldberg.remove("download_url");
+    if !goldberg.contains_key("gh_user") {
+        goldberg.insert("gh_user", toml_edit::value(default_goldberg_gh_user()));
+    }
+    if !goldberg.contains_key("gh_repo") {
+        goldberg.insert("gh_repo", toml_edit::value(default_goldberg_gh_repo()));
+    }
+    if !goldberg.contains_key("asset_search") {
+        let search = toml_edit::Array::from_iter(default_goldberg_asset_search());
+        goldberg.insert("asset_search", toml_edit::value(search));
+    }
+}
+
+/// Version 1 only retried release lookups, configured in `[github]`. The
+/// `[retries]` section replacing it counts the first try too.
+fn move_github_retries(doc: &mut DocumentMut) {
+    let Some(github) = doc.get_mut("github").and_then(|g| g.as_table_like_mut()) else {
+        return;
+    };
+    let retries = github.remove("retries").and_then(|r| r.as_integer());
+    let delay = github.remove("retry_delay_ms").and_then(|d| d.as_integer());
+    if retries.is_none() && delay.is_none() {
+        return;
+    }
+    if doc.get("retries").is_none() {
+        doc["retries"] = toml_edit::table();
+    }
+    let section = &mut doc["retries"];
+    if let Some(retries) = retries {
+        section["max_attempts"] = toml_edit::value(retries + 1);
+    }
+    if let Some(delay) = delay {
+        section["backoff_ms"] = toml_edit::value(delay);
+    }
+}
+
+/// Sets the `keys` of `doc` to their values in `table`, removing the ones it
+/// leaves unset. Replaced values keep their comments.
+fn write_keys(doc: &mut DocumentMut, table: &Table, keys: &[(&str, &str)]) -> Result<()> {
+    for &(section, key) in keys {
+        let value = table.get(section).and_then(|s| s.get(key));
+        let entries = doc[section]
+            .or_insert(toml_edit::table())
+            .as_table_like_mut()
+            .ok_or_else(|| anyhow!("{section} in {CONFIG_FILE} is not a table"))?;
+        let Some(value) = value else {
+            entries.remove(key);
+            continue;
+        };
+        // toml writes the value, toml_edit parses it back for the document.
+        let written = toml::to_string(&Table::from_iter([(key.to_string(), value.clone())]))?;
+        let mut parsed: DocumentMut = written.parse()?;
+        let Some(mut item) = parsed.remove(key) else {
+            bail!("Unable to write {section}.{key}");
+        };
+        match entries.get_mut(key) {
+            Some(existing) => {
+                if let (Some(old), Some(new)) = (existing.as_value(), item.as_value_mut()) {
+                    *new.decor_mut() = old.decor().clone();
+                }
+                *existing = item;
+            }
+            None => {
+                entries.insert(key, item);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The keys of the default config.toml that `table` lacks and no default
+/// fills in, as `section.key`.
+fn missing_keys(table: &Table) -> Vec<String> {
+    let defaults: Table = toml::from_str(DEFAULT_CONFIG).expect("the default config is valid");
+    let mut missing = Vec::new();
+    missing_below(&defaults, table, &mut Vec::new(), &defaults, &mut missing);
+    missing
 }
 
-#[derive(Deserialize)]
+fn missing_below(
+    defaults: &Table,
+    table: &Table,
+    path: &mut Vec<String>,
+    root: &Table,
+    missing: &mut Vec<String>,
+) {
+    for (key, default) in defaults {
+        path.push(key.clone());
+        match table.get(key) {
+            Some(value) => {
+                if let (Some(defaults), Some(table)) = (default.as_table(), value.as_table()) {
+                    missing_below(defaults, table, path, root, missing);
+                }
+            }
+            // Required when the defaults don't load without it.
+            None if without(root, path).try_into::<Config>().is_err() => {
+                missing.push(path.join("."));
+            }
+            None => {}
+        }
+        path.pop();
+    }
+}
+
+/// `root` without the key at `path`.
+fn without(root: &Table, path: &[String]) -> Table {
+    let mut root = root.clone();
+    let mut table = &mut root;
+    for key in &path[..path.len() - 1] {
+        table = table
+            .get_mut(key)
+            .and_then(|value| value.as_table_mut())
+            .expect("the path leads through tables");
+    }
+    table.remove(&path[path.len() - 1]);
+    root
+}
+
+/// The keys of `table` the config didn't read, going by what `read`
+/// serializes back to. Arrays of tables aren't looked into.
+fn unknown_keys(table: &Table, read: &Table, prefix: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for (key, value) in table {
+        let path = format!("{prefix}{key}");
+        match (value.as_table(), read.get(key)) {
+            (_, None) => unknown.push(path),
+            (Some(table), Some(read)) => {
+                if let Some(read) = read.as_table() {
+                    unknown.extend(unknown_keys(table, read, &format!("{path}.")));
+                }
+            }
+            (None, Some(_)) => {}
+        }
+    }
+    unknown
+}
+
+fn default_launcher_version() -> Option<String> {
+    // Newer releases changed the config format patch_launcher_config edits.
+    Some("v1.11.2".to_string())
+}
+
+/// A pinned release tag, or `None` for the latest release.
+pub fn pinned_version(version: &Option<String>) -> Option<&str> {
+    version
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty() && !v.eq_ignore_ascii_case("latest"))
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Goldberg {
-    pub download_url: String,
+    /// Direct archive url. When set, the github release lookup is skipped.
+    #[serde(default)]
+    pub download_url: Option<String>,
+    /// A release archive downloaded by hand, used instead of downloading one.
+    #[serde(default)]
+    pub local_archive: Option<PathBuf>,
+    #[serde(default = "default_goldberg_gh_user")]
+    pub gh_user: String,
+    #[serde(default = "default_goldberg_gh_repo")]
+    pub gh_repo: String,
+    /// Consider prereleases when looking up the latest release.
+    #[serde(default)]
+    pub prerelease: bool,
+    /// Substrings the release asset name must contain.
+    #[serde(default = "default_goldberg_asset_search")]
+    pub asset_search: Vec<String>,
+    #[serde(default)]
+    pub layout: GoldbergLayout,
+    /// Ship the loader encrypted so antivirus doesn't quarantine it.
+    #[serde(default = "default_true")]
+    pub encrypt_loader: bool,
+    #[serde(default)]
+    pub injection: Injection,
+    /// A previously created archive to carry saves and user settings over from.
+    #[serde(default)]
+    pub import_from: Option<PathBuf>,
+    /// Copy the game profile (settings, hotkeys, single player saves) of
+    /// this machine into the archive.
+    #[serde(default)]
+    pub import_profile: bool,
+    /// Pull steam_settings from the Steam Web API instead of the bundled assets.
+    #[serde(default = "default_true")]
+    pub generate_steam_settings: bool,
+    /// Optional key for the full achievement/stat schema. Falls back to the
+    /// `STEAM_WEB_API_KEY` environment variable.
+    #[serde(default)]
+    pub steam_web_api_key: String,
+}
+
+fn default_goldberg_gh_user() -> String {
+    "Detanup01".to_string()
+}
+
+fn default_goldberg_gh_repo() -> String {
+    "gbe_fork".to_string()
+}
+
+fn default_goldberg_asset_search() -> Vec<String> {
+    vec!["emu-win-release".to_string()]
+}
+
+/// How the files we need are laid out inside a goldberg fork's release archive.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum GoldbergLayout {
+    /// Detanup01/gbe_fork and otavepto/gbe_fork: `release/steamclient_experimental/`.
+    #[default]
+    GbeFork,
+    /// Mr_Goldberg's original emulator: `experimental_steamclient/`.
+    Goldberg,
 }
 
-#[derive(Deserialize)]
+/// How goldberg gets into the game.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Injection {
+    /// Start the untouched game through the cold client loader.
+    #[default]
+    ColdClientLoader,
+    /// Replace the game's steam_api64.dll with goldberg's.
+    SteamApi,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct AoE2 {
-    #[allow(dead_code)]
     pub steam_folder: String,
     pub gh_companion_user: String,
     pub gh_companion_repo: String,
     pub gh_launcher_user: String,
     pub gh_launcher_repo: String,
+    /// A companion zip downloaded by hand, used instead of downloading one.
+    #[serde(default)]
+    pub companion_zip: Option<PathBuf>,
+    /// A launcher zip downloaded by hand, used instead of downloading one.
+    #[serde(default)]
+    pub launcher_zip: Option<PathBuf>,
+    /// A server zip downloaded by hand, used instead of downloading one.
+    #[serde(default)]
+    pub server_zip: Option<PathBuf>,
+    /// Release tag of the companion to install, or "latest".
+    #[serde(default)]
+    pub companion_version: Option<String>,
+    /// Release tag of the launcher to install, or "latest".
+    #[serde(default = "default_launcher_version")]
+    pub launcher_version: Option<String>,
+    /// Let "latest" pick companion prereleases.
+    #[serde(default)]
+    pub companion_prerelease: bool,
+    /// Let "latest" pick launcher prereleases.
+    #[serde(default)]
+    pub launcher_prerelease: bool,
+    /// Let "latest" pick server prereleases.
+    #[serde(default)]
+    pub server_prerelease: bool,
+    /// Expected SHA-256 of the companion zip, instead of the one published
+    /// with the release.
+    #[serde(default)]
+    pub companion_sha256: Option<String>,
+    /// Expected SHA-256 of the launcher zip.
+    #[serde(default)]
+    pub launcher_sha256: Option<String>,
+    /// Substrings the release asset names must contain.
+    #[serde(default = "default_companion_asset_search")]
+    pub companion_asset_search: Vec<String>,
+    #[serde(default = "default_launcher_asset_search")]
+    pub launcher_asset_search: Vec<String>,
+    #[serde(default = "default_server_asset_search")]
+    pub server_asset_search: Vec<String>,
+    /// The steam language code the game runs in, goldberg's default when unset.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Expansions and other content with their own steam app id.
+    #[serde(default)]
+    pub dlcs: Vec<Dlc>,
+    /// Which files of the companion zip to install, and where.
+    #[serde(default = "default_companion_components")]
+    pub companion_components: Vec<CompanionComponent>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CompanionComponent {
+    pub name: String,
+    /// Substrings of the file names in the companion zip that belong to this
+    /// component. Empty for all files not claimed by an earlier component.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Folder relative to the archive the files are written to.
+    pub dest: String,
+    /// Drop the folders the files are in within the zip.
+    #[serde(default)]
+    pub flatten: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_companion_components() -> Vec<CompanionComponent> {
+    vec![
+        CompanionComponent {
+            name: "Game dlls".to_string(),
+            patterns: vec!["age2".to_string(), "fakehost".to_string()],
+            dest: "goldberg/dlls".to_string(),
+            flatten: true,
+            enabled: true,
+        },
+        CompanionComponent {
+            name: "Other tools".to_string(),
+            patterns: vec![],
+            dest: "companion".to_string(),
+            flatten: false,
+            enabled: false,
+        },
+    ]
+}
+
+fn default_companion_asset_search() -> Vec<String> {
+    vec!["_full_".to_string()]
+}
+
+fn default_launcher_asset_search() -> Vec<String> {
+    vec!["_full_".to_string(), "win_x86-64".to_string()]
+}
+
+fn default_server_asset_search() -> Vec<String> {
+    vec!["_server_".to_string(), "win_x86-64".to_string()]
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Dlc {
+    pub app_id: u32,
+    pub name: String,
+    /// Folder relative to the game directory that holds this dlc's content.
+    pub content_dir: Option<String>,
+}
+
+/// Options of goldberg's `ColdClientLoader.ini` beyond what the archive needs to run.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ColdClientLoader {
+    /// Extra arguments passed to the game. Written to `launch.ini`, where
+    /// players can change them from launcher.exe.
+    pub exe_command_line: String,
+    /// `[Persistence] Mode`, 0 disables persistence.
+    pub persistence_mode: u8,
+    pub ignore_loader_arch_difference: bool,
+    /// Suspend the game on start until a debugger resumes it.
+    pub resume_by_debugger: bool,
+    /// Dlls copied into the injection folder next to the companion.
+    pub extra_dlls: Vec<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Privacy {
+    /// Replace personal data found in the archive instead of only reporting it.
+    #[serde(default)]
+    pub rewrite: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Lan {
+    /// Addresses goldberg broadcasts to besides the local subnet, for lan
+    /// parties spanning several subnets or a vpn.
+    #[serde(default)]
+    pub custom_broadcasts: Vec<String>,
+    /// The port goldberg listens on, its default when unset.
+    #[serde(default)]
+    pub listen_port: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Proxy {
+    /// A proxy every download goes through, e.g. `http://proxy.corp:8080`.
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Use the proxy from the `HTTPS_PROXY`/`HTTP_PROXY`/`ALL_PROXY`
+    /// environment variables when no url is set.
+    #[serde(default = "default_true")]
+    pub from_env: bool,
+}
+
+impl Default for Proxy {
+    fn default() -> Self {
+        Self {
+            url: None,
+            username: None,
+            password: None,
+            from_env: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct GitHub {
+    /// A personal access token, raising the api rate limit from 60 to 5000
+    /// requests an hour. Falls back to the `GITHUB_TOKEN` environment variable.
+    #[serde(default)]
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Retries {
+    /// How often an operation is tried in all, 1 to not retry.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// The delay before the first retry, doubled for every following one.
+    #[serde(default = "default_backoff_ms")]
+    pub backoff_ms: u64,
+    /// The operations that are retried, the others are tried once.
+    #[serde(default = "default_retried_operations")]
+    pub operations: Vec<RetryOperation>,
+}
+
+impl Default for Retries {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            backoff_ms: default_backoff_ms(),
+            operations: default_retried_operations(),
+        }
+    }
+}
+
+impl Retries {
+    /// How often `operation` is tried in all.
+    pub fn attempts(&self, operation: RetryOperation) -> u32 {
+        if self.operations.contains(&operation) {
+            self.max_attempts.max(1)
+        } else {
+            1
+        }
+    }
+
+    /// How long to wait after the `attempt`th try failed.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(self.backoff_ms).saturating_mul(factor)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryOperation {
+    /// Asking the github api for releases. Failing that, the cached release
+    /// info is used.
+    ReleaseLookup,
+    /// Downloads, and resuming them when the connection drops.
+    Download,
+    /// Copying a game file, e.g. while antivirus is scanning it.
+    FileCopy,
+}
+
+fn default_max_attempts() -> u32 {
+    4
+}
+
+fn default_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_retried_operations() -> Vec<RetryOperation> {
+    vec![
+        RetryOperation::ReleaseLookup,
+        RetryOperation::Download,
+        RetryOperation::FileCopy,
+    ]
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Server {
+    /// Install the ageLANServer into the archive so it can host.
+    #[serde(default)]
+    pub install: bool,
+    /// The machine hosting the lan server. Empty to let the launcher find one.
+    #[serde(default)]
+    pub host: String,
+    #[serde(default)]
+    pub start: ServerStart,
+}
+
+/// Whether the archive's launcher starts a lan server itself.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerStart {
+    /// Start one when none is found on the network.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ServerStart {
+    /// The launcher's `Start` setting.
+    pub fn launcher_value(self) -> &'static str {
+        match self {
+            ServerStart::Auto => "auto",
+            ServerStart::Always => "true",
+            ServerStart::Never => "false",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Firewall {
+    /// Add a setup_firewall.bat to the archive.
+    #[serde(default = "default_true")]
+    pub generate_script: bool,
+    /// Run it elevated on this machine after creating the archive.
+    #[serde(default)]
+    pub install_rules: bool,
+    #[serde(default = "default_server_tcp_ports")]
+    pub server_tcp_ports: Vec<u16>,
+    #[serde(default = "default_server_udp_ports")]
+    pub server_udp_ports: Vec<u16>,
+}
+
+impl Default for Firewall {
+    fn default() -> Self {
+        Self {
+            generate_script: true,
+            install_rules: false,
+            server_tcp_ports: default_server_tcp_ports(),
+            server_udp_ports: default_server_udp_ports(),
+        }
+    }
+}
+
+fn default_server_tcp_ports() -> Vec<u16> {
+    vec![443]
+}
+
+fn default_server_udp_ports() -> Vec<u16> {
+    vec![31978]
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Shortcuts {
+    /// Add a create_shortcuts.exe to the archive.
+    #[serde(default = "default_true")]
+    pub add_helper: bool,
+    /// Create shortcuts to the archive on this machine.
+    #[serde(default)]
+    pub desktop: bool,
+    #[serde(default)]
+    pub start_menu: bool,
+    /// Add the archive to steam as a non-steam game on this machine.
+    #[serde(default)]
+    pub steam: bool,
+}
+
+impl Default for Shortcuts {
+    fn default() -> Self {
+        Self {
+            add_helper: true,
+            desktop: false,
+            start_menu: false,
+            steam: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SmokeTest {
+    /// Start the archived game after creating the archive.
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long the game has to keep running to pass.
+    #[serde(default = "default_alive_seconds")]
+    pub alive_seconds: u64,
+}
+
+impl Default for SmokeTest {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            alive_seconds: default_alive_seconds(),
+        }
+    }
+}
+
+fn default_alive_seconds() -> u64 {
+    30
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Update {
+    /// A folder (e.g. a network share) or url holding an up to date copy of
+    /// the archive, which archives check for changes when starting.
+    #[serde(default)]
+    pub source: String,
+    /// SHA-256 of the source's update_manifest.txt. Archives only take
+    /// executables from a source whose manifest matches it.
+    #[serde(default)]
+    pub manifest_sha256: String,
+    /// Let the archive's launcher look for newer releases of the archiver.
+    #[serde(default)]
+    pub check_releases: bool,
+    /// The github repo `owner/name` of the archiver's releases.
+    #[serde(default = "default_archiver_repo")]
+    pub archiver_repo: String,
+}
+
+impl Default for Update {
+    fn default() -> Self {
+        Self {
+            source: String::new(),
+            manifest_sha256: String::new(),
+            check_releases: false,
+            archiver_repo: default_archiver_repo(),
+        }
+    }
+}
+
+fn default_archiver_repo() -> String {
+    "codabrink/Age-of-Empires-2-DE-Archiver".to_string()
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Redist {
+    /// Bundle the Visual C++ runtime installer.
+    #[serde(default = "default_true")]
+    pub vc: bool,
+    /// Bundle the offline DirectX End-User Runtime installer.
+    #[serde(default)]
+    pub directx: bool,
+    #[serde(default = "default_vc_redist_url")]
+    pub vc_url: String,
+    #[serde(default = "default_directx_redist_url")]
+    pub directx_url: String,
+    /// Expected SHA-256 of the installers. Pinned installers are checked
+    /// and kept in the download cache across runs.
+    #[serde(default)]
+    pub vc_sha256: Option<String>,
+    #[serde(default)]
+    pub directx_sha256: Option<String>,
+}
+
+impl Default for Redist {
+    fn default() -> Self {
+        Self {
+            vc: true,
+            directx: false,
+            vc_url: default_vc_redist_url(),
+            directx_url: default_directx_redist_url(),
+            vc_sha256: None,
+            directx_sha256: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Download {
+    /// Tried in order when a download fails, with the download's url
+    /// appended, e.g. `https://ghproxy.net/` for networks blocking github.
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    /// How long a server may take to respond, in seconds.
+    #[serde(default = "default_response_timeout_secs")]
+    pub response_timeout_secs: u64,
+    /// How long a download may receive nothing before it's resumed, in
+    /// seconds.
+    #[serde(default = "default_read_timeout_secs")]
+    pub read_timeout_secs: u64,
+}
+
+impl Default for Download {
+    fn default() -> Self {
+        Self {
+            mirrors: Vec::new(),
+            response_timeout_secs: default_response_timeout_secs(),
+            read_timeout_secs: default_read_timeout_secs(),
+        }
+    }
+}
+
+fn default_response_timeout_secs() -> u64 {
+    30
+}
+
+fn default_read_timeout_secs() -> u64 {
+    60
+}
+
+fn default_vc_redist_url() -> String {
+    "https://aka.ms/vs/17/release/vc_redist.x64.exe".to_string()
+}
+
+fn default_directx_redist_url() -> String {
+    "https://download.microsoft.com/download/8/4/A/84A35BF1-DAFE-4AE8-82AF-AD2AE20B6B14/directx_Jun2010_redist.exe".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CONFIG_VERSION, Config, DEFAULT_CONFIG, migrate, missing_keys, unknown_keys, write_keys,
+    };
+    use toml::Table;
+    use toml_edit::DocumentMut;
+
+    /// config.toml as the first release shipped it.
+    const BASELINE_CONFIG: &str = r#"[goldberg]
+download_url = "https://github.com/Detanup01/gbe_fork/releases/latest/download/emu-win-release.7z"
+
+[aoe2]
+steam_folder = "AoE2DE"
+gh_companion_user = "luskaner"
+gh_companion_repo = "ageLANServerLauncherCompanion"
+gh_launcher_user = "luskaner"
+gh_launcher_repo = "ageLANServer"
+"#;
+
+    #[test]
+    fn migrates_the_first_config() {
+        let mut doc: DocumentMut = BASELINE_CONFIG.parse().unwrap();
+        assert_eq!(migrate(&mut doc).unwrap(), Some(0));
+        let goldberg = &doc["goldberg"];
+        assert!(goldberg.get("download_url").is_none());
+        assert_eq!(goldberg["gh_user"].as_str(), Some("Detanup01"));
+        assert_eq!(goldberg["gh_repo"].as_str(), Some("gbe_fork"));
+        assert_eq!(
+            goldberg["asset_search"][0].as_str(),
+            Some("emu-win-release")
+        );
+
+        let table: Table = toml::from_str(&doc.to_string()).unwrap();
+        assert!(missing_keys(&table).is_empty());
+        let config: Config = table.try_into().unwrap();
+        assert_eq!(config.aoe2.gh_launcher_repo, "ageLANServer");
+    }
+
+    #[test]
+    fn migrates_unversioned_configs() {
+        let mut doc: DocumentMut = "[goldberg]\ndownload_url = \
+            \"https://github.com/Detanup01/gbe_fork/releases/latest/download/emu-win-release.7z\"\n\
+            gh_user = \"Detanup01\"\n"
+            .parse()
+            .unwrap();
+        assert_eq!(migrate(&mut doc).unwrap(), Some(0));
+        assert_eq!(doc["version"].as_integer(), Some(CONFIG_VERSION));
+        assert!(doc["goldberg"].get("download_url").is_none());
+        assert_eq!(doc["goldberg"]["gh_user"].as_str(), Some("Detanup01"));
+        assert_eq!(migrate(&mut doc).unwrap(), None);
+    }
+
+    #[test]
+    fn moves_github_retries() {
+        let mut doc: DocumentMut =
+            "version = 1\n[github]\ntoken = \"\"\nretries = 2\nretry_delay_ms = 500\n"
+                .parse()
+                .unwrap();
+        assert_eq!(migrate(&mut doc).unwrap(), Some(1));
+        assert_eq!(doc["retries"]["max_attempts"].as_integer(), Some(3));
+        assert_eq!(doc["retries"]["backoff_ms"].as_integer(), Some(500));
+        assert!(doc["github"].get("retries").is_none());
+        assert!(doc["github"].get("token").is_some());
+    }
+
+    #[test]
+    fn lists_missing_and_unknown_keys() {
+        let mut table: Table = toml::from_str(DEFAULT_CONFIG).unwrap();
+        let aoe2 = table["aoe2"].as_table_mut().unwrap();
+        aoe2.remove("gh_launcher_user");
+        aoe2.remove("gh_launcher_repo");
+        // Have defaults.
+        aoe2.remove("launcher_prerelease");
+        aoe2.remove("server_prerelease");
+        let goldberg = table["goldberg"].as_table_mut().unwrap();
+        goldberg.remove("gh_user");
+        table.remove("privacy");
+        let mut missing = missing_keys(&table);
+        missing.sort();
+        assert_eq!(missing, ["aoe2.gh_launcher_repo", "aoe2.gh_launcher_user"]);
+
+        let read: Table = toml::from_str("version = 1\n[proxy]\nfrom_env = true\n").unwrap();
+        let table: Table =
+            toml::from_str("version = 1\ntypo = 1\n[proxy]\nfrom_env = true\nurll = \"\"\n")
+                .unwrap();
+        let mut unknown = unknown_keys(&table, &read, "");
+        unknown.sort();
+        assert_eq!(unknown, ["proxy.urll", "typo"]);
+    }
+
+    #[test]
+    fn writes_keys_keeping_comments() {
+        let mut doc: DocumentMut = "[aoe2]\n# Pinned for the config format.\n\
+            launcher_version = \"v1.11.2\" # tag\nlauncher_sha256 = \"ab\"\n"
+            .parse()
+            .unwrap();
+        let table: Table = toml::from_str(
+            "[aoe2]\nlauncher_version = \"v1.12.0\"\n[download]\nmirrors = [\"https://a/\"]\n",
+        )
+        .unwrap();
+        let keys = [
+            ("aoe2", "launcher_version"),
+            ("aoe2", "launcher_sha256"),
+            ("download", "mirrors"),
+        ];
+        write_keys(&mut doc, &table, &keys).unwrap();
+        let text = doc.to_string();
+        assert!(
+            text.contains("# Pinned for the config format.\nlauncher_version = \"v1.12.0\" # tag")
+        );
+        assert!(!text.contains("launcher_sha256"));
+        assert_eq!(doc["download"]["mirrors"][0].as_str(), Some("https://a/"));
+    }
 }
@@ -1,43 +1,422 @@
-use crate::{AppUpdate, config::Config, steam::steam_aoe2_path, utils::desktop_dir};
-use anyhow::{Result, bail};
+use crate::{
+    AppUpdate, FileProgress, StepProgress,
+    cache::DownloadCache,
+    config::{Config, Proxy},
+    default_steps,
+    download::{HttpClients, ReleaseAsset, http_client},
+    errors::{ErrorKind, classify},
+    eta::{self, Timings},
+    events::EventBus,
+    history::{Run, push_run},
+    i18n::{self, Language},
+    resume::{self, RunState},
+    settings::{Layout, Preset, Settings, Theme, diff, expand_destination, push_recent},
+    source_checks,
+    steam::steam_aoe2_path,
+    tasks::Supervisor,
+    temp::TempDir,
+    utils::{desktop_dir, validate_aoe2_source},
+};
+use anyhow::{Result, anyhow, bail};
+use common::{GAME_PROFILES_PATH, profile::newest_profile};
 use eframe::egui::Color32;
 use fs_extra::dir::get_size;
 use fs2::available_space;
+use serde::{Deserialize, Serialize};
 use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
     path::PathBuf,
-    sync::{Arc, Mutex, mpsc::Sender},
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
 };
+use tokio::{sync::Notify, task::JoinHandle};
+use toml::Table;
+use tracing::{info, warn};
+
+type Fetched = Result<(ReleaseAsset, PathBuf)>;
+
+thread_local! {
+    // Set on prefetch threads, which stop with the task that started them
+    // rather than the one running.
+    static TOKEN: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+    // Set on prefetch threads, whose progress would fight the running step's.
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
 
 pub struct Context {
-    pub config: Config,
-    pub tx: Sender<AppUpdate>,
+    config: RwLock<Arc<Config>>,
+    pub events: EventBus,
+    pub temp: TempDir,
+    pub cache: DownloadCache,
+    /// Runs the downloads, see [`Supervisor`].
+    pub tasks: Supervisor,
+    /// Shared by every request so proxy settings apply everywhere, with the
+    /// proxy it was built for.
+    http: Mutex<(Proxy, HttpClients)>,
     sourcedir: Mutex<Option<PathBuf>>,
     outdir: Mutex<PathBuf>,
-    current_task: Mutex<Option<Task>>,
-    pub step_status: Mutex<[StepStatus; 4]>,
+    current_task: Mutex<Option<StepId>>,
+    /// Cancelled by the Cancel button, checked by copies, downloads and
+    /// extractions of the running task. Each task gets a new one, except
+    /// within a run, see below.
+    cancellation: Mutex<CancellationToken>,
+    /// The token of a run of several steps, which each of them gets, so a
+    /// cancel between two steps stops the next one too.
+    run: Mutex<Option<CancellationToken>>,
+    /// Picked when the destination holds game files of an earlier run.
+    overwrite: Mutex<OverwritePolicy>,
+    /// Archives to create one after another, see [`Context::queue_job`].
+    jobs: Mutex<Vec<Job>>,
+    phase: Mutex<Option<Phase>>,
+    /// File names of the companion dlls the companion step installed.
+    pub companion_dlls: Mutex<Vec<String>>,
+    /// Downloads started ahead of the step that installs them, by step name.
+    prefetched: Mutex<HashMap<&'static str, (CancellationToken, JoinHandle<Fetched>)>>,
+    /// The steps in the order Run All Steps runs them, with how they went.
+    steps: Mutex<Vec<Step>>,
+    /// Saved on exit, see [`Context::save_settings`].
+    settings: Mutex<Settings>,
 }
 
 impl Context {
-    pub fn new(tx: Sender<AppUpdate>) -> Result<Self> {
+    pub fn new(events: EventBus) -> Result<Self> {
+        let settings = Settings::load();
+        let config = if settings.remember {
+            Config::load_with(&settings.options)?
+        } else {
+            Config::load()?
+        };
+        Self::with(events, config, settings)
+    }
+
+    /// A context with the given config and settings rather than the saved
+    /// ones.
+    pub fn with(events: EventBus, config: Config, settings: Settings) -> Result<Self> {
+        let sourcedir = settings
+            .sourcedir
+            .clone()
+            .filter(|dir| settings.remember && validate_aoe2_source(dir).is_ok());
+        let outdir = settings.outdir.clone().filter(|_| settings.remember);
+        i18n::set_language(settings.language);
+
         let ctx = Self {
-            tx,
-            config: Config::load()?,
+            events,
+            http: Mutex::new((config.proxy.clone(), http_client(&config.proxy)?)),
+            config: RwLock::new(Arc::new(config)),
+            temp: TempDir::new()?,
+            cache: DownloadCache::new()?,
+            tasks: Supervisor::new()?,
             sourcedir: Mutex::default(),
             outdir: Mutex::default(),
             current_task: Mutex::default(),
-
-            step_status: Mutex::new([const { StepStatus::NotStarted }; 4]),
+            cancellation: Mutex::default(),
+            run: Mutex::default(),
+            overwrite: Mutex::default(),
+            jobs: Mutex::default(),
+            phase: Mutex::default(),
+            companion_dlls: Mutex::default(),
+            prefetched: Mutex::default(),
+            steps: Mutex::default(),
+            settings: Mutex::new(settings),
         };
+        for step in default_steps() {
+            ctx.register_step(step);
+        }
 
-        if let Some(source) = steam_aoe2_path()? {
+        let sourcedir = match sourcedir {
+            Some(dir) => Some(dir),
+            None => steam_aoe2_path()?,
+        };
+        if let Some(source) = sourcedir {
             ctx.set_sourcedir(source);
         }
 
-        ctx.set_outdir(desktop_dir()?.join("AoE2"));
+        // Mods make the profile big enough to measure in the background.
+        std::thread::spawn({
+            let events = ctx.events.clone();
+            move || {
+                let size = dirs::home_dir()
+                    .and_then(|home| newest_profile(&home.join(GAME_PROFILES_PATH), None))
+                    .and_then(|profile| get_size(profile).ok());
+                if let Some(size) = size {
+                    events.send(AppUpdate::ProfileSize(size));
+                }
+            }
+        });
+
+        let outdir = match outdir {
+            Some(dir) => dir,
+            None => desktop_dir()?.join("AoE2"),
+        };
+        ctx.set_outdir(outdir);
 
         Ok(ctx)
     }
 
+    pub fn remember_settings(&self) -> bool {
+        self.settings.lock().unwrap().remember
+    }
+
+    pub fn set_remember_settings(&self, remember: bool) {
+        self.settings.lock().unwrap().remember = remember;
+    }
+
+    pub fn language(&self) -> Language {
+        self.settings.lock().unwrap().language
+    }
+
+    pub fn set_language(&self, language: Language) {
+        i18n::set_language(language);
+        self.settings.lock().unwrap().language = language;
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.settings.lock().unwrap().layout
+    }
+
+    pub fn set_layout(&self, layout: Layout) {
+        self.settings.lock().unwrap().layout = layout;
+    }
+
+    pub fn theme(&self) -> Theme {
+        self.settings.lock().unwrap().theme
+    }
+
+    pub fn set_theme(&self, theme: Theme) {
+        self.settings.lock().unwrap().theme = theme;
+    }
+
+    pub fn ui_scale(&self) -> f32 {
+        self.settings.lock().unwrap().ui_scale
+    }
+
+    pub fn set_ui_scale(&self, scale: f32) {
+        self.settings.lock().unwrap().ui_scale = scale;
+    }
+
+    pub fn font_size(&self) -> f32 {
+        self.settings.lock().unwrap().font_size
+    }
+
+    pub fn set_font_size(&self, size: f32) {
+        self.settings.lock().unwrap().font_size = size;
+    }
+
+    pub fn check_archiver_updates(&self) -> bool {
+        self.settings.lock().unwrap().check_archiver_updates
+    }
+
+    pub fn set_check_archiver_updates(&self, check: bool) {
+        self.settings.lock().unwrap().check_archiver_updates = check;
+    }
+
+    pub fn recent_sourcedirs(&self) -> Vec<PathBuf> {
+        self.settings.lock().unwrap().recent_sourcedirs.clone()
+    }
+
+    pub fn recent_outdirs(&self) -> Vec<PathBuf> {
+        self.settings.lock().unwrap().recent_outdirs.clone()
+    }
+
+    /// Sets the source directory picked in the UI, listing it in the recent
+    /// ones when remembering settings.
+    pub fn pick_sourcedir(&self, path: PathBuf) {
+        let mut settings = self.settings.lock().unwrap();
+        if settings.remember {
+            push_recent(&mut settings.recent_sourcedirs, &path);
+        }
+        drop(settings);
+        self.set_sourcedir(path);
+    }
+
+    /// Sets the destination directory picked in the UI, listing it in the
+    /// recent ones when remembering settings.
+    pub fn pick_outdir(&self, path: PathBuf) {
+        let mut settings = self.settings.lock().unwrap();
+        if settings.remember {
+            push_recent(&mut settings.recent_outdirs, &path);
+        }
+        drop(settings);
+        self.set_outdir(path);
+    }
+
+    /// Saves the directories and the options changed in the UI for the next
+    /// run, when remembering them. The proxy password and github token are
+    /// left out, they're only kept in config.toml.
+    pub fn save_settings(&self) -> Result<()> {
+        let mut settings = self.settings.lock().unwrap();
+        if settings.remember {
+            settings.sourcedir = self.sourcedir();
+            settings.outdir = Some(self.outdir());
+            let mut options = self.changed_options()?;
+            options.remove("github");
+            if let Some(proxy) = options.get_mut("proxy").and_then(|p| p.as_table_mut()) {
+                proxy.remove("password");
+            }
+            settings.options = options;
+        }
+        settings.save()
+    }
+
+    /// Forgets the saved directories and options, going back to config.toml.
+    pub fn reset_settings(&self) -> Result<()> {
+        *self.config.write().unwrap() = Arc::new(Config::load()?);
+        let mut settings = self.settings.lock().unwrap();
+        *settings = Settings {
+            remember: settings.remember,
+            theme: settings.theme,
+            language: settings.language,
+            layout: settings.layout,
+            ui_scale: settings.ui_scale,
+            font_size: settings.font_size,
+            presets: std::mem::take(&mut settings.presets),
+            check_archiver_updates: settings.check_archiver_updates,
+            timings: std::mem::take(&mut settings.timings),
+            history: std::mem::take(&mut settings.history),
+            ..Settings::default()
+        };
+        settings.save()
+    }
+
+    pub fn presets(&self) -> Vec<Preset> {
+        self.settings.lock().unwrap().presets.clone()
+    }
+
+    /// Saves the current options as a preset, replacing the one with the same
+    /// name. The proxy and github settings belong to this machine and aren't
+    /// part of it.
+    pub fn save_preset(&self, name: &str, destination: &str) -> Result<()> {
+        let mut options = self.changed_options()?;
+        options.remove("proxy");
+        options.remove("github");
+        let mut settings = self.settings.lock().unwrap();
+        settings.presets.retain(|preset| preset.name != name);
+        settings.presets.push(Preset {
+            name: name.to_string(),
+            destination: destination.trim().to_string(),
+            options,
+        });
+        settings.save()
+    }
+
+    pub fn history(&self) -> Vec<Run> {
+        self.settings.lock().unwrap().history.clone()
+    }
+
+    /// Adds the run of all steps that just ended in the destination to the
+    /// history, with `status` how it went.
+    pub fn record_run(&self, status: &StepStatus) -> Result<()> {
+        let run = Run::new(self.outdir(), status);
+        let mut settings = self.settings.lock().unwrap();
+        push_run(&mut settings.history, run);
+        settings.save()
+    }
+
+    pub fn remove_run(&self, i: usize) -> Result<()> {
+        let mut settings = self.settings.lock().unwrap();
+        if i < settings.history.len() {
+            settings.history.remove(i);
+        }
+        settings.save()
+    }
+
+    pub fn delete_preset(&self, name: &str) -> Result<()> {
+        let mut settings = self.settings.lock().unwrap();
+        settings.presets.retain(|preset| preset.name != name);
+        settings.save()
+    }
+
+    /// Switches to a preset's options over config.toml and to its
+    /// destination, if it has one.
+    pub fn apply_preset(&self, name: &str) -> Result<()> {
+        let preset = self
+            .presets()
+            .into_iter()
+            .find(|preset| preset.name == name)
+            .ok_or_else(|| anyhow!("No preset named {name}"))?;
+        self.apply_options(&preset.options)?;
+        if !preset.destination.is_empty() {
+            self.set_outdir(expand_destination(&preset.destination));
+        }
+        info!("Applied the {name} preset");
+        Ok(())
+    }
+
+    /// Switches to `options` over config.toml, keeping this machine's proxy
+    /// and github settings.
+    fn apply_options(&self, options: &Table) -> Result<()> {
+        let mut config = Config::load_with(options)?;
+        let current = self.config();
+        config.proxy = current.proxy.clone();
+        config.github = current.github.clone();
+        *self.config.write().unwrap() = Arc::new(config);
+        Ok(())
+    }
+
+    /// The options changed from config.toml.
+    pub fn changed_options(&self) -> Result<Table> {
+        Ok(diff(
+            &Config::load()?.to_table()?,
+            &self.config().to_table()?,
+        ))
+    }
+
+    /// Goes back to the source and options of the unfinished run in the
+    /// destination, with the `done` steps as they went then.
+    pub fn restore_run(&self, state: &RunState, done: &[Step]) -> Result<()> {
+        self.reset_steps();
+        self.apply_options(&state.options)?;
+        if let Some(source) = &state.source {
+            self.set_sourcedir(source.clone());
+        }
+        // The files copied already are kept, the rest are copied over them.
+        self.set_overwrite_policy(OverwritePolicy::Merge);
+        for step in done {
+            if let Some(status) = state.steps.get(step.name) {
+                self.set_step_status(step.id, status.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// A snapshot of the current config. Steps should take one snapshot and
+    /// use it throughout, so UI edits don't change settings mid-step.
+    pub fn config(&self) -> Arc<Config> {
+        self.config.read().unwrap().clone()
+    }
+
+    pub fn update_config(&self, update: impl FnOnce(&mut Config)) {
+        let mut config = self.config.write().unwrap();
+        update(Arc::make_mut(&mut config));
+    }
+
+    /// The client for requests, rebuilt when the proxy was changed in the UI.
+    /// An invalid proxy keeps the last valid one.
+    pub fn http(&self) -> reqwest::blocking::Client {
+        self.http_clients().blocking
+    }
+
+    /// The async client for downloads, see [`Context::http`].
+    pub fn download_client(&self) -> reqwest::Client {
+        self.http_clients().downloads
+    }
+
+    fn http_clients(&self) -> HttpClients {
+        let proxy = self.config().proxy.clone();
+        let mut http = self.http.lock().unwrap();
+        if http.0 != proxy {
+            match http_client(&proxy) {
+                Ok(client) => *http = (proxy, client),
+                Err(err) => warn!("Keeping the previous proxy: {err:#}"),
+            }
+        }
+        http.1.clone()
+    }
+
     pub fn sourcedir(&self) -> Option<PathBuf> {
         self.sourcedir.lock().unwrap().clone()
     }
@@ -48,38 +427,408 @@ impl Context {
 
     pub fn set_sourcedir(&self, path: PathBuf) {
         // Get sizes and check disk space
-        if let Ok(dir_size) = get_size(&path) {
-            let _ = self.tx.send(AppUpdate::SourceSize(dir_size));
+        let dir_size = get_size(&path).ok();
+        if let Some(dir_size) = dir_size {
+            self.events.send(AppUpdate::SourceSize(dir_size));
         }
+        source_checks::spawn(self.events.clone(), path.clone(), dir_size);
 
         *self.sourcedir.lock().unwrap() = Some(path);
     }
 
     pub fn set_outdir(&self, path: PathBuf) {
         if let Ok(disk_size) = available_space(&path) {
-            let _ = self.tx.send(AppUpdate::DestDriveAvailable(disk_size));
-        } else if let Some(parent) = path.parent() {
-            if let Ok(disk_size) = available_space(&parent) {
-                let _ = self.tx.send(AppUpdate::DestDriveAvailable(disk_size));
-            }
+            self.events.send(AppUpdate::DestDriveAvailable(disk_size));
+        } else if let Some(parent) = path.parent()
+            && let Ok(disk_size) = available_space(parent)
+        {
+            self.events.send(AppUpdate::DestDriveAvailable(disk_size));
         }
+        resume::check(self.events.clone(), self.steps(), path.clone());
 
         *self.outdir.lock().unwrap() = path;
     }
 
-    pub fn set_step_status(&self, step: usize, status: StepStatus) {
-        if let Ok(mut steps) = self.step_status.lock() {
-            if step < steps.len() {
-                steps[step] = status;
+    /// Adds a step after the ones registered before, not run yet. Replaces
+    /// a registered step with the same id in its place.
+    pub fn register_step(&self, step: Step) {
+        let mut steps = self.steps.lock().unwrap();
+        match steps.iter_mut().find(|registered| registered.id == step.id) {
+            Some(registered) => *registered = step,
+            None => steps.push(step),
+        }
+    }
+
+    /// The registered steps with how they went, in the order they run.
+    pub fn steps(&self) -> Vec<Step> {
+        self.steps.lock().unwrap().clone()
+    }
+
+    pub fn step_statuses(&self) -> Vec<StepStatus> {
+        self.steps
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|step| step.status.clone())
+            .collect()
+    }
+
+    /// How the step went, not started for steps that aren't registered.
+    pub fn step_status(&self, id: StepId) -> StepStatus {
+        self.steps
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|step| step.id == id)
+            .map_or(StepStatus::NotStarted, |step| step.status.clone())
+    }
+
+    /// The kind of error the step failed with.
+    pub fn step_error(&self, id: StepId) -> Option<ErrorKind> {
+        self.steps
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|step| step.id == id)
+            .and_then(|step| step.error)
+    }
+
+    pub fn set_step_status(&self, id: StepId, status: StepStatus) {
+        self.update_step(id, status, None);
+    }
+
+    /// Marks the step failed with `err`, remembering its kind for the advice
+    /// shown with it.
+    pub fn fail_step(&self, id: StepId, err: &anyhow::Error) {
+        self.update_step(
+            id,
+            StepStatus::Failed(format!("{err:#}")),
+            Some(classify(err)),
+        );
+    }
+
+    fn update_step(&self, id: StepId, status: StepStatus, error: Option<ErrorKind>) {
+        if let Some(step) = self
+            .steps
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|step| step.id == id)
+        {
+            let time = &mut step.time;
+            match status {
+                StepStatus::NotStarted => *time = StepTime::default(),
+                // Run All marks steps as running before the step does.
+                StepStatus::InProgress if step.status != StepStatus::InProgress => {
+                    *time = StepTime {
+                        started: Some(Instant::now()),
+                        took: None,
+                    };
+                }
+                StepStatus::InProgress => {}
+                _ => time.took = time.started.map(|started| started.elapsed()),
+            }
+            if let (StepStatus::Completed, Some(took)) = (&status, time.took) {
+                self.settings
+                    .lock()
+                    .unwrap()
+                    .timings
+                    .record_step(step.name, took);
             }
+            step.status = status;
+            step.error = error;
+        }
+
+        self.events.send(AppUpdate::StepStatusChanged);
+    }
+
+    /// How long the running step has been running.
+    pub fn step_elapsed(&self, id: StepId) -> Option<Duration> {
+        self.steps
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|step| step.id == id)?
+            .time
+            .started
+            .map(|started| started.elapsed())
+    }
+
+    pub fn timings(&self) -> Timings {
+        self.settings.lock().unwrap().timings.clone()
+    }
+
+    /// Remembers how fast the game files were copied, to estimate the next
+    /// copy.
+    pub fn record_copy(&self, bytes: u64, took: Duration) {
+        self.settings
+            .lock()
+            .unwrap()
+            .timings
+            .record_copy(bytes, took);
+    }
+
+    /// When the first of the steps run since the last reset started.
+    pub fn run_started(&self) -> Option<Instant> {
+        self.steps
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|step| step.time.started)
+            .min()
+    }
+
+    /// Forgets how a step went, so it counts as not run. Running steps keep
+    /// their status.
+    pub fn reset_step(&self, id: StepId) {
+        if self.step_status(id) != StepStatus::InProgress {
+            self.set_step_status(id, StepStatus::NotStarted);
+        }
+    }
+
+    pub fn reset_steps(&self) {
+        for step in self.steps() {
+            self.reset_step(step.id);
+        }
+    }
+
+    pub fn overwrite_policy(&self) -> OverwritePolicy {
+        *self.overwrite.lock().unwrap()
+    }
+
+    pub fn set_overwrite_policy(&self, policy: OverwritePolicy) {
+        *self.overwrite.lock().unwrap() = policy;
+    }
+
+    /// The game folder in the destination, when an earlier run left files in
+    /// it.
+    pub fn existing_game_folder(&self) -> Option<PathBuf> {
+        let folder = self.outdir().join(self.sourcedir()?.file_name()?);
+        std::fs::read_dir(&folder)
+            .ok()?
+            .next()
+            .is_some()
+            .then_some(folder)
+    }
+
+    /// Queues creating an archive in the current destination with the current
+    /// options, so the next destination can be picked meanwhile.
+    pub fn queue_job(&self) {
+        self.jobs.lock().unwrap().push(Job {
+            outdir: self.outdir(),
+            config: self.config(),
+            overwrite: self.overwrite_policy(),
+            status: StepStatus::NotStarted,
+        });
+    }
+
+    pub fn jobs(&self) -> Vec<Job> {
+        self.jobs.lock().unwrap().clone()
+    }
+
+    /// Removes a job from the queue, unless it is running.
+    pub fn remove_job(&self, index: usize) {
+        let mut jobs = self.jobs.lock().unwrap();
+        if jobs
+            .get(index)
+            .is_some_and(|job| job.status != StepStatus::InProgress)
+        {
+            jobs.remove(index);
+        }
+    }
+
+    pub fn clear_finished_jobs(&self) {
+        self.jobs
+            .lock()
+            .unwrap()
+            .retain(|job| matches!(job.status, StepStatus::NotStarted | StepStatus::InProgress));
+    }
+
+    /// Starts working through the queue. The destination and options the
+    /// jobs switch to are put back when the returned guard is dropped.
+    pub fn begin_jobs(self: &Arc<Self>) -> JobsReset {
+        JobsReset {
+            ctx: self.clone(),
+            outdir: self.outdir(),
+            config: self.config(),
+            overwrite: self.overwrite_policy(),
+        }
+    }
+
+    /// Marks the next queued job as running and switches to its destination
+    /// and options. Returns false when none is left.
+    pub fn start_next_job(&self) -> bool {
+        let mut jobs = self.jobs.lock().unwrap();
+        let Some(job) = jobs
+            .iter_mut()
+            .find(|job| job.status == StepStatus::NotStarted)
+        else {
+            return false;
+        };
+        job.status = StepStatus::InProgress;
+        let job = job.clone();
+        drop(jobs);
+
+        self.set_outdir(job.outdir);
+        *self.config.write().unwrap() = job.config;
+        self.set_overwrite_policy(job.overwrite);
+        true
+    }
+
+    /// Records how the running job went.
+    pub fn finish_job(&self, status: StepStatus) {
+        let mut jobs = self.jobs.lock().unwrap();
+        if let Some(job) = jobs
+            .iter_mut()
+            .find(|job| job.status == StepStatus::InProgress)
+        {
+            job.status = status;
+        }
+    }
+
+    /// Why a step needing `prerequisite` can't run yet, if it can't.
+    pub fn unmet(&self, prerequisite: Prerequisite) -> Option<&'static str> {
+        let completed = |id| self.step_status(id) == StepStatus::Completed;
+        let outdir = self.outdir();
+        match prerequisite {
+            Prerequisite::Source => self
+                .sourcedir()
+                .is_none_or(|dir| validate_aoe2_source(&dir).is_err())
+                .then_some("Select the source directory first"),
+            Prerequisite::Archive => (!completed(StepId::Copy)
+                && validate_aoe2_source(&outdir.join(&self.config().aoe2.steam_folder)).is_err())
+            .then_some("Copy the game files first"),
+            Prerequisite::Goldberg => (!completed(StepId::Goldberg)
+                && !outdir.join("launcher.exe").exists())
+            .then_some("Install Goldberg first"),
+        }
+    }
+}
+
+/// What the copy does with the game files of an earlier run.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum OverwritePolicy {
+    /// Copies over them, replacing changed files and keeping the rest.
+    #[default]
+    Merge,
+    /// Deletes them first, so nothing stale is left.
+    Replace,
+}
+
+#[derive(Clone, Copy, Default)]
+struct StepTime {
+    started: Option<Instant>,
+    took: Option<Duration>,
+}
+
+/// Tells the steps apart wherever they are in [`Context::steps`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepId {
+    Copy,
+    Goldberg,
+    Companion,
+    Launcher,
+    Server,
+    Redist,
+    Firewall,
+    Privacy,
+    Shortcuts,
+    Test,
+}
+
+/// The work of a step of creating the archive. Run on its own thread by
+/// `spawn_step`, which sets the step's status from the result.
+pub trait InstallStep: Send + Sync {
+    fn id(&self) -> StepId;
+
+    /// Shown translated, and taken by `--steps` in lower case.
+    fn name(&self) -> &'static str;
+
+    /// What it needs to run on its own, met by an earlier step or by an
+    /// archive created before.
+    fn prerequisites(&self) -> Prerequisite;
+
+    /// Whether the options ask for it, it's skipped otherwise.
+    fn enabled(&self, _ctx: &Context) -> bool {
+        true
+    }
+
+    /// Whether it writes to the archive. The update manifest is written after
+    /// the last step that does, so later ones don't end up in it.
+    fn changes_archive(&self) -> bool {
+        true
+    }
+
+    /// Starts what it can do ahead while the step before it runs, e.g.
+    /// downloads with [`Context::prefetch`].
+    fn prefetch(&self, _ctx: &Arc<Context>) {}
+
+    fn run(
+        &self,
+        ctx: &Arc<Context>,
+        progress: &Progress,
+        cancel: &CancellationToken,
+    ) -> Result<()>;
+}
+
+/// A registered step with how it went, see [`Context::register_step`].
+#[derive(Clone)]
+pub struct Step {
+    pub id: StepId,
+    pub name: &'static str,
+    pub prerequisite: Prerequisite,
+    pub install: Arc<dyn InstallStep>,
+    pub status: StepStatus,
+    /// The kind of error it failed with.
+    pub error: Option<ErrorKind>,
+    /// When it started and how long it took, for the summary.
+    time: StepTime,
+}
+
+impl Step {
+    pub fn new(install: impl InstallStep + 'static) -> Self {
+        Self {
+            id: install.id(),
+            name: install.name(),
+            prerequisite: install.prerequisites(),
+            install: Arc::new(install),
+            status: StepStatus::NotStarted,
+            error: None,
+            time: StepTime::default(),
         }
+    }
 
-        let _ = self.tx.send(AppUpdate::StepStatusChanged);
+    /// How long it took, once finished.
+    pub fn took(&self) -> Option<Duration> {
+        self.time.took
     }
 }
 
+/// An archive queued to be created from the current source, with the
+/// destination and options it was queued with.
+#[derive(Clone)]
+pub struct Job {
+    pub outdir: PathBuf,
+    pub config: Arc<Config>,
+    pub overwrite: OverwritePolicy,
+    /// Not started while queued.
+    pub status: StepStatus,
+}
+
+/// What a step needs before it can run, met by an earlier step of this run
+/// or by an archive created before.
+#[derive(Clone, Copy)]
+pub enum Prerequisite {
+    /// A game installation to copy.
+    Source,
+    /// The game files in the destination.
+    Archive,
+    /// Goldberg and its launcher in the destination.
+    Goldberg,
+}
+
 impl Context {
-    pub fn set_task(self: &Arc<Self>, task: Task) -> Result<TaskReset> {
+    pub fn set_task(self: &Arc<Self>, task: StepId) -> Result<TaskReset> {
         let mut guard = self.current_task.lock().unwrap();
         if let Some(existing_task) = &*guard {
             bail!("Task already running: {existing_task:?}");
@@ -87,21 +836,270 @@ impl Context {
 
         let reset = TaskReset::new(self.clone());
         *guard = Some(task);
+        *self.cancellation.lock().unwrap() = self.run.lock().unwrap().clone().unwrap_or_default();
 
         Ok(reset)
     }
 
+    /// Starts a run of several steps, which share the returned token until
+    /// it is dropped.
+    pub fn begin_run(self: &Arc<Self>) -> RunReset {
+        let token = CancellationToken::default();
+        *self.run.lock().unwrap() = Some(token.clone());
+        RunReset {
+            ctx: self.clone(),
+            token,
+        }
+    }
+
+    /// Whether a step or a job is running. Jobs count between their steps
+    /// too.
     pub fn is_busy(&self) -> bool {
         self.current_task.lock().unwrap().is_some()
+            || self
+                .jobs
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|job| job.status == StepStatus::InProgress)
+    }
+
+    /// Asks the running task to stop, along with the downloads started
+    /// ahead of later steps. It fails with "Cancelled" at its next check,
+    /// releasing the task for another step.
+    pub fn cancel(&self) {
+        let task = *self.current_task.lock().unwrap();
+        let run = self.run.lock().unwrap().clone();
+        match (task, &run) {
+            (Some(task), _) => info!("Cancelling {task:?}"),
+            (None, Some(_)) => info!("Cancelling the run"),
+            (None, None) => return,
+        }
+        if let Some(run) = run {
+            run.cancel();
+        }
+        self.cancellation.lock().unwrap().cancel();
+        for (token, _) in self.prefetched.lock().unwrap().values() {
+            token.cancel();
+        }
+    }
+
+    /// The token the work on this thread stops with: the running task's, or
+    /// on prefetch threads the one of the task that started them.
+    pub fn cancellation(&self) -> CancellationToken {
+        TOKEN
+            .with_borrow(Clone::clone)
+            .unwrap_or_else(|| self.cancellation.lock().unwrap().clone())
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation().is_cancelled()
+    }
+
+    /// Starts a phase of the running step, which fills `start..end` of the
+    /// step's overall progress bar.
+    pub fn begin_phase(&self, step: (StepId, &'static str), start: f32, end: f32, desc: &str) {
+        if QUIET.get() {
+            return;
+        }
+        *self.phase.lock().unwrap() = Some(Phase {
+            step,
+            start,
+            end,
+            started: Instant::now(),
+            first_bytes: None,
+        });
+        self.report_progress(desc, 0.0);
+    }
+
+    /// Reports how far along the current phase is, so nested work like
+    /// downloads doesn't need to know which step it is part of.
+    pub fn report_progress(&self, desc: &str, fraction: f32) {
+        self.send_progress(desc, fraction, None, None);
+    }
+
+    /// Reports `done` bytes of `total`, if known, e.g. of a download.
+    pub fn report_bytes(&self, desc: &str, done: u64, total: Option<u64>) {
+        let fraction = total.map_or(0.0, |total| done as f32 / total.max(1) as f32);
+        self.send_progress(desc, fraction, Some((done, total)), None);
+    }
+
+    /// Reports `done` of `total` items, e.g. files extracted.
+    pub fn report_items(&self, desc: &str, done: usize, total: usize) {
+        let fraction = done as f32 / total.max(1) as f32;
+        self.send_progress(desc, fraction, None, Some((done, total)));
+    }
+
+    fn send_progress(
+        &self,
+        desc: &str,
+        fraction: f32,
+        bytes: Option<(u64, Option<u64>)>,
+        items: Option<(usize, usize)>,
+    ) {
+        if QUIET.get() {
+            return;
+        }
+        let fraction = fraction.clamp(0.0, 1.0);
+        let mut phase = self.phase.lock().unwrap();
+        let Some(phase) = phase.as_mut() else {
+            self.events.send(AppUpdate::Progress(Some(StepProgress {
+                step: None,
+                phase: desc.to_string(),
+                fraction,
+                bytes,
+                items,
+                rate: None,
+                eta: None,
+            })));
+            return;
+        };
+
+        // Counted from the first report, downloads may resume part way.
+        let rate = bytes.and_then(|(done, _)| {
+            let (since, from) = *phase.first_bytes.get_or_insert((Instant::now(), done));
+            let secs = since.elapsed().as_secs_f64();
+            (secs >= 1.0).then(|| done.saturating_sub(from) as f64 / secs)
+        });
+        let eta = match (bytes, rate) {
+            (Some((done, Some(total))), Some(rate)) if rate > 0.0 => Some(Duration::from_secs_f64(
+                total.saturating_sub(done) as f64 / rate,
+            )),
+            _ => eta::remaining(phase.started.elapsed(), Some(fraction), None),
+        };
+        self.events.send(AppUpdate::Progress(Some(StepProgress {
+            step: Some(phase.step),
+            phase: desc.to_string(),
+            fraction: phase.start + (phase.end - phase.start) * fraction,
+            bytes,
+            items,
+            rate,
+            eta,
+        })));
+    }
+
+    /// Reports the file being copied or extracted, with `done` of `total`
+    /// files finished before it.
+    pub fn report_file(&self, name: &str, done: usize, total: usize) {
+        if QUIET.get() {
+            return;
+        }
+        self.events.send(AppUpdate::File(FileProgress {
+            name: name.to_string(),
+            done,
+            total,
+        }));
+    }
+
+    /// Starts downloading what a later step installs in the background, so it
+    /// overlaps with the steps before it. Only the steps write to the outdir.
+    pub fn prefetch(self: &Arc<Self>, step: &'static str, fetch: fn(&Context) -> Fetched) {
+        let ctx = self.clone();
+        let token = self.cancellation();
+        let handle = self.tasks.spawn_blocking({
+            let token = token.clone();
+            move || {
+                QUIET.set(true);
+                TOKEN.set(Some(token));
+                let fetched = fetch(&ctx);
+                // The pool's threads are reused for other work.
+                QUIET.set(false);
+                TOKEN.set(None);
+                fetched
+            }
+        });
+        self.prefetched
+            .lock()
+            .unwrap()
+            .insert(step, (token, handle));
+    }
+
+    /// Waits for the download [`Context::prefetch`] started for `step`, if any.
+    /// A cancelled one counts as none, for the step to download again.
+    pub fn take_prefetched(&self, step: &'static str) -> Option<Fetched> {
+        let (token, handle) = self.prefetched.lock().unwrap().remove(step)?;
+        let fetched = self
+            .tasks
+            .join(handle)
+            .unwrap_or_else(|err| Err(err.context(format!("The {step} download failed"))));
+        (!token.is_cancelled()).then_some(fetched)
+    }
+
+    pub fn clear_progress(&self) {
+        *self.phase.lock().unwrap() = None;
+        self.events.send(AppUpdate::Progress(None));
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum Task {
-    Copy,
-    Goldberg,
-    Companion,
-    Launcher,
+struct Phase {
+    step: (StepId, &'static str),
+    start: f32,
+    end: f32,
+    started: Instant,
+    /// When the first bytes were reported and how many, for the rate.
+    first_bytes: Option<(Instant, u64)>,
+}
+
+/// Reports the progress of a running step, see [`InstallStep::run`].
+pub struct Progress<'a> {
+    ctx: &'a Context,
+    step: (StepId, &'static str),
+}
+
+impl<'a> Progress<'a> {
+    pub fn new(ctx: &'a Context, id: StepId, name: &'static str) -> Self {
+        Self {
+            ctx,
+            step: (id, name),
+        }
+    }
+
+    /// Starts a phase filling `start..end` of the step's progress bar, see
+    /// [`Context::begin_phase`].
+    pub fn phase(&self, start: f32, end: f32, desc: &str) {
+        self.ctx.begin_phase(self.step, start, end, desc);
+    }
+
+    /// Reports how far along the current phase is.
+    pub fn report(&self, desc: &str, fraction: f32) {
+        self.ctx.report_progress(desc, fraction);
+    }
+}
+
+/// Asks the work of a task to stop, checked between files and awaited by
+/// downloads. Clones share the state, so threads helping a task stop with it.
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<(AtomicBool, Notify)>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.0.store(true, Ordering::Relaxed);
+        self.0.1.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.0.load(Ordering::Relaxed)
+    }
+
+    /// Finishes once cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            // Created before checking, so a cancel in between still wakes it.
+            let notified = self.0.1.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Fails with "Cancelled" once cancelled.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            bail!("Cancelled");
+        }
+        Ok(())
+    }
 }
 
 pub struct TaskReset {
@@ -115,23 +1113,66 @@ impl TaskReset {
 impl Drop for TaskReset {
     fn drop(&mut self) {
         *self.ctx.current_task.lock().unwrap() = None;
+        self.ctx.clear_progress();
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Ends the run [`Context::begin_run`] started when dropped.
+pub struct RunReset {
+    ctx: Arc<Context>,
+    pub token: CancellationToken,
+}
+
+impl Drop for RunReset {
+    fn drop(&mut self) {
+        *self.ctx.run.lock().unwrap() = None;
+    }
+}
+
+/// Puts back what [`Context::begin_jobs`] saved when dropped, so the queue
+/// doesn't leave the last job's destination and options picked.
+pub struct JobsReset {
+    ctx: Arc<Context>,
+    outdir: PathBuf,
+    config: Arc<Config>,
+    overwrite: OverwritePolicy,
+}
+
+impl Drop for JobsReset {
+    fn drop(&mut self) {
+        self.ctx.set_outdir(self.outdir.clone());
+        *self.ctx.config.write().unwrap() = self.config.clone();
+        self.ctx.set_overwrite_policy(self.overwrite);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum StepStatus {
     NotStarted,
     InProgress,
     Completed,
+    Skipped,
     Failed(String),
 }
 
 impl StepStatus {
+    /// Shown next to the icon, which screen readers can't make sense of.
+    pub fn label(&self) -> &'static str {
+        match self {
+            StepStatus::NotStarted => "Not run",
+            StepStatus::InProgress => "Running",
+            StepStatus::Completed => "Done",
+            StepStatus::Skipped => "Skipped",
+            StepStatus::Failed(_) => "Failed",
+        }
+    }
+
     pub fn icon(&self) -> &str {
         match self {
             StepStatus::NotStarted => "⚪",
             StepStatus::InProgress => "⏳",
             StepStatus::Completed => "✅",
+            StepStatus::Skipped => "⏭",
             StepStatus::Failed(_) => "❌",
         }
     }
@@ -141,7 +1182,8 @@ impl StepStatus {
             StepStatus::NotStarted => Color32::GRAY,
             StepStatus::InProgress => Color32::from_rgb(255, 165, 0), // Orange
             StepStatus::Completed => Color32::from_rgb(0, 200, 0),    // Green
-            StepStatus::Failed(_) => Color32::from_rgb(220, 0, 0),    // Red
+            StepStatus::Skipped => Color32::GRAY,
+            StepStatus::Failed(_) => Color32::from_rgb(220, 0, 0), // Red
         }
     }
 }
@@ -0,0 +1,591 @@
+//! Everything the steps download goes through here: the shared clients with
+//! the proxy, streaming to disk with resuming, the download cache, mirrors,
+//! progress and checksum verification of release assets.
+
+use crate::{
+    Context,
+    config::{Proxy, RetryOperation},
+    ctx::Progress,
+    errors::{ErrorKind, Tag},
+    retry::retry_async,
+    tasks::timeout,
+};
+use anyhow::{Result, anyhow, bail};
+use common::sha256_file;
+use fs2::FileExt;
+use reqwest::{
+    StatusCode,
+    header::{ETAG, IF_NONE_MATCH, IF_RANGE, RANGE},
+};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::SeekFrom,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tracing::{info, warn};
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const USER_AGENT: &str =
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:143.0) Gecko/20100101 Firefox/143.0";
+
+/// The clients all requests go through, honoring the proxy settings.
+#[derive(Clone)]
+pub struct HttpClients {
+    /// For api requests, made from the steps' threads.
+    pub blocking: reqwest::blocking::Client,
+    /// For downloads, which run on the [`Supervisor`](crate::tasks::Supervisor).
+    pub downloads: reqwest::Client,
+}
+
+pub fn http_client(proxy: &Proxy) -> Result<HttpClients> {
+    let mut blocking = reqwest::blocking::Client::builder().user_agent(USER_AGENT);
+    let mut downloads = reqwest::Client::builder().user_agent(USER_AGENT);
+
+    if let Some(url) = proxy.url.as_deref().filter(|u| !u.trim().is_empty()) {
+        let mut reqwest_proxy =
+            reqwest::Proxy::all(url.trim()).map_err(|e| anyhow!("Invalid proxy url {url}: {e}"))?;
+        if let Some(username) = &proxy.username {
+            reqwest_proxy =
+                reqwest_proxy.basic_auth(username, proxy.password.as_deref().unwrap_or_default());
+        }
+        info!("Using proxy {url}");
+        blocking = blocking.proxy(reqwest_proxy.clone());
+        downloads = downloads.proxy(reqwest_proxy);
+    } else if !proxy.from_env {
+        blocking = blocking.no_proxy();
+        downloads = downloads.no_proxy();
+    }
+
+    Ok(HttpClients {
+        blocking: blocking.build()?,
+        downloads: downloads.build()?,
+    })
+}
+
+/// Downloads `url` to disk, returning where the file is. Progress is
+/// reported to the UI and dropped or stalled connections are resumed with
+/// range requests, then retried from the configured mirrors. Downloads with
+/// an etag are cached across runs and only revalidated, those without one
+/// are kept in the run's temp directory.
+pub(crate) fn download(ctx: &Context, url: &str) -> Result<PathBuf> {
+    let label = format!("Downloading {}", download_name(url));
+    let on_bytes = |done, total| ctx.report_bytes(&label, done, total);
+    ctx.tasks
+        .run(&ctx.cancellation(), download_async(ctx, url, &on_bytes))
+}
+
+/// Downloads all `urls` at the same time like [`download`], reporting their
+/// progress together. The results are in the order of `urls`.
+pub(crate) fn download_all(ctx: &Context, urls: &[&str]) -> Vec<Result<PathBuf>> {
+    let label = format!("Downloading {} files", urls.len());
+    let transfers = Mutex::new(vec![(0, None); urls.len()]);
+    let reporters: Vec<_> = (0..urls.len())
+        .map(|i| {
+            let (label, transfers) = (&label, &transfers);
+            move |done, total| {
+                let mut transfers = transfers.lock().unwrap();
+                transfers[i] = (done, total);
+                let done = transfers.iter().map(|(done, _)| done).sum();
+                let total = transfers.iter().map(|(_, total)| *total).sum();
+                ctx.report_bytes(label, done, total);
+            }
+        })
+        .collect();
+    let downloads = urls
+        .iter()
+        .zip(&reporters)
+        .map(|(url, on_bytes)| download_async(ctx, url, on_bytes));
+    ctx.tasks.run_all(&ctx.cancellation(), downloads)
+}
+
+fn download_name(url: &str) -> &str {
+    url.rsplit('/')
+        .next()
+        .filter(|n| !n.is_empty())
+        .unwrap_or("download")
+}
+
+/// `url` through each of `mirrors`, which take the whole url after them.
+fn mirror_urls(mirrors: &[String], url: &str) -> Vec<String> {
+    mirrors
+        .iter()
+        .map(|mirror| mirror.trim().trim_end_matches('/'))
+        .filter(|mirror| !mirror.is_empty())
+        .map(|mirror| format!("{mirror}/{url}"))
+        .collect()
+}
+
+/// Downloads `url`, and from the mirrors in turn while it fails. Calls
+/// `on_bytes` with the bytes received and the size as they come in.
+async fn download_async(
+    ctx: &Context,
+    url: &str,
+    on_bytes: &dyn Fn(u64, Option<u64>),
+) -> Result<PathBuf> {
+    let mut result = download_retried(ctx, url, url, on_bytes).await;
+    for mirror in mirror_urls(&ctx.config().download.mirrors, url) {
+        let Err(err) = &result else {
+            break;
+        };
+        warn!("Failed to download {url}, trying {mirror}: {err:#}");
+        result = download_retried(ctx, url, &mirror, on_bytes).await;
+    }
+    result
+}
+
+/// [`download_from`] tried again as the `[retries]` config allows.
+async fn download_retried(
+    ctx: &Context,
+    url: &str,
+    source: &str,
+    on_bytes: &dyn Fn(u64, Option<u64>),
+) -> Result<PathBuf> {
+    retry_async(
+        &ctx.config().retries,
+        RetryOperation::Download,
+        &format!("Downloading {source}"),
+        || download_from(ctx, url, source, on_bytes),
+    )
+    .await
+}
+
+/// Downloads `url` from `source`, the url itself or a mirror of it. The cache
+/// goes by `url`, a partial download left by an earlier run is resumed.
+async fn download_from(
+    ctx: &Context,
+    url: &str,
+    source: &str,
+    on_bytes: &dyn Fn(u64, Option<u64>),
+) -> Result<PathBuf> {
+    let cached = ctx.cache.get(url);
+    let client = ctx.download_client();
+    let config = ctx.config();
+    let response_timeout = Duration::from_secs(config.download.response_timeout_secs);
+    let name = download_name(url);
+    let part = PartFile::open(ctx, url, name)?;
+
+    let mut resume = part.etag.clone();
+    let response = loop {
+        let mut request = client.get(source);
+        if let Some(entry) = &cached {
+            request = request.header(IF_NONE_MATCH, &entry.etag);
+        } else if let Some(etag) = &resume {
+            info!("Resuming the download of {name} after {} bytes", part.len);
+            request = request
+                .header(RANGE, format!("bytes={}-", part.len))
+                .header(IF_RANGE, etag);
+        }
+
+        let response = match timeout(response_timeout, "Connecting", async {
+            Ok(request.send().await?)
+        })
+        .await
+        {
+            Ok(response) => response,
+            Err(err) => {
+                part.discard();
+                let Some(entry) = cached else {
+                    return Err(err);
+                };
+                warn!("Failed to reach {source}, using the cached download: {err:#}");
+                return Ok(entry.data_path);
+            }
+        };
+        // The partial file is already complete, e.g. the archiver closed
+        // before caching it.
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE && resume.is_some() {
+            warn!("Unable to resume the download of {name}, starting over");
+            resume = None;
+            continue;
+        }
+        break response;
+    };
+
+    if response.status() == StatusCode::NOT_MODIFIED
+        && let Some(entry) = cached
+    {
+        info!("Using cached download of {url}");
+        part.discard();
+        return Ok(entry.data_path);
+    }
+
+    let response = response.error_for_status()?;
+    let etag = response
+        .headers()
+        .get(ETAG)
+        .and_then(|e| e.to_str().ok())
+        .map(str::to_string);
+    let written = match response.status() {
+        StatusCode::PARTIAL_CONTENT => part.len,
+        _ => 0,
+    };
+    if part.in_cache {
+        ctx.cache.set_partial_etag(url, etag.as_deref())?;
+    }
+
+    let download = Download {
+        client: &client,
+        url: source,
+        name,
+        etag: etag.as_deref(),
+        on_bytes,
+        response_timeout,
+        read_timeout: Duration::from_secs(config.download.read_timeout_secs),
+        attempts: config.retries.attempts(RetryOperation::Download),
+    };
+    let file = tokio::fs::File::from_std(part.file);
+    download.stream_to_file(response, file, written).await?;
+
+    if part.in_cache {
+        ctx.cache.set_partial_etag(url, None)?;
+        if let Some(etag) = etag {
+            match ctx.cache.insert(url, &etag, &part.path) {
+                Ok(path) => return Ok(path),
+                Err(err) => warn!("Failed to cache download of {url}: {err:#}"),
+            }
+        }
+    }
+    // Kept for this run only.
+    let path = ctx.temp.path(name);
+    if fs::rename(&part.path, &path).is_err() {
+        // The temp dir may live on another drive.
+        fs::copy(&part.path, &path)?;
+        fs::remove_file(&part.path)?;
+    }
+    Ok(path)
+}
+
+/// The file a download is written to, the cache's partial file for its url
+/// unless another archiver is downloading the same, then a new one in the
+/// temp dir.
+struct PartFile {
+    path: PathBuf,
+    file: File,
+    /// The bytes the file holds already.
+    len: u64,
+    /// What the bytes are the start of, when they can be resumed.
+    etag: Option<String>,
+    in_cache: bool,
+}
+
+impl PartFile {
+    fn open(ctx: &Context, url: &str, name: &str) -> Result<Self> {
+        let partial = ctx.cache.partial(url);
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(&partial.path)?;
+        if file.try_lock_exclusive().is_ok() {
+            let len = file.metadata()?.len();
+            return Ok(Self {
+                path: partial.path,
+                file,
+                len,
+                etag: partial.etag.filter(|_| len > 0),
+                in_cache: true,
+            });
+        }
+        let path = ctx.temp.path(&format!("{name}.part"));
+        Ok(Self {
+            file: File::create(&path)?,
+            path,
+            len: 0,
+            etag: None,
+            in_cache: false,
+        })
+    }
+
+    /// Removes the file when it was only created to be written to.
+    fn discard(self) {
+        if self.len == 0 {
+            drop(self.file);
+            let _ = fs::remove_file(self.path);
+        }
+    }
+}
+
+struct Download<'a> {
+    client: &'a reqwest::Client,
+    url: &'a str,
+    name: &'a str,
+    etag: Option<&'a str>,
+    on_bytes: &'a dyn Fn(u64, Option<u64>),
+    response_timeout: Duration,
+    /// How long the download may receive nothing before it's resumed.
+    read_timeout: Duration,
+    /// How often an interrupted download is resumed before giving up.
+    attempts: u32,
+}
+
+impl Download<'_> {
+    /// Writes `response` to `file` after the `written` bytes it resumes.
+    async fn stream_to_file(
+        &self,
+        mut response: reqwest::Response,
+        mut file: tokio::fs::File,
+        mut written: u64,
+    ) -> Result<()> {
+        let total = response.content_length().map(|len| written + len);
+        file.set_len(written).await?;
+        file.seek(SeekFrom::Start(written)).await?;
+
+        for attempt in 1.. {
+            let err = match self
+                .copy_with_progress(&mut response, &mut file, &mut written, total)
+                .await
+            {
+                Ok(()) => return Ok(()),
+                Err(err) if attempt >= self.attempts => return Err(err),
+                Err(err) => err,
+            };
+            warn!(
+                "Download of {} interrupted after {written} bytes, resuming: {err:#}",
+                self.name
+            );
+
+            let mut request = self
+                .client
+                .get(self.url)
+                .header(RANGE, format!("bytes={written}-"));
+            // Without a matching etag the server sends the whole file again.
+            if let Some(etag) = self.etag {
+                request = request.header(IF_RANGE, etag);
+            }
+            response = timeout(self.response_timeout, "Connecting", async {
+                Ok(request.send().await?.error_for_status()?)
+            })
+            .await?;
+
+            if response.status() != StatusCode::PARTIAL_CONTENT {
+                warn!("{} doesn't support resuming, starting over", self.url);
+                file.set_len(0).await?;
+                file.seek(SeekFrom::Start(0)).await?;
+                written = 0;
+            }
+        }
+
+        unreachable!()
+    }
+
+    async fn copy_with_progress(
+        &self,
+        response: &mut reqwest::Response,
+        file: &mut tokio::fs::File,
+        written: &mut u64,
+        total: Option<u64>,
+    ) -> Result<()> {
+        let mut last_report: Option<Instant> = None;
+
+        while let Some(chunk) = timeout(self.read_timeout, "Receiving data", async {
+            Ok(response.chunk().await?)
+        })
+        .await?
+        {
+            file.write_all(&chunk).await?;
+            *written += chunk.len() as u64;
+
+            if last_report.is_none_or(|t| t.elapsed() >= PROGRESS_INTERVAL) {
+                last_report = Some(Instant::now());
+                (self.on_bytes)(*written, total.filter(|total| *total > 0));
+            }
+        }
+        file.flush().await?;
+
+        if let Some(total) = total
+            && *written < total
+        {
+            return Err(anyhow!(
+                "connection closed after {written} of {total} bytes"
+            ))
+            .kind(ErrorKind::NetworkBlocked);
+        }
+
+        Ok(())
+    }
+}
+
+/// A release asset and the tag of the release it belongs to.
+pub struct ReleaseAsset {
+    pub tag: String,
+    pub name: String,
+    pub url: String,
+    /// The digest github computed for the asset.
+    pub sha256: Option<String>,
+    /// A checksums file published alongside the asset.
+    pub checksums_url: Option<String>,
+    /// Picked by the user instead of downloaded.
+    pub local: bool,
+}
+
+impl ReleaseAsset {
+    /// Refuses the downloaded asset unless its SHA-256 matches the configured
+    /// checksum, github's digest or the release's checksums file. Assets
+    /// without any published checksum are installed with a warning.
+    pub(crate) fn verify(
+        &self,
+        ctx: &Context,
+        configured: Option<&str>,
+        path: &Path,
+    ) -> Result<()> {
+        let configured = configured.map(str::trim).filter(|s| !s.is_empty());
+        if self.local && configured.is_none() {
+            warn!(
+                "Installing the local {} without checksum verification",
+                self.name
+            );
+            return Ok(());
+        }
+        let expected = match (configured, &self.sha256, &self.checksums_url) {
+            (Some(sha256), _, _) => sha256.to_lowercase(),
+            (None, Some(sha256), _) => sha256.clone(),
+            (None, None, Some(checksums_url)) => {
+                let checksums = fs::read_to_string(download(ctx, checksums_url)?)?;
+                let Some(sha256) = checksum_for(&checksums, &self.name) else {
+                    bail!("{} is not listed in {checksums_url}", self.name);
+                };
+                sha256
+            }
+            (None, None, None) => {
+                warn!(
+                    "No checksum is published for {}, installing it unverified. Set its sha256 in config.toml to verify it.",
+                    self.name
+                );
+                return Ok(());
+            }
+        };
+
+        let actual = sha256_file(path)?;
+        if actual != expected {
+            return Err(anyhow!(
+                "Checksum mismatch for {}: expected {expected}, got {actual}",
+                self.name
+            ))
+            .kind(ErrorKind::ChecksumMismatch);
+        }
+        info!("Verified the checksum of {}", self.name);
+
+        Ok(())
+    }
+}
+
+/// Where a step's archive comes from: a local file picked instead, or the
+/// release found on github.
+pub struct ReleaseSource<'a> {
+    /// Shown in the log, e.g. "launcher".
+    pub name: &'a str,
+    pub local: Option<&'a Path>,
+    /// Whether the archive has to match a checksum, see [`ReleaseAsset::verify`].
+    pub verify: bool,
+    /// The checksum from config.toml the archive has to match.
+    pub sha256: Option<&'a str>,
+}
+
+impl ReleaseSource<'_> {
+    /// Picks the local archive, or downloads the release `lookup` finds,
+    /// filling `0.0..end` of the step's progress bar.
+    pub fn fetch(
+        &self,
+        ctx: &Context,
+        progress: &Progress,
+        end: f32,
+        lookup: impl FnOnce() -> Result<ReleaseAsset>,
+    ) -> Result<(ReleaseAsset, PathBuf)> {
+        let (release, path) = match self.local {
+            Some(path) => {
+                progress.phase(0.0, end, "Reading the local archive");
+                local_release(path)?
+            }
+            None => {
+                progress.phase(0.0, end / 10.0, "Looking up the release");
+                let release = lookup()?;
+                info!(
+                    "Downloading {} {} from {}",
+                    self.name, release.tag, release.url
+                );
+                progress.phase(end / 10.0, end, "Downloading");
+                let path = download(ctx, &release.url)?;
+                (release, path)
+            }
+        };
+        if self.verify {
+            release.verify(ctx, self.sha256, &path)?;
+        }
+        Ok((release, path))
+    }
+}
+
+/// A release archive downloaded by hand, for machines without internet
+/// access.
+pub fn local_release(path: &Path) -> Result<(ReleaseAsset, PathBuf)> {
+    if !path.is_file() {
+        bail!("Failed to read {}: no such file", path.display());
+    }
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    info!("Using local archive {}", path.display());
+
+    Ok((
+        ReleaseAsset {
+            tag: "local".to_string(),
+            name,
+            url: path.display().to_string(),
+            sha256: None,
+            checksums_url: None,
+            local: true,
+        },
+        path.to_path_buf(),
+    ))
+}
+
+/// Finds the checksum of `name` in a `sha256sum` style checksums file.
+fn checksum_for(checksums: &str, name: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let (sha256, file) = line.trim().split_once(char::is_whitespace)?;
+        let file = file.trim().trim_start_matches('*');
+        (file == name && sha256.len() == 64).then(|| sha256.to_lowercase())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{checksum_for, mirror_urls};
+
+    #[test]
+    fn find_checksum() {
+        let checksums = "\
+0f343b0931126a20f133d67c2b018a3b5b1b1d2d3e0b7c6f6ad1f1e4a5b6c7d8  launcher_full_win_x86-64.zip
+AB343B0931126A20F133D67C2B018A3B5B1B1D2D3E0B7C6F6AD1F1E4A5B6C7D8 *server_full_win_x86-64.zip
+";
+
+        assert_eq!(
+            checksum_for(checksums, "server_full_win_x86-64.zip").unwrap(),
+            "ab343b0931126a20f133d67c2b018a3b5b1b1d2d3e0b7c6f6ad1f1e4a5b6c7d8"
+        );
+        assert!(checksum_for(checksums, "launcher_full_win_x86-64").is_none());
+    }
+
+    #[test]
+    fn builds_mirror_urls() {
+        let mirrors = [
+            "https://ghproxy.net/".to_string(),
+            " ".to_string(),
+            "https://mirror.lan".to_string(),
+        ];
+        assert_eq!(
+            mirror_urls(
+                &mirrors,
+                "https://github.com/a/b/releases/download/v1/x.zip"
+            ),
+            [
+                "https://ghproxy.net/https://github.com/a/b/releases/download/v1/x.zip",
+                "https://mirror.lan/https://github.com/a/b/releases/download/v1/x.zip",
+            ]
+        );
+    }
+}
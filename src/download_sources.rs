@@ -0,0 +1,366 @@
+//! Where the tools are downloaded from and through which proxy, the parts of
+//! config.toml that only need changing when upstream moves or a network
+//! blocks github.
+
+use crate::{config::Config, ctx::Context, i18n::tr};
+use eframe::egui::{self, Button, Color32, RichText, TextEdit, Ui};
+use tracing::{error, info};
+
+/// The `[section] key`s this section edits, written to config.toml.
+const KEYS: &[(&str, &str)] = &[
+    ("goldberg", "download_url"),
+    ("goldberg", "gh_user"),
+    ("goldberg", "gh_repo"),
+    ("goldberg", "asset_search"),
+    ("aoe2", "gh_companion_user"),
+    ("aoe2", "gh_companion_repo"),
+    ("aoe2", "companion_asset_search"),
+    ("aoe2", "companion_version"),
+    ("aoe2", "companion_sha256"),
+    ("aoe2", "gh_launcher_user"),
+    ("aoe2", "gh_launcher_repo"),
+    ("aoe2", "launcher_asset_search"),
+    ("aoe2", "server_asset_search"),
+    ("aoe2", "launcher_version"),
+    ("aoe2", "launcher_sha256"),
+    ("download", "mirrors"),
+    ("proxy", "url"),
+    ("proxy", "username"),
+    ("proxy", "password"),
+    ("proxy", "from_env"),
+];
+
+pub fn draw_download_sources(ui: &mut Ui, ctx: &Context) {
+    egui::CollapsingHeader::new(tr("Download sources")).show(ui, |ui| {
+        let config = ctx.config();
+
+        ui.label(RichText::new("Goldberg").strong());
+        let mut url = config.goldberg.download_url.clone().unwrap_or_default();
+        if text_row(
+            ui,
+            tr("Download url:"),
+            &mut url,
+            tr("Looked up on github"),
+            url_error,
+        ) {
+            ctx.update_config(|c| {
+                c.goldberg.download_url = Some(url).filter(|url| !url.trim().is_empty())
+            });
+        }
+        repo_rows(
+            ui,
+            ctx,
+            (&config.goldberg.gh_user, &config.goldberg.gh_repo),
+            |c| (&mut c.goldberg.gh_user, &mut c.goldberg.gh_repo),
+        );
+        patterns_row(
+            ui,
+            ctx,
+            tr("Asset name contains:"),
+            &config.goldberg.asset_search,
+            |c| &mut c.goldberg.asset_search,
+        );
+
+        ui.add_space(4.0);
+        ui.label(RichText::new("Companion").strong());
+        repo_rows(
+            ui,
+            ctx,
+            (
+                &config.aoe2.gh_companion_user,
+                &config.aoe2.gh_companion_repo,
+            ),
+            |c| (&mut c.aoe2.gh_companion_user, &mut c.aoe2.gh_companion_repo),
+        );
+        patterns_row(
+            ui,
+            ctx,
+            tr("Asset name contains:"),
+            &config.aoe2.companion_asset_search,
+            |c| &mut c.aoe2.companion_asset_search,
+        );
+        pin_rows(
+            ui,
+            ctx,
+            (&config.aoe2.companion_version, &config.aoe2.companion_sha256),
+            |c| (&mut c.aoe2.companion_version, &mut c.aoe2.companion_sha256),
+        );
+
+        ui.add_space(4.0);
+        ui.label(RichText::new(tr("Launcher and server")).strong());
+        repo_rows(
+            ui,
+            ctx,
+            (&config.aoe2.gh_launcher_user, &config.aoe2.gh_launcher_repo),
+            |c| (&mut c.aoe2.gh_launcher_user, &mut c.aoe2.gh_launcher_repo),
+        );
+        patterns_row(
+            ui,
+            ctx,
+            tr("Asset name contains:"),
+            &config.aoe2.launcher_asset_search,
+            |c| &mut c.aoe2.launcher_asset_search,
+        );
+        patterns_row(
+            ui,
+            ctx,
+            tr("Server asset name contains:"),
+            &config.aoe2.server_asset_search,
+            |c| &mut c.aoe2.server_asset_search,
+        );
+        pin_rows(
+            ui,
+            ctx,
+            (&config.aoe2.launcher_version, &config.aoe2.launcher_sha256),
+            |c| (&mut c.aoe2.launcher_version, &mut c.aoe2.launcher_sha256),
+        );
+
+        ui.add_space(4.0);
+        ui.label(RichText::new(tr("Mirrors")).strong());
+        let mut mirrors = config.download.mirrors.join(",");
+        if text_row(
+            ui,
+            tr("Mirror urls:"),
+            &mut mirrors,
+            "https://ghproxy.net/",
+            mirrors_error,
+        ) {
+            ctx.update_config(|c| {
+                c.download.mirrors = mirrors
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|mirror| !mirror.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            });
+        }
+
+        ui.add_space(4.0);
+        ui.label(RichText::new(tr("Proxy")).strong());
+        let mut proxy = config.proxy.clone();
+        let mut url = proxy.url.clone().unwrap_or_default();
+        let mut username = proxy.username.clone().unwrap_or_default();
+        let mut password = proxy.password.clone().unwrap_or_default();
+        let mut changed = text_row(
+            ui,
+            tr("Proxy url:"),
+            &mut url,
+            "http://proxy:8080",
+            proxy_error,
+        );
+        changed |= text_row(ui, tr("Username:"), &mut username, "", |_| None);
+        ui.horizontal(|ui| {
+            ui.label(tr("Password:"));
+            changed |= ui
+                .add(TextEdit::singleline(&mut password).password(true))
+                .changed();
+        });
+        changed |= ui
+            .checkbox(
+                &mut proxy.from_env,
+                tr("Use the proxy environment variables when no url is set"),
+            )
+            .changed();
+        if changed {
+            let non_empty = |text: String| Some(text).filter(|text| !text.trim().is_empty());
+            proxy.url = non_empty(url);
+            proxy.username = non_empty(username);
+            proxy.password = non_empty(password);
+            ctx.update_config(|c| c.proxy = proxy);
+        }
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!ctx.is_busy(), Button::new(tr("Save to config.toml")))
+                .on_hover_text(tr("Keep the changes made in this section for the next runs"))
+                .clicked()
+            {
+                save(ctx);
+            }
+            if ui
+                .add_enabled(!ctx.is_busy(), Button::new(tr("Reset to defaults")))
+                .on_hover_text(tr(
+                    "Go back to the archiver's sources and remove the pinned versions and checksums",
+                ))
+                .clicked()
+            {
+                match Config::defaults() {
+                    Ok(defaults) => {
+                        ctx.update_config(|c| reset_download_sources(c, defaults));
+                        info!("Download sources reset to the defaults");
+                        save(ctx);
+                    }
+                    Err(err) => error!("Failed to load the default config: {err:#}"),
+                }
+            }
+        });
+    });
+}
+
+fn save(ctx: &Context) {
+    match ctx.config().save_keys(KEYS) {
+        Ok(path) => info!("Saved the download sources to {}", path.display()),
+        Err(err) => error!("Unable to save the download sources: {err:#}"),
+    }
+}
+
+/// A labelled text field, with what `validate` finds wrong shown under it. Returns whether it
+/// was edited.
+fn text_row(
+    ui: &mut Ui,
+    label: &str,
+    text: &mut String,
+    hint: &str,
+    validate: fn(&str) -> Option<&'static str>,
+) -> bool {
+    let changed = ui
+        .horizontal(|ui| {
+            ui.label(label);
+            ui.add(TextEdit::singleline(text).hint_text(hint)).changed()
+        })
+        .inner;
+    if let Some(error) = validate(text) {
+        ui.colored_label(Color32::from_rgb(255, 100, 0), format!("⚠ {}", tr(error)));
+    }
+    changed
+}
+
+fn repo_rows(
+    ui: &mut Ui,
+    ctx: &Context,
+    (user, repo): (&str, &str),
+    field: impl Fn(&mut Config) -> (&mut String, &mut String),
+) {
+    let mut user = user.to_string();
+    if text_row(ui, tr("Github owner:"), &mut user, "", name_error) {
+        ctx.update_config(|c| *field(c).0 = user);
+    }
+    let mut repo = repo.to_string();
+    if text_row(ui, tr("Github repository:"), &mut repo, "", name_error) {
+        ctx.update_config(|c| *field(c).1 = repo);
+    }
+}
+
+/// The release tag to install and the checksum its asset must have, unset
+/// when empty.
+fn pin_rows(
+    ui: &mut Ui,
+    ctx: &Context,
+    (version, sha256): (&Option<String>, &Option<String>),
+    field: impl Fn(&mut Config) -> (&mut Option<String>, &mut Option<String>),
+) {
+    let non_empty = |text: String| Some(text).filter(|text| !text.trim().is_empty());
+    let mut text = version.clone().unwrap_or_default();
+    if text_row(ui, tr("Release tag:"), &mut text, "latest", |_| None) {
+        ctx.update_config(|c| *field(c).0 = non_empty(text));
+    }
+    let mut text = sha256.clone().unwrap_or_default();
+    if text_row(
+        ui,
+        tr("Expected SHA-256:"),
+        &mut text,
+        tr("The one published with the release"),
+        sha256_error,
+    ) {
+        ctx.update_config(|c| *field(c).1 = non_empty(text));
+    }
+}
+
+/// The patterns comma separated. Empty ones are kept while typing.
+fn patterns_row(
+    ui: &mut Ui,
+    ctx: &Context,
+    label: &str,
+    patterns: &[String],
+    field: impl Fn(&mut Config) -> &mut Vec<String>,
+) {
+    let mut text = patterns.join(",");
+    if text_row(ui, label, &mut text, "win,x64", patterns_error) {
+        ctx.update_config(|c| *field(c) = text.split(',').map(str::to_string).collect());
+    }
+}
+
+fn reset_download_sources(config: &mut Config, defaults: Config) {
+    config.goldberg.download_url = defaults.goldberg.download_url;
+    config.goldberg.gh_user = defaults.goldberg.gh_user;
+    config.goldberg.gh_repo = defaults.goldberg.gh_repo;
+    config.goldberg.asset_search = defaults.goldberg.asset_search;
+    config.aoe2.gh_companion_user = defaults.aoe2.gh_companion_user;
+    config.aoe2.gh_companion_repo = defaults.aoe2.gh_companion_repo;
+    config.aoe2.companion_asset_search = defaults.aoe2.companion_asset_search;
+    config.aoe2.companion_version = defaults.aoe2.companion_version;
+    config.aoe2.companion_sha256 = defaults.aoe2.companion_sha256;
+    config.aoe2.gh_launcher_user = defaults.aoe2.gh_launcher_user;
+    config.aoe2.gh_launcher_repo = defaults.aoe2.gh_launcher_repo;
+    config.aoe2.launcher_asset_search = defaults.aoe2.launcher_asset_search;
+    config.aoe2.server_asset_search = defaults.aoe2.server_asset_search;
+    config.aoe2.launcher_version = defaults.aoe2.launcher_version;
+    config.aoe2.launcher_sha256 = defaults.aoe2.launcher_sha256;
+    config.download.mirrors = defaults.download.mirrors;
+    config.proxy = defaults.proxy;
+}
+
+fn url_error(url: &str) -> Option<&'static str> {
+    let url = url.trim();
+    (!url.is_empty() && !url.starts_with("https://") && !url.starts_with("http://"))
+        .then_some("Not an http or https url")
+}
+
+fn sha256_error(sha256: &str) -> Option<&'static str> {
+    let sha256 = sha256.trim();
+    (!sha256.is_empty() && (sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit())))
+        .then_some("Not a SHA-256, which has 64 hex digits")
+}
+
+fn mirrors_error(mirrors: &str) -> Option<&'static str> {
+    mirrors.split(',').find_map(url_error)
+}
+
+fn proxy_error(url: &str) -> Option<&'static str> {
+    let url = url.trim();
+    (!url.is_empty() && reqwest::Proxy::all(url).is_err())
+        .then_some("Not a valid proxy url, downloads keep the previous proxy")
+}
+
+/// Github owner and repository names.
+fn name_error(name: &str) -> Option<&'static str> {
+    if name.is_empty() {
+        Some("Required")
+    } else if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+    {
+        Some("Only letters, digits, - _ and . are allowed")
+    } else {
+        None
+    }
+}
+
+fn patterns_error(patterns: &str) -> Option<&'static str> {
+    patterns
+        .split(',')
+        .any(|pattern| pattern.trim().is_empty())
+        .then_some("Empty patterns match every asset")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{name_error, patterns_error, sha256_error, url_error};
+
+    #[test]
+    fn validates_fields() {
+        assert!(url_error("").is_none());
+        assert!(url_error("https://example.com/goldberg.7z").is_none());
+        assert!(url_error("example.com").is_some());
+        assert!(name_error("Detanup01").is_none());
+        assert!(name_error("gbe_fork").is_none());
+        assert!(name_error("").is_some());
+        assert!(name_error("owner/repo").is_some());
+        assert!(patterns_error("win,x64").is_none());
+        assert!(patterns_error("win,").is_some());
+        assert!(sha256_error("").is_none());
+        assert!(sha256_error(&"ab".repeat(32)).is_none());
+        assert!(sha256_error("abc").is_some());
+    }
+}
@@ -0,0 +1,166 @@
+//! Sorts the errors steps fail with into the kinds a user can do something
+//! about, so the window shows what to do next along with the error. Errors
+//! are tagged with their kind where they arise, see [`Tag::kind`], the rest
+//! are recognized by the io and http errors in their chain.
+
+use anyhow::Result;
+use reqwest::StatusCode;
+use std::{fmt, io};
+
+/// A file is open in another program, e.g. the game.
+const ERROR_SHARING_VIOLATION: i32 = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorKind {
+    /// The destination drive ran out of space.
+    DiskFull,
+    /// The source isn't a complete AoE2 DE installation.
+    SourceInvalid,
+    /// A download couldn't connect or stalled, e.g. behind a firewall.
+    NetworkBlocked,
+    /// Github refused the release lookup until its rate limit resets.
+    RateLimited,
+    /// A file couldn't be written or replaced, e.g. in use or protected.
+    AccessDenied,
+    /// A release of goldberg, the companion or the launcher no longer has
+    /// the files or settings where the archiver expects them.
+    UpstreamLayoutChanged,
+    /// A download doesn't match its published checksum.
+    ChecksumMismatch,
+    Cancelled,
+    Other,
+}
+
+impl ErrorKind {
+    /// What the user can do about it, `None` when there's nothing to advise.
+    pub fn advice(self) -> Option<&'static str> {
+        Some(match self {
+            ErrorKind::DiskFull => {
+                "Free up space on the destination drive or pick another destination, then run the step again"
+            }
+            ErrorKind::SourceInvalid => {
+                "Pick the folder AoE2 DE is installed in, and let Steam verify the game files if it is"
+            }
+            ErrorKind::NetworkBlocked => {
+                "Check the internet connection, or set a proxy or mirror under Download sources"
+            }
+            ErrorKind::RateLimited => {
+                "Wait for the limit to reset, or set a github token in config.toml"
+            }
+            ErrorKind::AccessDenied => {
+                "Close the game and programs using the archive, or restart the archiver as administrator"
+            }
+            ErrorKind::UpstreamLayoutChanged => {
+                "Pin an earlier release in config.toml, or look for a newer archiver"
+            }
+            ErrorKind::ChecksumMismatch => {
+                "Run the step again to download it anew, a proxy or antivirus may have changed the file"
+            }
+            ErrorKind::Cancelled | ErrorKind::Other => return None,
+        })
+    }
+
+    /// The command line's exit code for a step failing with it, listed in
+    /// [`crate::cli`]. Scripts rely on them, so they never change.
+    pub fn exit_code(self) -> u8 {
+        match self {
+            ErrorKind::SourceInvalid => 3,
+            ErrorKind::Other => 10,
+            ErrorKind::DiskFull => 11,
+            ErrorKind::NetworkBlocked => 12,
+            ErrorKind::RateLimited => 13,
+            ErrorKind::AccessDenied => 14,
+            ErrorKind::UpstreamLayoutChanged => 15,
+            ErrorKind::ChecksumMismatch => 16,
+            ErrorKind::Cancelled => 17,
+        }
+    }
+}
+
+/// An error of a known kind, wrapping its cause.
+#[derive(Debug)]
+pub struct ArchiveError {
+    pub kind: ErrorKind,
+    error: anyhow::Error,
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.error)
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+/// Tags the error of a result with its kind.
+pub trait Tag<T> {
+    fn kind(self, kind: ErrorKind) -> Result<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> Tag<T> for Result<T, E> {
+    fn kind(self, kind: ErrorKind) -> Result<T> {
+        self.map_err(|err| {
+            ArchiveError {
+                kind,
+                error: err.into(),
+            }
+            .into()
+        })
+    }
+}
+
+/// The kind of `err`: the one it was tagged with, else the one its io or
+/// http errors point to.
+pub fn classify(err: &anyhow::Error) -> ErrorKind {
+    if err.chain().any(|cause| cause.to_string() == "Cancelled") {
+        return ErrorKind::Cancelled;
+    }
+    for cause in err.chain() {
+        if let Some(err) = cause.downcast_ref::<ArchiveError>() {
+            return err.kind;
+        }
+        if let Some(err) = cause.downcast_ref::<io::Error>() {
+            match err.kind() {
+                io::ErrorKind::StorageFull => return ErrorKind::DiskFull,
+                io::ErrorKind::PermissionDenied => return ErrorKind::AccessDenied,
+                _ => {}
+            }
+            if cfg!(windows) && err.raw_os_error() == Some(ERROR_SHARING_VIOLATION) {
+                return ErrorKind::AccessDenied;
+            }
+        }
+        if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+            if err.status() == Some(StatusCode::TOO_MANY_REQUESTS) {
+                return ErrorKind::RateLimited;
+            }
+            if err.is_connect() || err.is_timeout() {
+                return ErrorKind::NetworkBlocked;
+            }
+        }
+    }
+    ErrorKind::Other
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ErrorKind, Tag, classify};
+    use anyhow::{Context, anyhow};
+    use std::io;
+
+    #[test]
+    fn classifies_tagged_and_io_errors() {
+        let tagged = Err::<(), _>(anyhow!("No AoE2DE.exe"))
+            .kind(ErrorKind::SourceInvalid)
+            .context("Unable to copy")
+            .unwrap_err();
+        assert_eq!(classify(&tagged), ErrorKind::SourceInvalid);
+        assert_eq!(format!("{tagged:#}"), "Unable to copy: No AoE2DE.exe");
+
+        let full = Err::<(), _>(io::Error::from(io::ErrorKind::StorageFull))
+            .context("Failed to copy AoE2DE_s.exe")
+            .unwrap_err();
+        assert_eq!(classify(&full), ErrorKind::DiskFull);
+        assert_eq!(classify(&anyhow!("Cancelled")), ErrorKind::Cancelled);
+        assert_eq!(classify(&anyhow!("Something else")), ErrorKind::Other);
+    }
+}
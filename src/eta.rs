@@ -0,0 +1,108 @@
+//! How long the steps should take, from how long they took on earlier runs,
+//! for showing the time left.
+
+use crate::ctx::{Step, StepId};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, time::Duration};
+
+/// How much the latest run counts against the earlier ones.
+const SMOOTHING: f64 = 0.5;
+/// Below this, progress says too little about the time left.
+const MIN_FRACTION: f32 = 0.05;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Timings {
+    /// How fast the game files were copied, in bytes per second.
+    #[serde(default)]
+    pub copy_rate: Option<f64>,
+    /// Seconds each step took, by step name.
+    #[serde(default)]
+    pub steps: BTreeMap<String, f64>,
+}
+
+impl Timings {
+    pub fn record_copy(&mut self, bytes: u64, took: Duration) {
+        if took.as_secs_f64() > 0.0 {
+            let rate = bytes as f64 / took.as_secs_f64();
+            self.copy_rate = Some(smooth(self.copy_rate, rate));
+        }
+    }
+
+    pub fn record_step(&mut self, name: &str, took: Duration) {
+        let secs = smooth(self.steps.get(name).copied(), took.as_secs_f64());
+        self.steps.insert(name.to_string(), secs);
+    }
+
+    /// How long `step` should take, with `source_size` bytes of game files to
+    /// copy. `None` before it ever ran.
+    pub fn estimate(&self, step: &Step, source_size: Option<u64>) -> Option<Duration> {
+        if step.id == StepId::Copy
+            && let (Some(rate), Some(size)) = (self.copy_rate, source_size)
+        {
+            return Some(Duration::from_secs_f64(size as f64 / rate));
+        }
+        self.steps
+            .get(step.name)
+            .map(|secs| Duration::from_secs_f64(*secs))
+    }
+}
+
+fn smooth(previous: Option<f64>, latest: f64) -> f64 {
+    match previous {
+        Some(previous) => previous * (1.0 - SMOOTHING) + latest * SMOOTHING,
+        None => latest,
+    }
+}
+
+/// The time left of a step `fraction` done after `elapsed`, from its progress
+/// once that is telling and from `expected` before.
+pub fn remaining(
+    elapsed: Duration,
+    fraction: Option<f32>,
+    expected: Option<Duration>,
+) -> Option<Duration> {
+    match fraction {
+        Some(fraction) if fraction >= MIN_FRACTION => {
+            Some(elapsed.mul_f32((1.0 - fraction.min(1.0)) / fraction))
+        }
+        _ => expected.map(|expected| expected.saturating_sub(elapsed)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Timings, remaining};
+    use crate::default_steps;
+    use std::time::Duration;
+
+    #[test]
+    fn estimates_from_history_then_progress() {
+        let steps = default_steps();
+        let (copy, goldberg) = (&steps[0], &steps[1]);
+        let mut timings = Timings::default();
+        assert_eq!(timings.estimate(copy, Some(1000)), None);
+        timings.record_copy(1000, Duration::from_secs(10));
+        timings.record_copy(1000, Duration::from_secs(5));
+        // 100 then 200 bytes per second, averaged.
+        assert_eq!(
+            timings.estimate(copy, Some(3000)),
+            Some(Duration::from_secs(20))
+        );
+        timings.record_step("Goldberg", Duration::from_secs(30));
+        assert_eq!(
+            timings.estimate(goldberg, None),
+            Some(Duration::from_secs(30))
+        );
+
+        let secs = Duration::from_secs;
+        assert_eq!(
+            remaining(secs(10), Some(0.5), Some(secs(100))),
+            Some(secs(10))
+        );
+        assert_eq!(
+            remaining(secs(10), Some(0.01), Some(secs(100))),
+            Some(secs(90))
+        );
+        assert_eq!(remaining(secs(10), None, None), None);
+    }
+}
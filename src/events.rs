@@ -0,0 +1,55 @@
+//! Hands the updates of the steps, their progress and the logs to everyone
+//! listening, e.g. the window, the command line output and tests, each on
+//! its own channel.
+
+use crate::AppUpdate;
+use std::sync::{
+    Arc, Mutex,
+    mpsc::{Receiver, Sender, channel},
+};
+
+/// Clones send to the same subscribers.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Vec<Sender<AppUpdate>>>>,
+}
+
+impl EventBus {
+    /// Receives every update sent from now on, until the receiver is
+    /// dropped.
+    pub fn subscribe(&self) -> Receiver<AppUpdate> {
+        let (tx, rx) = channel();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// Sends `update` to every subscriber, forgetting the ones that dropped
+    /// their receiver.
+    pub fn send(&self, update: AppUpdate) {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|tx| tx.send(update.clone()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EventBus;
+    use crate::AppUpdate;
+
+    #[test]
+    fn sends_to_every_subscriber() {
+        let events = EventBus::default();
+        let window = events.subscribe();
+        let output = events.subscribe();
+        events.send(AppUpdate::SourceSize(1));
+        assert!(matches!(window.try_recv(), Ok(AppUpdate::SourceSize(1))));
+        assert!(matches!(output.try_recv(), Ok(AppUpdate::SourceSize(1))));
+
+        drop(output);
+        events.send(AppUpdate::SourceSize(2));
+        assert!(matches!(window.try_recv(), Ok(AppUpdate::SourceSize(2))));
+        assert_eq!(events.subscribers.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    Context,
+    aoe::aoe2::server::SERVER_DIR,
+    config::{Config, Injection},
+    ctx::{CancellationToken, InstallStep, Prerequisite, Progress, StepId},
+    goldberg::GOLDBERG_SUBDIR,
+};
+use anyhow::{Result, bail};
+use std::{process::Command, sync::Arc};
+use tracing::{info, warn};
+
+pub const FIREWALL_SCRIPT: &str = "setup_firewall.bat";
+pub(crate) const RULE_PREFIX: &str = "AoE2 Archive";
+// Goldberg's default when no listen port is configured.
+const GOLDBERG_PORT: u16 = 47584;
+
+pub struct FirewallStep;
+
+impl InstallStep for FirewallStep {
+    fn id(&self) -> StepId {
+        StepId::Firewall
+    }
+
+    fn name(&self) -> &'static str {
+        "Firewall"
+    }
+
+    fn prerequisites(&self) -> Prerequisite {
+        Prerequisite::Archive
+    }
+
+    fn enabled(&self, ctx: &Context) -> bool {
+        ctx.config().firewall.generate_script
+    }
+
+    fn run(&self, ctx: &Arc<Context>, _: &Progress, _: &CancellationToken) -> Result<()> {
+        setup_firewall(ctx)
+    }
+}
+
+/// Writes a script allowing the game, loader and lan server through Windows
+/// Firewall to the archive, and runs it elevated on this machine if the user
+/// agreed to.
+pub fn setup_firewall(ctx: &Context) -> Result<()> {
+    let config = ctx.config();
+    let outdir = ctx.outdir();
+    let has_server = outdir.join(SERVER_DIR).join("server.exe").exists();
+
+    let script_path = outdir.join(FIREWALL_SCRIPT);
+    std::fs::write(&script_path, firewall_script(&config, has_server))?;
+    info!("Wrote {FIREWALL_SCRIPT}, run it on machines that can't find each other on the lan");
+
+    if !config.firewall.install_rules {
+        return Ok(());
+    }
+
+    info!("Installing firewall rules, confirm the administrator prompt");
+    let status = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(format!(
+            "$p = Start-Process -FilePath {} -Verb RunAs -Wait -PassThru; exit $p.ExitCode",
+            common::powershell_quote(&script_path.display().to_string())
+        ))
+        .status()?;
+    if !status.success() {
+        bail!("Installing the firewall rules failed or was declined ({status})");
+    }
+    info!("Firewall rules installed");
+
+    Ok(())
+}
+
+fn firewall_script(config: &Config, has_server: bool) -> String {
+    let steam_folder = &config.aoe2.steam_folder;
+    let mut programs = vec![("Game", format!("{steam_folder}\\AoE2DE_s.exe"))];
+    if config.goldberg.injection == Injection::ColdClientLoader {
+        programs.push((
+            "Loader",
+            format!("{GOLDBERG_SUBDIR}\\steamclient_loader_x64.exe"),
+        ));
+    }
+    if has_server {
+        programs.push(("Server", format!("{SERVER_DIR}\\server.exe")));
+    } else {
+        warn!("No lan server in the archive, skipping its firewall rules");
+    }
+
+    let goldberg_port = config.lan.listen_port.unwrap_or(GOLDBERG_PORT);
+    let mut ports = vec![
+        ("Goldberg TCP", "TCP", goldberg_port),
+        ("Goldberg UDP", "UDP", goldberg_port),
+    ];
+    if has_server {
+        ports.extend(
+            config
+                .firewall
+                .server_tcp_ports
+                .iter()
+                .map(|&p| ("Server TCP", "TCP", p)),
+        );
+        ports.extend(
+            config
+                .firewall
+                .server_udp_ports
+                .iter()
+                .map(|&p| ("Server UDP", "UDP", p)),
+        );
+    }
+
+    let mut script = String::from(
+        "@echo off\r\n\
+         :: Allows the archived game and lan server through Windows Firewall.\r\n\
+         net session >nul 2>&1 || (\r\n\
+         \x20   powershell -NoProfile -Command \"Start-Process -FilePath '%~f0' -Verb RunAs\"\r\n\
+         \x20   exit /b\r\n\
+         )\r\n\
+         cd /d \"%~dp0\"\r\n",
+    );
+
+    let mut add_rule = |name: String, rule: String| {
+        script.push_str(&format!(
+            "netsh advfirewall firewall delete rule name=\"{name}\" >nul 2>&1\r\n\
+             netsh advfirewall firewall add rule name=\"{name}\" dir=in action=allow {rule} || exit /b 1\r\n"
+        ));
+    };
+    for (label, program) in programs {
+        add_rule(
+            format!("{RULE_PREFIX} {label}"),
+            format!("program=\"%~dp0{program}\" enable=yes"),
+        );
+    }
+    for (label, protocol, port) in ports {
+        add_rule(
+            format!("{RULE_PREFIX} {label} {port}"),
+            format!("protocol={protocol} localport={port}"),
+        );
+    }
+
+    script.push_str("echo Firewall rules installed.\r\n");
+    script
+}
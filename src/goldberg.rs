@@ -1,19 +1,33 @@
-use crate::{Context, ctx::Task, utils::extract_7z};
+use crate::{
+    Context,
+    aoe::aoe2,
+    config::{Config, GoldbergLayout, Injection},
+    ctx::{CancellationToken, InstallStep, Prerequisite, Progress, StepId},
+    download::{ReleaseAsset, ReleaseSource, download},
+    errors::{ErrorKind, Tag},
+    metadata::record_component,
+    paths,
+    rollback::{self, Journal},
+    steam_web,
+    utils::{extract_archive, gh_latest_release_dl_url},
+};
 use aes_gcm::{
     Aes256Gcm, KeyInit,
-    aead::{Aead, array::Array},
+    aead::{Aead, AeadCore},
+};
+use anyhow::{Result, anyhow, bail};
+use common::{
+    GAME_PROFILE_DIR, GAME_PROFILES_PATH, KEY_FILE,
+    profile::{newest_profile, sync_saves},
+    update::LAUNCH_CONFIG,
 };
-use anyhow::{Result, anyhow};
-use common::KEY;
+use fs_extra::dir::CopyOptions;
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
-    sync::{
-        Arc, LazyLock,
-        mpsc::{self, Receiver},
-    },
+    sync::{Arc, LazyLock},
 };
-use tracing::{error, info};
+use tracing::{info, warn};
 
 const FILES: &[&str] = &[
     "steamclient.dll",
@@ -21,7 +35,8 @@ const FILES: &[&str] = &[
     "coldclientloader.ini",
     "steamclient_loader_x64.exe",
 ];
-const SUBDIRS: &[&str] = &["dlls", "steam_settings", "saves"];
+const STEAM_API_DLL: &str = "steam_api64.dll";
+const STEAM_API_BACKUP: &str = "steam_api64.dll.orig";
 
 const STEAM_SETTINGS_FILES_SLICE: &[(&str, &str)] = &[
     (
@@ -46,60 +61,185 @@ static STEAM_SETTINGS_FILES: LazyLock<HashMap<String, String>> = LazyLock::new(|
 });
 
 pub const GOLDBERG_SUBDIR: &str = "goldberg";
+const STEP: &str = "Goldberg";
 
-pub fn spawn_apply(ctx: Arc<Context>) -> Result<Receiver<()>> {
-    let guard = ctx.set_task(Task::Goldberg)?;
+pub struct GoldbergStep;
 
-    let (tx, rx) = mpsc::sync_channel(0);
+impl InstallStep for GoldbergStep {
+    fn id(&self) -> StepId {
+        StepId::Goldberg
+    }
 
-    std::thread::spawn(move || {
-        let _guard = guard;
-        ctx.set_step_status(1, crate::StepStatus::InProgress);
-        match apply_goldberg(ctx.clone()) {
-            Ok(_) => {
-                ctx.set_step_status(1, crate::StepStatus::Completed);
-                info!("Goldberg emulator applied successfully");
-                let _ = tx.send(());
-            }
-            Err(err) => {
-                let err_msg = format!("{:#}", err);
-                ctx.set_step_status(1, crate::StepStatus::Failed(err_msg.clone()));
-                error!("Goldberg installation failed: {err_msg}");
-            }
-        }
-    });
+    fn name(&self) -> &'static str {
+        STEP
+    }
+
+    fn prerequisites(&self) -> Prerequisite {
+        Prerequisite::Archive
+    }
 
-    Ok(rx)
+    fn run(&self, ctx: &Arc<Context>, progress: &Progress, _: &CancellationToken) -> Result<()> {
+        rollback::transaction(ctx, STEP, |journal| apply_goldberg(ctx, progress, journal))
+    }
 }
 
-pub fn apply_goldberg(ctx: Arc<Context>) -> Result<()> {
+/// Installs goldberg, tracking what it writes in `journal`.
+pub fn apply_goldberg(ctx: &Context, progress: &Progress, journal: &mut Journal) -> Result<()> {
     info!("Downloading Goldberg Emulator");
 
-    let goldberg_archive = {
-        let dl_url = &ctx.config.goldberg.download_url;
-        info!("Downloading goldberg from {}", dl_url);
-        let gbe_archive = reqwest::blocking::get(dl_url)?.bytes()?.to_vec();
+    let (release, goldberg_archive) = {
+        let config = ctx.config();
+        let source = ReleaseSource {
+            name: "goldberg",
+            local: config.goldberg.local_archive.as_deref(),
+            // Custom download urls come without a checksum.
+            verify: false,
+            sha256: None,
+        };
+        let (release, gbe_archive) = source.fetch(ctx, progress, 0.6, || goldberg_release(ctx))?;
 
         info!("Extracting Goldberg Emulator Archive");
-        let archive = extract_7z(&gbe_archive)?;
+        progress.phase(0.6, 0.7, "Extracting");
+        let archive = extract_archive(ctx, &release.name, &gbe_archive)?;
         info!("Extracted {} files from archive", archive.len());
         for path in archive.keys() {
             info!("  Archive contains: {}", path);
         }
-        archive
+        (release, archive)
     };
 
-    let goldberg_dir = ctx.outdir().join(GOLDBERG_SUBDIR);
-    std::fs::create_dir_all(&goldberg_dir)?;
+    let config = ctx.config();
+    let outdir = ctx.outdir();
+    let game_dir = outdir.join(&config.aoe2.steam_folder);
+    let goldberg_dir = outdir.join(GOLDBERG_SUBDIR);
+    journal.create_dir_all(&goldberg_dir)?;
     info!("Output directory: {}", goldberg_dir.display());
 
+    // The folder steam_settings and saves live in.
+    progress.phase(0.7, 0.8, "Installing");
+    let settings_root = match config.goldberg.injection {
+        Injection::ColdClientLoader => {
+            restore_steam_api(&game_dir, journal)?;
+            install_cold_client(ctx, goldberg_archive, &goldberg_dir, journal)?;
+            goldberg_dir.clone()
+        }
+        Injection::SteamApi => {
+            install_steam_api(ctx, goldberg_archive, &game_dir, journal)?;
+            game_dir.clone()
+        }
+    };
+
+    for subdir in ["steam_settings", "saves"] {
+        let subdir_path = settings_root.join(subdir);
+        info!("Creating subdirectory: {}", subdir_path.display());
+        journal.create_dir_all(&subdir_path).map_err(|e| {
+            anyhow!(
+                "Failed to create directory {}: {}",
+                subdir_path.display(),
+                e
+            )
+        })?;
+    }
+
+    let imported_user_ini = match &config.goldberg.import_from {
+        Some(previous) => import_previous_archive(ctx, previous, &settings_root, journal)?,
+        // Keep the account name and language when repairing an archive.
+        None => std::fs::read(
+            settings_root
+                .join("steam_settings")
+                .join("configs.user.ini"),
+        )
+        .ok(),
+    };
+
+    // Files in the assets folder stand in for the bundled defaults, the
+    // generated ones replace both.
+    let mut steam_settings = STEAM_SETTINGS_FILES.clone();
+    for (filename, default_file) in &mut steam_settings {
+        let src_path = paths::assets_dir().join(filename.as_str());
+        if std::fs::exists(&src_path)? {
+            *default_file = std::fs::read_to_string(src_path)?;
+        }
+    }
+    if config.goldberg.generate_steam_settings {
+        progress.phase(0.8, 0.95, "Generating steam settings");
+        match generate_steam_settings(ctx) {
+            Ok(generated) => steam_settings.extend(generated),
+            Err(err) => warn!("Failed to generate steam settings, using bundled defaults: {err:#}"),
+        }
+    }
+
+    progress.phase(0.95, 1.0, "Writing settings");
+    let steam_settings_dir = settings_root.join("steam_settings");
+    for (filename, file) in &steam_settings {
+        let dest_path = steam_settings_dir.join(filename);
+        journal.track(&dest_path)?;
+        std::fs::write(dest_path, file)?;
+    }
+
+    let user_ini_path = steam_settings_dir.join("configs.user.ini");
+    if let Some(user_ini) = imported_user_ini {
+        std::fs::write(&user_ini_path, user_ini)?;
+    }
+    if let Some(language) = &config.aoe2.language {
+        set_user_language(&user_ini_path, &steam_settings_dir, language)?;
+    }
+
+    write_lan_settings(&config, &steam_settings_dir, journal)?;
+
+    if config.goldberg.import_profile {
+        progress.report("Copying the game profile", 0.5);
+        import_game_profile(&settings_root, &user_ini_path, journal)?;
+    }
+
+    let app_ini_path = steam_settings_dir.join("configs.app.ini");
+    journal.track(&app_ini_path)?;
+    aoe2::dlc::write_dlc_ids(ctx, &app_ini_path)?;
+
+    let launcher = include_bytes!("../target/release-lto/launch.exe");
+    journal.track(&outdir.join("launcher.exe"))?;
+    std::fs::write(outdir.join("launcher.exe"), launcher)?;
+    journal.track(&outdir.join(LAUNCH_CONFIG))?;
+    write_launch_config(&config, &outdir)?;
+
+    record_component(ctx, "goldberg", &release.tag, &release.url)?;
+
+    info!("Done installing goldberg");
+
+    Ok(())
+}
+
+/// Installs the cold client loader, which injects goldberg's steamclient
+/// and the companion dlls into the untouched game.
+fn install_cold_client(
+    ctx: &Context,
+    goldberg_archive: HashMap<String, Vec<u8>>,
+    goldberg_dir: &Path,
+    journal: &mut Journal,
+) -> Result<()> {
     info!("Patching goldberg into export");
+    let config = ctx.config();
+    let layout = config.goldberg.layout;
+    let encrypt = config.goldberg.encrypt_loader;
+    // Don't leave the loader of a previous run with the other setting around.
+    let stale_files: &[&str] = if encrypt {
+        &["steamclient_loader_x64.exe"]
+    } else {
+        &["steamclient_loader_x64.encrypted", KEY_FILE]
+    };
+    for stale in stale_files {
+        let stale_path = goldberg_dir.join(stale);
+        if stale_path.exists() {
+            journal.track(&stale_path)?;
+            std::fs::remove_file(&stale_path)?;
+        }
+    }
+    validate_archive(&goldberg_archive, layout)?;
+
     for (path, mut file) in goldberg_archive {
-        const EXPERIMENTAL: &str = "release/steamclient_experimental/";
-        if !path.starts_with(EXPERIMENTAL) {
+        let Some(original_path) = layout_file_name(layout, &path) else {
             continue;
-        }
-        let original_path = path.replace(EXPERIMENTAL, "");
+        };
         let path_lower = original_path.to_lowercase();
 
         if !FILES.contains(&&*path_lower) {
@@ -109,12 +249,10 @@ pub fn apply_goldberg(ctx: Arc<Context>) -> Result<()> {
         info!("Processing file: {}", original_path);
 
         // Determine the output filename, preserving case for non-encrypted files
-        let output_filename = if path_lower == "steamclient_loader_x64.exe" {
+        let output_filename = if path_lower == "steamclient_loader_x64.exe" && encrypt {
             info!("Encrypting steamclient_loader_x64.exe");
-            let key = Array::try_from(&KEY[..32]).expect("Key is always 32 bytes");
-            let cipher = Aes256Gcm::new(&key);
-            let nonce = Array::try_from([0; 12]).expect("Nonce should always work");
-            file = cipher.encrypt(&nonce, &*file).expect("Encryption failure");
+            journal.track(&goldberg_dir.join(KEY_FILE))?;
+            file = encrypt_loader(&file, goldberg_dir)?;
             "steamclient_loader_x64.encrypted".to_string()
         } else {
             original_path
@@ -123,36 +261,30 @@ pub fn apply_goldberg(ctx: Arc<Context>) -> Result<()> {
         let file_path = goldberg_dir.join(&output_filename);
         info!("Writing file to: {}", file_path.display());
 
-        if let Some(parent) = file_path.parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent).map_err(|e| {
-                    anyhow!("Failed to create directory {}: {}", parent.display(), e)
-                })?;
-            }
+        if let Some(parent) = file_path.parent()
+            && !parent.exists()
+        {
+            journal
+                .create_dir_all(parent)
+                .map_err(|e| anyhow!("Failed to create directory {}: {}", parent.display(), e))?;
         }
 
+        journal.track(&file_path)?;
         std::fs::write(&file_path, file)
             .map_err(|e| anyhow!("Failed to write file {}: {}", file_path.display(), e))?;
         info!("Successfully wrote: {}", file_path.display());
     }
 
-    for subdir in SUBDIRS {
-        let subdir_path = goldberg_dir.join(subdir);
-        info!("Creating subdirectory: {}", subdir_path.display());
-        std::fs::create_dir_all(&subdir_path).map_err(|e| {
-            anyhow!(
-                "Failed to create directory {}: {}",
-                subdir_path.display(),
-                e
-            )
-        })?;
-    }
+    let dlls_dir = goldberg_dir.join("dlls");
+    journal
+        .create_dir_all(&dlls_dir)
+        .map_err(|e| anyhow!("Failed to create directory {}: {}", dlls_dir.display(), e))?;
 
     // Configure goldberg for AoE2
     info!("Patching goldberg configs");
 
     // Find the ini file case-insensitively
-    let ini_path = std::fs::read_dir(&goldberg_dir)?
+    let ini_path = std::fs::read_dir(goldberg_dir)?
         .filter_map(|entry| entry.ok())
         .map(|entry| entry.path())
         .find(|path| {
@@ -166,41 +298,527 @@ pub fn apply_goldberg(ctx: Arc<Context>) -> Result<()> {
                 "ColdClientLoader.ini not found in {}. The file may not have been extracted from the archive.",
                 goldberg_dir.display()
             )
-        })?;
+        })
+        .kind(ErrorKind::UpstreamLayoutChanged)?;
 
     info!("Found ini file at: {}", ini_path.display());
-    update_cold_client_loader(&ini_path)?;
+    journal.track(&ini_path)?;
+    update_cold_client_loader(&ini_path, &config)?;
 
-    for (filename, default_file) in &*STEAM_SETTINGS_FILES {
-        let src_path = PathBuf::from("assets").join(filename);
-        let dest_path = goldberg_dir.join("steam_settings").join(filename);
-        if std::fs::exists(&src_path)? {
-            std::fs::copy(src_path, dest_path)?;
-        } else {
-            std::fs::write(dest_path, default_file)?;
+    for dll in &config.cold_client_loader.extra_dlls {
+        let Some(file_name) = dll.file_name() else {
+            continue;
+        };
+        info!("Adding extra dll: {}", dll.display());
+        journal.track(&dlls_dir.join(file_name))?;
+        std::fs::copy(dll, dlls_dir.join(file_name))
+            .map_err(|e| anyhow!("Failed to copy extra dll {}: {}", dll.display(), e))?;
+    }
+
+    Ok(())
+}
+
+/// Fails early when the release layout changed and not every file the cold
+/// client needs can be found, instead of writing an incomplete install.
+fn validate_archive(
+    goldberg_archive: &HashMap<String, Vec<u8>>,
+    layout: GoldbergLayout,
+) -> Result<()> {
+    let found: Vec<String> = goldberg_archive
+        .keys()
+        .filter_map(|path| layout_file_name(layout, path))
+        .map(|name| name.to_lowercase())
+        .collect();
+    let missing: Vec<&str> = FILES
+        .iter()
+        .filter(|file| !found.iter().any(|f| f == *file))
+        .copied()
+        .collect();
+
+    if !missing.is_empty() {
+        bail!(
+            "The goldberg archive is missing {} for the {layout:?} layout. The archive contains: {}",
+            missing.join(", "),
+            archive_prefixes(goldberg_archive).join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// The distinct folders two levels deep in the archive, to tell what layout
+/// a release actually has.
+fn archive_prefixes(goldberg_archive: &HashMap<String, Vec<u8>>) -> Vec<String> {
+    let mut prefixes: Vec<String> = goldberg_archive
+        .keys()
+        .filter_map(|path| {
+            let path = path.replace('\\', "/");
+            let (dir, _) = path.rsplit_once('/')?;
+            Some(dir.splitn(3, '/').take(2).collect::<Vec<_>>().join("/") + "/")
+        })
+        .collect();
+    prefixes.sort_unstable();
+    prefixes.dedup();
+    prefixes
+}
+
+/// Replaces the game's own steam_api64.dll with goldberg's, keeping the
+/// original next to it so switching back to the loader can restore it.
+fn install_steam_api(
+    ctx: &Context,
+    mut goldberg_archive: HashMap<String, Vec<u8>>,
+    game_dir: &Path,
+    journal: &mut Journal,
+) -> Result<()> {
+    let layout = ctx.config().goldberg.layout;
+    let archive_path = steam_api_archive_path(layout);
+    let Some(steam_api) = goldberg_archive.remove(archive_path) else {
+        return Err(anyhow!(
+            "{archive_path} not found in the goldberg archive for the {layout:?} layout. The archive contains: {}",
+            archive_prefixes(&goldberg_archive).join(", ")
+        ))
+        .kind(ErrorKind::UpstreamLayoutChanged);
+    };
+
+    let dll_path = game_dir.join(STEAM_API_DLL);
+    let backup_path = game_dir.join(STEAM_API_BACKUP);
+    if !dll_path.exists() {
+        bail!(
+            "{} not found, the game files need to be copied first.",
+            dll_path.display()
+        );
+    }
+    journal.track(&dll_path)?;
+    journal.track(&backup_path)?;
+    if !backup_path.exists() {
+        info!("Backing up {}", dll_path.display());
+        std::fs::rename(&dll_path, &backup_path)
+            .map_err(|e| anyhow!("Failed to back up {}: {}", dll_path.display(), e))?;
+    }
+
+    info!("Replacing {}", dll_path.display());
+    std::fs::write(&dll_path, steam_api)
+        .map_err(|e| anyhow!("Failed to write file {}: {}", dll_path.display(), e))?;
+    journal.track(&game_dir.join("steam_appid.txt"))?;
+    std::fs::write(game_dir.join("steam_appid.txt"), aoe2::APP_ID.to_string())?;
+
+    Ok(())
+}
+
+/// Puts the game's original steam_api64.dll back if a previous run replaced it.
+fn restore_steam_api(game_dir: &Path, journal: &mut Journal) -> Result<()> {
+    let backup_path = game_dir.join(STEAM_API_BACKUP);
+    if !backup_path.exists() {
+        return Ok(());
+    }
+
+    info!("Restoring the original {STEAM_API_DLL}");
+    journal.track(&backup_path)?;
+    journal.track(&game_dir.join(STEAM_API_DLL))?;
+    std::fs::rename(&backup_path, game_dir.join(STEAM_API_DLL))?;
+    for stale in ["steam_appid.txt", "steam_settings"] {
+        let stale_path = game_dir.join(stale);
+        journal.track(&stale_path)?;
+        if stale_path.is_dir() {
+            std::fs::remove_dir_all(&stale_path)?;
+        } else if stale_path.exists() {
+            std::fs::remove_file(&stale_path)?;
         }
     }
 
-    let launcher = include_bytes!("../target/release-lto/launch.exe");
-    std::fs::write(ctx.outdir().join("launcher.exe"), launcher)?;
+    Ok(())
+}
 
-    info!("Done installing goldberg");
+/// Writes the broadcast addresses and listen port so copies on other
+/// subnets can find each other.
+fn write_lan_settings(
+    config: &Config,
+    steam_settings_dir: &Path,
+    journal: &mut Journal,
+) -> Result<()> {
+    use ini::Ini;
+
+    let broadcasts: Vec<&str> = config
+        .lan
+        .custom_broadcasts
+        .iter()
+        .map(|b| b.trim())
+        .filter(|b| !b.is_empty())
+        .collect();
+    if !broadcasts.is_empty() {
+        let path = steam_settings_dir.join("custom_broadcasts.txt");
+        journal.track(&path)?;
+        std::fs::write(&path, broadcasts.join("\n"))
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?;
+        info!("Broadcasting to {}", broadcasts.join(", "));
+    }
+
+    let Some(port) = config.lan.listen_port else {
+        return Ok(());
+    };
+    match config.goldberg.layout {
+        GoldbergLayout::GbeFork => {
+            let path = steam_settings_dir.join("configs.main.ini");
+            journal.track(&path)?;
+            let mut conf = if path.exists() {
+                Ini::load_from_file(&path)
+                    .map_err(|e| anyhow!("Failed to load {}: {}", path.display(), e))?
+            } else {
+                Ini::new()
+            };
+            conf.with_section(Some("main::connectivity"))
+                .set("listen_port", port.to_string());
+            conf.write_to_file(&path)
+                .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?;
+        }
+        GoldbergLayout::Goldberg => {
+            let path = steam_settings_dir.join("listen_port.txt");
+            journal.track(&path)?;
+            std::fs::write(&path, port.to_string())
+                .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?;
+        }
+    }
+    info!("Listening on port {port}");
 
     Ok(())
 }
 
-fn update_cold_client_loader(ini_path: &Path) -> Result<()> {
+/// The steam language codes the game can be run in.
+pub fn supported_languages() -> impl Iterator<Item = &'static str> {
+    STEAM_SETTINGS_FILES["supported_languages.txt"]
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+}
+
+/// Sets the language goldberg reports to the game, which the game picks its
+/// text and voices by.
+fn set_user_language(
+    user_ini_path: &Path,
+    steam_settings_dir: &Path,
+    language: &str,
+) -> Result<()> {
+    use ini::Ini;
+
+    let supported = std::fs::read_to_string(steam_settings_dir.join("supported_languages.txt"))
+        .unwrap_or_default();
+    if !supported.lines().any(|l| l.trim() == language) {
+        warn!("{language} is not in supported_languages.txt, the game may fall back to english");
+    }
+
+    let mut conf = Ini::load_from_file(user_ini_path)
+        .map_err(|e| anyhow!("Failed to load {}: {}", user_ini_path.display(), e))?;
+    conf.with_section(Some("user::general"))
+        .set("language", language);
+    conf.write_to_file(user_ini_path)
+        .map_err(|e| anyhow!("Failed to write {}: {}", user_ini_path.display(), e))?;
+    info!("Set the game language to {language}");
+
+    Ok(())
+}
+
+/// Writes the settings of the archive's launcher, keeping those of an
+/// existing archive.
+fn write_launch_config(config: &Config, outdir: &Path) -> Result<()> {
+    use ini::Ini;
+
+    let path = outdir.join(LAUNCH_CONFIG);
+    let mut conf = Ini::load_from_file(&path).unwrap_or_default();
+    conf.with_section(Some("update"))
+        .set("source", config.update.source.trim())
+        .set("manifest_sha256", config.update.manifest_sha256.trim())
+        .set("check_releases", config.update.check_releases.to_string())
+        .set("archiver_repo", config.update.archiver_repo.trim())
+        .set("archiver_version", env!("CARGO_PKG_VERSION"));
+    conf.with_section(Some("game"))
+        .set("folder", config.aoe2.steam_folder.trim())
+        .set(
+            "extra_args",
+            config.cold_client_loader.exe_command_line.trim(),
+        );
+    conf.write_to_file(&path)
+        .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?;
+    Ok(())
+}
+
+/// Copies the most recently played game profile of this machine into
+/// goldberg's saves, from where the archive's launcher restores it under the
+/// archive's steam id. Archives without one get a generated id, the
+/// profile's own id is the creator's real account.
+fn import_game_profile(
+    settings_root: &Path,
+    user_ini_path: &Path,
+    journal: &mut Journal,
+) -> Result<()> {
+    use ini::Ini;
+
+    let Some(profiles) = dirs::home_dir().map(|home| home.join(GAME_PROFILES_PATH)) else {
+        bail!("Unable to find the home folder");
+    };
+    let Some(profile) = newest_profile(&profiles, None) else {
+        warn!("No game profile found in {}", profiles.display());
+        return Ok(());
+    };
+    let steam_id = profile
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    let dest = settings_root.join("saves").join(GAME_PROFILE_DIR);
+    journal.track(&dest)?;
+    journal.track(user_ini_path)?;
+    if dest.exists() {
+        std::fs::remove_dir_all(&dest)?;
+    }
+    std::fs::create_dir_all(&dest)?;
+    let options = CopyOptions::new().content_only(true);
+    fs_extra::dir::copy(&profile, &dest, &options)
+        .map_err(|e| anyhow!("Failed to copy {}: {}", profile.display(), e))?;
+
+    let mut conf = Ini::load_from_file(user_ini_path)
+        .map_err(|e| anyhow!("Failed to load {}: {}", user_ini_path.display(), e))?;
+    let account_steamid = conf
+        .get_from(Some("user::general"), "account_steamid")
+        .unwrap_or_default();
+    if account_steamid.trim().is_empty() {
+        let generated = generated_steam_id();
+        conf.with_section(Some("user::general"))
+            .set("account_steamid", &generated);
+        conf.write_to_file(user_ini_path)
+            .map_err(|e| anyhow!("Failed to write {}: {}", user_ini_path.display(), e))?;
+        info!("Generated the steam id {generated} for the archive");
+    }
+    info!("Copied the game profile {steam_id} into the archive");
+
+    Ok(())
+}
+
+/// A random individual steam id, so archives don't carry their creator's.
+fn generated_steam_id() -> String {
+    use std::hash::{BuildHasher, RandomState};
+
+    const INDIVIDUAL_BASE: u64 = 76561197960265728;
+    let account = RandomState::new().hash_one(std::process::id()) % (1 << 31);
+    (INDIVIDUAL_BASE + 1 + account).to_string()
+}
+
+/// The steam id goldberg plays as in an archive, when one is set.
+pub(crate) fn archive_steam_id(archive: &Path, steam_folder: &str) -> Option<String> {
+    let root = archive_settings_root(archive, steam_folder).ok()?;
+    ini::Ini::load_from_file(root.join("steam_settings").join("configs.user.ini"))
+        .ok()?
+        .get_from(Some("user::general"), "account_steamid")
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Carries goldberg saves over from an archive created earlier, returning
+/// its user config (account name, steam id, language) to replace the default.
+fn import_previous_archive(
+    ctx: &Context,
+    previous: &Path,
+    settings_root: &Path,
+    journal: &mut Journal,
+) -> Result<Option<Vec<u8>>> {
+    let previous_root = archive_settings_root(previous, &ctx.config().aoe2.steam_folder)?;
+    info!("Importing saves and settings from {}", previous.display());
+
+    let previous_saves = previous_root.join("saves");
+    let saves = settings_root.join("saves");
+    if previous_saves.is_dir() && previous_saves.canonicalize()? != saves.canonicalize()? {
+        journal.track(&saves)?;
+        let options = CopyOptions::new().overwrite(true).content_only(true);
+        fs_extra::dir::copy(&previous_saves, &saves, &options)
+            .map_err(|e| anyhow!("Failed to import {}: {}", previous_saves.display(), e))?;
+    }
+
+    let previous_user_ini = previous_root
+        .join("steam_settings")
+        .join("configs.user.ini");
+    if !previous_user_ini.exists() {
+        return Ok(None);
+    }
+    let user_ini = std::fs::read(&previous_user_ini)
+        .map_err(|e| anyhow!("Failed to import {}: {}", previous_user_ini.display(), e))?;
+
+    Ok(Some(user_ini))
+}
+
+/// The folder holding goldberg's steam_settings and saves in an archive.
+fn archive_settings_root(archive: &Path, steam_folder: &str) -> Result<PathBuf> {
+    // Archives made in steam_api mode keep settings in the game folder.
+    [archive.join(GOLDBERG_SUBDIR), archive.join(steam_folder)]
+        .into_iter()
+        .find(|root| root.join("steam_settings").is_dir())
+        .ok_or_else(|| {
+            anyhow!(
+                "{} doesn't look like an archive, it has no goldberg settings",
+                archive.display()
+            )
+        })
+}
+
+/// Copies the game profile carried in an archive into this machine's game
+/// profile, so saves and recorded games played on the road show up in
+/// the steam install. Returns how many files were copied.
+pub fn export_archive_saves(archive: &Path, steam_folder: &str) -> Result<usize> {
+    let root = archive_settings_root(archive, steam_folder)?;
+    let saves = root.join("saves").join(GAME_PROFILE_DIR);
+    if !saves.is_dir() {
+        bail!(
+            "{} carries no game profile, save it with \"Copy saves to Steam\" in the archive's launcher.exe first",
+            archive.display()
+        );
+    }
+
+    let Some(profiles) = dirs::home_dir().map(|home| home.join(GAME_PROFILES_PATH)) else {
+        bail!("Unable to find the home folder");
+    };
+    // Archives made before steam ids were generated play as the creator's.
+    let Some(profile) = archive_steam_id(archive, steam_folder)
+        .map(|id| profiles.join(id))
+        .filter(|profile| profile.is_dir())
+        .or_else(|| newest_profile(&profiles, None))
+    else {
+        bail!(
+            "No game profile found in {}, start the game once first",
+            profiles.display()
+        );
+    };
+
+    let copied = sync_saves(&saves, &profile)
+        .map_err(|e| anyhow!("Failed to copy the saves to {}: {}", profile.display(), e))?;
+    info!(
+        "Copied {copied} files from {} to {}",
+        saves.display(),
+        profile.display()
+    );
+    Ok(copied)
+}
+
+/// Encrypts the loader with a fresh random key and nonce, which are written
+/// to the key file `launch` decrypts it with.
+fn encrypt_loader(loader: &[u8], goldberg_dir: &Path) -> Result<Vec<u8>> {
+    let key = Aes256Gcm::generate_key().map_err(|e| anyhow!("Failed to generate key: {e}"))?;
+    let nonce =
+        Aes256Gcm::generate_nonce().map_err(|e| anyhow!("Failed to generate nonce: {e}"))?;
+
+    let cipher = Aes256Gcm::new(&key);
+    let encrypted = cipher
+        .encrypt(&nonce, loader)
+        .map_err(|e| anyhow!("Failed to encrypt loader: {e}"))?;
+
+    let key_path = goldberg_dir.join(KEY_FILE);
+    std::fs::write(&key_path, [key.as_slice(), nonce.as_slice()].concat())
+        .map_err(|e| anyhow!("Failed to write key file {}: {}", key_path.display(), e))?;
+
+    Ok(encrypted)
+}
+
+/// Resolves the goldberg archive, either the configured direct url or the
+/// newest matching asset of the configured fork's github releases.
+fn goldberg_release(ctx: &Context) -> Result<ReleaseAsset> {
+    let config = ctx.config();
+    let goldberg = &config.goldberg;
+    if let Some(url) = &goldberg.download_url {
+        return Ok(ReleaseAsset {
+            tag: "custom".to_string(),
+            name: url.rsplit('/').next().unwrap_or(url).to_string(),
+            url: url.clone(),
+            sha256: None,
+            checksums_url: None,
+            local: false,
+        });
+    }
+
+    info!(
+        "Getting latest goldberg release url from {}/{}.",
+        goldberg.gh_user, goldberg.gh_repo
+    );
+    let search: Vec<&str> = goldberg.asset_search.iter().map(String::as_str).collect();
+    gh_latest_release_dl_url(
+        ctx,
+        &goldberg.gh_user,
+        &goldberg.gh_repo,
+        None,
+        goldberg.prerelease,
+        &search,
+    )?
+    .ok_or_else(|| {
+        anyhow!(
+            "No release asset of {}/{} matches {:?}",
+            goldberg.gh_user,
+            goldberg.gh_repo,
+            goldberg.asset_search
+        )
+    })
+}
+
+/// Maps a path inside the release archive to the file name it should have in
+/// the goldberg folder, or `None` if the file isn't part of the cold client.
+fn layout_file_name(layout: GoldbergLayout, path: &str) -> Option<String> {
+    let path = path.replace('\\', "/");
+    match layout {
+        GoldbergLayout::GbeFork => path
+            .strip_prefix("release/steamclient_experimental/")
+            .map(str::to_string),
+        GoldbergLayout::Goldberg => {
+            let name = path.strip_prefix("experimental_steamclient/")?;
+            if name.eq_ignore_ascii_case("steamclient_loader.exe") {
+                Some("steamclient_loader_x64.exe".to_string())
+            } else {
+                Some(name.to_string())
+            }
+        }
+    }
+}
+
+/// Where a fork keeps the regular 64 bit steam_api64.dll in its release archive.
+fn steam_api_archive_path(layout: GoldbergLayout) -> &'static str {
+    match layout {
+        GoldbergLayout::GbeFork => "release/regular/x64/steam_api64.dll",
+        GoldbergLayout::Goldberg => "steam_api64.dll",
+    }
+}
+
+fn generate_steam_settings(ctx: &Context) -> Result<HashMap<String, String>> {
+    let api_key = match ctx.config().goldberg.steam_web_api_key.trim() {
+        "" => std::env::var("STEAM_WEB_API_KEY").ok(),
+        key => Some(key.to_string()),
+    };
+
+    info!("Generating steam settings from the Steam Web API");
+    steam_web::generate_steam_settings(&ctx.http(), aoe2::APP_ID, api_key.as_deref())
+}
+
+fn update_cold_client_loader(ini_path: &Path, config: &Config) -> Result<()> {
     use ini::Ini;
 
+    let loader = &config.cold_client_loader;
     info!("Loading ini file from: {}", ini_path.display());
     let mut conf = Ini::load_from_file(ini_path)
         .map_err(|e| anyhow!("Failed to load {}: {}", ini_path.display(), e))?;
 
     conf.with_section(Some("SteamClient"))
-        .set("Exe", r#"..\AoE2DE\AoE2DE_s.exe"#)
-        .set("AppId", "813780");
+        .set(
+            "Exe",
+            format!(r#"..\{}\AoE2DE_s.exe"#, config.aoe2.steam_folder),
+        )
+        .set("ExeCommandLine", &loader.exe_command_line)
+        .set("AppId", aoe2::APP_ID.to_string());
     conf.with_section(Some("Injection"))
-        .set("DllsToInjectFolder", "dlls");
+        .set("DllsToInjectFolder", "dlls")
+        .set(
+            "IgnoreLoaderArchDifference",
+            u8::from(loader.ignore_loader_arch_difference).to_string(),
+        );
+    conf.with_section(Some("Persistence"))
+        .set("Mode", loader.persistence_mode.to_string());
+    conf.with_section(Some("Debug")).set(
+        "ResumeByDebugger",
+        u8::from(loader.resume_by_debugger).to_string(),
+    );
 
     info!("Writing updated ini file to: {}", ini_path.display());
     conf.write_to_file(ini_path)
@@ -211,6 +829,7 @@ fn update_cold_client_loader(ini_path: &Path) -> Result<()> {
 
 #[allow(dead_code)]
 pub fn latest_release(ctx: &Context) -> Result<HashMap<String, Vec<u8>>> {
-    let archive = reqwest::blocking::get(&ctx.config.goldberg.download_url)?.bytes()?;
-    extract_7z(&archive.to_vec())
+    let release = goldberg_release(ctx)?;
+    let archive = download(ctx, &release.url)?;
+    extract_archive(ctx, &release.name, &archive)
 }
@@ -0,0 +1,191 @@
+//! The guided layout: a page per decision, then the steps running and a
+//! summary of how they went.
+
+use crate::{
+    App,
+    ctx::StepStatus,
+    i18n::{tr, tr_fmt},
+    presets::draw_presets,
+    report::{Report, draw_report},
+    run_all_steps,
+    source_checks::draw_source_checks,
+    ui::{
+        RUN_ALL_SHORTCUT, confirm_destination, draw_disk_space, draw_logs, draw_option_fields,
+        draw_status_banner, draw_steps, outdir_folder_selection, run_all_pressed,
+        source_folder_selection,
+    },
+    utils::validate_aoe2_source,
+};
+use eframe::egui::{Button, Color32, RichText, Ui};
+
+const PAGES: [&str; 5] = ["Game", "Destination", "Options", "Create", "Summary"];
+
+pub fn draw_guided(app: &mut App, ui: &mut Ui) {
+    let page = app.guided_page.min(PAGES.len() - 1);
+    ui.heading("AoE2 DE Archiver");
+    ui.label(tr_fmt(
+        "Page {} of {}: {}",
+        &[&(page + 1), &PAGES.len(), &tr(PAGES[page])],
+    ));
+    ui.separator();
+    ui.add_space(10.0);
+    draw_status_banner(ui, app);
+
+    let can_continue = match page {
+        0 => draw_source_page(app, ui),
+        1 => draw_destination_page(app, ui),
+        2 => draw_options_page(app, ui),
+        3 => draw_create_page(app, ui),
+        _ => draw_summary_page(app, ui),
+    };
+
+    ui.add_space(10.0);
+    ui.separator();
+    ui.horizontal(|ui| {
+        let busy = app.ctx.is_busy();
+        if page > 0 && ui.add_enabled(!busy, Button::new(tr("◀ Back"))).clicked() {
+            app.guided_page = page - 1;
+            // Rebuilt when coming back, the steps may run again meanwhile.
+            app.report = None;
+        }
+        if page < PAGES.len() - 1
+            && ui
+                .add_enabled(can_continue, Button::new(tr("Next ▶")))
+                .clicked()
+        {
+            app.guided_page = page + 1;
+        }
+    });
+}
+
+fn draw_source_page(app: &mut App, ui: &mut Ui) -> bool {
+    ui.label(tr(
+        "The archiver copies your Age of Empires II: Definitive Edition installation. It was looked up in Steam, pick it yourself if it wasn't found.",
+    ));
+    ui.add_space(8.0);
+    source_folder_selection(
+        ui,
+        &app.ctx,
+        tr("AoE2 DE Source Directory"),
+        "",
+        app.ctx.sourcedir(),
+        Some(validate_aoe2_source),
+    );
+    ui.add_space(4.0);
+    draw_source_checks(ui, app);
+    app.ctx
+        .sourcedir()
+        .is_some_and(|dir| validate_aoe2_source(&dir).is_ok())
+}
+
+fn draw_destination_page(app: &mut App, ui: &mut Ui) -> bool {
+    ui.label(tr(
+        "The archive is created in this folder. Copy the folder to the other machines afterwards.",
+    ));
+    ui.add_space(8.0);
+    outdir_folder_selection(
+        ui,
+        &app.ctx,
+        tr("Destination Directory"),
+        "",
+        app.ctx.outdir(),
+    );
+    ui.add_space(8.0);
+    draw_disk_space(ui, app);
+
+    let enough_space = match (app.required_space(), app.available_space) {
+        (Some(required), Some(available)) => available > required,
+        _ => true,
+    };
+    if !enough_space {
+        ui.colored_label(
+            Color32::from_rgb(220, 0, 0),
+            tr("Free up space on the destination drive or pick another one"),
+        );
+    }
+    enough_space
+}
+
+fn draw_options_page(app: &mut App, ui: &mut Ui) -> bool {
+    ui.label(tr(
+        "The defaults suit most LAN parties. Each player picks their name in the archive's launch.exe.",
+    ));
+    ui.add_space(8.0);
+    draw_presets(ui, app);
+    draw_option_fields(ui, &app.ctx);
+    true
+}
+
+fn draw_create_page(app: &mut App, ui: &mut Ui) -> bool {
+    let step_status = app.ctx.step_statuses();
+    let not_started = step_status
+        .iter()
+        .all(|s| matches!(s, StepStatus::NotStarted));
+    let busy = app.ctx.is_busy();
+
+    let can_create = not_started && !busy;
+    let create = ui
+        .add_enabled(
+            can_create,
+            Button::new(tr("▶ Create the Archive")).min_size([150.0, 30.0].into()),
+        )
+        .on_hover_text(ui.ctx().format_shortcut(&RUN_ALL_SHORTCUT));
+    if (create.clicked() || can_create && run_all_pressed(ui))
+        && confirm_destination(&app.ctx, true)
+    {
+        run_all_steps(app.ctx.clone());
+    }
+    ui.add_space(8.0);
+    draw_steps(ui, app);
+    ui.add_space(8.0);
+    draw_logs(ui, app);
+
+    !busy
+        && !not_started
+        && !step_status
+            .iter()
+            .any(|s| matches!(s, StepStatus::InProgress))
+}
+
+fn draw_summary_page(app: &mut App, ui: &mut Ui) -> bool {
+    let step_status = app.ctx.step_statuses();
+    let failed = step_status
+        .iter()
+        .any(|s| matches!(s, StepStatus::Failed(_)));
+    ui.label(
+        RichText::new(if failed {
+            tr("Some steps failed, run them again from the previous page")
+        } else {
+            tr("The archive is ready")
+        })
+        .strong()
+        .size(16.0),
+    );
+    ui.add_space(8.0);
+
+    if app.report.is_none() {
+        app.report = Some(Report::new(app));
+    }
+    if let Some(report) = &app.report {
+        draw_report(ui, report);
+    }
+    ui.add_space(8.0);
+
+    let outdir = app.ctx.outdir();
+    ui.label(tr_fmt("The archive is in {}", &[&outdir.display()]));
+    ui.horizontal(|ui| {
+        if ui.button(tr("📂 Open Folder")).clicked() {
+            let _ = std::process::Command::new("explorer").arg(&outdir).spawn();
+        }
+        if ui
+            .button(tr("Start Over"))
+            .on_hover_text(tr("Create another archive"))
+            .clicked()
+        {
+            app.ctx.reset_steps();
+            app.report = None;
+            app.guided_page = 0;
+        }
+    });
+    true
+}
@@ -0,0 +1,237 @@
+//! The archives created before, with what went into them, for getting back
+//! to one to check or refresh it.
+
+use crate::{
+    ctx::{Context, StepStatus},
+    i18n::tr,
+    metadata, repair_archive, run_all_steps,
+    ui::{confirm_destination, labeled},
+};
+use anyhow::{Context as _, Result};
+use common::{
+    sha256_hex,
+    update::{UPDATE_MANIFEST, parse_manifest},
+};
+use eframe::egui::{self, Button, Color32, RichText, Ui, WidgetType};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::{error, info, warn};
+
+/// Runs kept, the oldest are dropped.
+const MAX_RUNS: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Run {
+    /// When the run ended, in seconds since the unix epoch.
+    pub finished: u64,
+    pub destination: PathBuf,
+    pub archiver_version: String,
+    pub game_version: Option<String>,
+    /// Component names with the installed version.
+    #[serde(default)]
+    pub components: BTreeMap<String, String>,
+    /// Why the run failed, none when it succeeded.
+    pub error: Option<String>,
+}
+
+impl Run {
+    /// The run that just ended in `destination`, with the versions recorded
+    /// in the archive.
+    pub fn new(destination: PathBuf, status: &StepStatus) -> Self {
+        let metadata = metadata::read(&destination).unwrap_or_default();
+        Self {
+            finished: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            destination,
+            archiver_version: env!("CARGO_PKG_VERSION").to_string(),
+            game_version: metadata.game_version,
+            components: metadata
+                .components
+                .into_iter()
+                .map(|(name, component)| (name, component.version))
+                .collect(),
+            error: match status {
+                StepStatus::Failed(err) => Some(err.clone()),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Adds `run` to the front of `history`.
+pub fn push_run(history: &mut Vec<Run>, run: Run) {
+    history.insert(0, run);
+    history.truncate(MAX_RUNS);
+}
+
+/// Compares the archive's files with the hashes listed when it was created,
+/// e.g. after antivirus quarantined a dll.
+pub fn spawn_verify(archive: PathBuf) {
+    std::thread::spawn(move || {
+        if let Err(err) = verify(&archive) {
+            error!("Unable to verify {}: {err:#}", archive.display());
+        }
+    });
+}
+
+fn verify(archive: &Path) -> Result<()> {
+    info!("Verifying {}", archive.display());
+    let manifest = std::fs::read_to_string(archive.join(UPDATE_MANIFEST))
+        .with_context(|| format!("No {UPDATE_MANIFEST}, the archive wasn't finished"))?;
+    let entries = parse_manifest(&manifest);
+    let mut changed = 0;
+    for (hash, path) in &entries {
+        match std::fs::read(archive.join(path)) {
+            Ok(data) if sha256_hex(&data) == *hash => {}
+            Ok(_) => {
+                warn!("{path} changed since the archive was created");
+                changed += 1;
+            }
+            Err(_) => {
+                warn!("{path} is missing");
+                changed += 1;
+            }
+        }
+    }
+    if changed == 0 {
+        info!(
+            "All {} files of {} are intact",
+            entries.len(),
+            archive.display()
+        );
+    } else {
+        warn!(
+            "{changed} of {} files differ, repair the archive to restore them",
+            entries.len()
+        );
+    }
+    Ok(())
+}
+
+/// `seconds` since the unix epoch as `YYYY-MM-DD HH:MM UTC`.
+pub fn format_date(seconds: u64) -> String {
+    let days = (seconds / 86_400) as i64;
+    let minutes = seconds % 86_400 / 60;
+    // Howard Hinnant's civil_from_days.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+pub fn draw_history(ui: &mut Ui, ctx: &Arc<Context>) {
+    egui::CollapsingHeader::new(tr("History")).show(ui, |ui| {
+        let history = ctx.history();
+        if history.is_empty() {
+            ui.label(
+                RichText::new(tr("No archives created yet"))
+                    .italics()
+                    .color(Color32::GRAY),
+            );
+            return;
+        }
+        let busy = ctx.is_busy();
+        egui::Grid::new("history").num_columns(4).striped(true).show(ui, |ui| {
+            for (i, run) in history.iter().enumerate() {
+                let status = match &run.error {
+                    Some(err) => StepStatus::Failed(err.clone()),
+                    None => StepStatus::Completed,
+                };
+                let icon = labeled(
+                    ui.label(RichText::new(status.icon()).color(status.color())),
+                    WidgetType::Label,
+                    tr(status.label()),
+                );
+                if let Some(err) = &run.error {
+                    icon.on_hover_text(err);
+                }
+                ui.label(format_date(run.finished));
+                ui.vertical(|ui| {
+                    ui.label(run.destination.display().to_string());
+                    let mut versions = vec![format!("Archiver {}", run.archiver_version)];
+                    versions.extend(run.game_version.clone());
+                    versions.extend(
+                        run.components
+                            .iter()
+                            .map(|(name, version)| format!("{name} {version}")),
+                    );
+                    ui.label(RichText::new(versions.join(", ")).small());
+                });
+                ui.horizontal(|ui| {
+                    let exists = run.destination.is_dir();
+                    let open = ui.add_enabled(exists, Button::new("📂"));
+                    if labeled(open, WidgetType::Button, tr("Open Folder"))
+                        .on_hover_text(tr("Open Folder"))
+                        .clicked()
+                    {
+                        let _ = std::process::Command::new("explorer")
+                            .arg(&run.destination)
+                            .spawn();
+                    }
+                    if ui
+                        .add_enabled(exists && !busy, Button::new(tr("Verify")))
+                        .on_hover_text(tr("Check the archive's files against the hashes listed when it was created"))
+                        .clicked()
+                    {
+                        spawn_verify(run.destination.clone());
+                    }
+                    if ui
+                        .add_enabled(!busy, Button::new(tr("Update")))
+                        .on_hover_text(tr("Run all steps again on this archive"))
+                        .clicked()
+                    {
+                        ctx.pick_outdir(run.destination.clone());
+                        if confirm_destination(ctx, true) {
+                            run_all_steps(ctx.clone());
+                        }
+                    }
+                    if ui
+                        .add_enabled(exists && !busy, Button::new(tr("Repair")))
+                        .on_hover_text(tr("Reinstall goldberg, the companion and the launcher in an archive created earlier, without copying the game again"))
+                        .clicked()
+                    {
+                        repair_archive(ctx.clone(), run.destination.clone());
+                    }
+                    let remove = ui.button("🗑");
+                    if labeled(remove, WidgetType::Button, tr("Remove from the list"))
+                        .on_hover_text(tr("Remove from the list"))
+                        .clicked()
+                        && let Err(err) = ctx.remove_run(i)
+                    {
+                        error!("Failed to save the history: {err:#}");
+                    }
+                });
+                ui.end_row();
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_date;
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(format_date(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_date(951_825_600), "2000-02-29 12:00 UTC");
+        assert_eq!(format_date(1_791_900_000), "2026-10-13 14:00 UTC");
+    }
+}
@@ -0,0 +1,637 @@
+//! German translations of the UI, see [`super::tr`].
+
+pub const STRINGS: &[(&str, &str)] = &[
+    ("Disk Space:", "Speicherplatz:"),
+    (
+        "{} GB required, {} GB available",
+        "{} GB benötigt, {} GB verfügbar",
+    ),
+    ("Configuration", "Konfiguration"),
+    ("AoE2 DE Source Directory", "AoE2-DE-Quellordner"),
+    (
+        "Select the folder containing your Age of Empires II: Definitive Edition installation",
+        "Wähle den Ordner mit deiner Installation von Age of Empires II: Definitive Edition",
+    ),
+    ("Destination Directory", "Zielordner"),
+    (
+        "Select where you want to create the archived copy of the game",
+        "Wähle, wo die archivierte Kopie des Spiels erstellt werden soll",
+    ),
+    ("Steps", "Schritte"),
+    ("Copy", "Kopieren"),
+    ("Redist", "Laufzeitumgebungen"),
+    ("Privacy", "Datenschutz"),
+    ("Shortcuts", "Verknüpfungen"),
+    ("Run", "Ausführen"),
+    ("Re-run", "Erneut ausführen"),
+    (
+        "Another step is running",
+        "Ein anderer Schritt läuft gerade",
+    ),
+    (
+        "Select the source directory first",
+        "Wähle zuerst den Quellordner",
+    ),
+    (
+        "Copy the game files first",
+        "Kopiere zuerst die Spieldateien",
+    ),
+    ("▶ Run All Steps", "▶ Alle Schritte ausführen"),
+    (
+        "Automatically run all steps in sequence",
+        "Alle Schritte automatisch nacheinander ausführen",
+    ),
+    (
+        "🔧 Repair Existing Archive",
+        "🔧 Vorhandenes Archiv reparieren",
+    ),
+    (
+        "Reinstall goldberg, the companion and the launcher in an archive created earlier, without copying the game again",
+        "Goldberg, den Companion und den Launcher in einem früher erstellten Archiv neu installieren, ohne das Spiel erneut zu kopieren",
+    ),
+    (
+        "Select the archive to repair",
+        "Wähle das zu reparierende Archiv",
+    ),
+    (
+        "📥 Copy Saves from an Archive",
+        "📥 Spielstände aus einem Archiv kopieren",
+    ),
+    (
+        "Copy the saves and recorded games played with an archive into your game profile",
+        "Spielstände und Aufzeichnungen, die mit einem Archiv gespielt wurden, in dein Spielprofil kopieren",
+    ),
+    (
+        "Select the archive to copy the saves from",
+        "Wähle das Archiv, aus dem die Spielstände kopiert werden",
+    ),
+    ("Logs", "Protokoll"),
+    ("No logs yet", "Noch keine Einträge"),
+    ("📁 Select Folder", "📁 Ordner wählen"),
+    ("Invalid Directory", "Ungültiger Ordner"),
+    ("Options", "Optionen"),
+    ("Goldberg injection:", "Goldberg-Injektion:"),
+    ("Replace steam_api64.dll", "steam_api64.dll ersetzen"),
+    (
+        "Replacing steam_api64.dll is an alternative for setups where the loader is flaky. The companion dlls can't be injected in this mode.",
+        "Das Ersetzen von steam_api64.dll ist eine Alternative für Systeme, auf denen der Loader unzuverlässig ist. Die Companion-DLLs können in diesem Modus nicht injiziert werden.",
+    ),
+    ("Game language:", "Spielsprache:"),
+    ("Default (english)", "Standard (Englisch)"),
+    (
+        "The language goldberg reports to the game",
+        "Die Sprache, die Goldberg dem Spiel meldet",
+    ),
+    (
+        "Encrypt the goldberg loader",
+        "Goldberg-Loader verschlüsseln",
+    ),
+    (
+        "Ship steamclient_loader_x64.exe encrypted so antivirus doesn't quarantine it. It is decrypted by the archive's launcher while playing.",
+        "steamclient_loader_x64.exe verschlüsselt ausliefern, damit der Virenschutz ihn nicht in Quarantäne verschiebt. Der Launcher des Archivs entschlüsselt ihn beim Spielen.",
+    ),
+    (
+        "Rewrite personal data found in the archive",
+        "Persönliche Daten im Archiv ersetzen",
+    ),
+    (
+        "Replace your user, machine and steam account names and steam ids in the archive's text files with placeholders",
+        "Ersetzt Benutzer-, Computer- und Steam-Kontonamen sowie Steam-IDs in den Textdateien des Archivs durch Platzhalter",
+    ),
+    ("Include my game profile", "Mein Spielprofil einbeziehen"),
+    (
+        "Copy your game settings, hotkeys and single player saves into the archive",
+        "Kopiert deine Spieleinstellungen, Tastenkürzel und Einzelspieler-Spielstände ins Archiv",
+    ),
+    ("Import saves from:", "Spielstände importieren aus:"),
+    (
+        "Carry goldberg saves and the user config over from an archive created earlier",
+        "Übernimmt Goldberg-Spielstände und die Benutzerkonfiguration aus einem früher erstellten Archiv",
+    ),
+    ("Nothing", "Nichts"),
+    ("Test launch the archive", "Archiv testweise starten"),
+    (
+        "Start the archived game once it is created and check it keeps running for {} seconds with the companion loaded",
+        "Startet das archivierte Spiel nach dem Erstellen und prüft, ob es {} Sekunden mit geladenem Companion läuft",
+    ),
+    (
+        "🗑 Clear download cache ({} MB)",
+        "🗑 Download-Cache leeren ({} MB)",
+    ),
+    (
+        "Downloads are kept between runs and only re-downloaded when they change upstream",
+        "Downloads bleiben zwischen Durchläufen erhalten und werden nur neu geladen, wenn sie sich an der Quelle ändern",
+    ),
+    ("Settings", "Einstellungen"),
+    (
+        "Remember directories and options",
+        "Ordner und Optionen merken",
+    ),
+    (
+        "Start the next run with the directories and options picked here",
+        "Beim nächsten Start die hier gewählten Ordner und Optionen verwenden",
+    ),
+    ("Language:", "Sprache:"),
+    ("Theme:", "Design:"),
+    ("Like Windows", "Wie Windows"),
+    ("Light", "Hell"),
+    ("Dark", "Dunkel"),
+    ("Saved to {} on exit", "Wird beim Beenden in {} gespeichert"),
+    ("Reset to defaults", "Auf Standard zurücksetzen"),
+    (
+        "Forget the saved directories and options, going back to config.toml",
+        "Vergisst die gespeicherten Ordner und Optionen und kehrt zu config.toml zurück",
+    ),
+    ("Game arguments:", "Spielargumente:"),
+    (
+        "Extra command line arguments passed to AoE2DE_s.exe. Players can change them from launcher.exe.",
+        "Zusätzliche Befehlszeilenargumente für AoE2DE_s.exe. Spieler können sie in launcher.exe ändern.",
+    ),
+    ("Persistence mode:", "Persistenzmodus:"),
+    ("Off", "Aus"),
+    ("Mode {}", "Modus {}"),
+    (
+        "See the [Persistence] section of ColdClientLoader.ini",
+        "Siehe den Abschnitt [Persistence] in ColdClientLoader.ini",
+    ),
+    (
+        "Ignore loader architecture difference",
+        "Architekturunterschied des Loaders ignorieren",
+    ),
+    ("Resume by debugger", "Durch Debugger fortsetzen"),
+    (
+        "Suspend the game on start until a debugger resumes it",
+        "Hält das Spiel beim Start an, bis ein Debugger es fortsetzt",
+    ),
+    ("Extra dlls to inject:", "Zusätzliche DLLs zum Injizieren:"),
+    ("➕ Add dll", "➕ DLL hinzufügen"),
+    ("Companion components:", "Companion-Komponenten:"),
+    (
+        "Parts of the companion zip to include in the archive, see config.toml for where each goes",
+        "Teile des Companion-Zips, die ins Archiv kommen; wohin sie jeweils gehen, steht in config.toml",
+    ),
+    ("Installed to {}", "Installiert nach {}"),
+    ("Shortcuts:", "Verknüpfungen:"),
+    ("Start Menu", "Startmenü"),
+    (
+        "Add the archive as a non-steam game, for Big Picture and Steam Input. Close steam first.",
+        "Fügt das Archiv als Nicht-Steam-Spiel hinzu, für Big Picture und Steam Input. Schließe Steam vorher.",
+    ),
+    (
+        "Add create_shortcuts.exe",
+        "create_shortcuts.exe hinzufügen",
+    ),
+    (
+        "Lets the machines the archive is copied to create the same shortcuts",
+        "Damit die Computer, auf die das Archiv kopiert wird, dieselben Verknüpfungen anlegen können",
+    ),
+    ("Bundle installers:", "Installationsprogramme beilegen:"),
+    (
+        "The archive's launcher offers to run them on machines lacking the runtime",
+        "Der Launcher des Archivs bietet sie auf Computern an, denen die Laufzeitumgebung fehlt",
+    ),
+    ("About 100 MB", "Etwa 100 MB"),
+    (
+        "Custom broadcast addresses, one per line:",
+        "Eigene Broadcast-Adressen, eine pro Zeile:",
+    ),
+    (
+        "Needed when the players aren't all on the same subnet, e.g. over a vpn",
+        "Nötig, wenn nicht alle Spieler im selben Subnetz sind, z. B. über ein VPN",
+    ),
+    ("LAN server:", "LAN-Server:"),
+    ("Find one automatically", "Automatisch suchen"),
+    (
+        "Hostname or ip of the machine hosting the ageLANServer",
+        "Hostname oder IP des Computers, auf dem der ageLANServer läuft",
+    ),
+    (
+        "Include the LAN server (host mode)",
+        "LAN-Server einbeziehen (Host-Modus)",
+    ),
+    (
+        "Install the ageLANServer with a host_server.bat, so this archive can host the party",
+        "Installiert den ageLANServer mit einer host_server.bat, damit dieses Archiv die Party hosten kann",
+    ),
+    ("When none is found", "Wenn keiner gefunden wird"),
+    ("Always", "Immer"),
+    ("Never", "Nie"),
+    ("Host a server:", "Server hosten:"),
+    (
+        "Whether the archive's launcher starts a lan server itself",
+        "Ob der Launcher des Archivs selbst einen LAN-Server startet",
+    ),
+    ("Listen port:", "Port:"),
+    (
+        "Every copy on the lan needs the same port",
+        "Jede Kopie im LAN braucht denselben Port",
+    ),
+    (
+        "Add a setup_firewall.bat to the archive",
+        "setup_firewall.bat zum Archiv hinzufügen",
+    ),
+    (
+        "Allows the game, loader and lan server through Windows Firewall on the machines the archive is copied to",
+        "Lässt Spiel, Loader und LAN-Server auf den Computern, auf die das Archiv kopiert wird, durch die Windows-Firewall",
+    ),
+    (
+        "Also install the firewall rules on this machine",
+        "Firewall-Regeln auch auf diesem Computer einrichten",
+    ),
+    (
+        "Runs setup_firewall.bat after creating the archive. Windows asks for administrator rights.",
+        "Führt setup_firewall.bat nach dem Erstellen des Archivs aus. Windows fragt nach Administratorrechten.",
+    ),
+    ("Advanced", "Erweitert"),
+    ("Release channels:", "Release-Kanäle:"),
+    ("Goldberg prereleases", "Goldberg-Vorabversionen"),
+    ("Companion prereleases", "Companion-Vorabversionen"),
+    (
+        "Only used when the companion version is \"latest\"",
+        "Nur wenn die Companion-Version \"latest\" ist",
+    ),
+    ("Launcher prereleases", "Launcher-Vorabversionen"),
+    ("Server prereleases", "Server-Vorabversionen"),
+    (
+        "Only used when the launcher version is \"latest\"",
+        "Nur wenn die Launcher-Version \"latest\" ist",
+    ),
+    ("Offline archives", "Offline-Archive"),
+    (
+        "Archives downloaded by hand, for machines without internet access:",
+        "Von Hand heruntergeladene Archive, für Computer ohne Internetzugang:",
+    ),
+    ("Download", "Herunterladen"),
+    ("Archive", "Archiv"),
+    (
+        "🧹 Clean temporary files ({} MB)",
+        "🧹 Temporäre Dateien löschen ({} MB)",
+    ),
+    (
+        "Remove partial downloads and extraction leftovers",
+        "Entfernt unvollständige Downloads und Reste vom Entpacken",
+    ),
+    ("✗ Error:", "✗ Fehler:"),
+    ("✖ Cancel", "✖ Abbrechen"),
+    (
+        "Stop the running step, it can be run again afterwards",
+        "Hält den laufenden Schritt an, er kann danach erneut ausgeführt werden",
+    ),
+    ("View", "Ansicht"),
+    ("Guided", "Geführt"),
+    ("Companion version:", "Companion-Version:"),
+    (
+        "A release tag like v1.2.1.0, or latest",
+        "Ein Release-Tag wie v1.2.1.0 oder latest",
+    ),
+    ("Launcher version:", "Launcher-Version:"),
+    (
+        "A release tag like v1.11.2, or latest. Newer launchers may not work with the archive yet.",
+        "Ein Release-Tag wie v1.11.2 oder latest. Neuere Launcher funktionieren eventuell noch nicht mit dem Archiv.",
+    ),
+    ("Game", "Spiel"),
+    ("Destination", "Ziel"),
+    ("Create", "Erstellen"),
+    ("Summary", "Zusammenfassung"),
+    ("Page {} of {}: {}", "Seite {} von {}: {}"),
+    ("◀ Back", "◀ Zurück"),
+    ("Next ▶", "Weiter ▶"),
+    (
+        "The archiver copies your Age of Empires II: Definitive Edition installation. It was looked up in Steam, pick it yourself if it wasn't found.",
+        "Der Archiver kopiert deine Installation von Age of Empires II: Definitive Edition. Sie wurde in Steam gesucht; wähle sie selbst, falls sie nicht gefunden wurde.",
+    ),
+    (
+        "The archive is created in this folder. Copy the folder to the other machines afterwards.",
+        "Das Archiv wird in diesem Ordner erstellt. Kopiere den Ordner danach auf die anderen Computer.",
+    ),
+    (
+        "Free up space on the destination drive or pick another one",
+        "Schaffe Platz auf dem Ziellaufwerk oder wähle ein anderes",
+    ),
+    (
+        "The defaults suit most LAN parties. Each player picks their name in the archive's launch.exe.",
+        "Die Standardwerte passen für die meisten LAN-Partys. Jeder Spieler wählt seinen Namen in der launch.exe des Archivs.",
+    ),
+    ("▶ Create the Archive", "▶ Archiv erstellen"),
+    (
+        "Some steps failed, run them again from the previous page",
+        "Einige Schritte sind fehlgeschlagen, führe sie auf der vorherigen Seite erneut aus",
+    ),
+    ("The archive is ready", "Das Archiv ist fertig"),
+    ("Not run", "Nicht ausgeführt"),
+    ("Done", "Erledigt"),
+    ("Skipped", "Übersprungen"),
+    ("Failed: {}", "Fehlgeschlagen: {}"),
+    ("The archive is in {}", "Das Archiv liegt in {}"),
+    ("📂 Open Folder", "📂 Ordner öffnen"),
+    ("Start Over", "Von vorn beginnen"),
+    ("Create another archive", "Ein weiteres Archiv erstellen"),
+    ("Recent folders", "Zuletzt verwendete Ordner"),
+    (
+        "No recent folders yet",
+        "Noch keine zuletzt verwendeten Ordner",
+    ),
+    ("💾 Export logs", "💾 Logs exportieren"),
+    (
+        "Save the whole session's logs to a file, e.g. for a bug report",
+        "Speichert alle Logs dieser Sitzung in einer Datei, z. B. für einen Fehlerbericht",
+    ),
+    ("🔍 Search", "🔍 Suchen"),
+    ("No logs match the filter", "Keine Logs passen zum Filter"),
+    ("{} of {} files: {}", "{} von {} Dateien: {}"),
+    ("Recent files", "Letzte Dateien"),
+    ("Show", "Anzeigen"),
+    ("Hide to the tray", "In den Infobereich ausblenden"),
+    ("Cancel the running step", "Laufenden Schritt abbrechen"),
+    ("The {} step failed", "Der Schritt {} ist fehlgeschlagen"),
+    ("Breakdown", "Aufschlüsselung"),
+    ("Game files", "Spieldateien"),
+    ("Goldberg emulator", "Goldberg-Emulator"),
+    ("Launcher and companion", "Launcher und Companion"),
+    ("Runtime installers", "Laufzeit-Installer"),
+    ("Game profile and mods", "Spielprofil und Mods"),
+    (
+        "An estimate, it's only known once downloaded",
+        "Eine Schätzung, genau bekannt erst nach dem Download",
+    ),
+    ("Download sources", "Downloadquellen"),
+    ("Download url:", "Download-URL:"),
+    ("Looked up on github", "Wird auf GitHub gesucht"),
+    ("Launcher and server", "Launcher und Server"),
+    ("Proxy url:", "Proxy-URL:"),
+    ("Username:", "Benutzername:"),
+    ("Password:", "Passwort:"),
+    (
+        "Use the proxy environment variables when no url is set",
+        "Die Proxy-Umgebungsvariablen verwenden, wenn keine URL gesetzt ist",
+    ),
+    ("Save to config.toml", "In config.toml speichern"),
+    (
+        "Keep the changes made in this section for the next runs",
+        "Behält die Änderungen in diesem Abschnitt für die nächsten Durchläufe",
+    ),
+    (
+        "Go back to the archiver's sources and remove the pinned versions and checksums",
+        "Stellt die Quellen des Archivers wieder her und entfernt festgelegte Versionen und Prüfsummen",
+    ),
+    ("Release tag:", "Release-Tag:"),
+    ("Expected SHA-256:", "Erwartete SHA-256:"),
+    (
+        "The one published with the release",
+        "Die mit dem Release veröffentlichte",
+    ),
+    (
+        "Not a SHA-256, which has 64 hex digits",
+        "Keine SHA-256, die aus 64 Hex-Ziffern besteht",
+    ),
+    ("Github owner:", "GitHub-Besitzer:"),
+    ("Github repository:", "GitHub-Repository:"),
+    ("Asset name contains:", "Asset-Name enthält:"),
+    ("Not an http or https url", "Keine http- oder https-URL"),
+    (
+        "Not a valid proxy url, downloads keep the previous proxy",
+        "Keine gültige Proxy-URL, Downloads verwenden weiter den vorherigen Proxy",
+    ),
+    ("Required", "Erforderlich"),
+    (
+        "Only letters, digits, - _ and . are allowed",
+        "Nur Buchstaben, Ziffern, - _ und . sind erlaubt",
+    ),
+    (
+        "Empty patterns match every asset",
+        "Leere Muster passen auf jedes Asset",
+    ),
+    ("Server asset name contains:", "Server-Asset-Name enthält:"),
+    ("Install Goldberg first", "Installiere zuerst Goldberg"),
+    (
+        "Reset, so the step counts as not run",
+        "Zurücksetzen, damit der Schritt als nicht ausgeführt gilt",
+    ),
+    ("↺ Reset Steps", "↺ Schritte zurücksetzen"),
+    (
+        "Forget how the steps went, to run them all again",
+        "Vergisst die Ergebnisse der Schritte, um alle erneut auszuführen",
+    ),
+    ("Update", "Aktualisieren"),
+    ("Replace", "Ersetzen"),
+    ("Pick another folder", "Anderen Ordner wählen"),
+    ("The destination isn't empty", "Das Ziel ist nicht leer"),
+    (
+        "{} already holds game files. Update copies over them and keeps the rest, Replace deletes them first.",
+        "{} enthält bereits Spieldateien. Aktualisieren kopiert darüber und behält den Rest, Ersetzen löscht sie zuerst.",
+    ),
+    ("Restart as administrator", "Als Administrator neu starten"),
+    (
+        "The destination can't be written",
+        "In das Ziel kann nicht geschrieben werden",
+    ),
+    (
+        "Windows doesn't allow writing to {} without administrator rights.",
+        "Windows erlaubt das Schreiben in {} nicht ohne Administratorrechte.",
+    ),
+    ("Jobs", "Aufträge"),
+    (
+        "Queue the destination and options above, then pick the next destination. The jobs run one after another.",
+        "Reihe das Zielverzeichnis und die Optionen oben ein und wähle dann das nächste Ziel. Die Aufträge laufen nacheinander.",
+    ),
+    ("➕ Queue this destination", "➕ Dieses Ziel einreihen"),
+    (
+        "Select the source directory, or a destination that isn't queued yet",
+        "Wähle das Quellverzeichnis oder ein Ziel, das noch nicht eingereiht ist",
+    ),
+    ("▶ Run Jobs", "▶ Aufträge ausführen"),
+    ("Clear finished", "Abgeschlossene entfernen"),
+    ("No jobs queued", "Keine Aufträge eingereiht"),
+    ("Remove from the list", "Aus der Liste entfernen"),
+    ("Presets", "Vorlagen"),
+    (
+        "Save the options below as a preset to switch back to them in one click.",
+        "Speichere die Optionen unten als Vorlage, um mit einem Klick zu ihnen zurückzukehren.",
+    ),
+    ("Apply these options", "Diese Optionen übernehmen"),
+    (
+        "Apply these options, archiving to:",
+        "Diese Optionen übernehmen, archiviert nach:",
+    ),
+    ("🗑 Delete", "🗑 Löschen"),
+    ("Destination:", "Ziel:"),
+    ("Keep the current one", "Aktuelles beibehalten"),
+    (
+        "{n} is replaced by the first number without a folder yet, e.g. E:\\AoE2-{n}",
+        "{n} wird durch die erste Zahl ersetzt, für die es noch keinen Ordner gibt, z. B. E:\\AoE2-{n}",
+    ),
+    ("💾 Save preset", "💾 Vorlage speichern"),
+    (
+        "Save the current options, replacing the preset with the same name",
+        "Die aktuellen Optionen speichern und die gleichnamige Vorlage ersetzen",
+    ),
+    (
+        "Right click a preset to delete it",
+        "Rechtsklick auf eine Vorlage, um sie zu löschen",
+    ),
+    (
+        "Copy the whole folder to each machine, e.g. on a USB stick.",
+        "Kopiere den ganzen Ordner auf jeden Rechner, z. B. mit einem USB-Stick.",
+    ),
+    (
+        "Run launcher.exe in it and pick a player name.",
+        "Starte darin launcher.exe und wähle einen Spielernamen.",
+    ),
+    (
+        "Run {} to remove the game from a machine again.",
+        "Führe {} aus, um das Spiel wieder von einem Rechner zu entfernen.",
+    ),
+    ("Game files copied: {}", "Kopierte Spieldateien: {}"),
+    ("Installed components:", "Installierte Komponenten:"),
+    ("Warnings: {}", "Warnungen: {}"),
+    ("Next steps:", "Nächste Schritte:"),
+    (
+        "💾 Save the report into the archive",
+        "💾 Bericht im Archiv speichern",
+    ),
+    ("Font size:", "Schriftgröße:"),
+    (
+        "Default zoom and font size",
+        "Standard-Zoom und -Schriftgröße",
+    ),
+    ("Running", "Läuft"),
+    ("Failed", "Fehlgeschlagen"),
+    ("Reset the {} step", "Schritt {} zurücksetzen"),
+    (
+        "About {} left in this step, {} overall",
+        "Noch etwa {} für diesen Schritt, {} insgesamt",
+    ),
+    ("About {} left", "Noch etwa {}"),
+    (
+        "The archiver {} is out, this is {}. Archives created with an outdated archiver may not start.",
+        "Der Archivierer {} ist erschienen, dies ist {}. Mit einem veralteten Archivierer erstellte Archive starten eventuell nicht.",
+    ),
+    ("⬇ Download", "⬇ Herunterladen"),
+    ("Release page", "Release-Seite"),
+    ("Dismiss", "Ausblenden"),
+    ("Release notes", "Versionshinweise"),
+    (
+        "Check for newer archiver releases on startup",
+        "Beim Start nach neueren Versionen des Archivierers suchen",
+    ),
+    (
+        "Asks github when starting, archives need an archiver that keeps up with the launcher and Goldberg",
+        "Fragt beim Start bei github nach, Archive brauchen einen Archivierer, der mit dem Launcher und Goldberg Schritt hält",
+    ),
+    ("Folder", "Ordner"),
+    ("Game executable", "Spieldatei"),
+    ("Game folders", "Spielordner"),
+    ("Size", "Größe"),
+    ("Not running", "Nicht gestartet"),
+    ("Local folder", "Lokaler Ordner"),
+    ("Passed", "Bestanden"),
+    ("Warning", "Warnung"),
+    ("🔄 Check again", "🔄 Erneut prüfen"),
+    (
+        "✅ The source passed every check",
+        "✅ Die Quelle hat jede Prüfung bestanden",
+    ),
+    (
+        "⚠ The source may not copy right",
+        "⚠ Die Quelle wird eventuell nicht richtig kopiert",
+    ),
+    (
+        "❌ The source can't be copied",
+        "❌ Die Quelle kann nicht kopiert werden",
+    ),
+    (
+        "Pick the folder containing AoE2DE_s.exe, usually steamapps/common/AoE2DE in the Steam library",
+        "Wähle den Ordner mit AoE2DE_s.exe, meist steamapps/common/AoE2DE in der Steam-Bibliothek",
+    ),
+    (
+        "The executable may be damaged, verify the game files in Steam",
+        "Die Spieldatei ist eventuell beschädigt, überprüfe die Spieldateien in Steam",
+    ),
+    (
+        "Verify the game files in Steam, or let the download finish",
+        "Überprüfe die Spieldateien in Steam oder lass den Download fertig laufen",
+    ),
+    (
+        "Smaller than a complete installation, let Steam finish downloading the game",
+        "Kleiner als eine vollständige Installation, lass Steam das Spiel fertig herunterladen",
+    ),
+    (
+        "Quit the game first, files in use can't be copied",
+        "Beende zuerst das Spiel, benutzte Dateien können nicht kopiert werden",
+    ),
+    (
+        "The game is on OneDrive, mark the folder as 'Always keep on this device' or move the game to a local drive",
+        "Das Spiel liegt auf OneDrive, markiere den Ordner als 'Immer auf diesem Gerät beibehalten' oder verschiebe das Spiel auf ein lokales Laufwerk",
+    ),
+    ("Source: {}", "Quelle: {}"),
+    ("Game version: {}", "Spielversion: {}"),
+    ("History", "Verlauf"),
+    ("No archives created yet", "Noch keine Archive erstellt"),
+    ("Open Folder", "Ordner öffnen"),
+    ("Verify", "Prüfen"),
+    (
+        "Check the archive's files against the hashes listed when it was created",
+        "Vergleicht die Dateien des Archivs mit den beim Erstellen notierten Prüfsummen",
+    ),
+    (
+        "Run all steps again on this archive",
+        "Alle Schritte erneut für dieses Archiv ausführen",
+    ),
+    ("Repair", "Reparieren"),
+    (
+        "The archive in {} wasn't finished, it stopped at the {} step.",
+        "Das Archiv in {} wurde nicht fertig, es hielt beim Schritt {} an.",
+    ),
+    (
+        "{} game files were copied already.",
+        "{} Spieldateien wurden bereits kopiert.",
+    ),
+    ("Resume", "Fortsetzen"),
+    (
+        "Carry on from that step with the source and options the archive was started with",
+        "Ab diesem Schritt mit der Quelle und den Optionen fortfahren, mit denen das Archiv begonnen wurde",
+    ),
+    ("Start over", "Neu beginnen"),
+    (
+        "Forget the unfinished run, Run All Steps starts from the first step",
+        "Den unfertigen Lauf vergessen, Alle Schritte ausführen beginnt beim ersten Schritt",
+    ),
+    ("Mirrors", "Spiegelserver"),
+    ("Mirror urls:", "Spiegel-URLs:"),
+    (
+        "Portable, the archiver's files are kept next to it",
+        "Portabel, die Dateien des Archivers liegen neben ihm",
+    ),
+    (
+        "Started with --portable or with a portable.txt next to it",
+        "Mit --portable gestartet oder mit einer portable.txt daneben",
+    ),
+    (
+        "Free up space on the destination drive or pick another destination, then run the step again",
+        "Gib Speicherplatz auf dem Ziellaufwerk frei oder wähle ein anderes Ziel und führe den Schritt dann erneut aus",
+    ),
+    (
+        "Pick the folder AoE2 DE is installed in, and let Steam verify the game files if it is",
+        "Wähle den Ordner, in dem AoE2 DE installiert ist, und lass Steam die Spieldateien überprüfen, falls er es ist",
+    ),
+    (
+        "Check the internet connection, or set a proxy or mirror under Download sources",
+        "Prüfe die Internetverbindung oder lege unter Downloadquellen einen Proxy oder Spiegelserver fest",
+    ),
+    (
+        "Wait for the limit to reset, or set a github token in config.toml",
+        "Warte, bis das Limit zurückgesetzt wird, oder lege ein GitHub-Token in config.toml fest",
+    ),
+    (
+        "Close the game and programs using the archive, or restart the archiver as administrator",
+        "Schließe das Spiel und Programme, die das Archiv verwenden, oder starte den Archiver als Administrator neu",
+    ),
+    (
+        "Pin an earlier release in config.toml, or look for a newer archiver",
+        "Lege in config.toml eine frühere Version fest oder suche nach einem neueren Archiver",
+    ),
+    (
+        "Run the step again to download it anew, a proxy or antivirus may have changed the file",
+        "Führe den Schritt erneut aus, um die Datei neu herunterzuladen, ein Proxy oder Virenschutz hat sie womöglich verändert",
+    ),
+];
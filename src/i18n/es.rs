@@ -0,0 +1,645 @@
+//! Spanish translations of the UI, see [`super::tr`].
+
+pub const STRINGS: &[(&str, &str)] = &[
+    ("Disk Space:", "Espacio en disco:"),
+    (
+        "{} GB required, {} GB available",
+        "{} GB necesarios, {} GB disponibles",
+    ),
+    ("Configuration", "Configuración"),
+    ("AoE2 DE Source Directory", "Carpeta de origen de AoE2 DE"),
+    (
+        "Select the folder containing your Age of Empires II: Definitive Edition installation",
+        "Selecciona la carpeta que contiene tu instalación de Age of Empires II: Definitive Edition",
+    ),
+    ("Destination Directory", "Carpeta de destino"),
+    (
+        "Select where you want to create the archived copy of the game",
+        "Selecciona dónde crear la copia archivada del juego",
+    ),
+    ("Steps", "Pasos"),
+    ("Copy", "Copiar"),
+    ("Server", "Servidor"),
+    ("Firewall", "Cortafuegos"),
+    ("Redist", "Redistribuibles"),
+    ("Privacy", "Privacidad"),
+    ("Shortcuts", "Accesos directos"),
+    ("Test", "Prueba"),
+    ("Run", "Ejecutar"),
+    ("Re-run", "Volver a ejecutar"),
+    ("Another step is running", "Hay otro paso en ejecución"),
+    (
+        "Select the source directory first",
+        "Selecciona primero la carpeta de origen",
+    ),
+    (
+        "Copy the game files first",
+        "Copia primero los archivos del juego",
+    ),
+    ("▶ Run All Steps", "▶ Ejecutar todos los pasos"),
+    (
+        "Automatically run all steps in sequence",
+        "Ejecuta todos los pasos en orden automáticamente",
+    ),
+    (
+        "🔧 Repair Existing Archive",
+        "🔧 Reparar una copia existente",
+    ),
+    (
+        "Reinstall goldberg, the companion and the launcher in an archive created earlier, without copying the game again",
+        "Reinstala goldberg, el companion y el launcher en una copia creada antes, sin volver a copiar el juego",
+    ),
+    (
+        "Select the archive to repair",
+        "Selecciona la copia que reparar",
+    ),
+    (
+        "📥 Copy Saves from an Archive",
+        "📥 Copiar partidas guardadas de una copia",
+    ),
+    (
+        "Copy the saves and recorded games played with an archive into your game profile",
+        "Copia las partidas guardadas y grabaciones jugadas con una copia a tu perfil del juego",
+    ),
+    (
+        "Select the archive to copy the saves from",
+        "Selecciona la copia de la que copiar las partidas",
+    ),
+    ("Logs", "Registro"),
+    ("No logs yet", "Aún no hay registros"),
+    ("📁 Select Folder", "📁 Elegir carpeta"),
+    ("Invalid Directory", "Carpeta no válida"),
+    ("Options", "Opciones"),
+    ("Goldberg injection:", "Inyección de goldberg:"),
+    ("Replace steam_api64.dll", "Reemplazar steam_api64.dll"),
+    (
+        "Replacing steam_api64.dll is an alternative for setups where the loader is flaky. The companion dlls can't be injected in this mode.",
+        "Reemplazar steam_api64.dll es una alternativa para equipos donde el loader falla. En este modo no se pueden inyectar las dll del companion.",
+    ),
+    ("Game language:", "Idioma del juego:"),
+    ("Default (english)", "Predeterminado (inglés)"),
+    (
+        "The language goldberg reports to the game",
+        "El idioma que goldberg comunica al juego",
+    ),
+    (
+        "Encrypt the goldberg loader",
+        "Cifrar el loader de goldberg",
+    ),
+    (
+        "Ship steamclient_loader_x64.exe encrypted so antivirus doesn't quarantine it. It is decrypted by the archive's launcher while playing.",
+        "Incluye steamclient_loader_x64.exe cifrado para que el antivirus no lo ponga en cuarentena. El launcher de la copia lo descifra al jugar.",
+    ),
+    (
+        "Rewrite personal data found in the archive",
+        "Reescribir los datos personales encontrados en la copia",
+    ),
+    (
+        "Replace your user, machine and steam account names and steam ids in the archive's text files with placeholders",
+        "Sustituye tus nombres de usuario, equipo y cuenta de steam y tus steam ids en los archivos de texto de la copia por marcadores",
+    ),
+    ("Include my game profile", "Incluir mi perfil del juego"),
+    (
+        "Copy your game settings, hotkeys and single player saves into the archive",
+        "Copia tu configuración del juego, atajos y partidas de un jugador a la copia",
+    ),
+    ("Import saves from:", "Importar partidas de:"),
+    (
+        "Carry goldberg saves and the user config over from an archive created earlier",
+        "Trae las partidas de goldberg y la configuración de usuario de una copia creada antes",
+    ),
+    ("Nothing", "Nada"),
+    ("Test launch the archive", "Probar a iniciar la copia"),
+    (
+        "Start the archived game once it is created and check it keeps running for {} seconds with the companion loaded",
+        "Inicia el juego archivado al crearlo y comprueba que sigue en marcha durante {} segundos con el companion cargado",
+    ),
+    (
+        "🗑 Clear download cache ({} MB)",
+        "🗑 Vaciar la caché de descargas ({} MB)",
+    ),
+    (
+        "Downloads are kept between runs and only re-downloaded when they change upstream",
+        "Las descargas se conservan entre ejecuciones y solo se vuelven a descargar si cambian en origen",
+    ),
+    ("Settings", "Ajustes"),
+    (
+        "Remember directories and options",
+        "Recordar carpetas y opciones",
+    ),
+    (
+        "Start the next run with the directories and options picked here",
+        "Empieza la próxima vez con las carpetas y opciones elegidas aquí",
+    ),
+    ("Language:", "Idioma:"),
+    ("Theme:", "Tema:"),
+    ("Like Windows", "Como Windows"),
+    ("Light", "Claro"),
+    ("Dark", "Oscuro"),
+    ("Saved to {} on exit", "Se guarda en {} al salir"),
+    ("Reset to defaults", "Restablecer valores predeterminados"),
+    (
+        "Forget the saved directories and options, going back to config.toml",
+        "Olvida las carpetas y opciones guardadas y vuelve a config.toml",
+    ),
+    ("Game arguments:", "Argumentos del juego:"),
+    (
+        "Extra command line arguments passed to AoE2DE_s.exe. Players can change them from launcher.exe.",
+        "Argumentos de línea de comandos adicionales para AoE2DE_s.exe. Los jugadores pueden cambiarlos desde launcher.exe.",
+    ),
+    ("Persistence mode:", "Modo de persistencia:"),
+    ("Off", "Desactivado"),
+    ("Mode {}", "Modo {}"),
+    (
+        "See the [Persistence] section of ColdClientLoader.ini",
+        "Consulta la sección [Persistence] de ColdClientLoader.ini",
+    ),
+    (
+        "Ignore loader architecture difference",
+        "Ignorar la diferencia de arquitectura del loader",
+    ),
+    ("Resume by debugger", "Reanudar con depurador"),
+    (
+        "Suspend the game on start until a debugger resumes it",
+        "Suspende el juego al iniciar hasta que un depurador lo reanude",
+    ),
+    ("Extra dlls to inject:", "Dll adicionales que inyectar:"),
+    ("➕ Add dll", "➕ Añadir dll"),
+    ("Companion components:", "Componentes del companion:"),
+    (
+        "Parts of the companion zip to include in the archive, see config.toml for where each goes",
+        "Partes del zip del companion que incluir en la copia; en config.toml se indica dónde va cada una",
+    ),
+    ("Installed to {}", "Se instala en {}"),
+    ("Shortcuts:", "Accesos directos:"),
+    ("Desktop", "Escritorio"),
+    ("Start Menu", "Menú Inicio"),
+    (
+        "Add the archive as a non-steam game, for Big Picture and Steam Input. Close steam first.",
+        "Añade la copia como juego ajeno a steam, para Big Picture y Steam Input. Cierra steam antes.",
+    ),
+    ("Add create_shortcuts.exe", "Añadir create_shortcuts.exe"),
+    (
+        "Lets the machines the archive is copied to create the same shortcuts",
+        "Permite que los equipos a los que se copie creen los mismos accesos directos",
+    ),
+    ("Bundle installers:", "Incluir instaladores:"),
+    (
+        "The archive's launcher offers to run them on machines lacking the runtime",
+        "El launcher de la copia ofrece ejecutarlos en equipos a los que les falte el runtime",
+    ),
+    ("About 100 MB", "Unos 100 MB"),
+    (
+        "Custom broadcast addresses, one per line:",
+        "Direcciones de broadcast propias, una por línea:",
+    ),
+    (
+        "Needed when the players aren't all on the same subnet, e.g. over a vpn",
+        "Necesario si los jugadores no están todos en la misma subred, p. ej. por vpn",
+    ),
+    ("LAN server:", "Servidor LAN:"),
+    ("Find one automatically", "Buscar uno automáticamente"),
+    (
+        "Hostname or ip of the machine hosting the ageLANServer",
+        "Nombre o ip del equipo que aloja el ageLANServer",
+    ),
+    (
+        "Include the LAN server (host mode)",
+        "Incluir el servidor LAN (modo anfitrión)",
+    ),
+    (
+        "Install the ageLANServer with a host_server.bat, so this archive can host the party",
+        "Instala el ageLANServer con un host_server.bat, para que esta copia pueda alojar la partida",
+    ),
+    ("When none is found", "Si no se encuentra ninguno"),
+    ("Always", "Siempre"),
+    ("Never", "Nunca"),
+    ("Host a server:", "Alojar un servidor:"),
+    (
+        "Whether the archive's launcher starts a lan server itself",
+        "Si el launcher de la copia inicia un servidor lan por sí mismo",
+    ),
+    ("Listen port:", "Puerto de escucha:"),
+    (
+        "Every copy on the lan needs the same port",
+        "Todas las copias de la lan necesitan el mismo puerto",
+    ),
+    (
+        "Add a setup_firewall.bat to the archive",
+        "Añadir un setup_firewall.bat a la copia",
+    ),
+    (
+        "Allows the game, loader and lan server through Windows Firewall on the machines the archive is copied to",
+        "Permite el juego, el loader y el servidor lan en el Firewall de Windows de los equipos a los que se copie",
+    ),
+    (
+        "Also install the firewall rules on this machine",
+        "Instalar también las reglas del firewall en este equipo",
+    ),
+    (
+        "Runs setup_firewall.bat after creating the archive. Windows asks for administrator rights.",
+        "Ejecuta setup_firewall.bat tras crear la copia. Windows pide permisos de administrador.",
+    ),
+    ("Advanced", "Avanzado"),
+    ("Release channels:", "Canales de versiones:"),
+    ("Goldberg prereleases", "Versiones preliminares de goldberg"),
+    (
+        "Companion prereleases",
+        "Versiones preliminares del companion",
+    ),
+    (
+        "Only used when the companion version is \"latest\"",
+        "Solo se usa si la versión del companion es \"latest\"",
+    ),
+    (
+        "Launcher prereleases",
+        "Versiones preliminares del launcher",
+    ),
+    ("Server prereleases", "Versiones preliminares del servidor"),
+    (
+        "Only used when the launcher version is \"latest\"",
+        "Solo se usa si la versión del launcher es \"latest\"",
+    ),
+    ("Offline archives", "Paquetes sin conexión"),
+    (
+        "Archives downloaded by hand, for machines without internet access:",
+        "Paquetes descargados a mano, para equipos sin internet:",
+    ),
+    ("Download", "Descargar"),
+    ("Archive", "Paquete"),
+    (
+        "🧹 Clean temporary files ({} MB)",
+        "🧹 Limpiar archivos temporales ({} MB)",
+    ),
+    (
+        "Remove partial downloads and extraction leftovers",
+        "Elimina descargas incompletas y restos de extracciones",
+    ),
+    ("✖ Cancel", "✖ Cancelar"),
+    (
+        "Stop the running step, it can be run again afterwards",
+        "Detiene el paso en curso; se puede volver a ejecutar después",
+    ),
+    ("View", "Ver"),
+    ("Guided", "Guiado"),
+    ("Companion version:", "Versión del companion:"),
+    (
+        "A release tag like v1.2.1.0, or latest",
+        "Una etiqueta de versión como v1.2.1.0, o latest",
+    ),
+    ("Launcher version:", "Versión del launcher:"),
+    (
+        "A release tag like v1.11.2, or latest. Newer launchers may not work with the archive yet.",
+        "Una etiqueta de versión como v1.11.2, o latest. Puede que los launcher más nuevos aún no funcionen con la copia.",
+    ),
+    ("Game", "Juego"),
+    ("Destination", "Destino"),
+    ("Create", "Crear"),
+    ("Summary", "Resumen"),
+    ("Page {} of {}: {}", "Página {} de {}: {}"),
+    ("◀ Back", "◀ Atrás"),
+    ("Next ▶", "Siguiente ▶"),
+    (
+        "The archiver copies your Age of Empires II: Definitive Edition installation. It was looked up in Steam, pick it yourself if it wasn't found.",
+        "El archivador copia tu instalación de Age of Empires II: Definitive Edition. Se ha buscado en Steam; elígela tú si no se encontró.",
+    ),
+    (
+        "The archive is created in this folder. Copy the folder to the other machines afterwards.",
+        "La copia se crea en esta carpeta. Después, copia la carpeta a los demás equipos.",
+    ),
+    (
+        "Free up space on the destination drive or pick another one",
+        "Libera espacio en la unidad de destino o elige otra",
+    ),
+    (
+        "The defaults suit most LAN parties. Each player picks their name in the archive's launch.exe.",
+        "Los valores predeterminados sirven para la mayoría de fiestas LAN. Cada jugador elige su nombre en el launch.exe de la copia.",
+    ),
+    ("▶ Create the Archive", "▶ Crear la copia"),
+    (
+        "Some steps failed, run them again from the previous page",
+        "Algunos pasos han fallado; vuelve a ejecutarlos desde la página anterior",
+    ),
+    ("The archive is ready", "La copia está lista"),
+    ("Not run", "Sin ejecutar"),
+    ("Done", "Hecho"),
+    ("Skipped", "Omitido"),
+    ("Failed: {}", "Error: {}"),
+    ("The archive is in {}", "La copia está en {}"),
+    ("📂 Open Folder", "📂 Abrir carpeta"),
+    ("Start Over", "Empezar de nuevo"),
+    ("Create another archive", "Crear otra copia"),
+    ("Recent folders", "Carpetas recientes"),
+    ("No recent folders yet", "Aún no hay carpetas recientes"),
+    ("💾 Export logs", "💾 Exportar registros"),
+    (
+        "Save the whole session's logs to a file, e.g. for a bug report",
+        "Guarda todos los registros de la sesión en un archivo, p. ej. para informar de un error",
+    ),
+    ("🔍 Search", "🔍 Buscar"),
+    (
+        "No logs match the filter",
+        "Ningún registro coincide con el filtro",
+    ),
+    ("{} of {} files: {}", "{} de {} archivos: {}"),
+    ("Recent files", "Archivos recientes"),
+    ("Show", "Mostrar"),
+    ("Hide to the tray", "Ocultar en la bandeja"),
+    ("Cancel the running step", "Cancelar el paso en curso"),
+    ("The {} step failed", "El paso {} ha fallado"),
+    ("Breakdown", "Desglose"),
+    ("Game files", "Archivos del juego"),
+    ("Goldberg emulator", "Emulador Goldberg"),
+    ("Launcher and companion", "Launcher y companion"),
+    ("Runtime installers", "Instaladores de componentes"),
+    ("Game profile and mods", "Perfil del juego y mods"),
+    (
+        "An estimate, it's only known once downloaded",
+        "Una estimación, solo se conoce tras la descarga",
+    ),
+    ("Download sources", "Orígenes de descarga"),
+    ("Download url:", "URL de descarga:"),
+    ("Looked up on github", "Se busca en GitHub"),
+    ("Launcher and server", "Launcher y servidor"),
+    ("Proxy url:", "URL del proxy:"),
+    ("Username:", "Usuario:"),
+    ("Password:", "Contraseña:"),
+    (
+        "Use the proxy environment variables when no url is set",
+        "Usar las variables de entorno del proxy si no hay URL",
+    ),
+    ("Save to config.toml", "Guardar en config.toml"),
+    (
+        "Keep the changes made in this section for the next runs",
+        "Conserva los cambios de esta sección para las próximas ejecuciones",
+    ),
+    (
+        "Go back to the archiver's sources and remove the pinned versions and checksums",
+        "Vuelve a las fuentes del archivador y quita las versiones y sumas de comprobación fijadas",
+    ),
+    ("Release tag:", "Etiqueta de versión:"),
+    ("Expected SHA-256:", "SHA-256 esperado:"),
+    (
+        "The one published with the release",
+        "La publicada con la versión",
+    ),
+    (
+        "Not a SHA-256, which has 64 hex digits",
+        "No es un SHA-256, que tiene 64 dígitos hexadecimales",
+    ),
+    ("Github owner:", "Propietario en GitHub:"),
+    ("Github repository:", "Repositorio de GitHub:"),
+    ("Asset name contains:", "El nombre del archivo contiene:"),
+    ("Not an http or https url", "No es una URL http o https"),
+    (
+        "Not a valid proxy url, downloads keep the previous proxy",
+        "URL de proxy no válida, las descargas siguen usando el proxy anterior",
+    ),
+    ("Required", "Obligatorio"),
+    (
+        "Only letters, digits, - _ and . are allowed",
+        "Solo se permiten letras, dígitos, - _ y .",
+    ),
+    (
+        "Empty patterns match every asset",
+        "Los patrones vacíos coinciden con cualquier archivo",
+    ),
+    (
+        "Server asset name contains:",
+        "El nombre del archivo del servidor contiene:",
+    ),
+    ("Install Goldberg first", "Instala primero Goldberg"),
+    (
+        "Reset, so the step counts as not run",
+        "Restablecer para que el paso cuente como no ejecutado",
+    ),
+    ("↺ Reset Steps", "↺ Restablecer pasos"),
+    (
+        "Forget how the steps went, to run them all again",
+        "Olvida el resultado de los pasos para ejecutarlos todos de nuevo",
+    ),
+    ("Update", "Actualizar"),
+    ("Replace", "Reemplazar"),
+    ("Pick another folder", "Elegir otra carpeta"),
+    ("The destination isn't empty", "El destino no está vacío"),
+    (
+        "{} already holds game files. Update copies over them and keeps the rest, Replace deletes them first.",
+        "{} ya contiene archivos del juego. Actualizar copia encima y conserva el resto; Reemplazar los borra antes.",
+    ),
+    ("Restart as administrator", "Reiniciar como administrador"),
+    (
+        "The destination can't be written",
+        "No se puede escribir en el destino",
+    ),
+    (
+        "Windows doesn't allow writing to {} without administrator rights.",
+        "Windows no permite escribir en {} sin permisos de administrador.",
+    ),
+    ("Jobs", "Trabajos"),
+    (
+        "Queue the destination and options above, then pick the next destination. The jobs run one after another.",
+        "Pon en cola el destino y las opciones de arriba y elige el siguiente destino. Los trabajos se ejecutan uno tras otro.",
+    ),
+    ("➕ Queue this destination", "➕ Poner este destino en cola"),
+    (
+        "Select the source directory, or a destination that isn't queued yet",
+        "Selecciona el directorio de origen o un destino que aún no esté en cola",
+    ),
+    ("▶ Run Jobs", "▶ Ejecutar trabajos"),
+    ("Clear finished", "Quitar terminados"),
+    ("No jobs queued", "No hay trabajos en cola"),
+    ("Remove from the list", "Quitar de la lista"),
+    ("Presets", "Ajustes predefinidos"),
+    (
+        "Save the options below as a preset to switch back to them in one click.",
+        "Guarda las opciones de abajo como ajuste predefinido para volver a ellas con un clic.",
+    ),
+    ("Apply these options", "Aplicar estas opciones"),
+    (
+        "Apply these options, archiving to:",
+        "Aplicar estas opciones, archivando en:",
+    ),
+    ("🗑 Delete", "🗑 Eliminar"),
+    ("Name:", "Nombre:"),
+    ("Destination:", "Destino:"),
+    ("Keep the current one", "Mantener el actual"),
+    (
+        "{n} is replaced by the first number without a folder yet, e.g. E:\\AoE2-{n}",
+        "{n} se sustituye por el primer número que aún no tiene carpeta, p. ej. E:\\AoE2-{n}",
+    ),
+    ("💾 Save preset", "💾 Guardar ajuste"),
+    (
+        "Save the current options, replacing the preset with the same name",
+        "Guardar las opciones actuales, reemplazando el ajuste con el mismo nombre",
+    ),
+    (
+        "Right click a preset to delete it",
+        "Haz clic derecho en un ajuste para eliminarlo",
+    ),
+    (
+        "Copy the whole folder to each machine, e.g. on a USB stick.",
+        "Copia la carpeta entera a cada equipo, p. ej. con una memoria USB.",
+    ),
+    (
+        "Run launcher.exe in it and pick a player name.",
+        "Ejecuta launcher.exe en ella y elige un nombre de jugador.",
+    ),
+    (
+        "Run {} to remove the game from a machine again.",
+        "Ejecuta {} para quitar el juego de un equipo.",
+    ),
+    ("Game files copied: {}", "Archivos del juego copiados: {}"),
+    ("Installed components:", "Componentes instalados:"),
+    ("Warnings: {}", "Advertencias: {}"),
+    ("Next steps:", "Siguientes pasos:"),
+    (
+        "💾 Save the report into the archive",
+        "💾 Guardar el informe en la copia",
+    ),
+    ("Font size:", "Tamaño de letra:"),
+    (
+        "Default zoom and font size",
+        "Zoom y tamaño de letra predeterminados",
+    ),
+    ("Running", "En curso"),
+    ("Failed", "Falló"),
+    ("Reset the {} step", "Restablecer el paso {}"),
+    (
+        "About {} left in this step, {} overall",
+        "Quedan unos {} en este paso, {} en total",
+    ),
+    ("About {} left", "Quedan unos {}"),
+    (
+        "The archiver {} is out, this is {}. Archives created with an outdated archiver may not start.",
+        "Ya está disponible el archivador {}, este es {}. Las copias creadas con un archivador desactualizado pueden no iniciarse.",
+    ),
+    ("⬇ Download", "⬇ Descargar"),
+    ("Release page", "Página de la versión"),
+    ("Dismiss", "Descartar"),
+    ("Release notes", "Notas de la versión"),
+    (
+        "Check for newer archiver releases on startup",
+        "Buscar versiones más recientes del archivador al iniciar",
+    ),
+    (
+        "Asks github when starting, archives need an archiver that keeps up with the launcher and Goldberg",
+        "Consulta github al iniciar, las copias necesitan un archivador al día con el launcher y Goldberg",
+    ),
+    ("Folder", "Carpeta"),
+    ("Game executable", "Ejecutable del juego"),
+    ("Version", "Versión"),
+    ("Game folders", "Carpetas del juego"),
+    ("Size", "Tamaño"),
+    ("Not running", "No en ejecución"),
+    ("Local folder", "Carpeta local"),
+    ("Passed", "Correcto"),
+    ("Warning", "Advertencia"),
+    ("🔄 Check again", "🔄 Comprobar de nuevo"),
+    (
+        "✅ The source passed every check",
+        "✅ El origen pasó todas las comprobaciones",
+    ),
+    (
+        "⚠ The source may not copy right",
+        "⚠ Puede que el origen no se copie bien",
+    ),
+    (
+        "❌ The source can't be copied",
+        "❌ No se puede copiar el origen",
+    ),
+    (
+        "Pick the folder containing AoE2DE_s.exe, usually steamapps/common/AoE2DE in the Steam library",
+        "Elige la carpeta que contiene AoE2DE_s.exe, normalmente steamapps/common/AoE2DE en la biblioteca de Steam",
+    ),
+    (
+        "The executable may be damaged, verify the game files in Steam",
+        "El ejecutable puede estar dañado, verifica los archivos del juego en Steam",
+    ),
+    (
+        "Verify the game files in Steam, or let the download finish",
+        "Verifica los archivos del juego en Steam o deja que termine la descarga",
+    ),
+    (
+        "Smaller than a complete installation, let Steam finish downloading the game",
+        "Más pequeño que una instalación completa, deja que Steam termine de descargar el juego",
+    ),
+    (
+        "Quit the game first, files in use can't be copied",
+        "Cierra primero el juego, los archivos en uso no se pueden copiar",
+    ),
+    (
+        "The game is on OneDrive, mark the folder as 'Always keep on this device' or move the game to a local drive",
+        "El juego está en OneDrive, marca la carpeta como 'Mantener siempre en este dispositivo' o mueve el juego a una unidad local",
+    ),
+    ("Source: {}", "Origen: {}"),
+    ("Game version: {}", "Versión del juego: {}"),
+    ("History", "Historial"),
+    ("No archives created yet", "Aún no se han creado archivos"),
+    ("Open Folder", "Abrir carpeta"),
+    ("Verify", "Verificar"),
+    (
+        "Check the archive's files against the hashes listed when it was created",
+        "Compara los archivos del archivo con los hashes anotados al crearlo",
+    ),
+    (
+        "Run all steps again on this archive",
+        "Ejecutar de nuevo todos los pasos en este archivo",
+    ),
+    ("Repair", "Reparar"),
+    (
+        "The archive in {} wasn't finished, it stopped at the {} step.",
+        "El archivo en {} no se terminó, se detuvo en el paso {}.",
+    ),
+    (
+        "{} game files were copied already.",
+        "Ya se copiaron {} archivos del juego.",
+    ),
+    ("Resume", "Reanudar"),
+    (
+        "Carry on from that step with the source and options the archive was started with",
+        "Continuar desde ese paso con el origen y las opciones con que se empezó el archivo",
+    ),
+    ("Start over", "Empezar de nuevo"),
+    (
+        "Forget the unfinished run, Run All Steps starts from the first step",
+        "Olvidar la ejecución sin terminar, Ejecutar todos los pasos empieza desde el primer paso",
+    ),
+    ("Mirrors", "Espejos"),
+    ("Mirror urls:", "URL de espejos:"),
+    (
+        "Portable, the archiver's files are kept next to it",
+        "Portátil, los archivos del archivador se guardan junto a él",
+    ),
+    (
+        "Started with --portable or with a portable.txt next to it",
+        "Iniciado con --portable o con un portable.txt junto a él",
+    ),
+    (
+        "Free up space on the destination drive or pick another destination, then run the step again",
+        "Libera espacio en la unidad de destino o elige otro destino y vuelve a ejecutar el paso",
+    ),
+    (
+        "Pick the folder AoE2 DE is installed in, and let Steam verify the game files if it is",
+        "Elige la carpeta en la que está instalado AoE2 DE y, si es esa, deja que Steam verifique los archivos del juego",
+    ),
+    (
+        "Check the internet connection, or set a proxy or mirror under Download sources",
+        "Comprueba la conexión a internet o configura un proxy o un espejo en Orígenes de descarga",
+    ),
+    (
+        "Wait for the limit to reset, or set a github token in config.toml",
+        "Espera a que se restablezca el límite o configura un token de github en config.toml",
+    ),
+    (
+        "Close the game and programs using the archive, or restart the archiver as administrator",
+        "Cierra el juego y los programas que usan el archivo, o reinicia el archivador como administrador",
+    ),
+    (
+        "Pin an earlier release in config.toml, or look for a newer archiver",
+        "Fija una versión anterior en config.toml o busca un archivador más reciente",
+    ),
+    (
+        "Run the step again to download it anew, a proxy or antivirus may have changed the file",
+        "Vuelve a ejecutar el paso para descargarlo de nuevo, puede que un proxy o un antivirus haya modificado el archivo",
+    ),
+];
@@ -0,0 +1,649 @@
+//! French translations of the UI, see [`super::tr`].
+
+pub const STRINGS: &[(&str, &str)] = &[
+    ("Disk Space:", "Espace disque :"),
+    (
+        "{} GB required, {} GB available",
+        "{} Go requis, {} Go disponibles",
+    ),
+    ("AoE2 DE Source Directory", "Dossier source d'AoE2 DE"),
+    (
+        "Select the folder containing your Age of Empires II: Definitive Edition installation",
+        "Sélectionnez le dossier contenant votre installation d'Age of Empires II: Definitive Edition",
+    ),
+    ("Destination Directory", "Dossier de destination"),
+    (
+        "Select where you want to create the archived copy of the game",
+        "Sélectionnez où créer la copie archivée du jeu",
+    ),
+    ("Steps", "Étapes"),
+    ("Copy", "Copie"),
+    ("Server", "Serveur"),
+    ("Firewall", "Pare-feu"),
+    ("Redist", "Redistribuables"),
+    ("Privacy", "Confidentialité"),
+    ("Shortcuts", "Raccourcis"),
+    ("Run", "Lancer"),
+    ("Re-run", "Relancer"),
+    ("Another step is running", "Une autre étape est en cours"),
+    (
+        "Select the source directory first",
+        "Sélectionnez d'abord le dossier source",
+    ),
+    (
+        "Copy the game files first",
+        "Copiez d'abord les fichiers du jeu",
+    ),
+    ("▶ Run All Steps", "▶ Lancer toutes les étapes"),
+    (
+        "Automatically run all steps in sequence",
+        "Lance automatiquement toutes les étapes dans l'ordre",
+    ),
+    (
+        "🔧 Repair Existing Archive",
+        "🔧 Réparer une archive existante",
+    ),
+    (
+        "Reinstall goldberg, the companion and the launcher in an archive created earlier, without copying the game again",
+        "Réinstalle goldberg, le companion et le launcher dans une archive créée auparavant, sans recopier le jeu",
+    ),
+    (
+        "Select the archive to repair",
+        "Sélectionnez l'archive à réparer",
+    ),
+    (
+        "📥 Copy Saves from an Archive",
+        "📥 Copier les sauvegardes d'une archive",
+    ),
+    (
+        "Copy the saves and recorded games played with an archive into your game profile",
+        "Copie les sauvegardes et parties enregistrées jouées avec une archive dans votre profil de jeu",
+    ),
+    (
+        "Select the archive to copy the saves from",
+        "Sélectionnez l'archive dont copier les sauvegardes",
+    ),
+    ("Logs", "Journal"),
+    ("No logs yet", "Aucun journal pour l'instant"),
+    ("📁 Select Folder", "📁 Choisir un dossier"),
+    ("Invalid Directory", "Dossier invalide"),
+    ("Goldberg injection:", "Injection de goldberg :"),
+    ("Replace steam_api64.dll", "Remplacer steam_api64.dll"),
+    (
+        "Replacing steam_api64.dll is an alternative for setups where the loader is flaky. The companion dlls can't be injected in this mode.",
+        "Remplacer steam_api64.dll est une alternative pour les machines où le loader est instable. Les dll du companion ne peuvent pas être injectées dans ce mode.",
+    ),
+    ("Game language:", "Langue du jeu :"),
+    ("Default (english)", "Par défaut (anglais)"),
+    (
+        "The language goldberg reports to the game",
+        "La langue que goldberg indique au jeu",
+    ),
+    (
+        "Encrypt the goldberg loader",
+        "Chiffrer le loader de goldberg",
+    ),
+    (
+        "Ship steamclient_loader_x64.exe encrypted so antivirus doesn't quarantine it. It is decrypted by the archive's launcher while playing.",
+        "Livre steamclient_loader_x64.exe chiffré pour que l'antivirus ne le mette pas en quarantaine. Le launcher de l'archive le déchiffre pendant la partie.",
+    ),
+    (
+        "Rewrite personal data found in the archive",
+        "Réécrire les données personnelles trouvées dans l'archive",
+    ),
+    (
+        "Replace your user, machine and steam account names and steam ids in the archive's text files with placeholders",
+        "Remplace vos noms d'utilisateur, de machine et de compte steam et vos steam ids dans les fichiers texte de l'archive par des valeurs génériques",
+    ),
+    ("Include my game profile", "Inclure mon profil de jeu"),
+    (
+        "Copy your game settings, hotkeys and single player saves into the archive",
+        "Copie vos paramètres de jeu, raccourcis clavier et sauvegardes solo dans l'archive",
+    ),
+    ("Import saves from:", "Importer les sauvegardes de :"),
+    (
+        "Carry goldberg saves and the user config over from an archive created earlier",
+        "Reprend les sauvegardes goldberg et la configuration utilisateur d'une archive créée auparavant",
+    ),
+    ("Nothing", "Rien"),
+    (
+        "Test launch the archive",
+        "Tester le lancement de l'archive",
+    ),
+    (
+        "Start the archived game once it is created and check it keeps running for {} seconds with the companion loaded",
+        "Lance le jeu archivé une fois créé et vérifie qu'il tourne pendant {} secondes avec le companion chargé",
+    ),
+    (
+        "🗑 Clear download cache ({} MB)",
+        "🗑 Vider le cache des téléchargements ({} Mo)",
+    ),
+    (
+        "Downloads are kept between runs and only re-downloaded when they change upstream",
+        "Les téléchargements sont conservés entre les exécutions et ne sont retéléchargés que s'ils changent à la source",
+    ),
+    ("Settings", "Paramètres"),
+    (
+        "Remember directories and options",
+        "Mémoriser les dossiers et options",
+    ),
+    (
+        "Start the next run with the directories and options picked here",
+        "Reprend au prochain lancement les dossiers et options choisis ici",
+    ),
+    ("Language:", "Langue :"),
+    ("Theme:", "Thème :"),
+    ("Like Windows", "Comme Windows"),
+    ("Light", "Clair"),
+    ("Dark", "Sombre"),
+    ("Saved to {} on exit", "Enregistré dans {} à la fermeture"),
+    ("Reset to defaults", "Rétablir les valeurs par défaut"),
+    (
+        "Forget the saved directories and options, going back to config.toml",
+        "Oublie les dossiers et options enregistrés et revient à config.toml",
+    ),
+    ("Game arguments:", "Arguments du jeu :"),
+    (
+        "Extra command line arguments passed to AoE2DE_s.exe. Players can change them from launcher.exe.",
+        "Arguments de ligne de commande supplémentaires passés à AoE2DE_s.exe. Les joueurs peuvent les modifier depuis launcher.exe.",
+    ),
+    ("Persistence mode:", "Mode de persistance :"),
+    ("Off", "Désactivé"),
+    (
+        "See the [Persistence] section of ColdClientLoader.ini",
+        "Voir la section [Persistence] de ColdClientLoader.ini",
+    ),
+    (
+        "Ignore loader architecture difference",
+        "Ignorer la différence d'architecture du loader",
+    ),
+    ("Resume by debugger", "Reprise par le débogueur"),
+    (
+        "Suspend the game on start until a debugger resumes it",
+        "Suspend le jeu au démarrage jusqu'à ce qu'un débogueur le reprenne",
+    ),
+    ("Extra dlls to inject:", "Dll supplémentaires à injecter :"),
+    ("➕ Add dll", "➕ Ajouter une dll"),
+    ("Companion components:", "Composants du companion :"),
+    (
+        "Parts of the companion zip to include in the archive, see config.toml for where each goes",
+        "Parties du zip du companion à inclure dans l'archive ; config.toml indique où va chacune",
+    ),
+    ("Installed to {}", "Installé dans {}"),
+    ("Shortcuts:", "Raccourcis :"),
+    ("Desktop", "Bureau"),
+    ("Start Menu", "Menu Démarrer"),
+    (
+        "Add the archive as a non-steam game, for Big Picture and Steam Input. Close steam first.",
+        "Ajoute l'archive comme jeu non-steam, pour Big Picture et Steam Input. Fermez steam avant.",
+    ),
+    ("Add create_shortcuts.exe", "Ajouter create_shortcuts.exe"),
+    (
+        "Lets the machines the archive is copied to create the same shortcuts",
+        "Permet aux machines où l'archive est copiée de créer les mêmes raccourcis",
+    ),
+    ("Bundle installers:", "Inclure les installateurs :"),
+    (
+        "The archive's launcher offers to run them on machines lacking the runtime",
+        "Le launcher de l'archive propose de les lancer sur les machines où le runtime manque",
+    ),
+    ("About 100 MB", "Environ 100 Mo"),
+    (
+        "Custom broadcast addresses, one per line:",
+        "Adresses de broadcast personnalisées, une par ligne :",
+    ),
+    (
+        "Needed when the players aren't all on the same subnet, e.g. over a vpn",
+        "Nécessaire quand les joueurs ne sont pas tous sur le même sous-réseau, par ex. via un vpn",
+    ),
+    ("LAN server:", "Serveur LAN :"),
+    ("Find one automatically", "En trouver un automatiquement"),
+    (
+        "Hostname or ip of the machine hosting the ageLANServer",
+        "Nom d'hôte ou ip de la machine qui héberge l'ageLANServer",
+    ),
+    (
+        "Include the LAN server (host mode)",
+        "Inclure le serveur LAN (mode hôte)",
+    ),
+    (
+        "Install the ageLANServer with a host_server.bat, so this archive can host the party",
+        "Installe l'ageLANServer avec un host_server.bat, pour que cette archive puisse héberger la partie",
+    ),
+    ("When none is found", "Si aucun n'est trouvé"),
+    ("Always", "Toujours"),
+    ("Never", "Jamais"),
+    ("Host a server:", "Héberger un serveur :"),
+    (
+        "Whether the archive's launcher starts a lan server itself",
+        "Si le launcher de l'archive démarre lui-même un serveur lan",
+    ),
+    ("Listen port:", "Port d'écoute :"),
+    (
+        "Every copy on the lan needs the same port",
+        "Chaque copie sur le lan doit utiliser le même port",
+    ),
+    (
+        "Add a setup_firewall.bat to the archive",
+        "Ajouter un setup_firewall.bat à l'archive",
+    ),
+    (
+        "Allows the game, loader and lan server through Windows Firewall on the machines the archive is copied to",
+        "Autorise le jeu, le loader et le serveur lan dans le pare-feu Windows des machines où l'archive est copiée",
+    ),
+    (
+        "Also install the firewall rules on this machine",
+        "Installer aussi les règles du pare-feu sur cette machine",
+    ),
+    (
+        "Runs setup_firewall.bat after creating the archive. Windows asks for administrator rights.",
+        "Lance setup_firewall.bat après la création de l'archive. Windows demande les droits administrateur.",
+    ),
+    ("Advanced", "Avancé"),
+    ("Release channels:", "Canaux de publication :"),
+    ("Goldberg prereleases", "Préversions de goldberg"),
+    ("Companion prereleases", "Préversions du companion"),
+    (
+        "Only used when the companion version is \"latest\"",
+        "Utilisé seulement quand la version du companion est \"latest\"",
+    ),
+    ("Launcher prereleases", "Préversions du launcher"),
+    ("Server prereleases", "Préversions du serveur"),
+    (
+        "Only used when the launcher version is \"latest\"",
+        "Utilisé seulement quand la version du launcher est \"latest\"",
+    ),
+    ("Offline archives", "Archives hors ligne"),
+    (
+        "Archives downloaded by hand, for machines without internet access:",
+        "Archives téléchargées à la main, pour les machines sans accès à internet :",
+    ),
+    ("Download", "Télécharger"),
+    (
+        "🧹 Clean temporary files ({} MB)",
+        "🧹 Nettoyer les fichiers temporaires ({} Mo)",
+    ),
+    (
+        "Remove partial downloads and extraction leftovers",
+        "Supprime les téléchargements partiels et les restes d'extraction",
+    ),
+    ("✗ Error:", "✗ Erreur :"),
+    ("✖ Cancel", "✖ Annuler"),
+    (
+        "Stop the running step, it can be run again afterwards",
+        "Arrête l'étape en cours, elle peut être relancée ensuite",
+    ),
+    ("View", "Affichage"),
+    ("Guided", "Guidé"),
+    ("Companion version:", "Version du companion :"),
+    (
+        "A release tag like v1.2.1.0, or latest",
+        "Un tag de version comme v1.2.1.0, ou latest",
+    ),
+    ("Launcher version:", "Version du launcher :"),
+    (
+        "A release tag like v1.11.2, or latest. Newer launchers may not work with the archive yet.",
+        "Un tag de version comme v1.11.2, ou latest. Les launchers plus récents peuvent ne pas encore fonctionner avec l'archive.",
+    ),
+    ("Game", "Jeu"),
+    ("Create", "Création"),
+    ("Summary", "Résumé"),
+    ("Page {} of {}: {}", "Page {} sur {} : {}"),
+    ("◀ Back", "◀ Retour"),
+    ("Next ▶", "Suivant ▶"),
+    (
+        "The archiver copies your Age of Empires II: Definitive Edition installation. It was looked up in Steam, pick it yourself if it wasn't found.",
+        "L'archiveur copie votre installation d'Age of Empires II: Definitive Edition. Elle a été cherchée dans Steam, choisissez-la vous-même si elle n'a pas été trouvée.",
+    ),
+    (
+        "The archive is created in this folder. Copy the folder to the other machines afterwards.",
+        "L'archive est créée dans ce dossier. Copiez ensuite le dossier sur les autres machines.",
+    ),
+    (
+        "Free up space on the destination drive or pick another one",
+        "Libérez de l'espace sur le disque de destination ou choisissez-en un autre",
+    ),
+    (
+        "The defaults suit most LAN parties. Each player picks their name in the archive's launch.exe.",
+        "Les valeurs par défaut conviennent à la plupart des LAN. Chaque joueur choisit son nom dans le launch.exe de l'archive.",
+    ),
+    ("▶ Create the Archive", "▶ Créer l'archive"),
+    (
+        "Some steps failed, run them again from the previous page",
+        "Certaines étapes ont échoué, relancez-les depuis la page précédente",
+    ),
+    ("The archive is ready", "L'archive est prête"),
+    ("Not run", "Non lancée"),
+    ("Done", "Terminée"),
+    ("Skipped", "Ignorée"),
+    ("Failed: {}", "Échec : {}"),
+    ("The archive is in {}", "L'archive se trouve dans {}"),
+    ("📂 Open Folder", "📂 Ouvrir le dossier"),
+    ("Start Over", "Recommencer"),
+    ("Create another archive", "Créer une autre archive"),
+    ("Recent folders", "Dossiers récents"),
+    (
+        "No recent folders yet",
+        "Aucun dossier récent pour l'instant",
+    ),
+    ("💾 Export logs", "💾 Exporter les journaux"),
+    (
+        "Save the whole session's logs to a file, e.g. for a bug report",
+        "Enregistre tous les journaux de la session dans un fichier, par ex. pour un rapport de bug",
+    ),
+    ("🔍 Search", "🔍 Rechercher"),
+    (
+        "No logs match the filter",
+        "Aucun journal ne correspond au filtre",
+    ),
+    ("{} of {} files: {}", "{} fichiers sur {} : {}"),
+    ("Recent files", "Fichiers récents"),
+    ("Show", "Afficher"),
+    ("Hide to the tray", "Masquer dans la zone de notification"),
+    ("Cancel the running step", "Annuler l'étape en cours"),
+    ("The {} step failed", "L'étape {} a échoué"),
+    ("Breakdown", "Détail"),
+    ("Game files", "Fichiers du jeu"),
+    ("Goldberg emulator", "Émulateur Goldberg"),
+    ("Launcher and companion", "Launcher et companion"),
+    ("Runtime installers", "Installeurs des runtimes"),
+    ("Game profile and mods", "Profil du jeu et mods"),
+    (
+        "An estimate, it's only known once downloaded",
+        "Une estimation, connue seulement une fois téléchargé",
+    ),
+    ("Download sources", "Sources de téléchargement"),
+    ("Download url:", "URL de téléchargement :"),
+    ("Looked up on github", "Recherché sur GitHub"),
+    ("Launcher and server", "Launcher et serveur"),
+    ("Proxy url:", "URL du proxy :"),
+    ("Username:", "Nom d'utilisateur :"),
+    ("Password:", "Mot de passe :"),
+    (
+        "Use the proxy environment variables when no url is set",
+        "Utiliser les variables d'environnement du proxy si aucune URL n'est définie",
+    ),
+    ("Save to config.toml", "Enregistrer dans config.toml"),
+    (
+        "Keep the changes made in this section for the next runs",
+        "Conserve les modifications de cette section pour les prochaines exécutions",
+    ),
+    (
+        "Go back to the archiver's sources and remove the pinned versions and checksums",
+        "Revient aux sources de l'archiveur et retire les versions et sommes de contrôle épinglées",
+    ),
+    ("Release tag:", "Tag de version :"),
+    ("Expected SHA-256:", "SHA-256 attendu :"),
+    (
+        "The one published with the release",
+        "Celle publiée avec la version",
+    ),
+    (
+        "Not a SHA-256, which has 64 hex digits",
+        "Pas un SHA-256, qui compte 64 chiffres hexadécimaux",
+    ),
+    ("Github owner:", "Propriétaire GitHub :"),
+    ("Github repository:", "Dépôt GitHub :"),
+    ("Asset name contains:", "Le nom du fichier contient :"),
+    ("Not an http or https url", "Pas une URL http ou https"),
+    (
+        "Not a valid proxy url, downloads keep the previous proxy",
+        "URL de proxy invalide, les téléchargements gardent le proxy précédent",
+    ),
+    ("Required", "Obligatoire"),
+    (
+        "Only letters, digits, - _ and . are allowed",
+        "Seuls les lettres, chiffres, - _ et . sont autorisés",
+    ),
+    (
+        "Empty patterns match every asset",
+        "Les motifs vides correspondent à tous les fichiers",
+    ),
+    (
+        "Server asset name contains:",
+        "Le nom du fichier du serveur contient :",
+    ),
+    ("Install Goldberg first", "Installez d'abord Goldberg"),
+    (
+        "Reset, so the step counts as not run",
+        "Réinitialiser, l'étape compte comme non lancée",
+    ),
+    ("↺ Reset Steps", "↺ Réinitialiser les étapes"),
+    (
+        "Forget how the steps went, to run them all again",
+        "Oublie le résultat des étapes pour toutes les relancer",
+    ),
+    ("Update", "Mettre à jour"),
+    ("Replace", "Remplacer"),
+    ("Pick another folder", "Choisir un autre dossier"),
+    (
+        "The destination isn't empty",
+        "La destination n'est pas vide",
+    ),
+    (
+        "{} already holds game files. Update copies over them and keeps the rest, Replace deletes them first.",
+        "{} contient déjà des fichiers du jeu. Mettre à jour copie par-dessus et garde le reste, Remplacer les supprime d'abord.",
+    ),
+    ("Restart as administrator", "Redémarrer en administrateur"),
+    (
+        "The destination can't be written",
+        "Impossible d'écrire dans la destination",
+    ),
+    (
+        "Windows doesn't allow writing to {} without administrator rights.",
+        "Windows n'autorise pas l'écriture dans {} sans droits d'administrateur.",
+    ),
+    ("Jobs", "Tâches"),
+    (
+        "Queue the destination and options above, then pick the next destination. The jobs run one after another.",
+        "Mettez en file la destination et les options ci-dessus, puis choisissez la destination suivante. Les tâches s'exécutent l'une après l'autre.",
+    ),
+    (
+        "➕ Queue this destination",
+        "➕ Mettre cette destination en file",
+    ),
+    (
+        "Select the source directory, or a destination that isn't queued yet",
+        "Sélectionnez le dossier source, ou une destination qui n'est pas encore en file",
+    ),
+    ("▶ Run Jobs", "▶ Lancer les tâches"),
+    ("Clear finished", "Retirer les terminées"),
+    ("No jobs queued", "Aucune tâche en file"),
+    ("Remove from the list", "Retirer de la liste"),
+    ("Presets", "Préréglages"),
+    (
+        "Save the options below as a preset to switch back to them in one click.",
+        "Enregistrez les options ci-dessous comme préréglage pour y revenir en un clic.",
+    ),
+    ("Apply these options", "Appliquer ces options"),
+    (
+        "Apply these options, archiving to:",
+        "Appliquer ces options, en archivant dans :",
+    ),
+    ("🗑 Delete", "🗑 Supprimer"),
+    ("Name:", "Nom :"),
+    ("Destination:", "Destination :"),
+    ("Keep the current one", "Garder l'actuelle"),
+    (
+        "{n} is replaced by the first number without a folder yet, e.g. E:\\AoE2-{n}",
+        "{n} est remplacé par le premier numéro sans dossier, par ex. E:\\AoE2-{n}",
+    ),
+    ("💾 Save preset", "💾 Enregistrer le préréglage"),
+    (
+        "Save the current options, replacing the preset with the same name",
+        "Enregistrer les options actuelles, en remplaçant le préréglage du même nom",
+    ),
+    (
+        "Right click a preset to delete it",
+        "Clic droit sur un préréglage pour le supprimer",
+    ),
+    (
+        "Copy the whole folder to each machine, e.g. on a USB stick.",
+        "Copiez tout le dossier sur chaque machine, par ex. avec une clé USB.",
+    ),
+    (
+        "Run launcher.exe in it and pick a player name.",
+        "Lancez launcher.exe qu'il contient et choisissez un nom de joueur.",
+    ),
+    (
+        "Run {} to remove the game from a machine again.",
+        "Lancez {} pour retirer le jeu d'une machine.",
+    ),
+    ("Game files copied: {}", "Fichiers du jeu copiés : {}"),
+    ("Installed components:", "Composants installés :"),
+    ("Warnings: {}", "Avertissements : {}"),
+    ("Next steps:", "Étapes suivantes :"),
+    (
+        "💾 Save the report into the archive",
+        "💾 Enregistrer le rapport dans l'archive",
+    ),
+    ("Zoom:", "Zoom :"),
+    ("Font size:", "Taille du texte :"),
+    (
+        "Default zoom and font size",
+        "Zoom et taille du texte par défaut",
+    ),
+    ("Running", "En cours"),
+    ("Failed", "Échec"),
+    ("Reset the {} step", "Réinitialiser l'étape {}"),
+    (
+        "About {} left in this step, {} overall",
+        "Environ {} restant pour cette étape, {} au total",
+    ),
+    ("About {} left", "Environ {} restant"),
+    (
+        "The archiver {} is out, this is {}. Archives created with an outdated archiver may not start.",
+        "L'archiveur {} est disponible, celui-ci est {}. Les archives créées avec un archiveur obsolète risquent de ne pas démarrer.",
+    ),
+    ("⬇ Download", "⬇ Télécharger"),
+    ("Release page", "Page de la version"),
+    ("Dismiss", "Ignorer"),
+    ("Release notes", "Notes de version"),
+    (
+        "Check for newer archiver releases on startup",
+        "Rechercher une version plus récente de l'archiveur au démarrage",
+    ),
+    (
+        "Asks github when starting, archives need an archiver that keeps up with the launcher and Goldberg",
+        "Interroge github au démarrage, les archives ont besoin d'un archiveur à jour avec le launcher et Goldberg",
+    ),
+    ("Folder", "Dossier"),
+    ("Game executable", "Exécutable du jeu"),
+    ("Game folders", "Dossiers du jeu"),
+    ("Size", "Taille"),
+    ("Not running", "Pas en cours d'exécution"),
+    ("Local folder", "Dossier local"),
+    ("Passed", "Réussi"),
+    ("Warning", "Avertissement"),
+    ("🔄 Check again", "🔄 Vérifier à nouveau"),
+    (
+        "✅ The source passed every check",
+        "✅ La source a passé toutes les vérifications",
+    ),
+    (
+        "⚠ The source may not copy right",
+        "⚠ La source risque de mal se copier",
+    ),
+    (
+        "❌ The source can't be copied",
+        "❌ La source ne peut pas être copiée",
+    ),
+    (
+        "Pick the folder containing AoE2DE_s.exe, usually steamapps/common/AoE2DE in the Steam library",
+        "Choisissez le dossier contenant AoE2DE_s.exe, en général steamapps/common/AoE2DE dans la bibliothèque Steam",
+    ),
+    (
+        "The executable may be damaged, verify the game files in Steam",
+        "L'exécutable est peut-être endommagé, vérifiez les fichiers du jeu dans Steam",
+    ),
+    (
+        "Verify the game files in Steam, or let the download finish",
+        "Vérifiez les fichiers du jeu dans Steam ou laissez le téléchargement se terminer",
+    ),
+    (
+        "Smaller than a complete installation, let Steam finish downloading the game",
+        "Plus petit qu'une installation complète, laissez Steam finir de télécharger le jeu",
+    ),
+    (
+        "Quit the game first, files in use can't be copied",
+        "Quittez d'abord le jeu, les fichiers utilisés ne peuvent pas être copiés",
+    ),
+    (
+        "The game is on OneDrive, mark the folder as 'Always keep on this device' or move the game to a local drive",
+        "Le jeu est sur OneDrive, marquez le dossier comme 'Toujours conserver sur cet appareil' ou déplacez le jeu sur un disque local",
+    ),
+    ("Source: {}", "Source : {}"),
+    ("Game version: {}", "Version du jeu : {}"),
+    ("History", "Historique"),
+    (
+        "No archives created yet",
+        "Aucune archive créée pour l'instant",
+    ),
+    ("Open Folder", "Ouvrir le dossier"),
+    ("Verify", "Vérifier"),
+    (
+        "Check the archive's files against the hashes listed when it was created",
+        "Compare les fichiers de l'archive aux empreintes notées à sa création",
+    ),
+    (
+        "Run all steps again on this archive",
+        "Relancer toutes les étapes sur cette archive",
+    ),
+    ("Repair", "Réparer"),
+    (
+        "The archive in {} wasn't finished, it stopped at the {} step.",
+        "L'archive dans {} n'a pas été terminée, elle s'est arrêtée à l'étape {}.",
+    ),
+    (
+        "{} game files were copied already.",
+        "{} fichiers du jeu ont déjà été copiés.",
+    ),
+    ("Resume", "Reprendre"),
+    (
+        "Carry on from that step with the source and options the archive was started with",
+        "Continuer à partir de cette étape avec la source et les options avec lesquelles l'archive a été commencée",
+    ),
+    ("Start over", "Recommencer"),
+    (
+        "Forget the unfinished run, Run All Steps starts from the first step",
+        "Oublier l'exécution inachevée, Lancer toutes les étapes commence à la première étape",
+    ),
+    ("Mirrors", "Miroirs"),
+    ("Mirror urls:", "URL des miroirs :"),
+    (
+        "Portable, the archiver's files are kept next to it",
+        "Portable, les fichiers de l'archiveur sont conservés à côté de lui",
+    ),
+    (
+        "Started with --portable or with a portable.txt next to it",
+        "Lancé avec --portable ou avec un portable.txt à côté",
+    ),
+    (
+        "Free up space on the destination drive or pick another destination, then run the step again",
+        "Libérez de l'espace sur le lecteur de destination ou choisissez une autre destination, puis relancez l'étape",
+    ),
+    (
+        "Pick the folder AoE2 DE is installed in, and let Steam verify the game files if it is",
+        "Choisissez le dossier où AoE2 DE est installé et, si c'est le cas, laissez Steam vérifier les fichiers du jeu",
+    ),
+    (
+        "Check the internet connection, or set a proxy or mirror under Download sources",
+        "Vérifiez la connexion internet ou définissez un proxy ou un miroir dans Sources de téléchargement",
+    ),
+    (
+        "Wait for the limit to reset, or set a github token in config.toml",
+        "Attendez que la limite soit réinitialisée ou définissez un jeton github dans config.toml",
+    ),
+    (
+        "Close the game and programs using the archive, or restart the archiver as administrator",
+        "Fermez le jeu et les programmes qui utilisent l'archive, ou redémarrez l'archiveur en tant qu'administrateur",
+    ),
+    (
+        "Pin an earlier release in config.toml, or look for a newer archiver",
+        "Épinglez une version antérieure dans config.toml ou cherchez un archiveur plus récent",
+    ),
+    (
+        "Run the step again to download it anew, a proxy or antivirus may have changed the file",
+        "Relancez l'étape pour le télécharger à nouveau, un proxy ou un antivirus a peut-être modifié le fichier",
+    ),
+];
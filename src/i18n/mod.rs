@@ -0,0 +1,109 @@
+//! The UI's translations. The English text is the key, so untranslated
+//! strings show in English. Logs stay in English for bug reports.
+
+mod de;
+mod es;
+mod fr;
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    Spanish,
+    French,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [
+        Language::English,
+        Language::German,
+        Language::Spanish,
+        Language::French,
+    ];
+
+    /// The language's own name, for picking it without reading English.
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+            Language::Spanish => "Español",
+            Language::French => "Français",
+        }
+    }
+
+    fn strings(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => &[],
+            Language::German => de::STRINGS,
+            Language::Spanish => es::STRINGS,
+            Language::French => fr::STRINGS,
+        }
+    }
+}
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+pub fn language() -> Language {
+    Language::ALL[LANGUAGE.load(Ordering::Relaxed) as usize]
+}
+
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+/// `text` in the UI's language.
+pub fn tr(text: &'static str) -> &'static str {
+    language()
+        .strings()
+        .iter()
+        .find(|(english, _)| *english == text)
+        .map_or(text, |(_, translated)| translated)
+}
+
+/// Translates `template`, then fills its `{}` with `args` in order.
+pub fn tr_fmt(template: &'static str, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut pieces = tr(template).split("{}");
+    let mut text = pieces.next().unwrap_or_default().to_string();
+    for piece in pieces {
+        if let Some(arg) = args.next() {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(piece);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Language, tr_fmt};
+    use std::collections::HashSet;
+
+    #[test]
+    fn translations_keep_placeholders() {
+        for language in Language::ALL {
+            let mut keys = HashSet::new();
+            for (english, translated) in language.strings() {
+                assert!(keys.insert(english), "{language:?} repeats {english}");
+                assert_eq!(
+                    english.matches("{}").count(),
+                    translated.matches("{}").count(),
+                    "{language:?}: {translated}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        assert_eq!(tr_fmt("Installed to {}", &[&"mods"]), "Installed to mods");
+    }
+}
@@ -0,0 +1,83 @@
+//! Creating several archives in a row, e.g. one per USB stick, from the same
+//! game installation.
+
+use crate::{
+    ctx::{Context, StepStatus},
+    i18n::tr,
+    run_jobs,
+    ui::{confirm_destination, labeled},
+};
+use eframe::egui::{self, Button, RichText, Ui, WidgetType};
+use std::sync::Arc;
+
+pub fn draw_jobs(ui: &mut Ui, ctx: &Arc<Context>) {
+    egui::CollapsingHeader::new(tr("Jobs")).show(ui, |ui| {
+        ui.label(tr(
+            "Queue the destination and options above, then pick the next destination. The jobs run one after another.",
+        ));
+        ui.add_space(4.0);
+
+        let jobs = ctx.jobs();
+        let busy = ctx.is_busy();
+        let queued = jobs.iter().any(|job| job.status == StepStatus::NotStarted);
+        ui.horizontal(|ui| {
+            let outdir = ctx.outdir();
+            let already_queued = jobs
+                .iter()
+                .any(|job| job.outdir == outdir && job.status == StepStatus::NotStarted);
+            if ui
+                .add_enabled(
+                    ctx.sourcedir().is_some() && !already_queued,
+                    Button::new(tr("➕ Queue this destination")),
+                )
+                .on_disabled_hover_text(tr("Select the source directory, or a destination that isn't queued yet"))
+                .clicked()
+                && confirm_destination(ctx, true)
+            {
+                ctx.queue_job();
+            }
+            if ui
+                .add_enabled(queued && !busy, Button::new(tr("▶ Run Jobs")))
+                .clicked()
+            {
+                run_jobs(ctx.clone());
+            }
+            if ui
+                .add_enabled(
+                    jobs.len() > jobs.iter().filter(|job| job.status == StepStatus::NotStarted).count(),
+                    Button::new(tr("Clear finished")),
+                )
+                .clicked()
+            {
+                ctx.clear_finished_jobs();
+            }
+        });
+
+        if jobs.is_empty() {
+            ui.label(RichText::new(tr("No jobs queued")).italics().color(egui::Color32::GRAY));
+            return;
+        }
+        egui::Grid::new("jobs").num_columns(3).striped(true).show(ui, |ui| {
+            for (i, job) in jobs.iter().enumerate() {
+                labeled(
+                    ui.label(RichText::new(job.status.icon()).color(job.status.color())),
+                    WidgetType::Label,
+                    tr(job.status.label()),
+                );
+                let label = ui.label(job.outdir.display().to_string());
+                if let StepStatus::Failed(err) = &job.status {
+                    label.on_hover_text(err);
+                }
+                let remove =
+                    ui.add_enabled(job.status != StepStatus::InProgress, Button::new("🗑"));
+                if labeled(remove, WidgetType::Button, tr("Remove from the list"))
+                    .on_hover_text(tr("Remove from the list"))
+                    .clicked()
+                {
+                    ctx.remove_job(i);
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
@@ -1,98 +1,371 @@
 mod aoe;
+mod cache;
+pub mod cli;
 mod config;
 mod ctx;
+mod download;
+mod download_sources;
+mod errors;
+mod eta;
+mod events;
+mod firewall;
 mod goldberg;
+mod guided;
+mod history;
+mod i18n;
+mod jobs;
+mod log;
+mod metadata;
+mod paths;
+mod presets;
+mod privacy;
+mod redist;
+mod report;
+mod resume;
+mod retry;
+mod rollback;
+mod self_update;
+mod settings;
+mod shortcuts;
+mod smoke_test;
+mod source_checks;
+mod space;
 mod steam;
+mod steam_web;
+mod tasks;
+mod temp;
+mod tray;
 mod ui;
+mod uninstall;
 pub mod utils;
 
 use crate::aoe::aoe2;
-use crate::ctx::{Context, StepStatus, Task};
-use crate::ui::UiLayer;
+use crate::config::{Retries, RetryOperation};
+use crate::ctx::{
+    CancellationToken, Context, InstallStep, OverwritePolicy, Prerequisite, Progress, Step, StepId,
+    StepStatus,
+};
+use crate::events::EventBus;
+use crate::i18n::{tr, tr_fmt};
+use crate::log::RunLog;
+use crate::presets::PresetForm;
+use crate::report::{Report, format_duration};
+use crate::resume::{CopyManifest, Resumable};
+use crate::retry::retry;
+use crate::self_update::ArchiverRelease;
+use crate::source_checks::SourceReport;
+use crate::space::format_size;
+use crate::tray::TrayAction;
+use crate::ui::{LogFilter, UiLayer};
 use crate::utils::validate_aoe2_source;
-use anyhow::{bail, Context as AnyhowContext, Result};
+use anyhow::{Context as AnyhowContext, Result, bail};
 use eframe::egui;
-use fs_extra::copy_items;
-use fs_extra::dir::{get_size, CopyOptions};
+use fs_extra::dir::get_size;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{channel, Receiver, RecvError};
-use std::sync::{mpsc, Arc};
+use std::sync::mpsc::{Receiver, RecvError};
+use std::sync::{Arc, mpsc};
 use std::thread::sleep;
-use std::time::Duration;
-use tracing::{error, info};
+use std::time::{Duration, Instant};
+use tracing::{Level, error, info, warn};
 use tracing_subscriber::layer::SubscriberExt;
 
+/// Log lines kept for exporting, the panel shows only the latest.
+const MAX_LOGS: usize = 10_000;
+/// Finished files listed under the progress bar.
+const MAX_RECENT_FILES: usize = 20;
+
 struct App {
     pub update_rx: Receiver<AppUpdate>,
     pub state: Option<String>,
     pub error: Option<String>,
-    pub progress: Option<(String, f32)>,
-    pub logs: Vec<String>,
-    pub required_space: Option<u64>,
+    pub progress: Option<StepProgress>,
+    /// The file being copied or extracted, with the ones finished before it.
+    pub file_progress: Option<FileProgress>,
+    pub recent_files: VecDeque<String>,
+    pub logs: VecDeque<LogEntry>,
+    pub log_filter: LogFilter,
+    pub preset_form: PresetForm,
+    /// The size of the game files.
+    pub source_size: Option<u64>,
+    /// The size of the game profile with its mods.
+    pub profile_size: Option<u64>,
     pub available_space: Option<u64>,
+    /// The size of the temporary files, measured again when a step starts or
+    /// ends rather than every frame.
+    pub temp_size: Option<u64>,
+    /// The page shown in the guided layout.
+    pub guided_page: usize,
+    /// Kept for the notification area icon to stay.
+    pub tray: Option<tray_icon::TrayIcon>,
+    /// The window's handle, for the notification area icon to hide it.
+    pub hwnd: Option<isize>,
+    /// The step statuses last notified about.
+    pub notified_status: Vec<StepStatus>,
+    /// Shown once every step is done, until closed.
+    pub report: Option<Report>,
+    /// When the archiver started, which the logs of a run are told apart by.
+    pub start: Instant,
+    /// A newer release of the archiver, until dismissed.
+    pub archiver_release: Option<ArchiverRelease>,
+    /// The version and checks of a source folder, shown while it is the
+    /// source.
+    pub source_checks: Option<SourceReport>,
+    /// Offered to be resumed, until dealt with.
+    pub resumable: Option<Resumable>,
+    /// Set while copying the saves of an archive into the game profile.
+    pub copying_saves: Arc<AtomicBool>,
     pub ctx: Arc<Context>,
 }
 
 impl App {
-    fn add_log(&mut self, msg: String) {
-        self.logs.push(msg);
-        if self.logs.len() > 100 {
-            self.logs.remove(0);
+    fn add_log(&mut self, entry: LogEntry) {
+        self.logs.push_back(entry);
+        if self.logs.len() > MAX_LOGS {
+            self.logs.pop_front();
+        }
+    }
+
+    fn set_file_progress(&mut self, progress: FileProgress) {
+        if let Some(previous) = self.file_progress.take()
+            && previous.name != progress.name
+        {
+            self.recent_files.push_front(previous.name);
+            self.recent_files.truncate(MAX_RECENT_FILES);
+        }
+        self.file_progress = Some(progress);
+    }
+
+    /// Toasts failed steps, and the archive being ready once every step is
+    /// done, unless the window is in front anyway. The summary is shown then
+    /// too.
+    fn on_step_changes(&mut self, focused: bool) {
+        let status = self.ctx.step_statuses();
+        let all_done = |status: &[StepStatus]| {
+            status
+                .iter()
+                .all(|s| matches!(s, StepStatus::Completed | StepStatus::Skipped))
+        };
+        if all_done(&status) && !all_done(&self.notified_status) {
+            self.report = Some(Report::new(self));
+        }
+        if !focused {
+            let steps = self.ctx.steps();
+            for (step, old) in steps.iter().zip(&self.notified_status) {
+                if let StepStatus::Failed(err) = &step.status
+                    && *old != step.status
+                {
+                    tray::notify(&tr_fmt("The {} step failed", &[&tr(step.name)]), err);
+                }
+            }
+            if all_done(&status) && !all_done(&self.notified_status) {
+                tray::notify(
+                    tr("The archive is ready"),
+                    &self.ctx.outdir().display().to_string(),
+                );
+            }
         }
+        self.notified_status = status;
+    }
+
+    /// What the archive takes with the current options, once the game's size
+    /// is known.
+    fn space_parts(&self) -> Option<Vec<space::Part>> {
+        Some(space::breakdown(
+            &self.ctx.config(),
+            self.source_size?,
+            self.profile_size,
+        ))
+    }
+
+    fn required_space(&self) -> Option<u64> {
+        Some(self.space_parts()?.iter().map(|part| part.bytes).sum())
+    }
+
+    /// The time left of the running step and, when it's part of running all
+    /// the steps, of the ones after it too.
+    fn time_left(&self) -> Option<(Duration, Option<Duration>)> {
+        let steps = self.ctx.steps();
+        let running = steps
+            .iter()
+            .position(|s| s.status == StepStatus::InProgress)?;
+        let step = &steps[running];
+        let timings = self.ctx.timings();
+        let step_left = eta::remaining(
+            self.ctx.step_elapsed(step.id)?,
+            self.progress.as_ref().map(|progress| progress.fraction),
+            timings.estimate(step, self.source_size),
+        )?;
+
+        let earlier_ran = steps[..running]
+            .iter()
+            .all(|s| s.status != StepStatus::NotStarted);
+        let later: Vec<_> = steps[running + 1..]
+            .iter()
+            .filter(|later| later.status == StepStatus::NotStarted)
+            .collect();
+        let total_left = (earlier_ran && !later.is_empty())
+            .then(|| {
+                later
+                    .iter()
+                    .map(|later| timings.estimate(later, self.source_size))
+                    .sum::<Option<Duration>>()
+            })
+            .flatten()
+            .map(|later| step_left + later);
+        Some((step_left, total_left))
+    }
+
+    /// Writes the session's logs to `path`, for bug reports.
+    fn export_logs(&self, path: &Path) -> Result<()> {
+        let mut text = format!("AoE2 DE Archiver {}\n", env!("CARGO_PKG_VERSION"));
+        for log in &self.logs {
+            text.push_str(&format!("{log}\n"));
+        }
+        std::fs::write(path, text)
+            .with_context(|| format!("Unable to write the logs to {}", path.display()))
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 enum AppUpdate {
     #[default]
     Idle,
-    Progress(Option<(String, f32)>),
+    Progress(Option<StepProgress>),
     StepStatusChanged,
     SourceSize(u64),
+    ProfileSize(u64),
     DestDriveAvailable(u64),
-    Log(String),
+    File(FileProgress),
+    Tray(TrayAction),
+    ArchiverRelease(ArchiverRelease),
+    /// Every check of the source folder, see [`source_checks`].
+    SourceChecks(SourceReport),
+    /// An unfinished archive in the destination, see [`resume::check`].
+    Resumable(Option<Resumable>),
+    Log(LogEntry),
+}
+
+/// How far along the running step is, shown the same way by the window, the
+/// command line and the logs.
+#[derive(Clone)]
+struct StepProgress {
+    /// The step with its name, none for work outside the steps.
+    step: Option<(StepId, &'static str)>,
+    /// What the step is doing, e.g. "Downloading goldberg.7z".
+    phase: String,
+    /// Of the whole step.
+    fraction: f32,
+    /// Bytes done, and in total when known.
+    bytes: Option<(u64, Option<u64>)>,
+    /// Files or other items done and in total.
+    items: Option<(usize, usize)>,
+    /// Bytes per second.
+    rate: Option<f64>,
+    /// Left of the current phase.
+    eta: Option<Duration>,
+}
+
+impl std::fmt::Display for StepProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some((_, name)) = self.step {
+            write!(f, "{name}: ")?;
+        }
+        write!(f, "{}", self.phase)?;
+        let mut details = vec![];
+        match self.bytes {
+            Some((done, Some(total))) => {
+                details.push(format!("{} of {}", format_size(done), format_size(total)));
+            }
+            Some((done, None)) => details.push(format_size(done)),
+            None => {}
+        }
+        if let Some((done, total)) = self.items {
+            details.push(format!("{done} of {total}"));
+        }
+        if let Some(rate) = self.rate {
+            details.push(format!("{}/s", format_size(rate as u64)));
+        }
+        if let Some(eta) = self.eta {
+            details.push(format!("{} left", format_duration(eta)));
+        }
+        if !details.is_empty() {
+            write!(f, " ({})", details.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct FileProgress {
+    name: String,
+    /// Files finished before this one.
+    done: usize,
+    total: usize,
+}
+
+#[derive(Clone)]
+struct LogEntry {
+    level: Level,
+    /// Since the archiver started, to tell the logs of a run apart.
+    elapsed: Duration,
+    /// The wall clock's hour, minute and second when it was logged.
+    time: [u16; 3],
+    message: String,
+}
+
+impl std::fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [hour, minute, second] = self.time;
+        write!(
+            f,
+            "[{hour:02}:{minute:02}:{second:02}] [{}] {}",
+            self.level, self.message
+        )
+    }
 }
 
 pub fn launch() -> Result<()> {
-    let (update_tx, update_rx) = channel();
+    let events = EventBus::default();
+    let update_rx = events.subscribe();
+    let start = Instant::now();
 
     // Set up tracing to pipe logs to the UI
     let ui_layer = UiLayer {
-        tx: update_tx.clone(),
+        events: events.clone(),
+        start,
     };
 
     let subscriber = tracing_subscriber::fmt()
         .with_max_level(tracing::Level::INFO)
         .with_target(false)
         .finish()
-        .with(ui_layer);
+        .with(ui_layer)
+        .with(log::file_layer());
 
     tracing::subscriber::set_global_default(subscriber).expect("Failed to set tracing subscriber");
 
     // Load icon from assets
     let icon_data = include_bytes!("../assets/aoe2.ico");
-    let icon = match image::load_from_memory(icon_data) {
-        Ok(img) => {
-            let rgba = img.to_rgba8();
-            let (width, height) = rgba.dimensions();
-            Some(egui::IconData {
-                rgba: rgba.into_raw(),
-                width,
-                height,
-            })
-        }
+    let icon = match common::ui::icon_data(icon_data) {
+        Ok(icon) => Some(icon),
         Err(e) => {
             eprintln!("Failed to load icon: {}", e);
             None
         }
     };
 
+    let ctx = Arc::new(Context::new(events)?);
+    self_update::spawn_check(ctx.clone());
+    // The window grows with the zoom, its content would be cut off otherwise.
+    let scale = ctx.ui_scale();
     let mut viewport = egui::ViewportBuilder::default()
-        .with_inner_size([700.0, 600.0])
+        .with_inner_size([700.0 * scale, 600.0 * scale])
         .with_min_inner_size([600.0, 500.0])
         .with_resizable(true);
 
-    if let Some(icon) = icon {
+    if let Some(icon) = icon.clone() {
         viewport = viewport.with_icon(icon);
     }
 
@@ -106,16 +379,43 @@ pub fn launch() -> Result<()> {
         error: None,
         update_rx,
         progress: None,
-        logs: Vec::new(),
-        required_space: None,
+        file_progress: None,
+        recent_files: VecDeque::new(),
+        logs: VecDeque::new(),
+        log_filter: LogFilter::default(),
+        preset_form: PresetForm::default(),
+        source_size: None,
+        profile_size: None,
         available_space: None,
-        ctx: Arc::new(Context::new(update_tx)?),
+        temp_size: None,
+        guided_page: 0,
+        tray: None,
+        hwnd: None,
+        notified_status: ctx.step_statuses(),
+        report: None,
+        start,
+        archiver_release: None,
+        source_checks: None,
+        resumable: None,
+        copying_saves: Arc::default(),
+        ctx,
     };
 
     if let Err(err) = eframe::run_native(
         "AoE2 DE Archiver",
         options,
-        Box::new(|_cc| Ok(Box::new(app))),
+        Box::new(|cc| {
+            let mut app = app;
+            if let Some(icon) = &icon {
+                let hwnd = tray::window_handle(cc);
+                app.hwnd = hwnd;
+                match tray::create(icon, hwnd, cc.egui_ctx.clone(), app.ctx.events.clone()) {
+                    Ok(tray) => app.tray = Some(tray),
+                    Err(err) => error!("Unable to add the notification area icon: {err:#}"),
+                }
+            }
+            Ok(Box::new(app))
+        }),
     ) {
         println!("{err:?}");
     };
@@ -123,42 +423,36 @@ pub fn launch() -> Result<()> {
     Ok(())
 }
 
-fn spawn_copy_game_folder(ctx: Arc<Context>) -> Result<Receiver<()>> {
-    let guard = ctx.set_task(Task::Copy)?;
-    let ctx = ctx.clone();
+struct CopyStep;
 
-    let (tx, rx) = mpsc::sync_channel(0);
+impl InstallStep for CopyStep {
+    fn id(&self) -> StepId {
+        StepId::Copy
+    }
 
-    // Validate source directory
-    let source = ctx.sourcedir();
-    if source.is_none() {
-        bail!("No source directory selected");
+    fn name(&self) -> &'static str {
+        "Copy"
     }
 
-    std::thread::spawn({
-        move || {
-            let _guard = guard;
-            ctx.set_step_status(0, StepStatus::InProgress);
-
-            match copy_game_folder(ctx.clone()) {
-                Ok(_) => {
-                    ctx.set_step_status(0, StepStatus::Completed);
-                    info!("Copy completed successfully");
-                    let _ = tx.send(());
-                }
-                Err(err) => {
-                    let err_msg = format!("{:#}", err);
-                    ctx.set_step_status(0, StepStatus::Failed(err_msg.clone()));
-                    error!("Copy failed: {err_msg}");
-                }
-            }
-        }
-    });
+    fn prerequisites(&self) -> Prerequisite {
+        Prerequisite::Source
+    }
 
-    Ok(rx)
+    fn run(
+        &self,
+        ctx: &Arc<Context>,
+        progress: &Progress,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        copy_game_folder(ctx, progress, cancel)
+    }
 }
 
-fn copy_game_folder(ctx: Arc<Context>) -> Result<()> {
+fn copy_game_folder(
+    ctx: &Arc<Context>,
+    progress: &Progress,
+    cancel: &CancellationToken,
+) -> Result<()> {
     info!("Preparing to copy AoE2 files");
 
     let outdir = ctx.outdir();
@@ -180,8 +474,29 @@ fn copy_game_folder(ctx: Arc<Context>) -> Result<()> {
 
     std::fs::create_dir_all(&outdir).context("Failed to create destination directory")?;
 
+    let game_folder = source_aoe2_dir.file_name().unwrap_or_default();
+    let dest = outdir.join(game_folder);
+    if ctx.overwrite_policy() == OverwritePolicy::Replace && dest.exists() {
+        if dest.canonicalize()? == source_aoe2_dir.canonicalize()? {
+            bail!("The destination is the game installation itself");
+        }
+        info!(
+            "Removing the game files of an earlier run from {}",
+            dest.display()
+        );
+        std::fs::remove_dir_all(&dest).context("Failed to remove the earlier game files")?;
+    }
+
+    let mut manifest = CopyManifest::load(&outdir);
+    if !manifest.is_empty() {
+        info!(
+            "Leaving the {} files an earlier copy finished alone",
+            manifest.len()
+        );
+    }
     let complete = Arc::new(AtomicBool::new(false));
 
+    progress.phase(0.0, 1.0, "Copying");
     // Progress monitoring thread
     std::thread::spawn({
         let ctx = ctx.clone();
@@ -193,11 +508,7 @@ fn copy_game_folder(ctx: Arc<Context>) -> Result<()> {
             }
 
             if let Ok(dest_size) = get_size(&outdir) {
-                let pct_complete = (dest_size as f64 / dir_size as f64).min(1.0) as f32;
-                let _ = ctx.tx.send(AppUpdate::Progress(Some((
-                    format!("Copying... {:.1}%", pct_complete * 100.0),
-                    pct_complete,
-                ))));
+                ctx.report_bytes("Copying", dest_size.min(dir_size), Some(dir_size));
             }
 
             sleep(Duration::from_millis(500));
@@ -205,22 +516,244 @@ fn copy_game_folder(ctx: Arc<Context>) -> Result<()> {
     });
 
     // Perform the copy
-    let copy_options = CopyOptions::new();
-    let from_paths = vec![source_aoe2_dir];
-    copy_items(&from_paths, &outdir, &copy_options).context("Failed to copy files")?;
+    let started = Instant::now();
+    let total = count_files(&source_aoe2_dir);
+    let mut done = 0;
+    let retries = ctx.config().retries.clone();
+    let copied = copy_dir(
+        cancel,
+        &retries,
+        &source_aoe2_dir,
+        &dest,
+        &mut manifest,
+        &mut |file| {
+            let name = file.strip_prefix(&source_aoe2_dir).unwrap_or(file);
+            ctx.report_file(&name.to_string_lossy(), done, total);
+            done += 1;
+        },
+    );
 
     complete.store(true, Ordering::Relaxed);
-    ctx.tx.send(AppUpdate::Progress(None)).ok();
+    ctx.clear_progress();
+    // Saved when the copy fails too, resuming leaves the finished files alone.
+    let saved = manifest.save();
+    copied.context("Failed to copy files")?;
+    saved.context("Failed to save the copied files")?;
+    ctx.record_copy(dir_size, started.elapsed());
+    if let Some(version) = aoe2::game_version(&source_aoe2_dir) {
+        metadata::record_game_version(ctx, &version)?;
+    }
 
-    info!("Copy completed successfully");
+    if let Some(game_folder) = source_aoe2_dir.file_name() {
+        aoe2::dlc::verify_dlc_content(ctx, &source_aoe2_dir, &outdir.join(game_folder));
+    }
 
     Ok(())
 }
 
+/// The steps every context starts with, in the order Run All Steps runs them.
+fn default_steps() -> Vec<Step> {
+    vec![
+        Step::new(CopyStep),
+        Step::new(goldberg::GoldbergStep),
+        Step::new(aoe2::companion::CompanionStep),
+        Step::new(aoe2::launcher::LauncherStep),
+        Step::new(aoe2::server::ServerStep),
+        Step::new(redist::RedistStep),
+        Step::new(firewall::FirewallStep),
+        Step::new(privacy::PrivacyStep),
+        Step::new(shortcuts::ShortcutsStep),
+        Step::new(smoke_test::SmokeTestStep),
+    ]
+}
+
+/// Starts `step` on its own thread and keeps its status, skipping it when
+/// the options turn it off. The receiver gets a message when it succeeded or
+/// was skipped and is dropped when it failed.
+fn spawn_step(ctx: &Arc<Context>, step: Arc<dyn InstallStep>) -> Result<Receiver<()>> {
+    let guard = ctx.set_task(step.id())?;
+    let (id, name) = (step.id(), step.name());
+    let enabled = step.enabled(ctx);
+    if enabled {
+        ctx.set_step_status(id, StepStatus::InProgress);
+    }
+    let cancel = ctx.cancellation();
+
+    let (tx, rx) = mpsc::sync_channel(0);
+    let ctx = ctx.clone();
+    std::thread::spawn(move || {
+        if !enabled {
+            info!("Skipping the {name} step");
+            ctx.set_step_status(id, StepStatus::Skipped);
+            drop(guard);
+            let _ = tx.send(());
+            return;
+        }
+
+        match step.run(&ctx, &Progress::new(&ctx, id, name), &cancel) {
+            Ok(()) => {
+                ctx.set_step_status(id, StepStatus::Completed);
+                info!("{name} step completed");
+                // Released first, so the next step can start right away.
+                drop(guard);
+                let _ = tx.send(());
+            }
+            Err(err) => {
+                ctx.fail_step(id, &err);
+                error!("{name} step failed: {err:#}");
+            }
+        }
+    });
+
+    Ok(rx)
+}
+
+/// Runs a single step, e.g. again after it failed.
+fn run_step(ctx: Arc<Context>, step: &Step) {
+    let name = step.name;
+    if let Some(unmet) = ctx.unmet(step.prerequisite) {
+        error!("Unable to start the {name} step: {unmet}");
+        return;
+    }
+    if let Err(err) = spawn_step(&ctx, step.install.clone()) {
+        error!("Unable to start the {name} step: {err:#}");
+    }
+}
+
+/// Runs `steps` one after another, stopping at the first that fails. Each
+/// step prefetches for the next while it runs. The uninstaller and the update
+/// manifest are written after the last step changing the archive.
+/// With `persist`, how the steps went is kept in the destination for
+/// resuming, see [`resume`].
+fn run_steps(ctx: &Arc<Context>, steps: &[Step], persist: bool) -> Result<()> {
+    let run_log = RunLog::start(&ctx.events);
+    let run = ctx.begin_run();
+    let result = run_each_step(ctx, steps, persist, &run.token);
+    drop(run);
+    if let Err(err) = run_log.save(&ctx.outdir()) {
+        warn!("Failed to save the run's logs in the archive: {err:#}");
+    }
+    result
+}
+
+fn run_each_step(
+    ctx: &Arc<Context>,
+    steps: &[Step],
+    persist: bool,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    let last_change = steps
+        .iter()
+        .rposition(|step| step.install.changes_archive());
+    for (i, step) in steps.iter().enumerate() {
+        // Cancelled between two steps.
+        cancel.check()?;
+        let rx = spawn_step(ctx, step.install.clone())?;
+        if let Some(next) = steps.get(i + 1) {
+            next.install.prefetch(ctx);
+        }
+        if persist {
+            resume::record_steps(ctx);
+        }
+        let finished = rx.recv();
+        if persist {
+            resume::record_steps(ctx);
+        }
+        finished?;
+        info!("Step {}/{} completed: {}", i + 1, steps.len(), step.name);
+
+        if last_change == Some(i) {
+            uninstall::write_uninstaller(ctx)?;
+            write_update_manifest(ctx)?;
+        }
+    }
+    if persist {
+        resume::finish(&ctx.outdir());
+    }
+    Ok(())
+}
+
+/// Copies `from` to `to` file by file, replacing what an earlier copy left
+/// behind, so cancelling stops it between files. `on_file` is called before
+/// each file is copied. The files in `manifest` are left alone, the copied
+/// ones are added to it.
+fn copy_dir(
+    cancellation: &CancellationToken,
+    retries: &Retries,
+    from: &Path,
+    to: &Path,
+    manifest: &mut CopyManifest,
+    on_file: &mut dyn FnMut(&Path),
+) -> Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        cancellation.check()?;
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(
+                cancellation,
+                retries,
+                &entry.path(),
+                &dest,
+                manifest,
+                on_file,
+            )?;
+        } else {
+            let size = entry.metadata()?.len();
+            on_file(&entry.path());
+            if manifest.contains(&dest, size) {
+                continue;
+            }
+            let what = format!("Copying {}", entry.path().display());
+            retry(
+                retries,
+                RetryOperation::FileCopy,
+                cancellation,
+                &what,
+                || Ok(std::fs::copy(entry.path(), &dest)?),
+            )
+            .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+            manifest.add(&dest, size)?;
+        }
+    }
+    Ok(())
+}
+
+fn count_files(dir: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => count_files(&entry.path()),
+            _ => 1,
+        })
+        .sum()
+}
+
 fn run_all_steps(ctx: Arc<Context>) {
+    spawn_run(ctx, run_all_steps_inner);
+}
+
+/// Carries on with the run of all steps the destination's state was saved
+/// by, with the source and options it was started with.
+fn resume_run(ctx: Arc<Context>) {
+    spawn_run(ctx, resume_run_inner);
+}
+
+/// Runs all steps on their own thread with `run`, recording the run in the
+/// history.
+fn spawn_run(ctx: Arc<Context>, run: fn(Arc<Context>) -> Result<()>) {
     std::thread::spawn({
         move || {
-            if let Err(err) = run_all_steps_inner(ctx) {
+            let result = run(ctx.clone());
+            if let Err(err) = ctx.record_run(&run_outcome(&ctx, &result)) {
+                error!("Failed to save the history: {err:#}");
+            }
+            resume::check(ctx.events.clone(), ctx.steps(), ctx.outdir());
+            if let Err(err) = result {
                 // Don't log recv errors.
                 let Err(err) = err.downcast::<RecvError>() else {
                     return;
@@ -231,31 +764,259 @@ fn run_all_steps(ctx: Arc<Context>) {
     });
 }
 
-fn run_all_steps_inner(ctx: Arc<Context>) -> Result<()> {
-    // Step 1: Copy
-    ctx.set_step_status(0, StepStatus::InProgress);
-    let rx = spawn_copy_game_folder(ctx.clone())?;
-    rx.recv()?;
-    info!("Step 1/4 completed: Game files copied");
-
-    // Step 2: Goldberg
-    ctx.set_step_status(1, StepStatus::InProgress);
-    let rx = goldberg::spawn_apply(ctx.clone())?;
-    rx.recv()?;
-    info!("Step 2/4 completed: Goldberg installed");
-
-    // Step 3: Companion
-    ctx.set_step_status(2, StepStatus::InProgress);
-    let rx = aoe2::companion::spawn_install_launcher_companion(ctx.clone())?;
-    rx.recv()?;
-    info!("Step 3/4 completed: Launcher Companion Installed");
-
-    // Step 4: Launcher
-    ctx.set_step_status(3, StepStatus::InProgress);
-    let rx = aoe2::launcher::spawn_install_launcher(ctx.clone())?;
-
-    rx.recv()?;
-    info!("Step 4/4 completed: Launcher Installed");
+/// How a run of all steps went: the first failed step's error, else why the
+/// run stopped.
+fn run_outcome(ctx: &Context, result: &Result<()>) -> StepStatus {
+    let failed = ctx
+        .step_statuses()
+        .into_iter()
+        .find_map(|status| match status {
+            StepStatus::Failed(err) => Some(err),
+            _ => None,
+        });
+    match (failed, result) {
+        (Some(err), _) => StepStatus::Failed(err),
+        (None, Err(err)) => StepStatus::Failed(format!("{err:#}")),
+        (None, Ok(())) => StepStatus::Completed,
+    }
+}
+
+/// Runs the queued jobs one after another, each with all the steps.
+/// Cancelling stops the queue, the jobs after the cancelled one stay queued.
+fn run_jobs(ctx: Arc<Context>) {
+    std::thread::spawn(move || {
+        let _reset = ctx.begin_jobs();
+        while ctx.start_next_job() {
+            info!("Creating the archive in {}", ctx.outdir().display());
+            ctx.reset_steps();
+            let result = run_all_steps_inner(ctx.clone());
+            let outcome = run_outcome(&ctx, &result);
+            if let Err(err) = ctx.record_run(&outcome) {
+                error!("Failed to save the history: {err:#}");
+            }
+            ctx.finish_job(outcome);
+            resume::check(ctx.events.clone(), ctx.steps(), ctx.outdir());
+            if ctx.is_cancelled() {
+                info!("Jobs stopped, the remaining ones stay queued");
+                break;
+            }
+        }
+    });
+}
+
+/// Re-runs the goldberg, companion and launcher steps on `archive`, created
+/// earlier, for when antivirus deleted dlls or the upstream tools updated.
+/// The game files are left alone, and the configured destination is put back
+/// once it's done.
+fn repair_archive(ctx: Arc<Context>, archive: PathBuf) {
+    std::thread::spawn({
+        move || {
+            let outdir = ctx.outdir();
+            ctx.set_outdir(archive);
+            let result = repair_archive_inner(ctx.clone());
+            ctx.set_outdir(outdir);
+            if let Err(err) = result {
+                // Don't log recv errors.
+                let Err(err) = err.downcast::<RecvError>() else {
+                    return;
+                };
+                error!("{err:?}");
+            }
+        }
+    });
+}
+
+fn repair_archive_inner(ctx: Arc<Context>) -> Result<()> {
+    let archive = ctx.outdir();
+    validate_aoe2_source(&archive.join(&ctx.config().aoe2.steam_folder))
+        .context("Not an archive")?;
+    info!("Repairing {}", archive.display());
+
+    ctx.set_step_status(StepId::Copy, StepStatus::Skipped);
+    let steps: Vec<Step> = ctx
+        .steps()
+        .into_iter()
+        .filter(|step| {
+            matches!(
+                step.id,
+                StepId::Goldberg | StepId::Companion | StepId::Launcher
+            )
+        })
+        .collect();
+    for step in &steps {
+        ctx.set_step_status(step.id, StepStatus::NotStarted);
+    }
+
+    run_steps(&ctx, &steps, false)?;
+    info!("Repair of {} completed", archive.display());
 
     Ok(())
 }
+
+/// Lists the archive's files for archives updating from this one, and the
+/// game files for checking the archive.
+fn write_update_manifest(ctx: &Context) -> Result<()> {
+    let outdir = ctx.outdir();
+    let game_manifest = common::update::game_manifest(&outdir, &ctx.config().aoe2.steam_folder)
+        .context("Failed to list the game files")?;
+    std::fs::write(outdir.join(common::update::GAME_MANIFEST), game_manifest)?;
+    let manifest = common::update::manifest(&outdir).context("Failed to hash the archive")?;
+    std::fs::write(outdir.join(common::update::UPDATE_MANIFEST), &manifest)?;
+    info!(
+        "Wrote the update manifest, archives updating from this one can pin its SHA-256 {}",
+        common::sha256_hex(manifest.as_bytes())
+    );
+    Ok(())
+}
+
+fn run_all_steps_inner(ctx: Arc<Context>) -> Result<()> {
+    resume::start(&ctx);
+    run_steps(&ctx, &ctx.steps(), true)
+}
+
+fn resume_run_inner(ctx: Arc<Context>) -> Result<()> {
+    let outdir = ctx.outdir();
+    let state = resume::read(&outdir).context("No unfinished archive in the destination")?;
+    let steps = ctx.steps();
+    let from = state
+        .first_incomplete(&steps)
+        .context("Every step of the archive finished")?;
+    ctx.restore_run(&state, &steps[..from])?;
+    info!(
+        "Resuming {} at the {} step",
+        outdir.display(),
+        steps[from].name
+    );
+    run_steps(&ctx, &steps[from..], true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, settings::Settings};
+    use std::sync::Mutex;
+
+    /// Records that it ran, in place of a registered step.
+    struct Fake {
+        id: StepId,
+        enabled: bool,
+        fails: bool,
+        ran: Arc<Mutex<Vec<StepId>>>,
+    }
+
+    impl InstallStep for Fake {
+        fn id(&self) -> StepId {
+            self.id
+        }
+
+        fn name(&self) -> &'static str {
+            "Fake"
+        }
+
+        fn prerequisites(&self) -> Prerequisite {
+            Prerequisite::Source
+        }
+
+        fn enabled(&self, _: &Context) -> bool {
+            self.enabled
+        }
+
+        fn changes_archive(&self) -> bool {
+            false
+        }
+
+        fn run(&self, _: &Arc<Context>, _: &Progress, _: &CancellationToken) -> Result<()> {
+            self.ran.lock().unwrap().push(self.id);
+            if self.fails {
+                bail!("Failed on purpose");
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn runs_the_registered_steps() {
+        let outdir = std::env::temp_dir().join(format!("aoe2-run-{}", std::process::id()));
+        std::fs::create_dir_all(&outdir).unwrap();
+        let settings = Settings {
+            outdir: Some(outdir.clone()),
+            unsaved: true,
+            ..Settings::default()
+        };
+        let config = Config::defaults().unwrap();
+        let ctx = Arc::new(Context::with(EventBus::default(), config, settings).unwrap());
+        let ran = Arc::new(Mutex::new(vec![]));
+        for step in ctx.steps() {
+            ctx.register_step(Step::new(Fake {
+                id: step.id,
+                enabled: step.id != StepId::Server,
+                fails: step.id == StepId::Privacy,
+                ran: ran.clone(),
+            }));
+        }
+
+        assert!(run_all_steps_inner(ctx.clone()).is_err());
+        use StepId::*;
+        assert_eq!(
+            *ran.lock().unwrap(),
+            [
+                Copy, Goldberg, Companion, Launcher, Redist, Firewall, Privacy
+            ]
+        );
+        assert_eq!(ctx.step_status(Server), StepStatus::Skipped);
+        assert!(matches!(ctx.step_status(Privacy), StepStatus::Failed(_)));
+        assert_eq!(ctx.step_status(Shortcuts), StepStatus::NotStarted);
+        let _ = std::fs::remove_dir_all(outdir);
+    }
+
+    #[test]
+    fn jobs_put_back_the_picked_destination() {
+        let picked = std::env::temp_dir().join(format!("aoe2-picked-{}", std::process::id()));
+        let settings = Settings {
+            outdir: Some(picked.clone()),
+            unsaved: true,
+            ..Settings::default()
+        };
+        let config = Config::defaults().unwrap();
+        let ctx = Arc::new(Context::with(EventBus::default(), config, settings).unwrap());
+        ctx.set_outdir(picked.with_extension("job"));
+        ctx.set_overwrite_policy(OverwritePolicy::Replace);
+        ctx.update_config(|config| config.aoe2.steam_folder = "Job".to_string());
+        ctx.queue_job();
+        ctx.set_outdir(picked.clone());
+        ctx.set_overwrite_policy(OverwritePolicy::Merge);
+        ctx.update_config(|config| config.aoe2.steam_folder = "Picked".to_string());
+
+        let reset = ctx.begin_jobs();
+        assert!(ctx.start_next_job());
+        assert_eq!(ctx.outdir(), picked.with_extension("job"));
+        assert_eq!(ctx.config().aoe2.steam_folder, "Job");
+        drop(reset);
+        assert_eq!(ctx.outdir(), picked);
+        assert_eq!(ctx.config().aoe2.steam_folder, "Picked");
+        assert!(ctx.overwrite_policy() == OverwritePolicy::Merge);
+    }
+
+    #[test]
+    fn describes_progress() {
+        let mut progress = StepProgress {
+            step: Some((StepId::Goldberg, "Goldberg")),
+            phase: "Downloading gbe.7z".to_string(),
+            fraction: 0.3,
+            bytes: Some((10 << 20, Some(40 << 20))),
+            items: None,
+            rate: Some(2.0 * 1_048_576.0),
+            eta: Some(Duration::from_secs(15)),
+        };
+        assert_eq!(
+            progress.to_string(),
+            "Goldberg: Downloading gbe.7z (10 MB of 40 MB, 2 MB/s, 15s left)"
+        );
+
+        progress.step = None;
+        progress.phase = "Extracting".to_string();
+        (progress.bytes, progress.items) = (None, Some((3, 8)));
+        (progress.rate, progress.eta) = (None, None);
+        assert_eq!(progress.to_string(), "Extracting (3 of 8)");
+    }
+}
@@ -0,0 +1,252 @@
+//! Keeps the logs in `archiver.log`, which the window's log panel only shows
+//! the last lines of, in [`paths::log_dir`]. Each run's warnings and errors
+//! are also written into the archive it created, for debugging archives
+//! that misbehave on someone else's machine.
+
+use crate::{AppUpdate, events::EventBus, paths, privacy::anonymize};
+use anyhow::{Context as _, Result};
+use common::update::RUN_LOG;
+use std::{
+    fmt::Write as _,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Mutex, mpsc::Receiver},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::{Level, Subscriber};
+use tracing_subscriber::{Layer, registry::LookupSpan};
+
+const LOG_FILE: &str = "archiver.log";
+const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+/// Older logs kept, `archiver.1.log` being the latest.
+const KEEP_LOGS: usize = 5;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+/// The most lines of a run kept in the archive, the latest ones.
+const MAX_RUN_LOG_LINES: usize = 200;
+
+/// Writes the logs to [`LOG_FILE`], none when it can't be opened.
+pub fn file_layer<S>() -> Option<impl Layer<S>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let path = paths::log_dir().ok()?.join(LOG_FILE);
+    let file = match RotatingFile::open(path.clone(), MAX_LOG_SIZE) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("Unable to open {}: {err}", path.display());
+            return None;
+        }
+    };
+    Some(
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_target(false)
+            .with_writer(Mutex::new(file)),
+    )
+}
+
+/// A log file started over each day and when it outgrows its size, the
+/// previous ones moved to `archiver.1.log`, `archiver.2.log` and so on.
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    max_size: u64,
+    size: u64,
+    /// The day since the epoch the file was started, in UTC.
+    day: u64,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf, max_size: u64) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // The log of an earlier session carries on, unless it's from another
+        // day or full.
+        if let Ok(metadata) = fs::metadata(&path)
+            && (metadata.modified().map(day_of).ok() != Some(today()) || metadata.len() >= max_size)
+        {
+            rotate(&path)?;
+        }
+        let file = append(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            max_size,
+            size,
+            day: today(),
+        })
+    }
+
+    fn start_over(&mut self) -> io::Result<()> {
+        rotate(&self.path)?;
+        self.file = append(&self.path)?;
+        self.size = 0;
+        self.day = today();
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let full = self.size > 0 && self.size + buf.len() as u64 > self.max_size;
+        if full || today() != self.day {
+            // Keeps writing to the full log rather than losing lines.
+            if let Err(err) = self.start_over() {
+                eprintln!("Unable to rotate {}: {err}", self.path.display());
+            }
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// `archiver.log` as the `n`th older log, `archiver.<n>.log`.
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    path.with_extension(format!("{n}.log"))
+}
+
+/// Moves the older logs up by one, dropping the oldest, and `path` to the
+/// first of them.
+fn rotate(path: &Path) -> io::Result<()> {
+    let _ = fs::remove_file(rotated(path, KEEP_LOGS));
+    for n in (1..KEEP_LOGS).rev() {
+        let _ = fs::rename(rotated(path, n), rotated(path, n + 1));
+    }
+    fs::rename(path, rotated(path, 1))
+}
+
+fn day_of(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECS_PER_DAY
+}
+
+fn today() -> u64 {
+    day_of(SystemTime::now())
+}
+
+/// The hour, minute and second of the wall clock, in local time on windows
+/// and in UTC elsewhere.
+pub fn clock_time() -> [u16; 3] {
+    #[cfg(windows)]
+    {
+        #[repr(C)]
+        #[derive(Default)]
+        struct SystemTime {
+            year: u16,
+            month: u16,
+            day_of_week: u16,
+            day: u16,
+            hour: u16,
+            minute: u16,
+            second: u16,
+            milliseconds: u16,
+        }
+        #[link(name = "kernel32")]
+        unsafe extern "system" {
+            fn GetLocalTime(time: *mut SystemTime);
+        }
+        let mut time = SystemTime::default();
+        // SAFETY: fills in the struct, which matches windows' SYSTEMTIME.
+        unsafe { GetLocalTime(&mut time) };
+        [time.hour, time.minute, time.second]
+    }
+    #[cfg(not(windows))]
+    {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        [
+            (secs / 3600 % 24) as u16,
+            (secs / 60 % 60) as u16,
+            (secs % 60) as u16,
+        ]
+    }
+}
+
+/// Collects the logs from when a run starts, to save them into the archive
+/// once it's over.
+pub struct RunLog {
+    rx: Receiver<AppUpdate>,
+}
+
+impl RunLog {
+    pub fn start(events: &EventBus) -> Self {
+        Self {
+            rx: events.subscribe(),
+        }
+    }
+
+    /// Appends the run's warnings and errors to [`RUN_LOG`] in `archive`,
+    /// unless the run didn't get to create it. Earlier runs on the archive
+    /// stay above. Paths and names of the creator are left out, as the
+    /// archive is passed on.
+    pub fn save(self, archive: &Path) -> Result<()> {
+        if !archive.is_dir() {
+            return Ok(());
+        }
+        let lines: Vec<String> = self
+            .rx
+            .try_iter()
+            .filter_map(|update| match update {
+                AppUpdate::Log(entry) if entry.level <= Level::WARN => Some(entry.to_string()),
+                _ => None,
+            })
+            .collect();
+        let mut text = format!("AoE2 DE Archiver {}\n", env!("CARGO_PKG_VERSION"));
+        let skipped = lines.len().saturating_sub(MAX_RUN_LOG_LINES);
+        if skipped > 0 {
+            let _ = writeln!(text, "({skipped} earlier lines left out)");
+        }
+        for line in &lines[skipped..] {
+            let _ = writeln!(text, "{line}");
+        }
+        let path = archive.join(RUN_LOG);
+        append(&path)
+            .and_then(|mut file| writeln!(file, "{}", anonymize(&text, archive)))
+            .with_context(|| format!("Unable to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RotatingFile, rotated};
+    use std::{fs, io::Write};
+
+    #[test]
+    fn rotates_full_logs() {
+        let dir = std::env::temp_dir().join(format!("log-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("archiver.log");
+
+        let mut file = RotatingFile::open(path.clone(), 10).unwrap();
+        file.write_all(b"first\n").unwrap();
+        file.write_all(b"second\n").unwrap();
+        file.write_all(b"third\n").unwrap();
+        drop(file);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "third\n");
+        assert_eq!(fs::read_to_string(rotated(&path, 1)).unwrap(), "second\n");
+        assert_eq!(fs::read_to_string(rotated(&path, 2)).unwrap(), "first\n");
+
+        // A new session carries on with the log until it's full.
+        let mut file = RotatingFile::open(path.clone(), 10).unwrap();
+        file.write_all(b"end\n").unwrap();
+        drop(file);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "third\nend\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
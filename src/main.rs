@@ -1,9 +1,14 @@
 #![windows_subsystem = "windows"]
 
-use aoe_archive::launch;
+use aoe_archive::{cli, launch};
+use std::process::ExitCode;
 
-fn main() {
+fn main() -> ExitCode {
+    if cli::requested() {
+        return cli::run();
+    }
     if let Err(err) = launch() {
         println!("App crashed: {err:?}");
     }
+    ExitCode::SUCCESS
 }
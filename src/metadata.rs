@@ -0,0 +1,70 @@
+use crate::Context;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+use tracing::info;
+
+pub use common::update::METADATA_FILE;
+
+/// What went into an archive, so archives created weeks apart can be told
+/// apart.
+#[derive(Serialize, Deserialize, Default)]
+pub struct ArchiveMetadata {
+    #[serde(default)]
+    pub archiver_version: String,
+    /// The release of the game that was copied, for players to compare
+    /// before playing together.
+    #[serde(default)]
+    pub game_version: Option<String>,
+    #[serde(default)]
+    pub components: BTreeMap<String, Component>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Component {
+    pub version: String,
+    pub url: String,
+}
+
+/// The metadata of the archive in `outdir`, if it has any.
+pub fn read(outdir: &Path) -> Option<ArchiveMetadata> {
+    let data = std::fs::read(outdir.join(METADATA_FILE)).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+/// Records which release of a component was installed into the archive.
+pub fn record_component(ctx: &Context, name: &str, version: &str, url: &str) -> Result<()> {
+    update(ctx, |metadata| {
+        metadata.components.insert(
+            name.to_string(),
+            Component {
+                version: version.to_string(),
+                url: url.to_string(),
+            },
+        );
+    })?;
+    info!("Recorded {name} {version} in {METADATA_FILE}");
+
+    Ok(())
+}
+
+/// Records which release of the game was copied into the archive.
+pub fn record_game_version(ctx: &Context, version: &str) -> Result<()> {
+    update(ctx, |metadata| {
+        metadata.game_version = Some(version.to_string())
+    })?;
+    info!("Recorded {version} in {METADATA_FILE}");
+
+    Ok(())
+}
+
+fn update(ctx: &Context, change: impl FnOnce(&mut ArchiveMetadata)) -> Result<()> {
+    let path = ctx.outdir().join(METADATA_FILE);
+    let mut metadata = read(&ctx.outdir()).unwrap_or_default();
+
+    metadata.archiver_version = env!("CARGO_PKG_VERSION").to_string();
+    change(&mut metadata);
+
+    std::fs::write(&path, serde_json::to_string_pretty(&metadata)?)
+        .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+}
@@ -0,0 +1,172 @@
+//! Where the archiver keeps its own files. Installed, they're in the user's
+//! app data. Portable, e.g. when running the archiver from a usb stick,
+//! they're next to the exe so nothing is left behind on the machine. The
+//! archiver is portable when started with `--portable` or with a
+//! `portable.txt` next to it.
+
+use anyhow::{Result, anyhow};
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// The folder in the app data directories.
+const APP_DIR: &str = "aoe2-archiver";
+const PORTABLE_MARKER: &str = "portable.txt";
+pub const PORTABLE_FLAG: &str = "--portable";
+pub const CONFIG_FILE: &str = "config.toml";
+const SETTINGS_FILE: &str = "settings.toml";
+/// Files in it stand in for the bundled steam settings.
+const ASSETS_DIR: &str = "assets";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Mode {
+    Portable,
+    Installed,
+}
+
+/// The mode the archiver runs in, decided on first use.
+pub fn mode() -> Mode {
+    static MODE: OnceLock<Mode> = OnceLock::new();
+    *MODE.get_or_init(|| {
+        let flag = std::env::args_os().skip(1).any(|arg| arg == PORTABLE_FLAG);
+        mode_for(flag, exe_dir().as_deref())
+    })
+}
+
+fn mode_for(flag: bool, exe_dir: Option<&Path>) -> Mode {
+    if flag || exe_dir.is_some_and(|dir| dir.join(PORTABLE_MARKER).is_file()) {
+        Mode::Portable
+    } else {
+        Mode::Installed
+    }
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    Some(std::env::current_exe().ok()?.parent()?.to_path_buf())
+}
+
+/// The folder portable files are kept in, or the app data folder `base`
+/// returns for installed ones.
+fn dir(base: fn() -> Option<PathBuf>) -> Result<PathBuf> {
+    dir_for(mode(), exe_dir(), base())
+}
+
+fn dir_for(mode: Mode, exe_dir: Option<PathBuf>, base: Option<PathBuf>) -> Result<PathBuf> {
+    let dir = match mode {
+        Mode::Portable => exe_dir,
+        Mode::Installed => base.map(|base| base.join(APP_DIR)),
+    };
+    dir.ok_or_else(|| anyhow!("Missing app data dir."))
+}
+
+/// `name` in `dir`. Installed, the one in the app data is preferred, else
+/// the one in the working directory earlier versions only read is used.
+fn read_from(mode: Mode, dir: Result<PathBuf>, name: &str) -> PathBuf {
+    match dir {
+        Ok(dir) if mode == Mode::Portable || dir.join(name).exists() => dir.join(name),
+        _ => PathBuf::from(name),
+    }
+}
+
+/// config.toml, see [`read_from`].
+pub fn config_file() -> PathBuf {
+    read_from(mode(), dir(dirs::config_dir), CONFIG_FILE)
+}
+
+/// The folder whose files stand in for the bundled steam settings, see
+/// [`read_from`].
+pub fn assets_dir() -> PathBuf {
+    read_from(mode(), dir(dirs::config_dir), ASSETS_DIR)
+}
+
+/// The config.toml options edited in the window are written to: the one
+/// read, else a new one in the app data.
+pub fn user_config_file() -> Result<PathBuf> {
+    let path = config_file();
+    if path.exists() {
+        return Ok(path);
+    }
+    Ok(dir(dirs::config_dir)?.join(CONFIG_FILE))
+}
+
+/// Where the options and directories picked in the window are saved.
+pub fn settings_file() -> Result<PathBuf> {
+    Ok(dir(dirs::config_dir)?.join(SETTINGS_FILE))
+}
+
+/// The downloads kept across runs.
+pub fn cache_dir() -> Result<PathBuf> {
+    Ok(dir(dirs::data_local_dir)?.join("cache"))
+}
+
+/// The folder `archiver.log` is kept in, next to the exe when portable.
+pub fn log_dir() -> Result<PathBuf> {
+    Ok(match mode() {
+        Mode::Portable => dir(dirs::data_local_dir)?,
+        Mode::Installed => dir(dirs::data_local_dir)?.join("logs"),
+    })
+}
+
+/// The root of the per-run scratch folders, next to the exe when portable.
+pub fn temp_dir() -> Result<PathBuf> {
+    Ok(match mode() {
+        Mode::Portable => dir(dirs::data_local_dir)?.join("temp"),
+        Mode::Installed => std::env::temp_dir().join(APP_DIR),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{APP_DIR, CONFIG_FILE, Mode, PORTABLE_MARKER, dir_for, mode_for, read_from};
+    use std::path::PathBuf;
+
+    #[test]
+    fn portable_when_flagged_or_marked() {
+        let exe = std::env::temp_dir().join(format!("aoe2-paths-{}", std::process::id()));
+        std::fs::create_dir_all(&exe).unwrap();
+        assert_eq!(mode_for(false, Some(&exe)), Mode::Installed);
+        assert_eq!(mode_for(false, None), Mode::Installed);
+        assert_eq!(mode_for(true, Some(&exe)), Mode::Portable);
+
+        std::fs::write(exe.join(PORTABLE_MARKER), "").unwrap();
+        assert_eq!(mode_for(false, Some(&exe)), Mode::Portable);
+        std::fs::remove_dir_all(&exe).unwrap();
+    }
+
+    #[test]
+    fn portable_files_are_next_to_the_exe() {
+        let exe = PathBuf::from("usb").join("archiver");
+        let base = PathBuf::from("appdata");
+        let dir = dir_for(Mode::Portable, Some(exe.clone()), Some(base)).unwrap();
+        assert_eq!(dir, exe);
+        assert!(dir_for(Mode::Portable, None, Some(PathBuf::from("appdata"))).is_err());
+        // Read from next to the exe even before it's written.
+        assert_eq!(
+            read_from(Mode::Portable, Ok(dir), CONFIG_FILE),
+            exe.join(CONFIG_FILE)
+        );
+    }
+
+    #[test]
+    fn installed_files_are_in_the_app_data() {
+        let exe = PathBuf::from("archiver");
+        let base = std::env::temp_dir().join(format!("aoe2-appdata-{}", std::process::id()));
+        let dir = dir_for(Mode::Installed, Some(exe.clone()), Some(base.clone())).unwrap();
+        assert_eq!(dir, base.join(APP_DIR));
+        assert!(dir_for(Mode::Installed, Some(exe), None).is_err());
+
+        // The working directory's until one is written to the app data.
+        assert_eq!(
+            read_from(Mode::Installed, Ok(dir.clone()), CONFIG_FILE),
+            PathBuf::from(CONFIG_FILE)
+        );
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(CONFIG_FILE), "").unwrap();
+        assert_eq!(
+            read_from(Mode::Installed, Ok(dir.clone()), CONFIG_FILE),
+            dir.join(CONFIG_FILE)
+        );
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,92 @@
+//! Named sets of options, e.g. a full archive, a minimal LAN archive and one
+//! for USB sticks, each one click away.
+
+use crate::{App, i18n::tr};
+use eframe::egui::{self, Button, TextEdit, Ui};
+use tracing::error;
+
+/// What is typed for saving a preset, kept between frames.
+#[derive(Default)]
+pub(crate) struct PresetForm {
+    name: String,
+    destination: String,
+}
+
+pub fn draw_presets(ui: &mut Ui, app: &mut App) {
+    egui::CollapsingHeader::new(tr("Presets")).show(ui, |ui| {
+        let ctx = &app.ctx;
+        let busy = ctx.is_busy();
+        let presets = ctx.presets();
+        ui.add_enabled_ui(!busy, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                if presets.is_empty() {
+                    ui.label(tr(
+                        "Save the options below as a preset to switch back to them in one click.",
+                    ));
+                }
+                for preset in &presets {
+                    let response =
+                        ui.button(&preset.name)
+                            .on_hover_text(if preset.destination.is_empty() {
+                                tr("Apply these options").to_string()
+                            } else {
+                                format!(
+                                    "{}\n{}",
+                                    tr("Apply these options, archiving to:"),
+                                    preset.destination
+                                )
+                            });
+                    if response.clicked() {
+                        if let Err(err) = ctx.apply_preset(&preset.name) {
+                            error!("Failed to apply the preset: {err:#}");
+                        }
+                        app.preset_form.name = preset.name.clone();
+                        app.preset_form.destination = preset.destination.clone();
+                    }
+                    response.context_menu(|ui| {
+                        if ui.button(tr("🗑 Delete")).clicked() {
+                            if let Err(err) = ctx.delete_preset(&preset.name) {
+                                error!("Failed to delete the preset: {err:#}");
+                            }
+                            ui.close();
+                        }
+                    });
+                }
+            });
+        });
+        ui.add_space(4.0);
+
+        let form = &mut app.preset_form;
+        ui.horizontal(|ui| {
+            ui.label(tr("Name:"));
+            ui.add(TextEdit::singleline(&mut form.name).desired_width(150.0));
+            ui.label(tr("Destination:"));
+            ui.add(
+                TextEdit::singleline(&mut form.destination)
+                    .hint_text(tr("Keep the current one"))
+                    .desired_width(200.0),
+            )
+            .on_hover_text(tr(
+                "{n} is replaced by the first number without a folder yet, e.g. E:\\AoE2-{n}",
+            ));
+            let name = form.name.trim();
+            if ui
+                .add_enabled(!name.is_empty(), Button::new(tr("💾 Save preset")))
+                .on_hover_text(tr(
+                    "Save the current options, replacing the preset with the same name",
+                ))
+                .clicked()
+                && let Err(err) = ctx.save_preset(name, &form.destination)
+            {
+                error!("Failed to save the preset: {err:#}");
+            }
+        });
+        if !presets.is_empty() {
+            ui.label(
+                egui::RichText::new(tr("Right click a preset to delete it"))
+                    .small()
+                    .color(egui::Color32::GRAY),
+            );
+        }
+    });
+}
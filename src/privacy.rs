@@ -0,0 +1,286 @@
+use crate::{
+    Context,
+    ctx::{CancellationToken, InstallStep, Prerequisite, Progress, StepId},
+    goldberg::archive_steam_id,
+    steam::steam_account_name,
+};
+use anyhow::Result;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{info, warn};
+
+const TEXT_EXTENSIONS: &[&str] = &[
+    "ini", "txt", "json", "toml", "cfg", "xml", "log", "bat", "cmd", "vdf", "acf", "yaml", "yml",
+    "csv",
+];
+const MAX_FILE_SIZE: u64 = 8 * 1024 * 1024;
+
+const STEAM_ID_PREFIX: &str = "7656119";
+const STEAM_ID_LEN: usize = 17;
+const STEAM_ID_REPLACEMENT: &str = "76561197960265728";
+/// Keys of the ini, json and vdf files steam ids are stored under. Other
+/// numbers that happen to look like one, e.g. workshop or build ids, stay.
+const STEAM_ID_FIELDS: &[&str] = &["account_steamid", "steamid", "steam_id", "steamid64"];
+
+struct Identifier {
+    kind: &'static str,
+    value: String,
+    replacement: &'static str,
+}
+
+pub struct PrivacyStep;
+
+impl InstallStep for PrivacyStep {
+    fn id(&self) -> StepId {
+        StepId::Privacy
+    }
+
+    fn name(&self) -> &'static str {
+        "Privacy"
+    }
+
+    fn prerequisites(&self) -> Prerequisite {
+        Prerequisite::Archive
+    }
+
+    fn run(&self, ctx: &Arc<Context>, _: &Progress, cancel: &CancellationToken) -> Result<()> {
+        scrub(ctx, cancel)
+    }
+}
+
+/// Scans the text files of the archive for the creator's account and machine
+/// names and the steam ids in [`STEAM_ID_FIELDS`], optionally rewriting them with neutral placeholders.
+pub fn scrub(ctx: &Context, cancel: &CancellationToken) -> Result<()> {
+    let config = ctx.config();
+    let rewrite = config.privacy.rewrite;
+    let identifiers = personal_identifiers();
+    // Generated for the archive when it imported a game profile.
+    let own_id = archive_steam_id(&ctx.outdir(), &config.aoe2.steam_folder);
+    for identifier in &identifiers {
+        info!("Searching for {}", identifier.kind);
+    }
+
+    let mut files = vec![];
+    text_files(&ctx.outdir(), &mut files)?;
+    info!("Scanning {} text files for personal data", files.len());
+
+    let mut findings = 0;
+    for path in files {
+        cancel.check()?;
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+
+        let mut scrubbed = content.clone();
+        for identifier in &identifiers {
+            if find_ignore_ascii_case(&scrubbed, &identifier.value).is_some() {
+                warn!("{} contains {}", path.display(), identifier.kind);
+                findings += 1;
+                scrubbed =
+                    replace_ignore_ascii_case(&scrubbed, &identifier.value, identifier.replacement);
+            }
+        }
+        if has_steam_id(&scrubbed, own_id.as_deref()) {
+            warn!("{} contains a steam id", path.display());
+            findings += 1;
+            scrubbed = replace_steam_ids(&scrubbed, own_id.as_deref());
+        }
+
+        if rewrite && scrubbed != content {
+            fs::write(&path, scrubbed)?;
+            info!("Rewrote {}", path.display());
+        }
+    }
+
+    match (findings, rewrite) {
+        (0, _) => info!("No personal data found"),
+        (n, true) => info!("Rewrote {n} occurrences of personal data"),
+        (n, false) => warn!(
+            "Found {n} occurrences of personal data. Enable rewriting or edit them before sharing the archive."
+        ),
+    }
+
+    Ok(())
+}
+
+/// `text` with paths in `archive` made relative to it and the creator's
+/// home folder, names and steam ids replaced, for what's written into the
+/// archive after the privacy step ran.
+pub fn anonymize(text: &str, archive: &Path) -> String {
+    let mut text = text.to_string();
+    let archive = archive.display().to_string();
+    let home = dirs::home_dir().map(|home| home.display().to_string());
+    for (dir, replacement) in [(Some(archive), "."), (home, "%USERPROFILE%")] {
+        let Some(dir) = dir.filter(|dir| dir.len() > 3) else {
+            continue;
+        };
+        for dir in [dir.replace('/', "\\"), dir.replace('\\', "/")] {
+            text = replace_ignore_ascii_case(&text, dir.trim_end_matches(['/', '\\']), replacement);
+        }
+    }
+    for identifier in personal_identifiers() {
+        text = replace_ignore_ascii_case(&text, &identifier.value, identifier.replacement);
+    }
+    replace_steam_ids(&text, None)
+}
+
+fn personal_identifiers() -> Vec<Identifier> {
+    let mut identifiers = vec![];
+    let mut push = |kind, value: Option<String>, replacement| {
+        // Very short names would match all over the place.
+        if let Some(value) = value.filter(|v| v.trim().len() >= 3) {
+            identifiers.push(Identifier {
+                kind,
+                value,
+                replacement,
+            });
+        }
+    };
+
+    push(
+        "the windows user name",
+        std::env::var("USERNAME").ok(),
+        "user",
+    );
+    push(
+        "the machine name",
+        std::env::var("COMPUTERNAME").ok(),
+        "computer",
+    );
+    push("the steam account name", steam_account_name(), "player");
+
+    identifiers
+}
+
+fn text_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let metadata = entry.metadata()?;
+
+        if metadata.is_dir() {
+            text_files(&path, files)?;
+            continue;
+        }
+
+        let is_text = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| TEXT_EXTENSIONS.contains(&&*e.to_lowercase()));
+        if is_text && metadata.len() <= MAX_FILE_SIZE {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    if needle.is_empty() {
+        return None;
+    }
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|w| w.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+fn replace_ignore_ascii_case(haystack: &str, needle: &str, replacement: &str) -> String {
+    let mut result = String::with_capacity(haystack.len());
+    let mut rest = haystack;
+    while let Some(pos) = find_ignore_ascii_case(rest, needle) {
+        result.push_str(&rest[..pos]);
+        result.push_str(replacement);
+        rest = &rest[pos + needle.len()..];
+    }
+    result.push_str(rest);
+    result
+}
+
+fn steam_id_positions(text: &str, own_id: Option<&str>) -> Vec<usize> {
+    let bytes = text.as_bytes();
+    text.match_indices(STEAM_ID_PREFIX)
+        .map(|(pos, _)| pos)
+        .filter(|&pos| {
+            let Some(candidate) = bytes.get(pos..pos + STEAM_ID_LEN) else {
+                return false;
+            };
+            let bounded_before = pos == 0 || !bytes[pos - 1].is_ascii_digit();
+            let bounded_after = bytes
+                .get(pos + STEAM_ID_LEN)
+                .is_none_or(|b| !b.is_ascii_digit());
+            bounded_before
+                && bounded_after
+                && candidate.iter().all(u8::is_ascii_digit)
+                && own_id.is_none_or(|id| id.as_bytes() != candidate)
+                && is_steam_id_field(&text[..pos])
+        })
+        .collect()
+}
+
+/// Whether the text before a number ends with one of [`STEAM_ID_FIELDS`]
+/// and its separator, like `steamid=`, `"SteamID": "` or `"steamid"\t\t"`.
+fn is_steam_id_field(before: &str) -> bool {
+    let key = before.trim_end_matches(|c: char| c.is_whitespace() || "=:\"'".contains(c));
+    let start = key
+        .trim_end_matches(|c: char| c.is_ascii_alphanumeric() || c == '_')
+        .len();
+    STEAM_ID_FIELDS
+        .iter()
+        .any(|field| key[start..].eq_ignore_ascii_case(field))
+}
+
+/// Whether the text has steam ids other than the placeholder and `own_id`.
+fn has_steam_id(text: &str, own_id: Option<&str>) -> bool {
+    steam_id_positions(text, own_id)
+        .iter()
+        .any(|&pos| &text[pos..pos + STEAM_ID_LEN] != STEAM_ID_REPLACEMENT)
+}
+
+fn replace_steam_ids(text: &str, own_id: Option<&str>) -> String {
+    let mut result = text.to_string();
+    for pos in steam_id_positions(text, own_id) {
+        result.replace_range(pos..pos + STEAM_ID_LEN, STEAM_ID_REPLACEMENT);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{anonymize, has_steam_id, replace_ignore_ascii_case, replace_steam_ids};
+    use std::path::Path;
+
+    #[test]
+    fn anonymizes_archive_paths() {
+        let text =
+            "Copied D:\\Games\\AoE2\\AoE2DE to d:/games/aoe2/AoE2DE\nsteamid=76561198012345678\n";
+        let text = anonymize(text, Path::new("D:\\Games\\AoE2"));
+        assert!(text.starts_with("Copied .\\AoE2DE to ./AoE2DE\n"), "{text}");
+        assert!(!text.contains("76561198012345678"));
+    }
+
+    #[test]
+    fn scrub_text() {
+        let text = r#"path=C:\Users\Bob\Games
+steamid=76561198012345678
+"SteamID": "76561198012345677"
+workshop=76561198012345676
+build=1234567656119000000000"#;
+
+        let text = replace_ignore_ascii_case(text, "bob", "user");
+        assert!(text.contains(r"C:\Users\user\Games"));
+
+        assert!(has_steam_id(&text, None));
+        let text = replace_steam_ids(&text, Some("76561198012345677"));
+        assert!(text.contains(r#""SteamID": "76561198012345677""#));
+        let text = replace_steam_ids(&text, None);
+        assert!(text.contains("steamid=76561197960265728"));
+        assert!(text.contains(r#""SteamID": "76561197960265728""#));
+        assert!(text.contains("workshop=76561198012345676"));
+        assert!(text.contains("build=1234567656119000000000"));
+        assert!(!has_steam_id(&text, None));
+    }
+}
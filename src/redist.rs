@@ -0,0 +1,177 @@
+use crate::{
+    Context,
+    config::Redist,
+    ctx::{CancellationToken, InstallStep, Prerequisite, Progress, StepId},
+    download::download_all,
+    errors::{ErrorKind, Tag},
+    rollback::{self, Journal},
+};
+use anyhow::{Result, anyhow};
+use common::{
+    runtimes::{DIRECTX, Runtime, VC_REDIST},
+    sha256_file,
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{info, warn};
+
+const STEP: &str = "Redist";
+
+pub struct RedistStep;
+
+impl InstallStep for RedistStep {
+    fn id(&self) -> StepId {
+        StepId::Redist
+    }
+
+    fn name(&self) -> &'static str {
+        STEP
+    }
+
+    fn prerequisites(&self) -> Prerequisite {
+        Prerequisite::Archive
+    }
+
+    fn enabled(&self, ctx: &Context) -> bool {
+        let redist = &ctx.config().redist;
+        redist.vc || redist.directx
+    }
+
+    fn run(&self, ctx: &Arc<Context>, progress: &Progress, _: &CancellationToken) -> Result<()> {
+        rollback::transaction(ctx, STEP, |journal| bundle_redists(ctx, progress, journal))
+    }
+}
+
+/// An installer to bundle, and the SHA-256 it has to match.
+struct Installer<'a> {
+    runtime: Runtime,
+    url: &'a str,
+    sha256: Option<String>,
+    path: PathBuf,
+}
+
+/// Downloads the installers of the runtimes the game needs into the
+/// archive, which its launcher runs on machines lacking them. Installers
+/// matching their pinned SHA-256 are reused from the archive or the
+/// download cache, the others are downloaded at the same time.
+pub fn bundle_redists(ctx: &Context, progress: &Progress, journal: &mut Journal) -> Result<()> {
+    let config = ctx.config();
+    let outdir = ctx.outdir();
+    let mut missing = vec![];
+    for installer in installers(&config.redist, &outdir) {
+        journal.track(&installer.path)?;
+        if installer.sha256.is_none() {
+            warn!(
+                "No sha256 is pinned for the {} installer, bundling it unverified",
+                installer.runtime.name
+            );
+        }
+
+        if matches_pin(&installer.path, installer.sha256.as_deref()) {
+            info!(
+                "The {} installer is bundled already",
+                installer.runtime.name
+            );
+            continue;
+        }
+        if let Some(entry) = ctx.cache.get(installer.url)
+            && matches_pin(&entry.data_path, installer.sha256.as_deref())
+        {
+            info!("Using the cached {} installer", installer.runtime.name);
+            copy_installer(&entry.data_path, &installer.path)?;
+            continue;
+        }
+
+        info!(
+            "Bundling the {} installer from {}",
+            installer.runtime.name, installer.url
+        );
+        missing.push(installer);
+    }
+
+    // Don't leave the installer of a previous run with the other setting around.
+    for (runtime, enabled) in [
+        (VC_REDIST, config.redist.vc),
+        (DIRECTX, config.redist.directx),
+    ] {
+        let path = outdir.join(runtime.installer);
+        if !enabled && path.exists() {
+            journal.track(&path)?;
+            std::fs::remove_file(&path)?;
+        }
+    }
+
+    progress.phase(0.0, 1.0, "Downloading runtime installers");
+    let urls: Vec<&str> = missing.iter().map(|installer| installer.url).collect();
+    for (installer, download) in missing.iter().zip(download_all(ctx, &urls)) {
+        let download = download?;
+        if let Some(expected) = &installer.sha256 {
+            let actual = sha256_file(&download)?;
+            if actual != *expected {
+                return Err(anyhow!(
+                    "Checksum mismatch for the {} installer: expected {expected}, got {actual}",
+                    installer.runtime.name
+                ))
+                .kind(ErrorKind::ChecksumMismatch);
+            }
+            // Kept for the next run even when the server sends no etag.
+            let in_cache = ctx
+                .cache
+                .get(installer.url)
+                .is_some_and(|entry| entry.data_path == download);
+            if !in_cache {
+                let cached =
+                    ctx.cache
+                        .insert(installer.url, &format!("sha256:{expected}"), &download)?;
+                copy_installer(&cached, &installer.path)?;
+                continue;
+            }
+        }
+        copy_installer(&download, &installer.path)?;
+    }
+    Ok(())
+}
+
+fn installers<'a>(redist: &'a Redist, outdir: &Path) -> Vec<Installer<'a>> {
+    let pin = |sha256: &Option<String>| {
+        sha256
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_lowercase)
+    };
+    [
+        (VC_REDIST, redist.vc, &redist.vc_url, pin(&redist.vc_sha256)),
+        (
+            DIRECTX,
+            redist.directx,
+            &redist.directx_url,
+            pin(&redist.directx_sha256),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, enabled, _, _)| *enabled)
+    .map(|(runtime, _, url, sha256)| Installer {
+        path: outdir.join(runtime.installer),
+        runtime,
+        url,
+        sha256,
+    })
+    .collect()
+}
+
+/// Whether `path` exists and has the pinned SHA-256. Unpinned installers
+/// are downloaded again, they may have been updated.
+fn matches_pin(path: &Path, sha256: Option<&str>) -> bool {
+    sha256.is_some_and(|expected| sha256_file(path).is_ok_and(|actual| actual == expected))
+}
+
+fn copy_installer(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(from, to).map_err(|e| anyhow!("Failed to write {}: {}", to.display(), e))?;
+    Ok(())
+}
@@ -0,0 +1,226 @@
+//! The summary shown when every step is done, which can be saved into the
+//! archive for whoever copies it next.
+
+use crate::{
+    App,
+    ctx::{StepId, StepStatus},
+    i18n::{tr, tr_fmt},
+    metadata,
+    privacy::anonymize,
+    space::format_size,
+    uninstall::UNINSTALL_SCRIPT,
+};
+use anyhow::{Context as _, Result};
+use common::update::RUN_REPORT;
+use eframe::egui::{self, Color32, RichText, Ui};
+use std::{fmt::Write, path::PathBuf, time::Duration};
+use tracing::{Level, error, info};
+
+pub struct Report {
+    pub outdir: PathBuf,
+    pub steps: Vec<(&'static str, StepStatus, Option<Duration>)>,
+    pub bytes_copied: Option<u64>,
+    /// The release of the game in the archive.
+    pub game_version: Option<String>,
+    /// Component names with the installed version.
+    pub components: Vec<(String, String)>,
+    /// The warnings and errors logged since the first step started.
+    pub warnings: Vec<String>,
+}
+
+impl Report {
+    pub fn new(app: &App) -> Self {
+        let ctx = &app.ctx;
+        let outdir = ctx.outdir();
+        let steps = ctx
+            .steps()
+            .into_iter()
+            .map(|step| {
+                let took = step.took();
+                (step.name, step.status, took)
+            })
+            .collect::<Vec<_>>();
+        let bytes_copied = app
+            .source_size
+            .filter(|_| ctx.step_status(StepId::Copy) == StepStatus::Completed);
+        let metadata = metadata::read(&outdir).unwrap_or_default();
+        let components = metadata
+            .components
+            .into_iter()
+            .map(|(name, component)| (name, component.version))
+            .collect();
+        let since = ctx
+            .run_started()
+            .map(|started| started.saturating_duration_since(app.start))
+            .unwrap_or_default();
+        let warnings = app
+            .logs
+            .iter()
+            .filter(|log| log.elapsed >= since && log.level <= Level::WARN)
+            .map(|log| log.to_string())
+            .collect();
+        Self {
+            outdir,
+            steps,
+            bytes_copied,
+            game_version: metadata.game_version,
+            components,
+            warnings,
+        }
+    }
+
+    fn next_steps(&self) -> [String; 3] {
+        [
+            tr("Copy the whole folder to each machine, e.g. on a USB stick.").to_string(),
+            tr("Run launcher.exe in it and pick a player name.").to_string(),
+            tr_fmt(
+                "Run {} to remove the game from a machine again.",
+                &[&UNINSTALL_SCRIPT],
+            ),
+        ]
+    }
+
+    /// The report as text, in the language of the UI.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("AoE2 DE Archiver {}\n", env!("CARGO_PKG_VERSION"));
+        let _ = writeln!(text, "{}\n", self.outdir.display());
+        for (name, status, took) in &self.steps {
+            let _ = writeln!(
+                text,
+                "{} {}: {}",
+                status.icon(),
+                tr(name),
+                step_result(status, *took)
+            );
+        }
+        if let Some(bytes) = self.bytes_copied {
+            let _ = writeln!(
+                text,
+                "\n{}",
+                tr_fmt("Game files copied: {}", &[&format_size(bytes)])
+            );
+        }
+        if let Some(version) = &self.game_version {
+            let _ = writeln!(text, "{}", tr_fmt("Game version: {}", &[version]));
+        }
+        if !self.components.is_empty() {
+            let _ = writeln!(text, "\n{}", tr("Installed components:"));
+            for (name, version) in &self.components {
+                let _ = writeln!(text, "  {name} {version}");
+            }
+        }
+        let _ = writeln!(
+            text,
+            "\n{}",
+            tr_fmt("Warnings: {}", &[&self.warnings.len()])
+        );
+        for warning in &self.warnings {
+            let _ = writeln!(text, "  {warning}");
+        }
+        let _ = writeln!(text, "\n{}", tr("Next steps:"));
+        for (i, step) in self.next_steps().iter().enumerate() {
+            let _ = writeln!(text, "  {}. {step}", i + 1);
+        }
+        text
+    }
+
+    /// Writes the report next to the game in the archive, without the
+    /// creator's paths and names.
+    pub fn save(&self) -> Result<PathBuf> {
+        let path = self.outdir.join(RUN_REPORT);
+        std::fs::write(&path, anonymize(&self.to_text(), &self.outdir))
+            .with_context(|| format!("Unable to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+pub fn draw_report(ui: &mut Ui, report: &Report) {
+    egui::Grid::new("report_steps")
+        .num_columns(3)
+        .show(ui, |ui| {
+            for (name, status, took) in &report.steps {
+                ui.label(RichText::new(status.icon()).color(status.color()));
+                ui.label(tr(name));
+                ui.label(step_result(status, *took));
+                ui.end_row();
+            }
+        });
+    ui.add_space(4.0);
+    if let Some(bytes) = report.bytes_copied {
+        ui.label(tr_fmt("Game files copied: {}", &[&format_size(bytes)]));
+    }
+    if let Some(version) = &report.game_version {
+        ui.label(tr_fmt("Game version: {}", &[version]));
+    }
+    if !report.components.is_empty() {
+        ui.label(RichText::new(tr("Installed components:")).strong());
+        for (name, version) in &report.components {
+            ui.label(format!("  {name} {version}"));
+        }
+    }
+
+    let warnings = tr_fmt("Warnings: {}", &[&report.warnings.len()]);
+    if report.warnings.is_empty() {
+        ui.label(warnings);
+    } else {
+        egui::CollapsingHeader::new(RichText::new(warnings).color(Color32::from_rgb(255, 140, 0)))
+            .id_salt("report_warnings")
+            .show(ui, |ui| {
+                for warning in &report.warnings {
+                    ui.label(RichText::new(warning).small());
+                }
+            });
+    }
+
+    ui.add_space(4.0);
+    ui.label(RichText::new(tr("Next steps:")).strong());
+    for (i, step) in report.next_steps().iter().enumerate() {
+        ui.label(format!("{}. {step}", i + 1));
+    }
+    ui.add_space(4.0);
+    if ui
+        .button(tr("💾 Save the report into the archive"))
+        .on_hover_text(RUN_REPORT)
+        .clicked()
+    {
+        match report.save() {
+            Ok(path) => info!("Saved the report to {}", path.display()),
+            Err(err) => error!("{err:#}"),
+        }
+    }
+}
+
+fn step_result(status: &StepStatus, took: Option<Duration>) -> String {
+    let result = match status {
+        StepStatus::NotStarted | StepStatus::InProgress => tr("Not run").to_string(),
+        StepStatus::Completed => tr("Done").to_string(),
+        StepStatus::Skipped => tr("Skipped").to_string(),
+        StepStatus::Failed(err) => tr_fmt("Failed: {}", &[err]),
+    };
+    match took {
+        Some(took) => format!("{result} ({})", format_duration(took)),
+        None => result,
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_duration;
+    use std::time::Duration;
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(Duration::from_millis(4_500)), "4s");
+        assert_eq!(format_duration(Duration::from_secs(83)), "1m 23s");
+        assert_eq!(format_duration(Duration::from_secs(7_380)), "2h 03m");
+    }
+}
@@ -0,0 +1,264 @@
+//! The state of a run of all steps, kept in the destination so a run cut
+//! short by a crash or a restart can carry on from the step it stopped at.
+
+use crate::{
+    AppUpdate,
+    ctx::{Context, Step, StepStatus},
+    events::EventBus,
+    i18n::{tr, tr_fmt},
+    resume_run,
+};
+use anyhow::{Context as _, Result};
+use common::update::RUN_STATE;
+use eframe::egui::{Color32, RichText, Ui};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use toml::Table;
+use tracing::{info, warn};
+
+/// How often the copy saves which files it finished.
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct RunState {
+    pub source: Option<PathBuf>,
+    /// The options differing from config.toml.
+    #[serde(default)]
+    pub options: Table,
+    /// Step names with how they went.
+    #[serde(default)]
+    pub steps: BTreeMap<String, StepStatus>,
+    /// The game files the copy finished, relative to the destination, with
+    /// their sizes.
+    #[serde(default)]
+    pub copied: BTreeMap<String, u64>,
+}
+
+impl RunState {
+    /// The first of `steps` that didn't finish, none when all did.
+    pub fn first_incomplete(&self, steps: &[Step]) -> Option<usize> {
+        steps.iter().position(|step| {
+            !matches!(
+                self.steps.get(step.name),
+                Some(StepStatus::Completed | StepStatus::Skipped)
+            )
+        })
+    }
+}
+
+/// An unfinished run found in the destination, see [`check`].
+#[derive(Clone)]
+pub struct Resumable {
+    pub outdir: PathBuf,
+    /// The step it stopped at.
+    pub step: &'static str,
+    /// Game files copied before it stopped.
+    pub copied: usize,
+}
+
+pub fn read(outdir: &Path) -> Option<RunState> {
+    let data = std::fs::read(outdir.join(RUN_STATE)).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+fn write(outdir: &Path, state: &RunState) -> Result<()> {
+    // Renamed into place, a crash while writing keeps the state before.
+    let path = outdir.join(RUN_STATE);
+    let temp = outdir.join(format!("{RUN_STATE}.tmp"));
+    std::fs::write(&temp, serde_json::to_vec(state)?)
+        .with_context(|| format!("Failed to write {}", temp.display()))?;
+    std::fs::rename(&temp, &path).with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+/// Starts the state of a new run in the destination, forgetting the files an
+/// earlier copy finished.
+pub fn start(ctx: &Context) {
+    save_steps(ctx, RunState::default());
+}
+
+/// Saves how the steps went so far. A state that can't be saved only costs
+/// resuming, the run goes on.
+pub fn record_steps(ctx: &Context) {
+    save_steps(ctx, read(&ctx.outdir()).unwrap_or_default());
+}
+
+fn save_steps(ctx: &Context, mut state: RunState) {
+    let outdir = ctx.outdir();
+    let saved = (|| {
+        std::fs::create_dir_all(&outdir)?;
+        state.source = ctx.sourcedir();
+        state.options = ctx.changed_options()?;
+        state.steps = ctx
+            .steps()
+            .into_iter()
+            .map(|step| (step.name.to_string(), step.status))
+            .collect();
+        write(&outdir, &state)
+    })();
+    if let Err(err) = saved {
+        warn!("Unable to save the state of the run: {err:#}");
+    }
+}
+
+/// Removes the state once every step is done.
+pub fn finish(outdir: &Path) {
+    match std::fs::remove_file(outdir.join(RUN_STATE)) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => warn!("Unable to remove {RUN_STATE}: {err}"),
+    }
+}
+
+/// Looks for an unfinished run in `outdir` in the background, the result
+/// arrives as [`AppUpdate::Resumable`].
+pub fn check(events: EventBus, steps: Vec<Step>, outdir: PathBuf) {
+    std::thread::spawn(move || {
+        let resumable = read(&outdir).and_then(|state| {
+            Some(Resumable {
+                step: steps[state.first_incomplete(&steps)?].name,
+                copied: state.copied.len(),
+                outdir,
+            })
+        });
+        events.send(AppUpdate::Resumable(resumable));
+    });
+}
+
+/// The game files finished copying, saved to the state as the copy goes so
+/// a resumed copy leaves them alone. Only kept during a run of all steps.
+pub struct CopyManifest {
+    outdir: PathBuf,
+    copied: BTreeMap<String, u64>,
+    saved: Instant,
+}
+
+impl CopyManifest {
+    pub fn load(outdir: &Path) -> Self {
+        Self {
+            outdir: outdir.to_path_buf(),
+            copied: read(outdir).map(|state| state.copied).unwrap_or_default(),
+            saved: Instant::now(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.copied.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.copied.is_empty()
+    }
+
+    /// Whether `dest` was copied with `size` bytes and still has them.
+    pub fn contains(&self, dest: &Path, size: u64) -> bool {
+        self.key(dest)
+            .is_some_and(|key| self.copied.get(&key) == Some(&size))
+            && dest.metadata().is_ok_and(|metadata| metadata.len() == size)
+    }
+
+    pub fn add(&mut self, dest: &Path, size: u64) -> Result<()> {
+        if let Some(key) = self.key(dest) {
+            self.copied.insert(key, size);
+        }
+        if self.saved.elapsed() >= SAVE_INTERVAL {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        self.saved = Instant::now();
+        // The steps are saved separately, only the files are this manifest's.
+        let Some(mut state) = read(&self.outdir) else {
+            return Ok(());
+        };
+        state.copied = self.copied.clone();
+        write(&self.outdir, &state)
+    }
+
+    fn key(&self, dest: &Path) -> Option<String> {
+        let relative = dest.strip_prefix(&self.outdir).ok()?;
+        Some(relative.to_string_lossy().replace('\\', "/"))
+    }
+}
+
+/// Offers to carry on with an unfinished run found in the destination.
+/// Returns whether it was dealt with.
+pub fn draw_banner(ui: &mut Ui, ctx: &Arc<Context>, resumable: &Resumable) -> bool {
+    let mut done = false;
+    ui.horizontal_wrapped(|ui| {
+        let mut text = tr_fmt(
+            "The archive in {} wasn't finished, it stopped at the {} step.",
+            &[&resumable.outdir.display(), &tr(resumable.step)],
+        );
+        if resumable.copied > 0 {
+            text.push(' ');
+            text.push_str(&tr_fmt(
+                "{} game files were copied already.",
+                &[&resumable.copied],
+            ));
+        }
+        ui.label(RichText::new(text).color(Color32::from_rgb(255, 140, 0)));
+        if ui
+            .button(tr("Resume"))
+            .on_hover_text(tr(
+                "Carry on from that step with the source and options the archive was started with",
+            ))
+            .clicked()
+        {
+            resume_run(ctx.clone());
+            done = true;
+        }
+        if ui
+            .button(tr("Start over"))
+            .on_hover_text(tr(
+                "Forget the unfinished run, Run All Steps starts from the first step",
+            ))
+            .clicked()
+        {
+            finish(&resumable.outdir);
+            info!(
+                "Forgot the unfinished run in {}",
+                resumable.outdir.display()
+            );
+            done = true;
+        }
+    });
+    done
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RunState;
+    use crate::{ctx::StepStatus, default_steps};
+
+    #[test]
+    fn resumes_at_the_first_incomplete_step() {
+        let steps = default_steps();
+        let mut state = RunState::default();
+        assert_eq!(state.first_incomplete(&steps), Some(0));
+
+        state
+            .steps
+            .insert(steps[0].name.to_string(), StepStatus::Completed);
+        state
+            .steps
+            .insert(steps[1].name.to_string(), StepStatus::Skipped);
+        state
+            .steps
+            .insert(steps[2].name.to_string(), StepStatus::Failed("x".into()));
+        assert_eq!(state.first_incomplete(&steps), Some(2));
+
+        for step in &steps {
+            state
+                .steps
+                .insert(step.name.to_string(), StepStatus::Completed);
+        }
+        assert_eq!(state.first_incomplete(&steps), None);
+    }
+}
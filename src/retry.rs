@@ -0,0 +1,127 @@
+//! Tries operations that failed for a passing reason again, as the
+//! `[retries]` section of config.toml allows: a dropped connection, github
+//! having a bad moment or antivirus holding a freshly copied file.
+
+use crate::{
+    config::{Retries, RetryOperation},
+    ctx::CancellationToken,
+    errors::{ErrorKind, classify},
+};
+use anyhow::Result;
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+/// How often a blocking wait checks for cancellation.
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Errors that trying again doesn't help with.
+fn is_final(err: &anyhow::Error) -> bool {
+    matches!(
+        classify(err),
+        ErrorKind::Cancelled
+            | ErrorKind::SourceInvalid
+            | ErrorKind::RateLimited
+            | ErrorKind::UpstreamLayoutChanged
+            | ErrorKind::DiskFull
+    )
+}
+
+/// Runs `op` until it succeeds, fails for good or `operation` is out of
+/// attempts, waiting longer after every failure. `what` names it in the
+/// logs. The wait ends early with "Cancelled" once `cancel` is.
+pub fn retry<T>(
+    retries: &Retries,
+    operation: RetryOperation,
+    cancel: &CancellationToken,
+    what: &str,
+    mut op: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let attempts = retries.attempts(operation);
+    for attempt in 1.. {
+        let err = match op() {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt >= attempts || is_final(&err) => return Err(err),
+            Err(err) => err,
+        };
+        let delay = retries.delay(attempt);
+        warn_retrying(what, &err, delay, attempt, attempts);
+        let until = Instant::now() + delay;
+        while let Some(left) = until.checked_duration_since(Instant::now()) {
+            cancel.check()?;
+            std::thread::sleep(left.min(CANCEL_CHECK_INTERVAL));
+        }
+    }
+    unreachable!()
+}
+
+/// [`retry`] for the downloads on the [`Supervisor`](crate::tasks::Supervisor),
+/// which cancels them itself.
+pub async fn retry_async<T, F: Future<Output = Result<T>>>(
+    retries: &Retries,
+    operation: RetryOperation,
+    what: &str,
+    mut op: impl FnMut() -> F,
+) -> Result<T> {
+    let attempts = retries.attempts(operation);
+    for attempt in 1.. {
+        let err = match op().await {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt >= attempts || is_final(&err) => return Err(err),
+            Err(err) => err,
+        };
+        let delay = retries.delay(attempt);
+        warn_retrying(what, &err, delay, attempt, attempts);
+        tokio::time::sleep(delay).await;
+    }
+    unreachable!()
+}
+
+fn warn_retrying(what: &str, err: &anyhow::Error, delay: Duration, attempt: u32, attempts: u32) {
+    warn!(
+        "{what} failed, retrying in {:.1}s ({attempt}/{}): {err:#}",
+        delay.as_secs_f32(),
+        attempts - 1
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::retry;
+    use crate::{
+        config::{Retries, RetryOperation},
+        ctx::CancellationToken,
+    };
+    use anyhow::bail;
+
+    #[test]
+    fn retries_until_out_of_attempts() {
+        let retries = Retries {
+            max_attempts: 3,
+            backoff_ms: 1,
+            operations: vec![RetryOperation::Download],
+        };
+        let cancel = CancellationToken::default();
+
+        let mut tries = 0;
+        let result = retry(&retries, RetryOperation::Download, &cancel, "Test", || {
+            tries += 1;
+            if tries < 3 {
+                bail!("dropped");
+            }
+            Ok(tries)
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut tries = 0;
+        let result: anyhow::Result<()> =
+            retry(&retries, RetryOperation::FileCopy, &cancel, "Test", || {
+                tries += 1;
+                bail!("in use")
+            });
+        assert!(result.is_err());
+        assert_eq!(tries, 1);
+    }
+}
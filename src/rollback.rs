@@ -0,0 +1,199 @@
+//! Undoes what a failed step wrote to the archive, so it's left as it was
+//! before the step instead of half patched, and the step can simply be run
+//! again.
+
+use crate::Context;
+use anyhow::{Context as _, Result, bail};
+use fs_extra::dir::CopyOptions;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::{error, info, warn};
+
+/// The files and folders a step changed, with copies of how they were.
+pub struct Journal {
+    backups: PathBuf,
+    changes: Vec<Change>,
+}
+
+enum Change {
+    /// Didn't exist before, removed again.
+    Created(PathBuf),
+    /// Existed before, put back from the copy in the backups.
+    Replaced { path: PathBuf, backup: PathBuf },
+}
+
+impl Change {
+    fn path(&self) -> &Path {
+        match self {
+            Change::Created(path) | Change::Replaced { path, .. } => path,
+        }
+    }
+}
+
+impl Journal {
+    /// Keeps the copies in `backups`, which is removed again when the journal
+    /// is dropped.
+    pub fn new(backups: PathBuf) -> Self {
+        Self {
+            backups,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Remembers `path` as it is now. Called before the step writes, replaces
+    /// or removes it, folders are remembered with everything in them.
+    pub fn track(&mut self, path: &Path) -> Result<()> {
+        if self
+            .changes
+            .iter()
+            .any(|change| path.starts_with(change.path()))
+        {
+            return Ok(());
+        }
+        if !path.exists() {
+            // Everything below the topmost missing folder is new.
+            let mut created = path;
+            while let Some(parent) = created.parent()
+                && !parent.as_os_str().is_empty()
+                && !parent.exists()
+            {
+                created = parent;
+            }
+            self.changes.push(Change::Created(created.to_path_buf()));
+            return Ok(());
+        }
+        let backup = self.backups.join(self.changes.len().to_string());
+        fs::create_dir_all(&self.backups)?;
+        copy(path, &backup).with_context(|| format!("Failed to back up {}", path.display()))?;
+        self.changes.push(Change::Replaced {
+            path: path.to_path_buf(),
+            backup,
+        });
+        Ok(())
+    }
+
+    /// Creates `path` like [`fs::create_dir_all`], remembering it when it's
+    /// new. Unlike [`Journal::track`], an existing folder isn't copied, the
+    /// step tracks the files it changes in it.
+    pub fn create_dir_all(&mut self, path: &Path) -> Result<()> {
+        if !path.exists() {
+            self.track(path)?;
+        }
+        fs::create_dir_all(path)?;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Puts everything tracked back the way it was, the latest change first.
+    /// Carries on past what can't be put back, failing at the end.
+    pub fn rollback(&mut self) -> Result<()> {
+        let mut failed = 0;
+        while let Some(change) = self.changes.pop() {
+            let undone = match &change {
+                Change::Created(path) => remove(path),
+                Change::Replaced { path, backup } => remove(path).and_then(|()| copy(backup, path)),
+            };
+            if let Err(err) = undone {
+                warn!("Unable to restore {}: {err:#}", change.path().display());
+                failed += 1;
+            }
+        }
+        if failed > 0 {
+            bail!("{failed} files couldn't be restored");
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Journal {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.backups);
+    }
+}
+
+/// Runs `install` with a journal of what it changes, rolling the changes back
+/// when it fails or is cancelled.
+pub fn transaction(
+    ctx: &Context,
+    step: &str,
+    install: impl FnOnce(&mut Journal) -> Result<()>,
+) -> Result<()> {
+    let backups = ctx.temp.path(&format!("rollback-{}", step.to_lowercase()));
+    let mut journal = Journal::new(backups);
+    let result = install(&mut journal);
+    if result.is_err() && !journal.is_empty() {
+        info!("Undoing the changes of the {step} step");
+        match journal.rollback() {
+            Ok(()) => info!("The archive is back to how it was before the {step} step"),
+            Err(err) => error!("Unable to undo the {step} step: {err:#}"),
+        }
+    }
+    result
+}
+
+fn copy(from: &Path, to: &Path) -> Result<()> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        fs_extra::dir::copy(from, to, &CopyOptions::new().content_only(true))?;
+    } else {
+        fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+fn remove(path: &Path) -> Result<()> {
+    let removed = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    match removed {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Journal;
+    use std::fs;
+
+    #[test]
+    fn rolls_back_written_files() {
+        let dir = std::env::temp_dir().join(format!("rollback-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("settings")).unwrap();
+        fs::write(dir.join("config.ini"), "before").unwrap();
+        fs::write(dir.join("settings").join("user.ini"), "before").unwrap();
+
+        let mut journal = Journal::new(dir.join("backups"));
+        journal.track(&dir.join("config.ini")).unwrap();
+        journal.track(&dir.join("settings")).unwrap();
+        journal
+            .track(&dir.join("settings").join("user.ini"))
+            .unwrap();
+        journal.track(&dir.join("dlls").join("extra.dll")).unwrap();
+        fs::write(dir.join("config.ini"), "after").unwrap();
+        fs::remove_dir_all(dir.join("settings")).unwrap();
+        fs::create_dir_all(dir.join("dlls")).unwrap();
+        fs::write(dir.join("dlls").join("extra.dll"), "new").unwrap();
+
+        journal.rollback().unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("config.ini")).unwrap(),
+            "before"
+        );
+        let user_ini = fs::read_to_string(dir.join("settings").join("user.ini")).unwrap();
+        assert_eq!(user_ini, "before");
+        assert!(!dir.join("dlls").exists());
+
+        drop(journal);
+        assert!(!dir.join("backups").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,108 @@
+//! Newer releases of the archiver itself. Archives follow upstream launcher
+//! and Goldberg changes only as well as the archiver creating them does.
+
+use crate::{
+    AppUpdate,
+    ctx::Context,
+    i18n::{tr, tr_fmt},
+    utils::gh_api_get,
+};
+use anyhow::{Result, anyhow};
+use common::update::parse_version;
+use eframe::egui::{self, Color32, RichText, Ui};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+#[derive(Clone)]
+pub struct ArchiverRelease {
+    pub tag: String,
+    /// The release's page on github.
+    pub url: String,
+    /// The archiver's download, when the release has one.
+    pub download_url: Option<String>,
+    pub notes: String,
+}
+
+/// Looks for a newer release in the background when checking is turned on,
+/// it arrives as [`AppUpdate::ArchiverRelease`].
+pub fn spawn_check(ctx: Arc<Context>) {
+    if !ctx.check_archiver_updates() {
+        return;
+    }
+    std::thread::spawn(move || match newer_release(&ctx) {
+        Ok(Some(release)) => {
+            ctx.events.send(AppUpdate::ArchiverRelease(release));
+        }
+        Ok(None) => {}
+        Err(err) => warn!("Unable to check for archiver updates: {err:#}"),
+    });
+}
+
+/// The latest release of the archiver when it is newer than this one.
+fn newer_release(ctx: &Context) -> Result<Option<ArchiverRelease>> {
+    let repo = ctx.config().update.archiver_repo.trim().to_string();
+    let url = format!("https://api.github.com/repos/{repo}/releases/latest");
+    let release = gh_api_get(ctx, &url)?;
+    let tag = release["tag_name"]
+        .as_str()
+        .ok_or_else(|| anyhow!("The latest release of {repo} has no tag"))?;
+    let current = env!("CARGO_PKG_VERSION");
+    if parse_version(tag) <= parse_version(current) {
+        return Ok(None);
+    }
+    info!("The archiver {tag} is out, this is {current}");
+
+    let download_url = release["assets"].as_array().and_then(|assets| {
+        assets
+            .iter()
+            .filter_map(|asset| {
+                let name = asset["name"].as_str()?.to_lowercase();
+                let patch = name.contains("patch");
+                (!patch && (name.ends_with(".exe") || name.ends_with(".zip")))
+                    .then(|| asset["browser_download_url"].as_str())
+                    .flatten()
+            })
+            .next()
+            .map(str::to_string)
+    });
+
+    Ok(Some(ArchiverRelease {
+        tag: tag.to_string(),
+        url: release["html_url"].as_str().unwrap_or_default().to_string(),
+        download_url,
+        notes: release["body"]
+            .as_str()
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+    }))
+}
+
+/// Tells about `release`. Returns whether it was dismissed.
+pub fn draw_banner(ui: &mut Ui, release: &ArchiverRelease) -> bool {
+    let mut dismissed = false;
+    ui.horizontal_wrapped(|ui| {
+        ui.label(
+            RichText::new(tr_fmt(
+                "The archiver {} is out, this is {}. Archives created with an outdated archiver may not start.",
+                &[&release.tag, &env!("CARGO_PKG_VERSION")],
+            ))
+            .color(Color32::from_rgb(255, 140, 0)),
+        );
+        if let Some(download_url) = &release.download_url {
+            ui.hyperlink_to(tr("⬇ Download"), download_url);
+        }
+        ui.hyperlink_to(tr("Release page"), &release.url);
+        dismissed = ui.button(tr("Dismiss")).clicked();
+    });
+    if !release.notes.is_empty() {
+        egui::CollapsingHeader::new(tr("Release notes"))
+            .id_salt("archiver_release_notes")
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(150.0)
+                    .show(ui, |ui| ui.label(&release.notes));
+            });
+    }
+    dismissed
+}
@@ -0,0 +1,266 @@
+//! What was picked in the UI, remembered across runs in
+//! `%APPDATA%/aoe2-archiver/settings.toml`, or next to the archiver when it's
+//! portable.
+
+use crate::{eta::Timings, history::Run, i18n::Language, paths};
+use anyhow::{Result, anyhow};
+use eframe::egui::ThemePreference;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use toml::{Table, Value};
+use tracing::warn;
+
+/// How many recent directories are kept per folder selector.
+const MAX_RECENT: usize = 5;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Settings {
+    /// Turned off, the directories and options aren't loaded or saved.
+    #[serde(default = "default_true")]
+    pub remember: bool,
+    #[serde(default)]
+    pub theme: Theme,
+    /// The UI's language.
+    #[serde(default)]
+    pub language: Language,
+    #[serde(default)]
+    pub layout: Layout,
+    /// Zoom over the system's display scaling, 1 for none.
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+    /// The size of body text in points, the other text scales along.
+    #[serde(default = "default_font_size")]
+    pub font_size: f32,
+    pub sourcedir: Option<PathBuf>,
+    pub outdir: Option<PathBuf>,
+    /// Source directories picked before, the latest first.
+    #[serde(default)]
+    pub recent_sourcedirs: Vec<PathBuf>,
+    /// Destination directories picked before, the latest first.
+    #[serde(default)]
+    pub recent_outdirs: Vec<PathBuf>,
+    /// The options changed in the UI, applied over config.toml.
+    #[serde(default)]
+    pub options: Table,
+    /// Named sets of options, saved whether or not settings are remembered.
+    #[serde(default)]
+    pub presets: Vec<Preset>,
+    /// Look for newer archiver releases on startup.
+    #[serde(default)]
+    pub check_archiver_updates: bool,
+    /// How long the steps took, for estimating the time left.
+    #[serde(default)]
+    pub timings: Timings,
+    /// The runs of all steps, the latest first. Kept whether or not settings
+    /// are remembered.
+    #[serde(default)]
+    pub history: Vec<Run>,
+    /// Kept in memory only, e.g. by tests, which leave the saved ones alone.
+    #[serde(skip)]
+    pub unsaved: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Preset {
+    pub name: String,
+    /// Where the archive goes, see [`expand_destination`]. Empty keeps the
+    /// current destination.
+    #[serde(default)]
+    pub destination: String,
+    /// The options that differ from config.toml, like [`Settings::options`].
+    #[serde(default)]
+    pub options: Table,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    /// Follows the system's dark or light mode.
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Layout {
+    /// Every option and step on one page.
+    #[default]
+    Advanced,
+    /// A page per decision, for creating an archive the first time.
+    Guided,
+}
+
+impl From<Theme> for ThemePreference {
+    fn from(theme: Theme) -> Self {
+        match theme {
+            Theme::System => ThemePreference::System,
+            Theme::Light => ThemePreference::Light,
+            Theme::Dark => ThemePreference::Dark,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            remember: true,
+            theme: Theme::default(),
+            language: Language::default(),
+            layout: Layout::default(),
+            ui_scale: default_ui_scale(),
+            font_size: default_font_size(),
+            sourcedir: None,
+            outdir: None,
+            recent_sourcedirs: vec![],
+            recent_outdirs: vec![],
+            options: Table::new(),
+            presets: vec![],
+            check_archiver_updates: false,
+            timings: Timings::default(),
+            history: vec![],
+            unsaved: false,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+/// egui's own body text size.
+pub fn default_font_size() -> f32 {
+    12.5
+}
+
+impl Settings {
+    pub fn path() -> Option<PathBuf> {
+        paths::settings_file().ok()
+    }
+
+    pub fn load() -> Self {
+        let Some(text) = Self::path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return Self::default();
+        };
+        toml::from_str(&text).unwrap_or_else(|err| {
+            warn!("Ignoring the saved settings: {err}");
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if self.unsaved {
+            return Ok(());
+        }
+        let path = Self::path().ok_or_else(|| anyhow!("Missing app data dir."))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// Moves `dir` to the front of `recent`, dropping the oldest past
+/// [`MAX_RECENT`].
+pub fn push_recent(recent: &mut Vec<PathBuf>, dir: &Path) {
+    recent.retain(|recent| recent != dir);
+    recent.insert(0, dir.to_path_buf());
+    recent.truncate(MAX_RECENT);
+}
+
+/// The folder a preset's destination points to, with `{n}` replaced by the
+/// first number that has no folder yet, e.g. `E:\AoE2-{n}` for numbered
+/// copies.
+pub fn expand_destination(pattern: &str) -> PathBuf {
+    let pattern = pattern.trim();
+    if !pattern.contains("{n}") {
+        return PathBuf::from(pattern);
+    }
+    (1..)
+        .map(|n| PathBuf::from(pattern.replace("{n}", &n.to_string())))
+        .find(|dir| !dir.exists())
+        .expect("Some number is free")
+}
+
+/// Applies `overrides` over `base`, key by key in nested tables.
+pub fn merge(base: &mut Table, overrides: &Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(key), value) {
+            (Some(Value::Table(base)), Value::Table(overrides)) => merge(base, overrides),
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// The values of `current` that differ from `base`, so defaults changed in a
+/// later config.toml still apply to what the UI didn't touch.
+pub fn diff(base: &Table, current: &Table) -> Table {
+    let mut changed = Table::new();
+    for (key, value) in current {
+        match (base.get(key), value) {
+            (Some(Value::Table(base)), Value::Table(current)) => {
+                let nested = diff(base, current);
+                if !nested.is_empty() {
+                    changed.insert(key.clone(), Value::Table(nested));
+                }
+            }
+            (Some(base), _) if base == value => {}
+            _ => {
+                changed.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_RECENT, diff, expand_destination, merge, push_recent};
+    use std::path::{Path, PathBuf};
+    use toml::Table;
+
+    #[test]
+    fn diff_then_merge_restores_changes() {
+        let base: Table = toml::from_str("a = 1\n[t]\nb = 2\nc = [1]\n").unwrap();
+        let current: Table = toml::from_str("a = 1\n[t]\nb = 3\nc = [1, 2]\n").unwrap();
+
+        let changed = diff(&base, &current);
+        assert_eq!(changed, toml::from_str("[t]\nb = 3\nc = [1, 2]\n").unwrap());
+
+        let mut merged = base.clone();
+        merge(&mut merged, &changed);
+        assert_eq!(merged, current);
+    }
+
+    #[test]
+    fn recent_dirs_are_unique_and_capped() {
+        let mut recent = vec![];
+        for dir in ["a", "b", "a", "c", "d", "e", "f"] {
+            push_recent(&mut recent, Path::new(dir));
+        }
+        let expected: Vec<PathBuf> = ["f", "e", "d", "c", "a"].map(PathBuf::from).into();
+        assert_eq!(recent, expected);
+        assert_eq!(recent.len(), MAX_RECENT);
+    }
+
+    #[test]
+    fn destination_numbers_skip_existing_folders() {
+        let dir = std::env::temp_dir().join(format!("aoe2-presets-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("AoE2-1")).unwrap();
+        let pattern = dir.join("AoE2-{n}").display().to_string();
+        assert_eq!(expand_destination(&pattern), dir.join("AoE2-2"));
+        assert_eq!(expand_destination(" E:\\AoE2 "), PathBuf::from("E:\\AoE2"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
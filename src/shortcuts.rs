@@ -0,0 +1,69 @@
+use crate::{
+    Context,
+    ctx::{CancellationToken, InstallStep, Prerequisite, Progress, StepId},
+    steam::steam_dir,
+};
+use anyhow::{Result, anyhow};
+use common::{
+    shortcuts::{SHORTCUTS_EXE, create_shortcuts},
+    steam_shortcuts::add_to_steam,
+};
+use std::sync::Arc;
+use tracing::info;
+
+pub struct ShortcutsStep;
+
+impl InstallStep for ShortcutsStep {
+    fn id(&self) -> StepId {
+        StepId::Shortcuts
+    }
+
+    fn name(&self) -> &'static str {
+        "Shortcuts"
+    }
+
+    fn prerequisites(&self) -> Prerequisite {
+        Prerequisite::Goldberg
+    }
+
+    fn enabled(&self, ctx: &Context) -> bool {
+        let config = &ctx.config().shortcuts;
+        config.add_helper || config.desktop || config.start_menu || config.steam
+    }
+
+    fn run(&self, ctx: &Arc<Context>, _: &Progress, _: &CancellationToken) -> Result<()> {
+        setup_shortcuts(ctx)
+    }
+}
+
+/// Adds the shortcut helper to the archive, and creates shortcuts to the
+/// archive's launcher on this machine if the user asked for them.
+pub fn setup_shortcuts(ctx: &Context) -> Result<()> {
+    let config = ctx.config().shortcuts.clone();
+    let outdir = ctx.outdir();
+
+    if config.add_helper {
+        // launch.exe creates shortcuts instead of starting the game when named like this.
+        let helper = outdir.join(SHORTCUTS_EXE);
+        std::fs::copy(outdir.join("launcher.exe"), &helper)
+            .map_err(|e| anyhow!("Failed to write {}: {}", helper.display(), e))?;
+        info!("Wrote {SHORTCUTS_EXE}");
+    }
+
+    if config.desktop || config.start_menu {
+        create_shortcuts(&outdir, config.desktop, config.start_menu)?;
+        info!("Created shortcuts to {}", outdir.display());
+    }
+
+    if config.steam {
+        let steam_dir = steam_dir().ok_or_else(|| anyhow!("Steam is not installed"))?;
+        match add_to_steam(&steam_dir, &outdir)? {
+            0 => info!("The archive is already in steam"),
+            added => {
+                info!("Added the archive to {added} steam account(s), restart steam to see it")
+            }
+        }
+    }
+
+    Ok(())
+}
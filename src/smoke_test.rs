@@ -0,0 +1,219 @@
+use crate::{
+    Context,
+    config::Injection,
+    ctx::{CancellationToken, InstallStep, Prerequisite, Progress, StepId},
+    goldberg::GOLDBERG_SUBDIR,
+};
+use anyhow::{Result, anyhow, bail};
+use common::DRY_RUN_ARG;
+use std::{
+    path::{Path, PathBuf},
+    process::{Child, Command},
+    sync::Arc,
+    thread::sleep,
+    time::Duration,
+};
+use tracing::{info, warn};
+
+const STEP: &str = "Test";
+const GAME_EXE: &str = "AoE2DE_s.exe";
+// The loader and game take a while to show up on slow drives.
+const STARTUP_TIMEOUT: u64 = 60;
+
+pub struct SmokeTestStep;
+
+/// Removes the loader the dry run decrypted however the test ends.
+struct DecryptedLoader(Option<PathBuf>);
+
+impl Drop for DecryptedLoader {
+    fn drop(&mut self) {
+        if let Some(loader) = &self.0 {
+            let _ = std::fs::remove_file(loader);
+        }
+    }
+}
+
+impl InstallStep for SmokeTestStep {
+    fn id(&self) -> StepId {
+        StepId::Test
+    }
+
+    fn name(&self) -> &'static str {
+        STEP
+    }
+
+    fn prerequisites(&self) -> Prerequisite {
+        Prerequisite::Goldberg
+    }
+
+    fn enabled(&self, ctx: &Context) -> bool {
+        ctx.config().smoke_test.enabled
+    }
+
+    /// Only launches the game, whose logs and profile don't belong in the
+    /// update manifest.
+    fn changes_archive(&self) -> bool {
+        false
+    }
+
+    fn run(
+        &self,
+        ctx: &Arc<Context>,
+        progress: &Progress,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        smoke_test(ctx, progress, cancel)
+    }
+}
+
+/// Checks the archive with the launcher's dry run, then starts the game and
+/// makes sure it keeps running with the companion dlls loaded.
+pub fn smoke_test(ctx: &Context, progress: &Progress, cancel: &CancellationToken) -> Result<()> {
+    let config = ctx.config();
+    let outdir = ctx.outdir();
+    let goldberg_dir = outdir.join(GOLDBERG_SUBDIR);
+
+    progress.phase(0.0, 0.1, "Dry run");
+    let output = Command::new(outdir.join("launcher.exe"))
+        .arg(DRY_RUN_ARG)
+        .current_dir(&outdir)
+        .output()
+        .map_err(|e| anyhow!("Failed to run launcher.exe: {}", e))?;
+    let _loader = DecryptedLoader(
+        config
+            .goldberg
+            .encrypt_loader
+            .then(|| goldberg_dir.join("steamclient_loader_x64.exe")),
+    );
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        info!("launcher: {line}");
+    }
+    if !output.status.success() {
+        bail!(
+            "The launcher's dry run failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    if is_game_running() {
+        bail!("{GAME_EXE} is already running, close it to test the archive");
+    }
+
+    progress.phase(0.1, 0.3, "Starting the game");
+    let game_dir = outdir.join(&config.aoe2.steam_folder);
+    let mut launch = match config.goldberg.injection {
+        Injection::ColdClientLoader => {
+            let mut cmd = Command::new(goldberg_dir.join("steamclient_loader_x64.exe"));
+            cmd.current_dir(&goldberg_dir);
+            cmd
+        }
+        Injection::SteamApi => {
+            let mut cmd = Command::new(game_dir.join(GAME_EXE));
+            cmd.current_dir(&game_dir);
+            cmd
+        }
+    };
+    let mut child = launch
+        .spawn()
+        .map_err(|e| anyhow!("Failed to start the game: {}", e))?;
+
+    let result = watch_game(
+        progress,
+        cancel,
+        config.smoke_test.alive_seconds,
+        &goldberg_dir,
+    );
+
+    stop_game(&mut child, &game_dir);
+
+    result
+}
+
+/// Stops the started process with its children, and the game the loader
+/// started from this archive in case the loader already exited. Copies of
+/// the game running from elsewhere are left alone.
+fn stop_game(child: &mut Child, game_dir: &Path) {
+    let _ = Command::new("taskkill")
+        .args(["/f", "/t", "/pid", &child.id().to_string()])
+        .output();
+    let _ = child.kill();
+    let _ = child.wait();
+
+    let game = game_dir.join(GAME_EXE).display().to_string();
+    let _ = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(format!(
+            "Get-Process -Name '{}' -ErrorAction SilentlyContinue | Where-Object {{ $_.Path -eq '{}' }} | Stop-Process -Force",
+            GAME_EXE.trim_end_matches(".exe"),
+            game.replace('\'', "''")
+        ))
+        .output();
+}
+
+fn watch_game(
+    progress: &Progress,
+    cancel: &CancellationToken,
+    alive_seconds: u64,
+    goldberg_dir: &Path,
+) -> Result<()> {
+    let mut waited = 0;
+    while !is_game_running() {
+        if waited >= STARTUP_TIMEOUT {
+            bail!("The game didn't start within {STARTUP_TIMEOUT} seconds");
+        }
+        sleep(Duration::from_secs(1));
+        cancel.check()?;
+        waited += 1;
+        progress.report(
+            "Waiting for the game to start",
+            waited as f32 / STARTUP_TIMEOUT as f32,
+        );
+    }
+
+    progress.phase(0.3, 1.0, "Watching the game");
+    for second in 1..=alive_seconds {
+        sleep(Duration::from_secs(1));
+        cancel.check()?;
+        if !is_game_running() {
+            bail!("The game exited after {second} seconds");
+        }
+        progress.report(
+            &format!("Running for {second} of {alive_seconds} seconds"),
+            second as f32 / alive_seconds.max(1) as f32,
+        );
+    }
+
+    // Companion dlls are only injected by the loader.
+    let dlls = std::fs::read_dir(goldberg_dir.join("dlls"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.to_lowercase().ends_with(".dll"));
+    for dll in dlls {
+        if !has_module(&dll) {
+            bail!("The game is running but didn't load {dll}");
+        }
+        info!("The game loaded {dll}");
+    }
+
+    Ok(())
+}
+
+fn is_game_running() -> bool {
+    tasklist(&["/fi", &format!("imagename eq {GAME_EXE}")])
+}
+
+fn has_module(dll: &str) -> bool {
+    tasklist(&["/m", dll, "/fi", &format!("imagename eq {GAME_EXE}")])
+}
+
+fn tasklist(args: &[&str]) -> bool {
+    match Command::new("tasklist").args(args).output() {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).contains(GAME_EXE),
+        Err(err) => {
+            warn!("Failed to run tasklist: {err}");
+            false
+        }
+    }
+}
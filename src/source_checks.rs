@@ -0,0 +1,305 @@
+//! What makes a folder a good source to copy, checked item by item so a
+//! failing one comes with what to do about it.
+
+use crate::{
+    App, AppUpdate,
+    aoe::aoe2,
+    events::EventBus,
+    i18n::{tr, tr_fmt},
+    space::format_size,
+};
+use anyhow::{Result, anyhow};
+use eframe::egui::{self, Color32, RichText, Ui};
+use pelite::pe64::{Pe, PeFile};
+use std::path::{Path, PathBuf};
+
+pub const GAME_EXE: &str = "AoE2DE_s.exe";
+/// Complete installations are well above this, even without expansions.
+const MIN_GAME_SIZE: u64 = 10 * 1024 * 1024 * 1024;
+/// Folders every installation has, their absence means a broken one.
+const EXPECTED_DIRS: &[&str] = &["resources/_common", "modes"];
+/// Windows refuses opening a file that is in use for writing with this.
+const ERROR_SHARING_VIOLATION: i32 = 32;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Pass,
+    /// The copy works, but may not turn out right.
+    Warn,
+    /// The folder can't be copied.
+    Fail,
+}
+
+#[derive(Clone)]
+pub struct Check {
+    /// Shown translated.
+    pub name: &'static str,
+    pub outcome: Outcome,
+    /// What was found, e.g. the version or the error.
+    pub detail: String,
+    /// What to do about it, shown translated unless the check passed.
+    pub guidance: &'static str,
+}
+
+impl Check {
+    fn new(
+        name: &'static str,
+        outcome: Outcome,
+        detail: impl Into<String>,
+        guidance: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            outcome,
+            detail: detail.into(),
+            guidance,
+        }
+    }
+}
+
+/// The checks a folder has to pass to be copied at all, quick enough to run
+/// every frame.
+pub fn required_checks(path: &Path) -> Vec<Check> {
+    let pick_guidance = "Pick the folder containing AoE2DE_s.exe, usually steamapps/common/AoE2DE in the Steam library";
+    let folder = if !path.exists() {
+        Check::new(
+            "Folder",
+            Outcome::Fail,
+            "Directory does not exist",
+            pick_guidance,
+        )
+    } else if !path.is_dir() {
+        Check::new(
+            "Folder",
+            Outcome::Fail,
+            "Path is not a directory",
+            pick_guidance,
+        )
+    } else {
+        Check::new("Folder", Outcome::Pass, path.display().to_string(), "")
+    };
+    let exe = if path.join(GAME_EXE).exists() {
+        Check::new("Game executable", Outcome::Pass, GAME_EXE, "")
+    } else {
+        Check::new(
+            "Game executable",
+            Outcome::Fail,
+            "This doesn't appear to be an AoE2 DE directory (AoE2DE_s.exe not found)",
+            pick_guidance,
+        )
+    };
+    vec![folder, exe]
+}
+
+/// Every check, with `size` the size of the folder if it was measured.
+pub fn all_checks(path: &Path, size: Option<u64>) -> Vec<Check> {
+    let mut checks = required_checks(path);
+    if checks.iter().any(|check| check.outcome == Outcome::Fail) {
+        return checks;
+    }
+
+    checks.push(match exe_version(path) {
+        Ok(version) => Check::new("Version", Outcome::Pass, version, ""),
+        Err(err) => Check::new(
+            "Version",
+            Outcome::Warn,
+            format!("{err:#}"),
+            "The executable may be damaged, verify the game files in Steam",
+        ),
+    });
+
+    let missing: Vec<_> = EXPECTED_DIRS
+        .iter()
+        .filter(|dir| !path.join(dir).is_dir())
+        .copied()
+        .collect();
+    checks.push(if missing.is_empty() {
+        Check::new("Game folders", Outcome::Pass, EXPECTED_DIRS.join(", "), "")
+    } else {
+        Check::new(
+            "Game folders",
+            Outcome::Warn,
+            format!("Missing {}", missing.join(", ")),
+            "Verify the game files in Steam, or let the download finish",
+        )
+    });
+
+    if let Some(size) = size {
+        checks.push(if size >= MIN_GAME_SIZE {
+            Check::new("Size", Outcome::Pass, format_size(size), "")
+        } else {
+            Check::new(
+                "Size",
+                Outcome::Warn,
+                format_size(size),
+                "Smaller than a complete installation, let Steam finish downloading the game",
+            )
+        });
+    }
+
+    let running = std::fs::OpenOptions::new()
+        .write(true)
+        .open(path.join(GAME_EXE))
+        .is_err_and(|err| err.raw_os_error() == Some(ERROR_SHARING_VIOLATION));
+    checks.push(if running {
+        Check::new(
+            "Not running",
+            Outcome::Warn,
+            "The game is running",
+            "Quit the game first, files in use can't be copied",
+        )
+    } else {
+        Check::new("Not running", Outcome::Pass, "", "")
+    });
+
+    // Online only files of OneDrive are downloaded one by one while copying,
+    // or fail to copy offline.
+    let resolved = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let on_onedrive = resolved.components().any(|part| {
+        part.as_os_str()
+            .to_string_lossy()
+            .to_lowercase()
+            .starts_with("onedrive")
+    });
+    checks.push(if on_onedrive {
+        Check::new(
+            "Local folder",
+            Outcome::Warn,
+            resolved.display().to_string(),
+            "The game is on OneDrive, mark the folder as 'Always keep on this device' or move the game to a local drive",
+        )
+    } else {
+        Check::new("Local folder", Outcome::Pass, "", "")
+    });
+
+    checks
+}
+
+/// The file version of the game's executable, e.g. `101.102.37106.0`.
+pub fn exe_version(source: &Path) -> Result<String> {
+    let map = pelite::FileMap::open(&source.join(GAME_EXE))?;
+    let file = PeFile::from_bytes(&map).map_err(|err| anyhow!("Unreadable executable: {err}"))?;
+    let fixed = file
+        .resources()
+        .map_err(|err| anyhow!("No resources: {err}"))?
+        .version_info()
+        .map_err(|err| anyhow!("No version info: {err}"))?
+        .fixed()
+        .ok_or_else(|| anyhow!("No version info"))?;
+    Ok(fixed.dwFileVersion.to_string())
+}
+
+/// What was found out about a source folder.
+#[derive(Clone)]
+pub struct SourceReport {
+    pub path: PathBuf,
+    /// See [`aoe2::game_version`].
+    pub version: Option<String>,
+    pub checks: Vec<Check>,
+}
+
+/// Runs every check in the background, the result arrives as
+/// [`AppUpdate::SourceChecks`].
+pub fn spawn(events: EventBus, path: PathBuf, size: Option<u64>) {
+    std::thread::spawn(move || {
+        let report = SourceReport {
+            version: aoe2::game_version(&path),
+            checks: all_checks(&path, size),
+            path,
+        };
+        events.send(AppUpdate::SourceChecks(report));
+    });
+}
+
+/// The game version and checks of the current source folder, with a button
+/// to run them again e.g. after quitting the game.
+pub fn draw_source_checks(ui: &mut Ui, app: &App) {
+    let Some(SourceReport {
+        path,
+        version,
+        checks,
+    }) = &app.source_checks
+    else {
+        return;
+    };
+    if app.ctx.sourcedir().as_ref() != Some(path) {
+        return;
+    }
+    if let Some(version) = version {
+        ui.label(tr_fmt("Source: {}", &[version]));
+    }
+    let worst = checks
+        .iter()
+        .map(|check| check.outcome)
+        .max_by_key(|outcome| match outcome {
+            Outcome::Pass => 0,
+            Outcome::Warn => 1,
+            Outcome::Fail => 2,
+        })
+        .unwrap_or(Outcome::Pass);
+    let title = match worst {
+        Outcome::Pass => tr("✅ The source passed every check"),
+        Outcome::Warn => tr("⚠ The source may not copy right"),
+        Outcome::Fail => tr("❌ The source can't be copied"),
+    };
+    egui::CollapsingHeader::new(RichText::new(title).color(outcome_color(worst)))
+        .id_salt("source_checks")
+        .default_open(worst != Outcome::Pass)
+        .show(ui, |ui| {
+            egui::Grid::new("source_checks_grid")
+                .num_columns(3)
+                .show(ui, |ui| {
+                    for check in checks {
+                        let (icon, label) = match check.outcome {
+                            Outcome::Pass => ("✅", tr("Passed")),
+                            Outcome::Warn => ("⚠", tr("Warning")),
+                            Outcome::Fail => ("❌", tr("Failed")),
+                        };
+                        ui.label(
+                            RichText::new(format!("{icon} {label}"))
+                                .color(outcome_color(check.outcome)),
+                        );
+                        ui.label(tr(check.name));
+                        ui.vertical(|ui| {
+                            if !check.detail.is_empty() {
+                                ui.label(RichText::new(&check.detail).small());
+                            }
+                            if check.outcome != Outcome::Pass {
+                                ui.label(RichText::new(tr(check.guidance)).small().italics());
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+            if ui.button(tr("🔄 Check again")).clicked() {
+                spawn(app.ctx.events.clone(), path.clone(), app.source_size);
+            }
+        });
+}
+
+fn outcome_color(outcome: Outcome) -> Color32 {
+    match outcome {
+        Outcome::Pass => Color32::from_rgb(0, 200, 0),
+        Outcome::Warn => Color32::from_rgb(255, 140, 0),
+        Outcome::Fail => Color32::from_rgb(220, 0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Outcome, all_checks, required_checks};
+
+    #[test]
+    fn missing_folder_fails_without_further_checks() {
+        let checks = all_checks(std::path::Path::new("Z:/no/such/AoE2DE"), None);
+        assert_eq!(checks.len(), 2);
+        assert!(checks.iter().all(|check| check.outcome == Outcome::Fail));
+    }
+
+    #[test]
+    fn folder_without_the_game_fails() {
+        let checks = required_checks(&std::env::temp_dir());
+        assert!(checks[0].outcome == Outcome::Pass);
+        assert!(checks[1].outcome == Outcome::Fail);
+    }
+}
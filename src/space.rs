@@ -0,0 +1,59 @@
+//! What the archive takes on the destination drive, part by part, so it's
+//! clear which options are worth turning off when space is short.
+
+use crate::config::Config;
+
+const MB: u64 = 1_048_576;
+/// Rough sizes of what the steps download, the releases aren't known yet.
+const GOLDBERG_SIZE: u64 = 20 * MB;
+const LAUNCHER_SIZE: u64 = 15 * MB;
+const SERVER_SIZE: u64 = 20 * MB;
+const VC_REDIST_SIZE: u64 = 25 * MB;
+const DIRECTX_SIZE: u64 = 100 * MB;
+
+pub struct Part {
+    /// Shown translated.
+    pub name: &'static str,
+    pub bytes: u64,
+    /// Whether `bytes` is a guess rather than measured.
+    pub estimate: bool,
+}
+
+/// The parts `config` adds to the archive. `profile_size` counts the game
+/// profile with its mods, copied when importing the profile.
+pub fn breakdown(config: &Config, game_size: u64, profile_size: Option<u64>) -> Vec<Part> {
+    let part = |name, bytes, estimate| Part {
+        name,
+        bytes,
+        estimate,
+    };
+    let mut parts = vec![
+        part("Game files", game_size, false),
+        part("Goldberg emulator", GOLDBERG_SIZE, true),
+        part("Launcher and companion", LAUNCHER_SIZE, true),
+    ];
+    if config.server.install {
+        parts.push(part("Server", SERVER_SIZE, true));
+    }
+    let redist_size =
+        config.redist.vc as u64 * VC_REDIST_SIZE + config.redist.directx as u64 * DIRECTX_SIZE;
+    if redist_size > 0 {
+        parts.push(part("Runtime installers", redist_size, true));
+    }
+    if config.goldberg.import_profile {
+        parts.push(part(
+            "Game profile and mods",
+            profile_size.unwrap_or_default(),
+            profile_size.is_none(),
+        ));
+    }
+    parts
+}
+
+pub fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * MB {
+        format!("{:.2} GB", bytes as f64 / (1024 * MB) as f64)
+    } else {
+        format!("{:.0} MB", bytes as f64 / MB as f64)
+    }
+}
@@ -1,10 +1,11 @@
+use crate::aoe::aoe2;
 use anyhow::Result;
 use std::path::PathBuf;
 use winreg::RegKey;
 use winreg::enums::*;
 
 pub fn steam_aoe2_path() -> Result<Option<PathBuf>> {
-    install_location("Steam App 813780")
+    install_location(&format!("Steam App {}", aoe2::APP_ID))
 }
 
 pub fn install_location(app_name: &str) -> Result<Option<PathBuf>> {
@@ -20,12 +21,28 @@ pub fn install_location(app_name: &str) -> Result<Option<PathBuf>> {
         let mut registry_path = root.to_string();
         registry_path.push_str(app_name);
 
-        if let Ok(key) = hklm.open_subkey(registry_path) {
-            if let Ok(install_path) = key.get_value::<String, _>("InstallLocation") {
-                return Ok(Some(PathBuf::from(install_path)));
-            }
+        if let Ok(key) = hklm.open_subkey(registry_path)
+            && let Ok(install_path) = key.get_value::<String, _>("InstallLocation")
+        {
+            return Ok(Some(PathBuf::from(install_path)));
         }
     }
 
     Ok(None)
 }
+
+/// The account name steam logs in with automatically, if any.
+pub fn steam_account_name() -> Option<String> {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let key = hkcu.open_subkey("Software\\Valve\\Steam").ok()?;
+    key.get_value::<String, _>("AutoLoginUser").ok()
+}
+
+/// Where the steam client is installed.
+pub fn steam_dir() -> Option<PathBuf> {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let key = hkcu.open_subkey("Software\\Valve\\Steam").ok()?;
+    key.get_value::<String, _>("SteamPath")
+        .ok()
+        .map(PathBuf::from)
+}
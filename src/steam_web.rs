@@ -0,0 +1,220 @@
+use anyhow::{Result, bail};
+use reqwest::blocking::Client;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+const APP_DETAILS_URL: &str = "https://store.steampowered.com/api/appdetails";
+const SCHEMA_URL: &str = "https://api.steampowered.com/ISteamUserStats/GetSchemaForGame/v2/";
+const GLOBAL_ACHIEVEMENTS_URL: &str =
+    "https://api.steampowered.com/ISteamUserStats/GetGlobalAchievementPercentagesForApp/v0002/";
+
+// Store display name -> steam api language code.
+const LANGUAGES: &[(&str, &str)] = &[
+    ("english", "english"),
+    ("french", "french"),
+    ("italian", "italian"),
+    ("german", "german"),
+    ("spanish - spain", "spanish"),
+    ("spanish - latin america", "latam"),
+    ("japanese", "japanese"),
+    ("korean", "koreana"),
+    ("portuguese - portugal", "portuguese"),
+    ("portuguese - brazil", "brazilian"),
+    ("russian", "russian"),
+    ("simplified chinese", "schinese"),
+    ("traditional chinese", "tchinese"),
+    ("turkish", "turkish"),
+    ("vietnamese", "vietnamese"),
+    ("polish", "polish"),
+    ("hindi", "hindi"),
+    ("malay", "malay"),
+    ("czech", "czech"),
+    ("dutch", "dutch"),
+    ("thai", "thai"),
+    ("ukrainian", "ukrainian"),
+];
+
+/// Builds the goldberg `steam_settings` files from the public Steam Web API.
+/// Achievements and stats come from the full schema when an api key is
+/// available, otherwise only achievement names can be resolved.
+pub fn generate_steam_settings(
+    client: &Client,
+    app_id: u32,
+    api_key: Option<&str>,
+) -> Result<HashMap<String, String>> {
+    let mut files = HashMap::new();
+
+    info!("Fetching supported languages for app {app_id}");
+    let languages = supported_languages(client, app_id)?;
+    files.insert("supported_languages.txt".to_string(), languages.join("\n"));
+
+    match api_key {
+        Some(key) => {
+            info!("Fetching achievement and stat schema for app {app_id}");
+            let (achievements, stats) = schema(client, app_id, key)?;
+            files.insert(
+                "achievements.json".to_string(),
+                serde_json::to_string_pretty(&achievements)?,
+            );
+            files.insert(
+                "stats.json".to_string(),
+                serde_json::to_string_pretty(&stats)?,
+            );
+        }
+        None => {
+            warn!("No steam web api key configured, only achievement names will be generated");
+            let achievements = achievement_names(client, app_id)?;
+            files.insert(
+                "achievements.json".to_string(),
+                serde_json::to_string_pretty(&achievements)?,
+            );
+        }
+    }
+
+    Ok(files)
+}
+
+fn supported_languages(client: &Client, app_id: u32) -> Result<Vec<String>> {
+    let json: Value = client
+        .get(format!("{APP_DETAILS_URL}?appids={app_id}"))
+        .send()?
+        .error_for_status()?
+        .json()?;
+
+    let Some(languages) = json
+        .pointer(&format!("/{app_id}/data/supported_languages"))
+        .and_then(|l| l.as_str())
+    else {
+        bail!("Steam store did not return supported languages for app {app_id}.");
+    };
+
+    let languages = parse_supported_languages(languages);
+    if languages.is_empty() {
+        bail!("Unable to recognize any supported languages for app {app_id}.");
+    }
+
+    Ok(languages)
+}
+
+/// The store returns a display string like
+/// `English<strong>*</strong>, French<br><strong>*</strong>languages with full audio support`.
+fn parse_supported_languages(languages: &str) -> Vec<String> {
+    let languages = languages.split("<br>").next().unwrap_or_default();
+
+    let mut stripped = String::with_capacity(languages.len());
+    let mut in_tag = false;
+    for c in languages.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            '*' => {}
+            c if !in_tag => stripped.push(c),
+            _ => {}
+        }
+    }
+
+    stripped
+        .split(',')
+        .filter_map(|name| {
+            let name = name.trim().to_lowercase();
+            let code = LANGUAGES.iter().find(|(n, _)| *n == name).map(|(_, c)| c);
+            if code.is_none() && !name.is_empty() {
+                warn!("Skipping unknown store language: {name}");
+            }
+            code.map(|c| c.to_string())
+        })
+        .collect()
+}
+
+fn schema(client: &Client, app_id: u32, api_key: &str) -> Result<(Vec<Value>, Vec<Value>)> {
+    let json: Value = client
+        .get(format!(
+            "{SCHEMA_URL}?key={api_key}&appid={app_id}&l=english"
+        ))
+        .send()?
+        .error_for_status()?
+        .json()?;
+
+    let Some(stats) = json.pointer("/game/availableGameStats") else {
+        bail!("Steam web api returned no game stats schema for app {app_id}.");
+    };
+
+    let achievements = stats
+        .get("achievements")
+        .and_then(|a| a.as_array())
+        .map(|a| {
+            a.iter()
+                .map(|a| {
+                    json!({
+                        "name": a.get("name").cloned().unwrap_or_default(),
+                        "displayName": a.get("displayName").cloned().unwrap_or_default(),
+                        "description": a.get("description").cloned().unwrap_or_default(),
+                        "hidden": a.get("hidden").cloned().unwrap_or(json!(0)),
+                        "icon": a.get("icon").cloned().unwrap_or_default(),
+                        "icongray": a.get("icongray").cloned().unwrap_or_default(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let stats = stats
+        .get("stats")
+        .and_then(|s| s.as_array())
+        .map(|s| {
+            s.iter()
+                .map(|s| {
+                    json!({
+                        "name": s.get("name").cloned().unwrap_or_default(),
+                        "type": "int",
+                        "default": s.get("defaultvalue").cloned().unwrap_or(json!(0)).to_string(),
+                        "global": "0",
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok((achievements, stats))
+}
+
+fn achievement_names(client: &Client, app_id: u32) -> Result<Vec<Value>> {
+    let json: Value = client
+        .get(format!("{GLOBAL_ACHIEVEMENTS_URL}?gameid={app_id}"))
+        .send()?
+        .error_for_status()?
+        .json()?;
+
+    let Some(achievements) = json
+        .pointer("/achievementpercentages/achievements")
+        .and_then(|a| a.as_array())
+    else {
+        bail!("Steam web api returned no achievements for app {app_id}.");
+    };
+
+    let mut names: Vec<&str> = achievements
+        .iter()
+        .filter_map(|a| a.get("name").and_then(|n| n.as_str()))
+        .collect();
+    names.sort_unstable();
+
+    Ok(names
+        .into_iter()
+        .map(|name| json!({ "name": name }))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_supported_languages;
+
+    #[test]
+    fn parse_store_languages() {
+        let languages = parse_supported_languages(
+            "English<strong>*</strong>, French<strong>*</strong>, Spanish - Latin America, Korean<br><strong>*</strong>languages with full audio support",
+        );
+
+        assert_eq!(languages, ["english", "french", "latam", "koreana"]);
+    }
+}
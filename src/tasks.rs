@@ -0,0 +1,148 @@
+//! The async runtime downloads run on. Steps stay plain threads and hand their
+//! network and file work to the [`Supervisor`], which stops it as soon as the
+//! task is cancelled instead of at the next check.
+
+use crate::{
+    ctx::CancellationToken,
+    errors::{ErrorKind, Tag},
+};
+use anyhow::{Result, anyhow, bail};
+use futures_util::future::{Either, join_all, select};
+use std::{future::Future, pin::pin, time::Duration};
+use tokio::{
+    runtime::{Builder, Runtime},
+    task::JoinHandle,
+};
+
+/// Worker threads of the runtime, downloads wait on the network more than
+/// they compute.
+const WORKERS: usize = 2;
+
+/// How long the runtime waits for its tasks to stop on exit.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+pub struct Supervisor {
+    runtime: Option<Runtime>,
+}
+
+impl Supervisor {
+    pub fn new() -> Result<Self> {
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(WORKERS)
+            .thread_name("archiver-io")
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            runtime: Some(runtime),
+        })
+    }
+
+    fn runtime(&self) -> &Runtime {
+        self.runtime
+            .as_ref()
+            .expect("the runtime lives as long as the supervisor")
+    }
+
+    /// Runs `future` from a step's thread and waits for it. Fails with
+    /// "Cancelled" as soon as `cancel` is, dropping the future.
+    pub fn run<T>(
+        &self,
+        cancel: &CancellationToken,
+        future: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        self.runtime().block_on(until_cancelled(cancel, future))
+    }
+
+    /// Runs `futures` at the same time, waiting for all of them. Each fails
+    /// with "Cancelled" as soon as `cancel` is.
+    pub fn run_all<T>(
+        &self,
+        cancel: &CancellationToken,
+        futures: impl IntoIterator<Item = impl Future<Output = Result<T>>>,
+    ) -> Vec<Result<T>> {
+        let futures = futures
+            .into_iter()
+            .map(|future| until_cancelled(cancel, future));
+        self.runtime().block_on(join_all(futures))
+    }
+
+    /// Runs blocking `work` in the background on the runtime's thread pool,
+    /// see [`Supervisor::join`].
+    pub fn spawn_blocking<T: Send + 'static>(
+        &self,
+        work: impl FnOnce() -> T + Send + 'static,
+    ) -> JoinHandle<T> {
+        self.runtime().spawn_blocking(work)
+    }
+
+    /// Waits for work started with [`Supervisor::spawn_blocking`].
+    pub fn join<T>(&self, handle: JoinHandle<T>) -> Result<T> {
+        self.runtime()
+            .block_on(handle)
+            .map_err(|err| anyhow!("The background task failed: {err}"))
+    }
+}
+
+impl Drop for Supervisor {
+    fn drop(&mut self) {
+        // Downloads of a cancelled prefetch may still be waiting on the network.
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_timeout(SHUTDOWN_TIMEOUT);
+        }
+    }
+}
+
+async fn until_cancelled<T>(
+    cancel: &CancellationToken,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match select(pin!(future), pin!(cancel.cancelled())).await {
+        Either::Left((result, _)) => result,
+        Either::Right(((), _)) => bail!("Cancelled"),
+    }
+}
+
+/// Fails when `future` takes longer than `duration`, with `what` timing out.
+pub async fn timeout<T>(
+    duration: Duration,
+    what: &str,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match tokio::time::timeout(duration, future).await {
+        Ok(result) => result,
+        // Only the network is waited on with a timeout.
+        Err(_) => Err(anyhow!("{what} timed out after {}s", duration.as_secs()))
+            .kind(ErrorKind::NetworkBlocked),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Supervisor;
+    use crate::ctx::CancellationToken;
+    use std::time::Duration;
+
+    #[test]
+    fn stops_when_cancelled() {
+        let tasks = Supervisor::new().unwrap();
+        let cancel = CancellationToken::default();
+        let results = tasks.run_all(&cancel, [1, 2].map(|i| async move { Ok(i) }));
+        assert_eq!(
+            results.into_iter().map(|r| r.unwrap()).collect::<Vec<_>>(),
+            [1, 2]
+        );
+
+        std::thread::spawn({
+            let cancel = cancel.clone();
+            move || {
+                std::thread::sleep(Duration::from_millis(50));
+                cancel.cancel();
+            }
+        });
+        let stalled = tasks.run(&cancel, async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(())
+        });
+        assert_eq!(stalled.unwrap_err().to_string(), "Cancelled");
+    }
+}
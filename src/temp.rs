@@ -0,0 +1,122 @@
+use crate::paths;
+use anyhow::{Context as AnyhowContext, Result};
+use fs_extra::dir::get_size;
+use fs2::FileExt;
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+use tracing::{info, warn};
+
+const LOCK_FILE: &str = ".lock";
+
+/// Per-run scratch directory for partial downloads, extraction and probe
+/// files. Every run gets its own locked subfolder of a shared root, so
+/// leftovers of crashed runs can be told apart from other running instances
+/// and cleaned up on the next start.
+pub struct TempDir {
+    root: PathBuf,
+    run_dir: PathBuf,
+    lock: Option<File>,
+}
+
+impl TempDir {
+    pub fn new() -> Result<Self> {
+        let root = paths::temp_dir()?;
+        let run_dir = root.join(format!("run-{}", std::process::id()));
+        fs::create_dir_all(&run_dir).context("Failed to create temp directory")?;
+
+        let lock = File::create(run_dir.join(LOCK_FILE))?;
+        lock.try_lock_exclusive()
+            .context("Failed to lock temp directory")?;
+
+        let temp = Self {
+            root,
+            run_dir,
+            lock: Some(lock),
+        };
+
+        let reclaimed = temp.clean_stale();
+        if reclaimed > 0 {
+            info!(
+                "Removed {:.2} MB of temporary files left over from a previous run",
+                reclaimed as f64 / 1_048_576.0
+            );
+        }
+
+        Ok(temp)
+    }
+
+    /// A path for a temporary file or folder of this run.
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.run_dir.join(name)
+    }
+
+    /// Bytes currently used by temporary files of this and crashed runs.
+    pub fn size(&self) -> u64 {
+        get_size(&self.root).unwrap_or_default()
+    }
+
+    /// Removes temporary files of this run and of crashed runs, returning the
+    /// number of bytes reclaimed. Must not be called while a task is running.
+    pub fn clean(&self) -> Result<u64> {
+        let mut reclaimed = self.clean_stale();
+
+        for entry in fs::read_dir(&self.run_dir)? {
+            let path = entry?.path();
+            if path.file_name().is_some_and(|n| n == LOCK_FILE) {
+                continue;
+            }
+            reclaimed += get_size(&path).unwrap_or_default();
+            remove(&path)?;
+        }
+
+        Ok(reclaimed)
+    }
+
+    /// Removes run folders whose owning process no longer holds the lock.
+    fn clean_stale(&self) -> u64 {
+        let Ok(entries) = fs::read_dir(&self.root) else {
+            return 0;
+        };
+
+        let mut reclaimed = 0;
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path == self.run_dir || !path.is_dir() {
+                continue;
+            }
+
+            let unlocked = File::open(path.join(LOCK_FILE))
+                .map(|lock| lock.try_lock_exclusive().is_ok())
+                .unwrap_or(true);
+            if !unlocked {
+                continue;
+            }
+
+            let size = get_size(&path).unwrap_or_default();
+            match fs::remove_dir_all(&path) {
+                Ok(_) => reclaimed += size,
+                Err(err) => warn!("Failed to remove {}: {err}", path.display()),
+            }
+        }
+
+        reclaimed
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        drop(self.lock.take());
+        let _ = fs::remove_dir_all(&self.run_dir);
+    }
+}
+
+fn remove(path: &Path) -> Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
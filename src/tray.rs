@@ -0,0 +1,153 @@
+//! The notification area icon, for keeping an eye on a long archiving run
+//! with the window out of the way, and toasts when it is done.
+
+use crate::{AppUpdate, events::EventBus, i18n::tr};
+use anyhow::Result;
+use eframe::egui::{self, IconData};
+use std::process::Command;
+use tray_icon::{
+    Icon, MouseButton, TrayIcon, TrayIconBuilder, TrayIconEvent,
+    menu::{Menu, MenuEvent, MenuItem},
+};
+
+const SHOW: &str = "show";
+const HIDE: &str = "hide";
+const CANCEL: &str = "cancel";
+
+#[derive(Clone, Copy)]
+pub enum TrayAction {
+    Show,
+    Hide,
+    Cancel,
+}
+
+/// Adds the icon, which stays until the returned [`TrayIcon`] is dropped.
+/// Its actions arrive as [`AppUpdate::Tray`], after showing or hiding the
+/// window `hwnd` if there is one.
+pub fn create(
+    icon: &IconData,
+    hwnd: Option<isize>,
+    egui_ctx: egui::Context,
+    events: EventBus,
+) -> Result<TrayIcon> {
+    let menu = Menu::with_items(&[
+        &MenuItem::with_id(SHOW, tr("Show"), true, None),
+        &MenuItem::with_id(HIDE, tr("Hide to the tray"), true, None),
+        &MenuItem::with_id(CANCEL, tr("Cancel the running step"), true, None),
+    ])?;
+    let tray = TrayIconBuilder::new()
+        .with_menu(Box::new(menu))
+        .with_icon(Icon::from_rgba(icon.rgba.clone(), icon.width, icon.height)?)
+        .with_tooltip("AoE2 DE Archiver")
+        .build()?;
+
+    // The handlers run on the UI thread between frames, so they wake it up.
+    // eframe doesn't draw hidden windows, so they show and hide the window
+    // themselves rather than leaving it to the next frame.
+    let send = move |action| {
+        if let Some(hwnd) = hwnd {
+            match action {
+                TrayAction::Show => show_window(hwnd, true),
+                TrayAction::Hide => show_window(hwnd, false),
+                TrayAction::Cancel => {}
+            }
+        }
+        events.send(AppUpdate::Tray(action));
+        egui_ctx.request_repaint();
+    };
+    let on_menu = send.clone();
+    MenuEvent::set_event_handler(Some(move |event: MenuEvent| match event.id().as_ref() {
+        SHOW => on_menu(TrayAction::Show),
+        HIDE => on_menu(TrayAction::Hide),
+        CANCEL => on_menu(TrayAction::Cancel),
+        _ => {}
+    }));
+    TrayIconEvent::set_event_handler(Some(move |event| {
+        if let TrayIconEvent::DoubleClick {
+            button: MouseButton::Left,
+            ..
+        } = event
+        {
+            send(TrayAction::Show);
+        }
+    }));
+
+    Ok(tray)
+}
+
+/// Brings the shown window to the front. Without a window handle hiding
+/// falls back to minimizing.
+pub fn apply(action: TrayAction, has_window: bool, egui_ctx: &egui::Context) {
+    match action {
+        TrayAction::Show => {
+            egui_ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+            egui_ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        }
+        TrayAction::Hide if !has_window => {
+            egui_ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true))
+        }
+        TrayAction::Hide | TrayAction::Cancel => {}
+    }
+}
+
+/// The window's handle, for the notification area icon to hide it.
+pub fn window_handle(window: &impl raw_window_handle::HasWindowHandle) -> Option<isize> {
+    match window.window_handle().ok()?.as_raw() {
+        raw_window_handle::RawWindowHandle::Win32(handle) => Some(handle.hwnd.get()),
+        _ => None,
+    }
+}
+
+fn show_window(hwnd: isize, show: bool) {
+    #[cfg(windows)]
+    {
+        #[link(name = "user32")]
+        unsafe extern "system" {
+            fn ShowWindow(hwnd: isize, cmd_show: i32) -> i32;
+            fn SetForegroundWindow(hwnd: isize) -> i32;
+        }
+        const SW_HIDE: i32 = 0;
+        const SW_RESTORE: i32 = 9;
+        // SAFETY: the handle is the app's window, which outlives the icon.
+        unsafe {
+            ShowWindow(hwnd, if show { SW_RESTORE } else { SW_HIDE });
+            if show {
+                SetForegroundWindow(hwnd);
+            }
+        }
+    }
+    #[cfg(not(windows))]
+    let _ = (hwnd, show);
+}
+
+/// Shows a Windows toast. Failures are ignored, it's only a courtesy.
+pub fn notify(title: &str, body: &str) {
+    // Toasts need a registered app id, powershell's is always there.
+    let script = format!(
+        "$m = [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime]; \
+         $xml = $m::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+         $text = $xml.GetElementsByTagName('text'); \
+         $text.Item(0).AppendChild($xml.CreateTextNode('{}')) > $null; \
+         $text.Item(1).AppendChild($xml.CreateTextNode('{}')) > $null; \
+         $m::CreateToastNotifier('{{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}}\\WindowsPowerShell\\v1.0\\powershell.exe').Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+        quote(title),
+        quote(body)
+    );
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(script);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // Not even a console flashing up, as -WindowStyle Hidden would.
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let _ = command.spawn();
+}
+
+// Single quoted powershell strings only escape quotes, by doubling them.
+fn quote(s: &str) -> String {
+    s.replace('\'', "''")
+}
@@ -1,16 +1,37 @@
 use crate::{
-    App, AppUpdate,
-    ctx::{Context, StepStatus},
-    run_all_steps,
-    utils::validate_aoe2_source,
+    App, AppUpdate, LogEntry,
+    config::{Config, Injection, ServerStart},
+    ctx::{Context, OverwritePolicy, StepId, StepStatus},
+    download_sources::draw_download_sources,
+    errors::ErrorKind,
+    events::EventBus,
+    goldberg,
+    guided::draw_guided,
+    history::draw_history,
+    i18n::{Language, tr, tr_fmt},
+    jobs::draw_jobs,
+    log, paths,
+    presets::draw_presets,
+    repair_archive,
+    report::{draw_report, format_duration},
+    resume, run_all_steps, run_step, self_update,
+    settings::{Layout, Settings, Theme, default_font_size},
+    source_checks::draw_source_checks,
+    space::format_size,
+    tray::{self, TrayAction},
+    utils::{check_writable, relaunch_elevated, validate_aoe2_source},
 };
 use anyhow::Result;
-use eframe::egui::{self, Button, Color32, ProgressBar, RichText, TextEdit, Ui};
+use eframe::egui::{
+    self, Button, Color32, Key, KeyboardShortcut, Label, Modifiers, ProgressBar, Response,
+    RichText, Sense, TextEdit, TextStyle, Ui, WidgetInfo, WidgetType,
+};
 use std::{
     path::{Path, PathBuf},
-    sync::mpsc::Sender,
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
 };
-use tracing::info;
+use tracing::{Level, error, info, warn};
 use tracing_subscriber::Layer;
 
 fn draw_main(app: &mut App, ui: &mut Ui) -> Result<()> {
@@ -21,157 +42,491 @@ fn draw_main(app: &mut App, ui: &mut Ui) -> Result<()> {
     // Status banner at the top
     draw_status_banner(ui, app);
 
-    // Disk space info
-    let required = app.required_space.unwrap_or_default() as f64;
-    let available = app.available_space.unwrap_or_default() as f64;
-    let required_gb = required as f64 / 1_073_741_824.0;
-    let available_gb = available as f64 / 1_073_741_824.0;
-    let color = if available > required {
-        Color32::from_rgb(0, 200, 0)
-    } else {
-        Color32::from_rgb(220, 0, 0)
-    };
-    ui.horizontal(|ui| {
-        ui.label("Disk Space:");
-        ui.label(
-            RichText::new(format!(
-                "{:.2} GB required, {:.2} GB available",
-                required_gb, available_gb
-            ))
-            .color(color),
-        );
-    });
+    draw_disk_space(ui, app);
+    draw_temp_files(ui, app);
     ui.add_space(10.0);
     ui.separator();
 
-    ui.label(RichText::new("Configuration").strong().size(16.0));
+    ui.label(RichText::new(tr("Configuration")).strong().size(16.0));
     ui.add_space(8.0);
 
     source_folder_selection(
         ui,
         &app.ctx,
-        "AoE2 DE Source Directory",
-        "Select the folder containing your Age of Empires II: Definitive Edition installation",
+        tr("AoE2 DE Source Directory"),
+        tr("Select the folder containing your Age of Empires II: Definitive Edition installation"),
         app.ctx.sourcedir(),
         Some(validate_aoe2_source),
     );
+    draw_source_checks(ui, app);
     ui.add_space(8.0);
 
     outdir_folder_selection(
         ui,
         &app.ctx,
-        "Destination Directory",
-        "Select where you want to create the archived copy of the game",
+        tr("Destination Directory"),
+        tr("Select where you want to create the archived copy of the game"),
         app.ctx.outdir(),
     );
+    ui.add_space(8.0);
+
+    draw_presets(ui, app);
+    draw_options(ui, &app.ctx);
+    draw_settings(ui, &app.ctx);
     ui.add_space(10.0);
 
     // Steps section
     ui.separator();
-    ui.label(RichText::new("Steps").strong().size(16.0));
+    ui.label(RichText::new(tr("Steps")).strong().size(16.0));
     ui.add_space(8.0);
 
-    ui.horizontal(|ui| {
-        let step_status = app.ctx.step_status.lock().unwrap();
-
-        // Step 1: Copy
-        ui.label(
-            RichText::new(step_status[0].icon())
-                .color(step_status[0].color())
-                .size(18.0),
-        );
-        ui.label("1. Copy");
-        ui.add_space(10.0);
-
-        // Step 2: Goldberg
-        ui.label(
-            RichText::new(step_status[1].icon())
-                .color(step_status[1].color())
-                .size(18.0),
-        );
-        ui.label("2. Goldberg");
-        ui.add_space(10.0);
-
-        // Step 3: Companion
-        ui.label(
-            RichText::new(step_status[2].icon())
-                .color(step_status[2].color())
-                .size(18.0),
-        );
-        ui.label("3. Companion");
-        ui.add_space(10.0);
-
-        // Step 4: Launcher
-        ui.label(
-            RichText::new(step_status[3].icon())
-                .color(step_status[3].color())
-                .size(18.0),
-        );
-        ui.label("4. Launcher");
-    });
+    draw_steps(ui, app);
     ui.add_space(10.0);
 
     // Run All button
+    let step_status = app.ctx.step_statuses();
     let source_exists = app.ctx.sourcedir().is_some();
+    let busy = app.ctx.is_busy();
     let can_run_all = source_exists
-        && !app.ctx.is_busy()
-        && app
-            .ctx
-            .step_status
-            .lock()
-            .unwrap()
+        && !busy
+        && step_status
             .iter()
             .all(|s| matches!(s, StepStatus::NotStarted));
 
-    if ui
+    let run_all = ui
         .add_enabled(
             can_run_all,
-            Button::new("▶ Run All Steps").min_size([150.0, 30.0].into()),
+            Button::new(tr("▶ Run All Steps")).min_size([150.0, 30.0].into()),
         )
-        .on_hover_text("Automatically run all steps in sequence")
-        .clicked()
+        .on_hover_text(format!(
+            "{} ({})",
+            tr("Automatically run all steps in sequence"),
+            ui.ctx().format_shortcut(&RUN_ALL_SHORTCUT)
+        ));
+    if (run_all.clicked() || can_run_all && run_all_pressed(ui))
+        && confirm_destination(&app.ctx, true)
     {
         run_all_steps(app.ctx.clone());
     }
+    if !busy
+        && step_status
+            .iter()
+            .any(|s| !matches!(s, StepStatus::NotStarted))
+        && ui
+            .add(Button::new(tr("↺ Reset Steps")).min_size([150.0, 30.0].into()))
+            .on_hover_text(tr("Forget how the steps went, to run them all again"))
+            .clicked()
+    {
+        app.ctx.reset_steps();
+    }
+
+    if ui
+        .add_enabled(
+            !app.ctx.is_busy(),
+            Button::new(tr("🔧 Repair Existing Archive")).min_size([150.0, 30.0].into()),
+        )
+        .on_hover_text(tr("Reinstall goldberg, the companion and the launcher in an archive created earlier, without copying the game again"))
+        .clicked()
+        && let Some(archive) = rfd::FileDialog::new()
+            .set_title(tr("Select the archive to repair"))
+            .pick_folder()
+    {
+        repair_archive(app.ctx.clone(), archive);
+    }
+
+    let copying = app.copying_saves.load(Ordering::Relaxed);
+    if ui
+        .add_enabled(
+            !copying && !app.ctx.is_busy(),
+            Button::new(tr("📥 Copy Saves from an Archive")).min_size([150.0, 30.0].into()),
+        )
+        .on_hover_text(tr(
+            "Copy the saves and recorded games played with an archive into your game profile",
+        ))
+        .clicked()
+        && let Some(archive) = rfd::FileDialog::new()
+            .set_title(tr("Select the archive to copy the saves from"))
+            .pick_folder()
+    {
+        let steam_folder = app.ctx.config().aoe2.steam_folder.clone();
+        let copying = app.copying_saves.clone();
+        copying.store(true, Ordering::Relaxed);
+        std::thread::spawn(move || {
+            if let Err(err) = goldberg::export_archive_saves(&archive, &steam_folder) {
+                error!("{err:#}");
+            }
+            copying.store(false, Ordering::Relaxed);
+        });
+    }
+    ui.add_space(8.0);
+    draw_jobs(ui, &app.ctx);
+    draw_history(ui, &app.ctx);
     ui.add_space(10.0);
 
     // Logs section
     ui.separator();
-    ui.label(RichText::new("Logs").strong().size(16.0));
+    ui.label(RichText::new(tr("Logs")).strong().size(16.0));
     ui.add_space(8.0);
 
+    draw_logs(ui, app);
+
+    Ok(())
+}
+
+/// Free space on the destination drive against the size of the game.
+pub(crate) fn draw_disk_space(ui: &mut Ui, app: &App) {
+    // Disk space info
+    let required = app.required_space().unwrap_or_default() as f64;
+    let available = app.available_space.unwrap_or_default() as f64;
+    let required_gb = required / 1_073_741_824.0;
+    let available_gb = available / 1_073_741_824.0;
+    let color = if available > required {
+        Color32::from_rgb(0, 200, 0)
+    } else {
+        Color32::from_rgb(220, 0, 0)
+    };
+    ui.horizontal(|ui| {
+        ui.label(tr("Disk Space:"));
+        ui.label(
+            RichText::new(tr_fmt(
+                "{} GB required, {} GB available",
+                &[&format!("{required_gb:.2}"), &format!("{available_gb:.2}")],
+            ))
+            .color(color),
+        );
+    });
+
+    let Some(parts) = app.space_parts() else {
+        return;
+    };
+    egui::CollapsingHeader::new(tr("Breakdown")).show(ui, |ui| {
+        egui::Grid::new("space").num_columns(2).show(ui, |ui| {
+            for part in parts {
+                ui.label(tr(part.name));
+                let size = format_size(part.bytes);
+                if part.estimate {
+                    ui.label(format!("~{size}"))
+                        .on_hover_text(tr("An estimate, it's only known once downloaded"));
+                } else {
+                    ui.label(size);
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
+
+/// The steps with their status and a button to run each on its own.
+/// Runs all the steps from anywhere in the window.
+pub(crate) const RUN_ALL_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND, Key::Enter);
+
+pub(crate) fn run_all_pressed(ui: &Ui) -> bool {
+    ui.input_mut(|i| i.consume_shortcut(&RUN_ALL_SHORTCUT))
+}
+
+pub(crate) fn draw_steps(ui: &mut Ui, app: &App) {
+    // Cloned so the steps started below can update them.
+    let steps = app.ctx.steps();
+    let busy = app.ctx.is_busy();
+
+    egui::Grid::new("steps").num_columns(4).show(ui, |ui| {
+        for (i, step) in steps.iter().enumerate() {
+            let (name, status) = (step.name, &step.status);
+            // Focusable, so the error can be read without a mouse.
+            let elapsed = app
+                .ctx
+                .step_elapsed(step.id)
+                .filter(|_| *status == StepStatus::InProgress);
+            let status_text = match elapsed {
+                Some(elapsed) => format!("{} {}", tr(status.label()), format_duration(elapsed)),
+                None => tr(status.label()).to_string(),
+            };
+            let status_label = ui
+                .horizontal(|ui| {
+                    if elapsed.is_some() {
+                        ui.spinner();
+                    } else {
+                        ui.label(
+                            RichText::new(status.icon())
+                                .color(status.color())
+                                .size(18.0),
+                        );
+                    }
+                    ui.add(
+                        Label::new(RichText::new(&status_text).color(status.color()))
+                            .sense(Sense::focusable_noninteractive()),
+                    )
+                })
+                .inner;
+            let status_label = labeled(
+                status_label,
+                WidgetType::Label,
+                &format!("{}: {}", tr(name), tr(status.label())),
+            );
+            if let StepStatus::Failed(err) = status {
+                hint(status_label, err);
+            }
+            ui.label(format!("{}. {}", i + 1, tr(name)));
+
+            // The later steps work on an archive copied earlier too.
+            let unmet = app.ctx.unmet(step.prerequisite);
+            let text = match status {
+                StepStatus::NotStarted => tr("Run"),
+                _ => tr("Re-run"),
+            };
+            if ui
+                .add_enabled(unmet.is_none() && !busy, Button::new(text).small())
+                .on_disabled_hover_text(if busy {
+                    tr("Another step is running")
+                } else {
+                    tr(unmet.unwrap_or_default())
+                })
+                .clicked()
+                && confirm_destination(&app.ctx, step.id == StepId::Copy)
+            {
+                run_step(app.ctx.clone(), step);
+            }
+            if !matches!(status, StepStatus::NotStarted | StepStatus::InProgress)
+                && labeled(
+                    ui.small_button("↺"),
+                    WidgetType::Button,
+                    &tr_fmt("Reset the {} step", &[&tr(name)]),
+                )
+                .on_hover_text(tr("Reset, so the step counts as not run"))
+                .clicked()
+            {
+                app.ctx.reset_step(step.id);
+            }
+            ui.end_row();
+        }
+    });
+    for step in &steps {
+        if let Some(advice) = step.error.and_then(ErrorKind::advice) {
+            ui.label(RichText::new(format!("💡 {}: {}", tr(step.name), tr(advice))).small());
+        }
+    }
+}
+
+/// Checks the destination can be written before any work starts, and asks
+/// what to do with the game files an earlier run left in it when `copying`.
+/// Returns false when the user backed out.
+pub(crate) fn confirm_destination(ctx: &Context, copying: bool) -> bool {
+    loop {
+        if let Err(err) = check_writable(&ctx.outdir())
+            && err.kind() == std::io::ErrorKind::PermissionDenied
+        {
+            warn!("Unable to write to {}: {err}", ctx.outdir().display());
+            let (admin, other) = (tr("Restart as administrator"), tr("Pick another folder"));
+            let result = rfd::MessageDialog::new()
+                .set_title(tr("The destination can't be written"))
+                .set_description(tr_fmt(
+                    "Windows doesn't allow writing to {} without administrator rights.",
+                    &[&ctx.outdir().display()],
+                ))
+                .set_buttons(rfd::MessageButtons::OkCancelCustom(
+                    admin.to_string(),
+                    other.to_string(),
+                ))
+                .show();
+            match result {
+                rfd::MessageDialogResult::Custom(choice) if choice == admin => {
+                    // The new instance starts from the saved destination.
+                    if let Err(err) = ctx.save_settings() {
+                        error!("Failed to save the settings: {err:#}");
+                    }
+                    match relaunch_elevated() {
+                        Ok(()) => std::process::exit(0),
+                        Err(err) => error!("{err:#}"),
+                    }
+                    return false;
+                }
+                rfd::MessageDialogResult::Custom(choice) if choice == other => {
+                    if !pick_other_outdir(ctx) {
+                        return false;
+                    }
+                    continue;
+                }
+                _ => return false,
+            }
+        }
+
+        let Some(existing) = ctx.existing_game_folder().filter(|_| copying) else {
+            ctx.set_overwrite_policy(OverwritePolicy::Merge);
+            return true;
+        };
+        let (merge, replace, other) = (tr("Update"), tr("Replace"), tr("Pick another folder"));
+        let result = rfd::MessageDialog::new()
+            .set_title(tr("The destination isn't empty"))
+            .set_description(tr_fmt(
+                "{} already holds game files. Update copies over them and keeps the rest, Replace deletes them first.",
+                &[&existing.display()],
+            ))
+            .set_buttons(rfd::MessageButtons::YesNoCancelCustom(
+                merge.to_string(),
+                replace.to_string(),
+                other.to_string(),
+            ))
+            .show();
+        let rfd::MessageDialogResult::Custom(choice) = result else {
+            return false;
+        };
+        if choice == merge {
+            ctx.set_overwrite_policy(OverwritePolicy::Merge);
+            return true;
+        } else if choice == replace {
+            ctx.set_overwrite_policy(OverwritePolicy::Replace);
+            return true;
+        }
+        if !pick_other_outdir(ctx) {
+            return false;
+        }
+    }
+}
+
+fn pick_other_outdir(ctx: &Context) -> bool {
+    match rfd::FileDialog::new()
+        .set_directory(ctx.outdir())
+        .pick_folder()
+    {
+        Some(dir) => {
+            info!("Selected directory: {}", dir.display());
+            ctx.pick_outdir(dir);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Which logs the log panel shows.
+pub(crate) struct LogFilter {
+    info: bool,
+    warn: bool,
+    error: bool,
+    search: String,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self {
+            info: true,
+            warn: true,
+            error: true,
+            search: String::new(),
+        }
+    }
+}
+
+impl LogFilter {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        let level = match entry.level {
+            Level::ERROR => self.error,
+            Level::WARN => self.warn,
+            _ => self.info,
+        };
+        level
+            && (self.search.is_empty()
+                || entry
+                    .message
+                    .to_lowercase()
+                    .contains(&self.search.to_lowercase()))
+    }
+}
+
+pub(crate) fn draw_logs(ui: &mut Ui, app: &mut App) {
+    ui.horizontal(|ui| {
+        let filter = &mut app.log_filter;
+        ui.toggle_value(&mut filter.info, "INFO");
+        ui.toggle_value(&mut filter.warn, "WARN");
+        ui.toggle_value(&mut filter.error, "ERROR");
+        ui.add(
+            TextEdit::singleline(&mut filter.search)
+                .hint_text(tr("🔍 Search"))
+                .desired_width(150.0),
+        );
+        if ui
+            .add_enabled(!app.logs.is_empty(), Button::new(tr("💾 Export logs")))
+            .on_hover_text(tr(
+                "Save the whole session's logs to a file, e.g. for a bug report",
+            ))
+            .clicked()
+            && let Some(path) = rfd::FileDialog::new()
+                .set_file_name("aoe2-archiver.log")
+                .add_filter("Log", &["log", "txt"])
+                .save_file()
+        {
+            match app.export_logs(&path) {
+                Ok(()) => info!("Exported the logs to {}", path.display()),
+                Err(err) => error!("{err:#}"),
+            }
+        }
+    });
     egui::ScrollArea::vertical()
         .max_height(150.0)
         .show(ui, |ui| {
             ui.group(|ui| {
                 ui.set_min_width(ui.available_width());
                 if app.logs.is_empty() {
-                    ui.label(RichText::new("No logs yet").italics().color(Color32::GRAY));
+                    ui.label(
+                        RichText::new(tr("No logs yet"))
+                            .italics()
+                            .color(Color32::GRAY),
+                    );
                 } else {
-                    for log in app.logs.iter().rev().take(50) {
-                        ui.label(RichText::new(log).small());
+                    let mut shown = app
+                        .logs
+                        .iter()
+                        .rev()
+                        .filter(|log| app.log_filter.matches(log))
+                        .take(200)
+                        .peekable();
+                    if shown.peek().is_none() {
+                        ui.label(
+                            RichText::new(tr("No logs match the filter"))
+                                .italics()
+                                .color(Color32::GRAY),
+                        );
+                    }
+                    for log in shown {
+                        let text = RichText::new(log.to_string()).small();
+                        ui.label(match log.level {
+                            Level::ERROR => text.color(Color32::from_rgb(220, 0, 0)),
+                            Level::WARN => text.color(Color32::from_rgb(255, 140, 0)),
+                            _ => text,
+                        });
                     }
                 }
             });
         });
-
-    Ok(())
 }
 
 impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         while let Ok(state) = self.update_rx.try_recv() {
             match state {
-                AppUpdate::Progress(progress) => self.progress = progress,
-                AppUpdate::SourceSize(required) => {
-                    self.required_space = Some(required);
+                AppUpdate::Progress(progress) => {
+                    if progress.is_none() {
+                        self.file_progress = None;
+                        self.recent_files.clear();
+                    }
+                    self.progress = progress;
                 }
+                AppUpdate::File(progress) => self.set_file_progress(progress),
+                AppUpdate::SourceSize(size) => self.source_size = Some(size),
+                AppUpdate::ProfileSize(size) => self.profile_size = Some(size),
                 AppUpdate::DestDriveAvailable(available) => {
                     self.available_space = Some(available);
                 }
                 AppUpdate::StepStatusChanged => {
-                    // Force UI update
+                    self.temp_size = None;
+                    let focused = ctx.input(|i| i.viewport().focused.unwrap_or(true));
+                    self.on_step_changes(focused);
                 }
+                AppUpdate::Tray(TrayAction::Cancel) if self.ctx.is_busy() => self.ctx.cancel(),
+                AppUpdate::Tray(action) => tray::apply(action, self.hwnd.is_some(), ctx),
+                AppUpdate::ArchiverRelease(release) => self.archiver_release = Some(release),
+                AppUpdate::SourceChecks(report) => self.source_checks = Some(report),
+                AppUpdate::Resumable(resumable) => self.resumable = resumable,
                 AppUpdate::Log(log) => {
                     self.add_log(log);
                 }
@@ -179,15 +534,71 @@ impl eframe::App for App {
             }
         }
 
+        // Steps can be quiet for minutes, the spinner and the elapsed time keep
+        // moving to show they're still at work.
+        if self.ctx.is_busy() {
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+        ctx.set_theme(self.ctx.theme());
+        apply_scale(ctx, self.ctx.ui_scale(), self.ctx.font_size());
+        egui::TopBottomPanel::top("menu").show(ctx, |ui| {
+            egui::MenuBar::new().ui(ui, |ui| {
+                ui.menu_button(tr("View"), |ui| {
+                    let mut layout = self.ctx.layout();
+                    ui.radio_value(&mut layout, Layout::Guided, tr("Guided"));
+                    ui.radio_value(&mut layout, Layout::Advanced, tr("Advanced"));
+                    if layout != self.ctx.layout() {
+                        self.ctx.set_layout(layout);
+                        ui.close();
+                    }
+                });
+            });
+        });
+        if let Some(release) = &self.archiver_release
+            && egui::TopBottomPanel::top("archiver_release")
+                .show(ctx, |ui| self_update::draw_banner(ui, release))
+                .inner
+        {
+            self.archiver_release = None;
+        }
+        if let Some(resumable) = &self.resumable
+            && !self.ctx.is_busy()
+            && resumable.outdir == self.ctx.outdir()
+            && egui::TopBottomPanel::top("resume")
+                .show(ctx, |ui| resume::draw_banner(ui, &self.ctx, resumable))
+                .inner
+        {
+            self.resumable = None;
+        }
         egui::CentralPanel::default().show(ctx, |ui| {
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                draw_main(self, ui).unwrap();
+            egui::ScrollArea::vertical().show(ui, |ui| match self.ctx.layout() {
+                Layout::Guided => draw_guided(self, ui),
+                Layout::Advanced => draw_main(self, ui).unwrap(),
             });
         });
+        // The guided layout has a page for it.
+        if self.ctx.layout() == Layout::Advanced
+            && let Some(report) = &self.report
+        {
+            let mut open = true;
+            egui::Window::new(tr("Summary"))
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| draw_report(ui, report));
+            if !open {
+                self.report = None;
+            }
+        }
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Err(err) = self.ctx.save_settings() {
+            error!("Failed to save the settings: {err:#}");
+        }
     }
 }
 
-fn source_folder_selection(
+pub(crate) fn source_folder_selection(
     ui: &mut Ui,
     ctx: &Context,
     label: &str,
@@ -200,7 +611,7 @@ fn source_folder_selection(
         ui.horizontal(|ui| {
             ui.label(label);
             if !tooltip.is_empty() {
-                ui.label("ℹ").on_hover_text(tooltip);
+                info_icon(ui, tooltip);
             }
         });
 
@@ -213,54 +624,59 @@ fn source_folder_selection(
         ui.horizontal(|ui| {
             let mut text_val = current_path_text.clone();
             let text_widget = TextEdit::singleline(&mut text_val).interactive(false);
-            ui.add_sized([ui.available_width() - 120.0, 20.0], text_widget);
+            ui.add_sized([ui.available_width() - 150.0, 20.0], text_widget);
 
-            if ui.button("📁 Select Folder").clicked() {
+            let mut picked = None;
+            if ui.button(tr("📁 Select Folder")).clicked() {
                 let current = dir_path.clone();
                 let mut dialog = rfd::FileDialog::new();
                 if let Some(current_path) = current {
                     dialog = dialog.set_directory(current_path);
                 }
-                if let Some(new_dir) = dialog.pick_folder() {
-                    info!("User selected directory: {}", new_dir.display());
-                    let mut valid = true;
-                    let mut error_msg = None;
-                    if let Some(validate_fn) = validation {
-                        if let Err(e) = validate_fn(&new_dir) {
-                            valid = false;
-                            error_msg = Some(format!("{}", e));
-                            info!("Validation failed: {}", e);
-                        }
-                    }
-                    if valid {
-                        info!("Updating source directory to: {}", new_dir.display());
-                        ctx.set_sourcedir(new_dir);
-                        info!("Source directory updated successfully");
-                        // Force UI update
-                        ui.ctx().request_repaint();
-                    } else if let Some(msg) = error_msg {
-                        rfd::MessageDialog::new()
-                            .set_title("Invalid Directory")
-                            .set_description(&msg)
-                            .set_buttons(rfd::MessageButtons::Ok)
-                            .show();
-                    }
+                picked = dialog.pick_folder();
+            }
+            if let Some(recent) = recent_dirs_menu(ui, ctx.recent_sourcedirs()) {
+                picked = Some(recent);
+            }
+
+            if let Some(new_dir) = picked {
+                info!("User selected directory: {}", new_dir.display());
+                let mut valid = true;
+                let mut error_msg = None;
+                if let Some(validate_fn) = validation
+                    && let Err(e) = validate_fn(&new_dir)
+                {
+                    valid = false;
+                    error_msg = Some(format!("{}", e));
+                    info!("Validation failed: {}", e);
+                }
+                if valid {
+                    info!("Updating source directory to: {}", new_dir.display());
+                    ctx.pick_sourcedir(new_dir);
+                    info!("Source directory updated successfully");
+                    // Force UI update
+                    ui.ctx().request_repaint();
+                } else if let Some(msg) = error_msg {
+                    rfd::MessageDialog::new()
+                        .set_title(tr("Invalid Directory"))
+                        .set_description(&msg)
+                        .set_buttons(rfd::MessageButtons::Ok)
+                        .show();
                 }
             }
         });
 
         // Show validation warning if present
-        if let Some(validate_fn) = validation {
-            if let Some(path) = &dir_path {
-                if let Err(e) = validate_fn(path) {
-                    ui.colored_label(Color32::from_rgb(255, 100, 0), format!("⚠ {}", e));
-                }
-            }
+        if let Some(validate_fn) = validation
+            && let Some(path) = &dir_path
+            && let Err(e) = validate_fn(path)
+        {
+            ui.colored_label(Color32::from_rgb(255, 100, 0), format!("⚠ {}", e));
         }
     });
 }
 
-fn outdir_folder_selection(
+pub(crate) fn outdir_folder_selection(
     ui: &mut Ui,
     ctx: &Context,
     label: &str,
@@ -272,7 +688,7 @@ fn outdir_folder_selection(
         ui.horizontal(|ui| {
             ui.label(label);
             if !tooltip.is_empty() {
-                ui.label("ℹ").on_hover_text(tooltip);
+                info_icon(ui, tooltip);
             }
         });
 
@@ -281,28 +697,788 @@ fn outdir_folder_selection(
             let mut text_val = dir_path.to_str().unwrap_or_default().to_string();
 
             let text_widget = TextEdit::singleline(&mut text_val).interactive(false);
-            ui.add_sized([ui.available_width() - 120.0, 20.0], text_widget);
+            ui.add_sized([ui.available_width() - 150.0, 20.0], text_widget);
 
-            if ui.button("📁 Select Folder").clicked() {
+            if ui.button(tr("📁 Select Folder")).clicked() {
                 let current = dir_path.clone();
                 let mut dialog = rfd::FileDialog::new();
                 dialog = dialog.set_directory(current);
                 if let Some(new_dir) = dialog.pick_folder() {
                     info!("Selected directory: {}", new_dir.display());
-                    ctx.set_outdir(new_dir);
+                    ctx.pick_outdir(new_dir);
                 }
             }
+            if let Some(recent) = recent_dirs_menu(ui, ctx.recent_outdirs()) {
+                info!("Selected recent directory: {}", recent.display());
+                ctx.pick_outdir(recent);
+            }
         });
     });
 }
 
-fn draw_status_banner(ui: &mut Ui, app: &App) {
+/// Names a widget showing only an icon, screen readers would read out the
+/// emoji.
+pub(crate) fn labeled(response: Response, kind: WidgetType, label: &str) -> Response {
+    response.widget_info(|| WidgetInfo::labeled(kind, response.enabled(), label));
+    response
+}
+
+/// Shows `text` on hover, and while focused for keyboard users.
+pub(crate) fn hint(response: Response, text: &str) -> Response {
+    if response.has_focus() {
+        response.show_tooltip_text(text);
+    }
+    response.on_hover_text(text)
+}
+
+/// An ℹ explaining the widget next to it, which the keyboard can reach.
+fn info_icon(ui: &mut Ui, tooltip: &str) {
+    let response = ui.add(Label::new("ℹ").sense(Sense::focusable_noninteractive()));
+    hint(labeled(response, WidgetType::Label, tooltip), tooltip);
+}
+
+/// A menu of the directories picked before. Returns the one clicked.
+fn recent_dirs_menu(ui: &mut Ui, recent: Vec<PathBuf>) -> Option<PathBuf> {
+    let mut picked = None;
+    ui.add_enabled_ui(!recent.is_empty(), |ui| {
+        let response = ui
+            .menu_button("🕘", |ui| {
+                for dir in recent {
+                    if ui.button(dir.display().to_string()).clicked() {
+                        picked = Some(dir);
+                        ui.close();
+                    }
+                }
+            })
+            .response;
+        labeled(response, WidgetType::Button, tr("Recent folders"))
+            .on_hover_text(tr("Recent folders"))
+            .on_disabled_hover_text(tr("No recent folders yet"));
+    });
+    picked
+}
+
+fn draw_options(ui: &mut Ui, ctx: &Context) {
+    egui::CollapsingHeader::new(tr("Options")).show(ui, |ui| draw_option_fields(ui, ctx));
+}
+
+pub(crate) fn draw_option_fields(ui: &mut Ui, ctx: &Context) {
+    {
+        let config = ctx.config();
+
+        ui.horizontal(|ui| {
+            let mut injection = config.goldberg.injection;
+            ui.label(tr("Goldberg injection:"));
+            egui::ComboBox::from_id_salt("injection")
+                .selected_text(match injection {
+                    Injection::ColdClientLoader => "Cold client loader",
+                    Injection::SteamApi => tr("Replace steam_api64.dll"),
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(
+                        &mut injection,
+                        Injection::ColdClientLoader,
+                        "Cold client loader",
+                    );
+                    ui.selectable_value(&mut injection, Injection::SteamApi, tr("Replace steam_api64.dll"));
+                })
+                .response
+                .on_hover_text(
+                    tr("Replacing steam_api64.dll is an alternative for setups where the loader is flaky. The companion dlls can't be injected in this mode."),
+                );
+            if injection != config.goldberg.injection {
+                ctx.update_config(|c| c.goldberg.injection = injection);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let mut language = config.aoe2.language.clone();
+            ui.label(tr("Game language:"));
+            egui::ComboBox::from_id_salt("language")
+                .selected_text(language.as_deref().unwrap_or(tr("Default (english)")))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut language, None, tr("Default (english)"));
+                    for code in goldberg::supported_languages() {
+                        ui.selectable_value(&mut language, Some(code.to_string()), code);
+                    }
+                })
+                .response
+                .on_hover_text(tr("The language goldberg reports to the game"));
+            if language != config.aoe2.language {
+                ctx.update_config(|c| c.aoe2.language = language);
+            }
+        });
+
+        let mut encrypt = config.goldberg.encrypt_loader;
+        if ui
+            .checkbox(&mut encrypt, tr("Encrypt the goldberg loader"))
+            .on_hover_text(
+                tr("Ship steamclient_loader_x64.exe encrypted so antivirus doesn't quarantine it. It is decrypted by the archive's launcher while playing."),
+            )
+            .changed()
+        {
+            ctx.update_config(|c| c.goldberg.encrypt_loader = encrypt);
+        }
+
+        let mut rewrite = config.privacy.rewrite;
+        if ui
+            .checkbox(&mut rewrite, tr("Rewrite personal data found in the archive"))
+            .on_hover_text(
+                tr("Replace your user, machine and steam account names and steam ids in the archive's text files with placeholders"),
+            )
+            .changed()
+        {
+            ctx.update_config(|c| c.privacy.rewrite = rewrite);
+        }
+
+        let mut import_profile = config.goldberg.import_profile;
+        if ui
+            .checkbox(&mut import_profile, tr("Include my game profile"))
+            .on_hover_text(tr(
+                "Copy your game settings, hotkeys and single player saves into the archive",
+            ))
+            .changed()
+        {
+            ctx.update_config(|c| c.goldberg.import_profile = import_profile);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(tr("Import saves from:")).on_hover_text(tr(
+                "Carry goldberg saves and the user config over from an archive created earlier",
+            ));
+            let import_from = config
+                .goldberg
+                .import_from
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| tr("Nothing").to_string());
+            ui.label(RichText::new(import_from).italics());
+            if ui.button("📁").clicked()
+                && let Some(previous) = rfd::FileDialog::new().pick_folder()
+            {
+                info!("Importing saves from: {}", previous.display());
+                ctx.update_config(|c| c.goldberg.import_from = Some(previous));
+            }
+            if config.goldberg.import_from.is_some() && ui.small_button("✖").clicked() {
+                ctx.update_config(|c| c.goldberg.import_from = None);
+            }
+        });
+
+        draw_cold_client_loader_options(ui, ctx);
+        draw_shortcut_options(ui, ctx);
+        draw_redist_options(ui, ctx);
+        draw_companion_options(ui, ctx);
+
+        let mut smoke_test = config.smoke_test.enabled;
+        if ui
+            .checkbox(&mut smoke_test, tr("Test launch the archive"))
+            .on_hover_text(tr_fmt(
+                "Start the archived game once it is created and check it keeps running for {} seconds with the companion loaded",
+                &[&config.smoke_test.alive_seconds],
+            ))
+            .changed()
+        {
+            ctx.update_config(|c| c.smoke_test.enabled = smoke_test);
+        }
+        draw_lan_options(ui, ctx);
+        draw_local_archive_options(ui, ctx);
+        draw_advanced_options(ui, ctx);
+        draw_download_sources(ui, ctx);
+
+        ui.add_space(4.0);
+        let cache_mb = ctx.cache.size() as f64 / 1_048_576.0;
+        if ui
+            .add_enabled(
+                !ctx.is_busy(),
+                Button::new(tr_fmt(
+                    "🗑 Clear download cache ({} MB)",
+                    &[&format!("{cache_mb:.2}")],
+                )),
+            )
+            .on_hover_text(tr(
+                "Downloads are kept between runs and only re-downloaded when they change upstream",
+            ))
+            .clicked()
+        {
+            match ctx.cache.clear() {
+                Ok(cleared) => info!(
+                    "Cleared {:.2} MB of cached downloads",
+                    cleared as f64 / 1_048_576.0
+                ),
+                Err(err) => error!("Failed to clear download cache: {err:#}"),
+            }
+        }
+    }
+}
+
+fn draw_settings(ui: &mut Ui, ctx: &Context) {
+    egui::CollapsingHeader::new(tr("Settings")).show(ui, |ui| {
+        let mut remember = ctx.remember_settings();
+        if ui
+            .checkbox(&mut remember, tr("Remember directories and options"))
+            .on_hover_text(tr(
+                "Start the next run with the directories and options picked here",
+            ))
+            .changed()
+        {
+            ctx.set_remember_settings(remember);
+        }
+        ui.horizontal(|ui| {
+            let mut language = ctx.language();
+            ui.label(tr("Language:"));
+            egui::ComboBox::from_id_salt("ui_language")
+                .selected_text(language.name())
+                .show_ui(ui, |ui| {
+                    for option in Language::ALL {
+                        ui.selectable_value(&mut language, option, option.name());
+                    }
+                });
+            if language != ctx.language() {
+                ctx.set_language(language);
+            }
+        });
+        ui.horizontal(|ui| {
+            let mut theme = ctx.theme();
+            ui.label(tr("Theme:"));
+            egui::ComboBox::from_id_salt("theme")
+                .selected_text(match theme {
+                    Theme::System => tr("Like Windows"),
+                    Theme::Light => tr("Light"),
+                    Theme::Dark => tr("Dark"),
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut theme, Theme::System, tr("Like Windows"));
+                    ui.selectable_value(&mut theme, Theme::Light, tr("Light"));
+                    ui.selectable_value(&mut theme, Theme::Dark, tr("Dark"));
+                });
+            if theme != ctx.theme() {
+                ctx.set_theme(theme);
+            }
+        });
+        ui.horizontal(|ui| {
+            let mut scale = ctx.ui_scale();
+            ui.label(tr("Zoom:"));
+            // Applied on release, the slider would move under the pointer.
+            let response = ui.add(
+                egui::Slider::new(&mut scale, 0.75..=2.5)
+                    .step_by(0.05)
+                    .custom_formatter(|scale, _| format!("{:.0}%", scale * 100.0))
+                    .custom_parser(|text| {
+                        text.trim_end_matches('%')
+                            .trim()
+                            .parse::<f64>()
+                            .ok()
+                            .map(|percent| percent / 100.0)
+                    }),
+            );
+            if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                ctx.set_ui_scale(scale);
+            }
+        });
+        ui.horizontal(|ui| {
+            let mut size = ctx.font_size();
+            ui.label(tr("Font size:"));
+            if ui
+                .add(egui::Slider::new(&mut size, 9.0..=24.0).step_by(0.5))
+                .changed()
+            {
+                ctx.set_font_size(size);
+            }
+            let reset = ui.add_enabled(
+                size != default_font_size() || ctx.ui_scale() != 1.0,
+                Button::new("↺"),
+            );
+            if labeled(reset, WidgetType::Button, tr("Default zoom and font size"))
+                .on_hover_text(tr("Default zoom and font size"))
+                .clicked()
+            {
+                ctx.set_ui_scale(1.0);
+                ctx.set_font_size(default_font_size());
+            }
+        });
+        let mut check = ctx.check_archiver_updates();
+        if ui
+            .checkbox(&mut check, tr("Check for newer archiver releases on startup"))
+            .on_hover_text(tr(
+                "Asks github when starting, archives need an archiver that keeps up with the launcher and Goldberg",
+            ))
+            .changed()
+        {
+            ctx.set_check_archiver_updates(check);
+        }
+        if let Some(path) = Settings::path() {
+            ui.label(RichText::new(tr_fmt("Saved to {} on exit", &[&path.display()])).small());
+        }
+        if paths::mode() == paths::Mode::Portable {
+            ui.label(
+                RichText::new(tr("Portable, the archiver's files are kept next to it")).small(),
+            )
+            .on_hover_text(tr("Started with --portable or with a portable.txt next to it"));
+        }
+        if ui
+            .add_enabled(!ctx.is_busy(), Button::new(tr("Reset to defaults")))
+            .on_hover_text(tr(
+                "Forget the saved directories and options, going back to config.toml",
+            ))
+            .clicked()
+        {
+            match ctx.reset_settings() {
+                Ok(()) => info!("Settings reset to config.toml"),
+                Err(err) => error!("Failed to reset the settings: {err:#}"),
+            }
+        }
+    });
+}
+
+/// Zooms over the system's display scaling and resizes the text, when the
+/// settings changed since the last frame.
+fn apply_scale(egui_ctx: &egui::Context, scale: f32, font_size: f32) {
+    let pixels_per_point = egui_ctx.native_pixels_per_point().unwrap_or(1.0) * scale;
+    if (egui_ctx.pixels_per_point() - pixels_per_point).abs() > 0.001 {
+        egui_ctx.set_pixels_per_point(pixels_per_point);
+    }
+
+    let body_size = egui_ctx
+        .style()
+        .text_styles
+        .get(&TextStyle::Body)
+        .map(|font| font.size);
+    if body_size != Some(font_size) {
+        let defaults = egui::Style::default().text_styles;
+        let factor = font_size / default_font_size();
+        egui_ctx.all_styles_mut(|style| {
+            for (text_style, font) in &mut style.text_styles {
+                if let Some(default) = defaults.get(text_style) {
+                    font.size = default.size * factor;
+                }
+            }
+        });
+    }
+}
+
+fn draw_cold_client_loader_options(ui: &mut Ui, ctx: &Context) {
+    egui::CollapsingHeader::new("Cold client loader").show(ui, |ui| {
+        let loader = ctx.config().cold_client_loader.clone();
+
+        ui.horizontal(|ui| {
+            let mut command_line = loader.exe_command_line.clone();
+            ui.label(tr("Game arguments:"));
+            if ui
+                .text_edit_singleline(&mut command_line)
+                .on_hover_text(tr("Extra command line arguments passed to AoE2DE_s.exe. Players can change them from launcher.exe."))
+                .changed()
+            {
+                ctx.update_config(|c| c.cold_client_loader.exe_command_line = command_line);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let mut mode = loader.persistence_mode;
+            ui.label(tr("Persistence mode:"));
+            egui::ComboBox::from_id_salt("persistence_mode")
+                .selected_text(match mode {
+                    0 => tr("Off").to_string(),
+                    mode => tr_fmt("Mode {}", &[&mode]),
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut mode, 0, tr("Off"));
+                    ui.selectable_value(&mut mode, 1, tr_fmt("Mode {}", &[&1]));
+                    ui.selectable_value(&mut mode, 2, tr_fmt("Mode {}", &[&2]));
+                })
+                .response
+                .on_hover_text(tr("See the [Persistence] section of ColdClientLoader.ini"));
+            if mode != loader.persistence_mode {
+                ctx.update_config(|c| c.cold_client_loader.persistence_mode = mode);
+            }
+        });
+
+        let mut ignore_arch = loader.ignore_loader_arch_difference;
+        if ui
+            .checkbox(&mut ignore_arch, tr("Ignore loader architecture difference"))
+            .changed()
+        {
+            ctx.update_config(|c| c.cold_client_loader.ignore_loader_arch_difference = ignore_arch);
+        }
+
+        let mut resume_by_debugger = loader.resume_by_debugger;
+        if ui
+            .checkbox(&mut resume_by_debugger, tr("Resume by debugger"))
+            .on_hover_text(tr("Suspend the game on start until a debugger resumes it"))
+            .changed()
+        {
+            ctx.update_config(|c| c.cold_client_loader.resume_by_debugger = resume_by_debugger);
+        }
+
+        ui.label(tr("Extra dlls to inject:"));
+        for (i, dll) in loader.extra_dlls.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.small_button("✖").clicked() {
+                    ctx.update_config(|c| {
+                        c.cold_client_loader.extra_dlls.remove(i);
+                    });
+                }
+                ui.label(dll.display().to_string());
+            });
+        }
+        if ui.button(tr("➕ Add dll")).clicked()
+            && let Some(dlls) = rfd::FileDialog::new()
+                .add_filter("Dll", &["dll"])
+                .pick_files()
+        {
+            ctx.update_config(|c| c.cold_client_loader.extra_dlls.extend(dlls));
+        }
+    });
+}
+
+fn draw_companion_options(ui: &mut Ui, ctx: &Context) {
+    let components = ctx.config().aoe2.companion_components.clone();
+    ui.horizontal_wrapped(|ui| {
+        ui.label(tr("Companion components:"))
+            .on_hover_text(tr("Parts of the companion zip to include in the archive, see config.toml for where each goes"));
+        for (i, component) in components.iter().enumerate() {
+            let mut enabled = component.enabled;
+            if ui
+                .checkbox(&mut enabled, &component.name)
+                .on_hover_text(tr_fmt("Installed to {}", &[&component.dest]))
+                .changed()
+            {
+                ctx.update_config(|c| c.aoe2.companion_components[i].enabled = enabled);
+            }
+        }
+    });
+}
+
+fn draw_shortcut_options(ui: &mut Ui, ctx: &Context) {
+    let shortcuts = ctx.config().shortcuts.clone();
+    ui.horizontal(|ui| {
+        ui.label(tr("Shortcuts:"));
+        let mut desktop = shortcuts.desktop;
+        if ui.checkbox(&mut desktop, tr("Desktop")).changed() {
+            ctx.update_config(|c| c.shortcuts.desktop = desktop);
+        }
+        let mut start_menu = shortcuts.start_menu;
+        if ui.checkbox(&mut start_menu, tr("Start Menu")).changed() {
+            ctx.update_config(|c| c.shortcuts.start_menu = start_menu);
+        }
+        let mut steam = shortcuts.steam;
+        if ui
+            .checkbox(&mut steam, "Steam")
+            .on_hover_text(tr("Add the archive as a non-steam game, for Big Picture and Steam Input. Close steam first."))
+            .changed()
+        {
+            ctx.update_config(|c| c.shortcuts.steam = steam);
+        }
+        let mut add_helper = shortcuts.add_helper;
+        if ui
+            .checkbox(&mut add_helper, tr("Add create_shortcuts.exe"))
+            .on_hover_text(tr("Lets the machines the archive is copied to create the same shortcuts"))
+            .changed()
+        {
+            ctx.update_config(|c| c.shortcuts.add_helper = add_helper);
+        }
+    });
+}
+
+fn draw_redist_options(ui: &mut Ui, ctx: &Context) {
+    let redist = ctx.config().redist.clone();
+    ui.horizontal(|ui| {
+        ui.label(tr("Bundle installers:")).on_hover_text(tr(
+            "The archive's launcher offers to run them on machines lacking the runtime",
+        ));
+        let mut vc = redist.vc;
+        if ui.checkbox(&mut vc, "Visual C++").changed() {
+            ctx.update_config(|c| c.redist.vc = vc);
+        }
+        let mut directx = redist.directx;
+        if ui
+            .checkbox(&mut directx, "DirectX")
+            .on_hover_text(tr("About 100 MB"))
+            .changed()
+        {
+            ctx.update_config(|c| c.redist.directx = directx);
+        }
+    });
+}
+
+fn draw_lan_options(ui: &mut Ui, ctx: &Context) {
+    egui::CollapsingHeader::new("LAN").show(ui, |ui| {
+        let lan = ctx.config().lan.clone();
+
+        ui.label(tr("Custom broadcast addresses, one per line:"))
+            .on_hover_text(
+                tr("Needed when the players aren't all on the same subnet, e.g. over a vpn"),
+            );
+        let mut broadcasts = lan.custom_broadcasts.join("\n");
+        if ui
+            .add(
+                TextEdit::multiline(&mut broadcasts)
+                    .hint_text("192.168.2.255")
+                    .desired_rows(3),
+            )
+            .changed()
+        {
+            ctx.update_config(|c| {
+                c.lan.custom_broadcasts = broadcasts.split('\n').map(str::to_string).collect()
+            });
+        }
+
+        ui.horizontal(|ui| {
+            let mut host = ctx.config().server.host.clone();
+            ui.label(tr("LAN server:"));
+            if ui
+                .add(TextEdit::singleline(&mut host).hint_text(tr("Find one automatically")))
+                .on_hover_text(tr("Hostname or ip of the machine hosting the ageLANServer"))
+                .changed()
+            {
+                ctx.update_config(|c| c.server.host = host);
+            }
+        });
+
+        let mut install = ctx.config().server.install;
+        if ui
+            .checkbox(&mut install, tr("Include the LAN server (host mode)"))
+            .on_hover_text(tr("Install the ageLANServer with a host_server.bat, so this archive can host the party"))
+            .changed()
+        {
+            ctx.update_config(|c| c.server.install = install);
+        }
+
+        ui.horizontal(|ui| {
+            let start = ctx.config().server.start;
+            let mut selected = start;
+            let label = |start| match start {
+                ServerStart::Auto => tr("When none is found"),
+                ServerStart::Always => tr("Always"),
+                ServerStart::Never => tr("Never"),
+            };
+            ui.label(tr("Host a server:"));
+            egui::ComboBox::from_id_salt("server_start")
+                .selected_text(label(selected))
+                .show_ui(ui, |ui| {
+                    for option in [ServerStart::Auto, ServerStart::Always, ServerStart::Never] {
+                        ui.selectable_value(&mut selected, option, label(option));
+                    }
+                })
+                .response
+                .on_hover_text(tr("Whether the archive's launcher starts a lan server itself"));
+            if selected != start {
+                ctx.update_config(|c| c.server.start = selected);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let mut custom_port = lan.listen_port.is_some();
+            let mut port = lan.listen_port.unwrap_or(47584);
+            let changed = ui
+                .checkbox(&mut custom_port, tr("Listen port:"))
+                .on_hover_text(tr("Every copy on the lan needs the same port"))
+                .changed();
+            let changed = ui
+                .add_enabled(
+                    custom_port,
+                    egui::DragValue::new(&mut port).range(1024..=65535),
+                )
+                .changed()
+                || changed;
+            if changed {
+                ctx.update_config(|c| c.lan.listen_port = custom_port.then_some(port));
+            }
+        });
+
+        let firewall = ctx.config().firewall.clone();
+        let mut generate_script = firewall.generate_script;
+        if ui
+            .checkbox(&mut generate_script, tr("Add a setup_firewall.bat to the archive"))
+            .on_hover_text(tr("Allows the game, loader and lan server through Windows Firewall on the machines the archive is copied to"))
+            .changed()
+        {
+            ctx.update_config(|c| c.firewall.generate_script = generate_script);
+        }
+        let mut install_rules = firewall.install_rules;
+        if ui
+            .add_enabled(
+                generate_script,
+                egui::Checkbox::new(&mut install_rules, tr("Also install the firewall rules on this machine")),
+            )
+            .on_hover_text(tr("Runs setup_firewall.bat after creating the archive. Windows asks for administrator rights."))
+            .changed()
+        {
+            ctx.update_config(|c| c.firewall.install_rules = install_rules);
+        }
+    });
+}
+
+fn draw_advanced_options(ui: &mut Ui, ctx: &Context) {
+    egui::CollapsingHeader::new(tr("Advanced")).show(ui, |ui| {
+        let config = ctx.config();
+        ui.label(tr("Release channels:"));
+
+        let mut goldberg = config.goldberg.prerelease;
+        if ui
+            .checkbox(&mut goldberg, tr("Goldberg prereleases"))
+            .changed()
+        {
+            ctx.update_config(|c| c.goldberg.prerelease = goldberg);
+        }
+
+        let mut companion = config.aoe2.companion_prerelease;
+        if ui
+            .checkbox(&mut companion, tr("Companion prereleases"))
+            .on_hover_text(tr("Only used when the companion version is \"latest\""))
+            .changed()
+        {
+            ctx.update_config(|c| c.aoe2.companion_prerelease = companion);
+        }
+
+        let mut launcher = config.aoe2.launcher_prerelease;
+        if ui
+            .checkbox(&mut launcher, tr("Launcher prereleases"))
+            .on_hover_text(tr("Only used when the launcher version is \"latest\""))
+            .changed()
+        {
+            ctx.update_config(|c| c.aoe2.launcher_prerelease = launcher);
+        }
+
+        let mut server = config.aoe2.server_prerelease;
+        if ui
+            .checkbox(&mut server, tr("Server prereleases"))
+            .on_hover_text(tr("Only used when the launcher version is \"latest\""))
+            .changed()
+        {
+            ctx.update_config(|c| c.aoe2.server_prerelease = server);
+        }
+
+        ui.horizontal(|ui| {
+            let mut version = config.aoe2.companion_version.clone().unwrap_or_default();
+            ui.label(tr("Companion version:"));
+            if ui
+                .add(TextEdit::singleline(&mut version).hint_text("latest"))
+                .on_hover_text(tr("A release tag like v1.2.1.0, or latest"))
+                .changed()
+            {
+                ctx.update_config(|c| c.aoe2.companion_version = Some(version));
+            }
+        });
+        ui.horizontal(|ui| {
+            let mut version = config.aoe2.launcher_version.clone().unwrap_or_default();
+            ui.label(tr("Launcher version:"));
+            if ui
+                .add(TextEdit::singleline(&mut version).hint_text("latest"))
+                .on_hover_text(tr("A release tag like v1.11.2, or latest. Newer launchers may not work with the archive yet."))
+                .changed()
+            {
+                ctx.update_config(|c| c.aoe2.launcher_version = Some(version));
+            }
+        });
+    });
+}
+
+fn draw_local_archive_options(ui: &mut Ui, ctx: &Context) {
+    egui::CollapsingHeader::new(tr("Offline archives")).show(ui, |ui| {
+        ui.label(tr(
+            "Archives downloaded by hand, for machines without internet access:",
+        ));
+        let config = ctx.config();
+
+        local_archive_row(
+            ui,
+            ctx,
+            "Goldberg:",
+            config.goldberg.local_archive.as_deref(),
+            &["7z", "zip"],
+            |c, path| c.goldberg.local_archive = path,
+        );
+        local_archive_row(
+            ui,
+            ctx,
+            "Companion:",
+            config.aoe2.companion_zip.as_deref(),
+            &["zip"],
+            |c, path| c.aoe2.companion_zip = path,
+        );
+        local_archive_row(
+            ui,
+            ctx,
+            "Launcher:",
+            config.aoe2.launcher_zip.as_deref(),
+            &["zip"],
+            |c, path| c.aoe2.launcher_zip = path,
+        );
+        local_archive_row(
+            ui,
+            ctx,
+            "Server:",
+            config.aoe2.server_zip.as_deref(),
+            &["zip"],
+            |c, path| c.aoe2.server_zip = path,
+        );
+    });
+}
+
+fn local_archive_row(
+    ui: &mut Ui,
+    ctx: &Context,
+    label: &str,
+    current: Option<&Path>,
+    extensions: &[&str],
+    set: impl Fn(&mut Config, Option<PathBuf>),
+) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let text = current
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| tr("Download").to_string());
+        ui.label(RichText::new(text).italics());
+        if ui.button("📁").clicked()
+            && let Some(path) = rfd::FileDialog::new()
+                .add_filter(tr("Archive"), extensions)
+                .pick_file()
+        {
+            info!("Using local archive: {}", path.display());
+            ctx.update_config(|c| set(c, Some(path)));
+        }
+        if current.is_some() && ui.small_button("✖").clicked() {
+            ctx.update_config(|c| set(c, None));
+        }
+    });
+}
+
+fn draw_temp_files(ui: &mut Ui, app: &mut App) {
+    let temp_size = *app.temp_size.get_or_insert_with(|| app.ctx.temp.size());
+    if temp_size == 0 {
+        return;
+    }
+
+    let temp_mb = temp_size as f64 / 1_048_576.0;
+    if ui
+        .add_enabled(
+            !app.ctx.is_busy(),
+            Button::new(tr_fmt(
+                "🧹 Clean temporary files ({} MB)",
+                &[&format!("{temp_mb:.2}")],
+            )),
+        )
+        .on_hover_text(tr("Remove partial downloads and extraction leftovers"))
+        .clicked()
+    {
+        app.temp_size = None;
+        match app.ctx.temp.clean() {
+            Ok(reclaimed) => info!(
+                "Reclaimed {:.2} MB of temporary files",
+                reclaimed as f64 / 1_048_576.0
+            ),
+            Err(err) => error!("Failed to clean temporary files: {err:#}"),
+        }
+    }
+}
+
+pub(crate) fn draw_status_banner(ui: &mut Ui, app: &App) {
     let mut has_banner = false;
 
     if let Some(err) = &app.error {
         ui.horizontal(|ui| {
             ui.label(
-                RichText::new("✗ Error:")
+                RichText::new(tr("✗ Error:"))
                     .color(Color32::from_rgb(220, 0, 0))
                     .strong(),
             );
@@ -317,9 +1493,33 @@ fn draw_status_banner(ui: &mut Ui, app: &App) {
         has_banner = true;
     }
 
-    if let Some((desc, pct)) = &app.progress {
-        let progress_bar = ProgressBar::new(*pct).text(desc);
-        ui.add_sized([ui.available_width(), 20.0], progress_bar);
+    let busy = app.ctx.is_busy();
+    if busy || app.progress.is_some() {
+        ui.horizontal(|ui| {
+            if busy
+                && ui
+                    .add_enabled(!app.ctx.is_cancelled(), Button::new(tr("✖ Cancel")))
+                    .on_hover_text(tr("Stop the running step, it can be run again afterwards"))
+                    .clicked()
+            {
+                app.ctx.cancel();
+            }
+            if let Some(progress) = &app.progress {
+                let progress_bar = ProgressBar::new(progress.fraction).text(progress.to_string());
+                ui.add_sized([ui.available_width(), 20.0], progress_bar);
+            }
+        });
+        if let Some((step_left, total_left)) = app.time_left() {
+            let text = match total_left {
+                Some(total_left) => tr_fmt(
+                    "About {} left in this step, {} overall",
+                    &[&format_duration(step_left), &format_duration(total_left)],
+                ),
+                None => tr_fmt("About {} left", &[&format_duration(step_left)]),
+            };
+            ui.label(RichText::new(text).small());
+        }
+        draw_file_progress(ui, app);
         has_banner = true;
     }
 
@@ -328,9 +1528,35 @@ fn draw_status_banner(ui: &mut Ui, app: &App) {
     }
 }
 
+fn draw_file_progress(ui: &mut Ui, app: &App) {
+    let Some(file) = &app.file_progress else {
+        return;
+    };
+    ui.label(
+        RichText::new(tr_fmt(
+            "{} of {} files: {}",
+            &[&file.done, &file.total, &file.name],
+        ))
+        .small(),
+    );
+    if !app.recent_files.is_empty() {
+        egui::CollapsingHeader::new(tr("Recent files")).show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(100.0)
+                .show(ui, |ui| {
+                    for name in &app.recent_files {
+                        ui.label(RichText::new(format!("✔ {name}")).small());
+                    }
+                });
+        });
+    }
+}
+
 // Custom tracing layer that sends logs to the UI
 pub struct UiLayer {
-    pub tx: Sender<AppUpdate>,
+    pub events: EventBus,
+    /// When the archiver started, to timestamp the logs.
+    pub start: Instant,
 }
 
 impl<S> Layer<S> for UiLayer
@@ -366,9 +1592,12 @@ where
         event.record(&mut visitor);
 
         if !visitor.message.is_empty() {
-            let level = event.metadata().level();
-            let log_msg = format!("[{}] {}", level, visitor.message);
-            let _ = self.tx.send(AppUpdate::Log(log_msg));
+            self.events.send(AppUpdate::Log(LogEntry {
+                level: *event.metadata().level(),
+                elapsed: self.start.elapsed(),
+                time: log::clock_time(),
+                message: visitor.message,
+            }));
         }
     }
 }
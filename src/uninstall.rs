@@ -0,0 +1,52 @@
+use crate::{Context, firewall::RULE_PREFIX};
+use anyhow::{Result, anyhow};
+use common::shortcuts::{REMOVE_SHORTCUTS_ARG, SHORTCUT_NAME};
+use tracing::info;
+
+pub const UNINSTALL_SCRIPT: &str = "uninstall.bat";
+
+/// Writes a script to the archive that removes what the archive set up on a
+/// machine, its firewall rules, trusted certificate and shortcuts, and then
+/// deletes the archive itself.
+pub fn write_uninstaller(ctx: &Context) -> Result<()> {
+    let path = ctx.outdir().join(UNINSTALL_SCRIPT);
+    std::fs::write(&path, uninstall_script())
+        .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?;
+    info!("Wrote {UNINSTALL_SCRIPT}, guests can run it to remove the game again");
+    Ok(())
+}
+
+fn uninstall_script() -> String {
+    format!(
+        "@echo off\r\n\
+         :: Removes this archive and everything it set up on this machine.\r\n\
+         net session >nul 2>&1 || (\r\n\
+         \x20   powershell -NoProfile -Command \"Start-Process -FilePath '%~f0' -Verb RunAs\"\r\n\
+         \x20   exit /b\r\n\
+         )\r\n\
+         cd /d \"%~dp0\"\r\n\
+         choice /m \"Remove the game in %~dp0 from this machine\"\r\n\
+         if errorlevel 2 exit /b\r\n\
+         echo Stopping the game and lan server started from this archive...\r\n\
+         powershell -NoProfile -Command \"Get-Process | Where-Object {{ $_.Path -and $_.Path.StartsWith('%~dp0', 'OrdinalIgnoreCase') }} | Stop-Process -Force\" >nul 2>&1\r\n\
+         echo Removing firewall rules...\r\n\
+         powershell -NoProfile -Command \"Get-NetFirewallRule -DisplayName '{RULE_PREFIX} *' -ErrorAction SilentlyContinue | Remove-NetFirewallRule\"\r\n\
+         echo Removing the lan server certificate...\r\n\
+         for %%c in (server\\resources\\certificates\\cacert.pem server\\resources\\certificates\\cert.pem) do (\r\n\
+         \x20   if exist \"%%c\" powershell -NoProfile -Command \"$c = New-Object Security.Cryptography.X509Certificates.X509Certificate2 '%~dp0%%c'; certutil -delstore Root $c.Thumbprint\" >nul\r\n\
+         )\r\n\
+         powershell -NoProfile -Command \"$f = Join-Path $env:LOCALAPPDATA 'aoe2-archiver\\trusted_certificates.txt'; $a = '%~dp0'.TrimEnd('\\'); if (Test-Path -LiteralPath $f) {{ Set-Content -LiteralPath $f @(Get-Content -LiteralPath $f | Where-Object {{ -not ($_.EndsWith(' ' + $a, 'OrdinalIgnoreCase') -or $_.EndsWith('\\' + $a, 'OrdinalIgnoreCase')) }}) }}\" >nul 2>&1\r\n\
+         echo Removing shortcuts...\r\n\
+         powershell -NoProfile -Command \"$shell = New-Object -ComObject WScript.Shell; foreach ($f in 'Desktop','Programs') {{ $p = Join-Path ([Environment]::GetFolderPath($f)) '{SHORTCUT_NAME}.lnk'; if ((Test-Path -LiteralPath $p) -and $shell.CreateShortcut($p).TargetPath.StartsWith('%~dp0', 'OrdinalIgnoreCase')) {{ Remove-Item -LiteralPath $p }} }}\"\r\n\
+         tasklist /fi \"imagename eq steam.exe\" | find /i \"steam.exe\" >nul && (\r\n\
+         \x20   echo Close steam first, it puts the game back in its library when exiting.\r\n\
+         \x20   pause\r\n\
+         )\r\n\
+         start \"\" /wait launcher.exe {REMOVE_SHORTCUTS_ARG}\r\n\
+         choice /m \"Delete every file in %~dp0, including saves kept there\"\r\n\
+         if errorlevel 2 exit /b\r\n\
+         echo Removing the game files...\r\n\
+         cd /d \"%TEMP%\"\r\n\
+         (goto) 2>nul & rmdir /s /q \"%~dp0\"\r\n"
+    )
+}
@@ -1,42 +1,121 @@
-use anyhow::{bail, Result};
+use crate::Context;
+use crate::cache::CacheEntry;
+use crate::config::RetryOperation;
+use crate::download::ReleaseAsset;
+use crate::errors::{ErrorKind, Tag};
+use crate::paths;
+use crate::retry::retry;
+use crate::source_checks::{Outcome, required_checks};
+use anyhow::{Result, anyhow, bail};
+use reqwest::StatusCode;
+use reqwest::blocking::Response;
+use reqwest::header::{ACCEPT, ETAG, IF_NONE_MATCH};
 use serde_json::Value;
 use sevenz_rust2::ArchiveReader;
 use std::collections::HashMap;
-use std::io::{Cursor, Read};
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Seek};
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
 use zip::ZipArchive;
 
-pub fn extract_7z(archive: &[u8]) -> Result<HashMap<String, Vec<u8>>> {
+pub use common::sha256_hex;
+
+pub fn extract_7z(
+    mut reader: impl Read + Seek,
+    mut on_entry: impl FnMut(&str, usize, usize) -> bool,
+) -> Result<HashMap<String, Vec<u8>>> {
     let mut files = HashMap::new();
 
-    let mut cursor = Cursor::new(archive);
-    let mut archive = ArchiveReader::new(&mut cursor, "".into())?;
+    let mut archive = ArchiveReader::new(&mut reader, "".into())?;
+    let total = archive.archive().files.len();
 
     archive.for_each_entries(|entry, reader| {
         let mut content = vec![];
         let _ = reader.read_to_end(&mut content);
-        files.insert(entry.name.clone(), content);
-        Ok(true)
+        let Some(name) = enclosed_name(&entry.name) else {
+            warn!("Skipped {} outside of the archive", entry.name);
+            return Ok(true);
+        };
+        files.insert(name, content);
+        Ok(on_entry(&entry.name, files.len(), total))
     })?;
 
     Ok(files)
 }
 
-pub fn extract_zip(data: &[u8]) -> Result<HashMap<String, Vec<u8>>> {
-    let reader = Cursor::new(data);
+pub fn extract_zip(
+    reader: impl Read + Seek,
+    mut on_entry: impl FnMut(&str, usize, usize) -> bool,
+) -> Result<HashMap<String, Vec<u8>>> {
     let mut archive = ZipArchive::new(reader)?;
     let mut map = HashMap::new();
+    let total = archive.len();
 
-    for i in 0..archive.len() {
+    for i in 0..total {
         let mut file = archive.by_index(i)?;
+        // Entries like "../x.dll" would be written outside of the archive.
+        let Some(enclosed) = file.enclosed_name() else {
+            warn!("Skipped {} outside of the archive", file.name());
+            continue;
+        };
+        let name = enclosed
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
         let mut contents = Vec::new();
         file.read_to_end(&mut contents)?;
-        map.insert(file.name().to_string(), contents);
+        map.insert(name.clone(), contents);
+        if !on_entry(&name, i + 1, total) {
+            break;
+        }
     }
 
     Ok(map)
 }
 
+/// `name` with '/' separators when it stays inside the folder it's
+/// extracted to, like [`zip::read::ZipFile::enclosed_name`].
+fn enclosed_name(name: &str) -> Option<String> {
+    let name = name.replace('\\', "/");
+    let parts: Vec<&str> = name
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect();
+    let escapes =
+        name.starts_with('/') || parts.iter().any(|part| *part == ".." || part.contains(':'));
+    (!escapes).then(|| parts.join("/"))
+}
+
+/// Extracts the zip or 7z archive at `path`, picked by the extension of
+/// `name`, reporting the files extracted so far. Stops early when cancelled.
+pub(crate) fn extract_archive(
+    ctx: &Context,
+    name: &str,
+    path: &Path,
+) -> Result<HashMap<String, Vec<u8>>> {
+    let label = name.rsplit('/').next().unwrap_or(name);
+    let cancellation = ctx.cancellation();
+    let on_entry = |file: &str, done: usize, total: usize| {
+        ctx.report_items(&format!("Extracting {label}"), done, total);
+        ctx.report_file(file, done - 1, total);
+        !cancellation.is_cancelled()
+    };
+
+    let file = BufReader::new(
+        File::open(path).map_err(|e| anyhow!("Failed to open {}: {}", path.display(), e))?,
+    );
+    let files = if name.to_lowercase().ends_with(".zip") {
+        extract_zip(file, on_entry)
+    } else {
+        extract_7z(file, on_entry)
+    }?;
+    cancellation.check()?;
+    Ok(files)
+}
+
 pub fn desktop_dir() -> Result<PathBuf> {
     let Some(desktop_dir) = dirs::desktop_dir() else {
         bail!("Missing desktop dir.");
@@ -44,43 +123,122 @@ pub fn desktop_dir() -> Result<PathBuf> {
     Ok(desktop_dir)
 }
 
-pub fn validate_aoe2_source(path: &Path) -> Result<()> {
-    if !path.exists() {
-        bail!("Directory does not exist");
+/// Whether files can be created in `dir`, or in the folder it would be
+/// created in. Catches protected folders before a step fails halfway.
+pub fn check_writable(dir: &Path) -> std::io::Result<()> {
+    let Some(existing) = dir.ancestors().find(|dir| dir.is_dir()) else {
+        return Ok(());
+    };
+    let probe = existing.join(".aoe2-archiver-write-test");
+    File::create(&probe)?;
+    fs::remove_file(probe)
+}
+
+/// Starts the archiver again with administrator rights, with the same
+/// arguments and working directory. Fails when the prompt is declined.
+pub fn relaunch_elevated() -> Result<()> {
+    let exe = std::env::current_exe()?;
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    // Portable by its marker stays portable anyway, the flag is kept too.
+    if paths::mode() == paths::Mode::Portable && !args.iter().any(|a| a == paths::PORTABLE_FLAG) {
+        args.push(paths::PORTABLE_FLAG.to_string());
+    }
+    // Single quoted powershell strings only escape quotes, by doubling them.
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let mut script = format!(
+        "Start-Process -FilePath {} -WorkingDirectory {} -Verb RunAs",
+        quote(&exe.display().to_string()),
+        quote(&std::env::current_dir()?.display().to_string())
+    );
+    if !args.is_empty() {
+        let args: Vec<String> = args
+            .iter()
+            .map(|arg| quote(&command_line_arg(arg)))
+            .collect();
+        script += &format!(" -ArgumentList {}", args.join(","));
     }
-    if !path.is_dir() {
-        bail!("Path is not a directory");
+    let status = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(script)
+        .status()?;
+    if !status.success() {
+        bail!("Restarting as administrator failed or was declined ({status})");
     }
+    Ok(())
+}
 
-    // Check for AoE2DE executable
-    let exe_path = path.join("AoE2DE_s.exe");
-    if !exe_path.exists() {
-        bail!("This doesn't appear to be an AoE2 DE directory (AoE2DE_s.exe not found)");
+/// `arg` quoted for a windows command line, which Start-Process joins its
+/// arguments into as they are.
+fn command_line_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
     }
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // Backslashes before a quote escape, as does one more.
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+            }
+        }
+        if c != '\\' {
+            quoted.push(c);
+        }
+    }
+    // Doubled so they don't escape the closing quote.
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
 
-    Ok(())
+/// Whether `path` passes the checks needed to copy it, the others are shown
+/// in the source checks panel.
+pub fn validate_aoe2_source(path: &Path) -> Result<()> {
+    match required_checks(path)
+        .into_iter()
+        .find(|check| check.outcome == Outcome::Fail)
+    {
+        Some(failed) => Err(anyhow!("{}", failed.detail)).kind(ErrorKind::SourceInvalid),
+        None => Ok(()),
+    }
 }
 
-pub fn gh_latest_release_dl_url(
+fn is_checksums_asset(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.contains("checksum") || name.ends_with(".sha256") || name.ends_with("sha256sums.txt")
+}
+
+pub(crate) fn gh_latest_release_dl_url(
+    ctx: &Context,
     gh_user: &str,
     gh_repo: &str,
     version: Option<&str>,
+    include_prereleases: bool,
     search: &[&str],
-) -> Result<Option<String>> {
+) -> Result<Option<ReleaseAsset>> {
     let url = format!("https://api.github.com/repos/{gh_user}/{gh_repo}/releases");
 
     // Ask the api for the latest release download
-    let client = reqwest::blocking::Client::new();
-    let json = client
-        .get(url)
-        .header(
-            "User-Agent",
-            "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:143.0) Gecko/20100101 Firefox/143.0",
-        )
-        .send()?
-        .text()?;
-    let json: Value = serde_json::from_str(&json)?;
+    let json = gh_api_get(ctx, &url)?;
+    find_release_asset(&json, gh_repo, version, include_prereleases, search)
+}
 
+/// Picks the asset matching `search` of the release `version`, or of the
+/// latest one, from github's releases json.
+fn find_release_asset(
+    json: &Value,
+    gh_repo: &str,
+    version: Option<&str>,
+    include_prereleases: bool,
+    search: &[&str],
+) -> Result<Option<ReleaseAsset>> {
     let Some(releases) = json.as_array() else {
         bail!("Expected releases json to be an array.");
     };
@@ -98,7 +256,20 @@ pub fn gh_latest_release_dl_url(
         };
         release
     } else {
-        releases.iter().nth(0).unwrap()
+        let is_flagged = |r: &Value, flag| r.get(flag).and_then(|f| f.as_bool()).unwrap_or(false);
+        let Some(release) = releases.iter().find(|r| {
+            !is_flagged(r, "draft") && (include_prereleases || !is_flagged(r, "prerelease"))
+        }) else {
+            if include_prereleases {
+                bail!("{gh_repo} has no published releases.");
+            }
+            bail!("{gh_repo} has no stable releases, enable its prereleases to use one.");
+        };
+        if is_flagged(release, "prerelease") {
+            let tag = release.get("tag_name").and_then(|t| t.as_str());
+            info!("Using prerelease {}", tag.unwrap_or("unknown"));
+        }
+        release
     };
 
     let Some(assets) = release.get("assets") else {
@@ -108,39 +279,260 @@ pub fn gh_latest_release_dl_url(
         bail!("Expected github assets to be an array, but it was not.");
     };
 
+    let checksums_url = assets
+        .iter()
+        .filter(|a| {
+            a.get("name")
+                .and_then(|n| n.as_str())
+                .is_some_and(is_checksums_asset)
+        })
+        .find_map(|a| a.get("browser_download_url").and_then(|u| u.as_str()))
+        .map(str::to_string);
+
     for asset in assets {
         let Some(name) = asset.get("name").and_then(|n| n.as_str()) else {
             continue;
         };
 
-        if !search.iter().all(|s| name.contains(s)) {
+        if is_checksums_asset(name) || !search.iter().all(|s| name.contains(s)) {
             continue;
         }
 
         let Some(url) = asset.get("browser_download_url").and_then(|u| u.as_str()) else {
             continue;
         };
+        let tag = release
+            .get("tag_name")
+            .and_then(|t| t.as_str())
+            .unwrap_or("unknown");
+
+        let sha256 = asset
+            .get("digest")
+            .and_then(|d| d.as_str())
+            .and_then(|d| d.strip_prefix("sha256:"))
+            .map(str::to_lowercase);
 
-        return Ok(Some(url.to_string()));
+        return Ok(Some(ReleaseAsset {
+            tag: tag.to_string(),
+            name: name.to_string(),
+            url: url.to_string(),
+            sha256,
+            checksums_url,
+            local: false,
+        }));
     }
 
     Ok(None)
 }
 
+/// Requests the github api, revalidating the cached response by etag. Such
+/// revalidations don't count against the rate limit, and the cached response
+/// is used when github can't be reached or the limit is exhausted.
+pub(crate) fn gh_api_get(ctx: &Context, url: &str) -> Result<Value> {
+    let cached = ctx.cache.get(url);
+
+    let response = match gh_send_with_retries(ctx, url, cached.as_ref()) {
+        Ok(response) => response,
+        Err(err) => {
+            let Some(entry) = cached else {
+                return Err(err);
+            };
+            warn!("Failed to reach github, using cached release info: {err:#}");
+            return Ok(serde_json::from_slice(&fs::read(entry.data_path)?)?);
+        }
+    };
+
+    if response.status() == StatusCode::NOT_MODIFIED
+        && let Some(entry) = cached
+    {
+        return Ok(serde_json::from_slice(&fs::read(entry.data_path)?)?);
+    }
+
+    if let Some(message) = gh_rate_limit_message(&response) {
+        let Some(entry) = cached else {
+            return Err(anyhow!("{message}")).kind(ErrorKind::RateLimited);
+        };
+        warn!("{message} Using cached release info.");
+        return Ok(serde_json::from_slice(&fs::read(entry.data_path)?)?);
+    }
+
+    let response = response.error_for_status()?;
+    let etag = response
+        .headers()
+        .get(ETAG)
+        .and_then(|e| e.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let body = response.bytes()?;
+    let json = serde_json::from_slice(&body)
+        .map_err(|e| anyhow!("Unexpected response from {url}: {e}"))?;
+
+    // Kept even without an etag, as the fallback for when github is down.
+    if let Err(err) = ctx.cache.insert_data(url, &etag, &body) {
+        warn!("Failed to cache {url}: {err:#}");
+    }
+
+    Ok(json)
+}
+
+/// Retries connection failures and server errors as the `[retries]` config
+/// allows. Client errors like the rate limit are returned right away.
+fn gh_send_with_retries(ctx: &Context, url: &str, cached: Option<&CacheEntry>) -> Result<Response> {
+    let retries = ctx.config().retries.clone();
+    let token = gh_token(ctx);
+    let cancel = ctx.cancellation();
+
+    retry(
+        &retries,
+        RetryOperation::ReleaseLookup,
+        &cancel,
+        "Github request",
+        || {
+            let mut request = ctx
+                .http()
+                .get(url)
+                .header(ACCEPT, "application/vnd.github+json");
+            if let Some(token) = &token {
+                request = request.bearer_auth(token);
+            }
+            if let Some(entry) = cached.filter(|e| !e.etag.is_empty()) {
+                request = request.header(IF_NONE_MATCH, &entry.etag);
+            }
+
+            let response = request.send()?;
+            if response.status().is_server_error() {
+                bail!("github responded with {}", response.status());
+            }
+            Ok(response)
+        },
+    )
+}
+
+fn gh_token(ctx: &Context) -> Option<String> {
+    match ctx.config().github.token.trim() {
+        "" => std::env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty()),
+        token => Some(token.to_string()),
+    }
+}
+
+fn gh_rate_limit_message(response: &Response) -> Option<String> {
+    let headers = response.headers();
+    let exhausted = headers
+        .get("x-ratelimit-remaining")
+        .is_some_and(|r| r == "0");
+    let limited = response.status() == StatusCode::TOO_MANY_REQUESTS
+        || (response.status() == StatusCode::FORBIDDEN && exhausted);
+    if !limited {
+        return None;
+    }
+
+    let reset = headers
+        .get("x-ratelimit-reset")
+        .and_then(|r| r.to_str().ok())
+        .and_then(|r| r.parse::<u64>().ok())
+        .and_then(|reset| {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+            Some(format!(
+                " for another {} minutes",
+                reset.saturating_sub(now).div_ceil(60)
+            ))
+        })
+        .unwrap_or_default();
+
+    Some(format!(
+        "The github api rate limit is exceeded{reset}. Set a token in the [github] section of config.toml or the GITHUB_TOKEN environment variable to raise it."
+    ))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::utils::gh_latest_release_dl_url;
+    use crate::utils::{command_line_arg, enclosed_name, extract_zip, find_release_asset};
+    use serde_json::json;
+    use std::io::{Cursor, Write};
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    #[test]
+    fn quotes_command_line_args() {
+        assert_eq!(command_line_arg("--portable"), "--portable");
+        assert_eq!(command_line_arg(""), r#""""#);
+        assert_eq!(command_line_arg(r"C:\My Games\"), r#""C:\My Games\\""#);
+        assert_eq!(command_line_arg(r#"say "hi""#), r#""say \"hi\"""#);
+    }
+
+    #[test]
+    fn extract_skips_files_outside_the_archive() {
+        let mut zip = ZipWriter::new(Cursor::new(vec![]));
+        for name in ["dlls/age2.dll", "../evil.dll", "/abs.dll"] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(name.as_bytes()).unwrap();
+        }
+        let zip = zip.finish().unwrap();
+
+        let files = extract_zip(zip, |_, _, _| true).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files["dlls/age2.dll"], b"dlls/age2.dll");
+
+        assert_eq!(enclosed_name("a\\b/./c").as_deref(), Some("a/b/c"));
+        assert!(enclosed_name("a/../../b").is_none());
+        assert!(enclosed_name("C:/b").is_none());
+    }
 
     #[test]
     fn load_specific_version() {
-        let result = gh_latest_release_dl_url(
-            "luskaner",
+        let base = "https://github.com/luskaner/ageLANServerLauncherCompanion/releases/download";
+        let releases = json!([
+            {
+                "tag_name": "v1.3.0.0",
+                "prerelease": true,
+                "assets": [{
+                    "name": "ageLANServerLauncherCompanion_full_1.3.0.0.zip",
+                    "browser_download_url": format!("{base}/v1.3.0.0/ageLANServerLauncherCompanion_full_1.3.0.0.zip"),
+                }],
+            },
+            {
+                "tag_name": "v1.2.1.0",
+                "assets": [
+                    {
+                        "name": "checksums.txt",
+                        "browser_download_url": format!("{base}/v1.2.1.0/checksums.txt"),
+                    },
+                    {
+                        "name": "ageLANServerLauncherCompanion_Age2FakeOnline_1.0.0.0.zip",
+                        "browser_download_url": format!("{base}/v1.2.1.0/ageLANServerLauncherCompanion_Age2FakeOnline_1.0.0.0.zip"),
+                        "digest": "sha256:AB12",
+                    },
+                ],
+            },
+        ]);
+
+        let asset = find_release_asset(
+            &releases,
             "ageLANServerLauncherCompanion",
             Some("v1.2.1.0"),
+            false,
             &[],
         )
+        .unwrap()
         .unwrap();
+        assert_eq!(
+            asset.url,
+            format!("{base}/v1.2.1.0/ageLANServerLauncherCompanion_Age2FakeOnline_1.0.0.0.zip")
+        );
+        assert_eq!(asset.sha256.as_deref(), Some("ab12"));
+        assert_eq!(
+            asset.checksums_url,
+            Some(format!("{base}/v1.2.1.0/checksums.txt"))
+        );
 
-        assert_eq!(result.unwrap(), "https://github.com/luskaner/ageLANServerLauncherCompanion/releases/download/v1.2.1.0/ageLANServerLauncherCompanion_Age2FakeOnline_1.0.0.0.zip");
+        // The prerelease is skipped unless asked for.
+        let latest = find_release_asset(&releases, "companion", None, false, &["_Age2"]);
+        assert_eq!(latest.unwrap().unwrap().tag, "v1.2.1.0");
+        let latest = find_release_asset(&releases, "companion", None, true, &["_full_"]);
+        assert_eq!(latest.unwrap().unwrap().tag, "v1.3.0.0");
+        assert!(
+            find_release_asset(&releases, "companion", Some("v9"), false, &[])
+                .unwrap()
+                .is_none()
+        );
     }
 }